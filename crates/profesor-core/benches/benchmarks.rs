@@ -4,13 +4,15 @@
 //! and the Vec2 physics primitives used in simulations.
 
 #![allow(clippy::unwrap_used)]
+#![allow(missing_docs)]
 
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
-use profesor_core::{Answer, Course, CourseId, CourseLevel, LearnerProgress, Question, Quiz, QuizId, QuestionId};
+use profesor_core::{
+    Course, CourseId, CourseLevel, LearnerProgress, Question, QuestionId, Quiz, QuizId,
+};
 
 fn make_quiz(n_questions: usize) -> Quiz {
-    let mut quiz = Quiz::new(QuizId::new("bench-quiz"), "Benchmark Quiz")
-        .with_passing_score(0.7);
+    let mut quiz = Quiz::new(QuizId::new("bench-quiz"), "Benchmark Quiz").with_passing_score(0.7);
     for i in 0..n_questions {
         quiz = quiz.with_question(Question::MultipleChoice {
             id: QuestionId::new(format!("q{i}")),
@@ -24,6 +26,7 @@ fn make_quiz(n_questions: usize) -> Quiz {
             correct: 0,
             explanation: format!("{i} + {i} = {}", i * 2),
             points: 10,
+            option_explanations: Vec::new(),
         });
     }
     quiz
@@ -38,13 +41,9 @@ fn bench_quiz_creation(c: &mut Criterion) {
 fn bench_quiz_creation_scaling(c: &mut Criterion) {
     let mut group = c.benchmark_group("quiz_creation");
     for count in [5, 10, 25, 50] {
-        group.bench_with_input(
-            BenchmarkId::new("questions", count),
-            &count,
-            |b, &count| {
-                b.iter(|| black_box(make_quiz(count)));
-            },
-        );
+        group.bench_with_input(BenchmarkId::new("questions", count), &count, |b, &count| {
+            b.iter(|| black_box(make_quiz(count)));
+        });
     }
     group.finish();
 }
@@ -0,0 +1,218 @@
+//! Multi-attempt quiz policies.
+//!
+//! A policy decides which of a learner's attempts counts towards progress
+//! and unlocking, how long they must wait between attempts, and how much
+//! each retry is discounted. Attempts keep their raw scores; the policy is
+//! applied when they are read back.
+
+use serde::{Deserialize, Serialize};
+
+use crate::progress::{QuizAttempt, Timestamp};
+
+/// Which attempt score counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum AttemptScoring {
+    /// Highest score of any attempt
+    #[default]
+    Best,
+    /// Score of the most recent attempt
+    Latest,
+    /// Mean score of all attempts
+    Average,
+}
+
+/// How repeated quiz attempts are scored and paced.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub struct AttemptPolicy {
+    /// Which attempt score counts
+    #[serde(default)]
+    pub scoring: AttemptScoring,
+    /// Seconds a learner must wait after finishing an attempt
+    #[serde(default)]
+    pub cooldown_secs: Option<u32>,
+    /// Fraction of the score deducted per retry (0.0 - 1.0)
+    #[serde(default)]
+    pub retry_penalty: f32,
+}
+
+impl AttemptPolicy {
+    /// Create a policy that keeps the best score, with no cooldown or penalty.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set which attempt score counts.
+    #[must_use]
+    pub fn with_scoring(mut self, scoring: AttemptScoring) -> Self {
+        self.scoring = scoring;
+        self
+    }
+
+    /// Require a wait between attempts.
+    #[must_use]
+    pub fn with_cooldown(mut self, seconds: u32) -> Self {
+        self.cooldown_secs = Some(seconds);
+        self
+    }
+
+    /// Deduct a fraction of the score for each retry, e.g. 0.1 for 10%.
+    #[must_use]
+    pub fn with_retry_penalty(mut self, penalty: f32) -> Self {
+        self.retry_penalty = penalty.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Apply the retry penalty to the score of an attempt (1-based).
+    ///
+    /// The penalty is clamped here too, as deserialized policies skip
+    /// [`with_retry_penalty`](Self::with_retry_penalty).
+    #[must_use]
+    pub fn penalized_score(&self, score: f32, attempt_number: u32) -> f32 {
+        let retries = attempt_number.saturating_sub(1) as f32;
+        let factor = (1.0 - self.retry_penalty.clamp(0.0, 1.0) * retries).max(0.0);
+        score * factor
+    }
+
+    /// Get the score that counts for a learner's attempts, oldest first.
+    ///
+    /// Returns `None` if there are no attempts.
+    #[must_use]
+    pub fn effective_score(&self, attempts: &[QuizAttempt]) -> Option<f32> {
+        let scores = attempts
+            .iter()
+            .zip(1u32..)
+            .map(|(attempt, number)| self.penalized_score(attempt.score, number));
+        match self.scoring {
+            AttemptScoring::Best => scores.reduce(f32::max),
+            AttemptScoring::Latest => scores.last(),
+            AttemptScoring::Average => {
+                let total: f32 = scores.sum();
+                (!attempts.is_empty()).then(|| total / attempts.len() as f32)
+            }
+        }
+    }
+
+    /// Get when the cooldown after an attempt that finished at `finished_at` ends.
+    #[must_use]
+    pub fn cooldown_until(&self, finished_at: Timestamp) -> Option<Timestamp> {
+        self.cooldown_secs.map(|secs| {
+            Timestamp::from_millis(
+                finished_at
+                    .as_millis()
+                    .saturating_add(u64::from(secs) * 1000),
+            )
+        })
+    }
+
+    /// Get when the next attempt may start, if a cooldown applies.
+    #[must_use]
+    pub fn next_attempt_at(&self, attempts: &[QuizAttempt]) -> Option<Timestamp> {
+        let last = attempts.last()?;
+        let finished_at = Timestamp::from_millis(
+            last.attempted_at
+                .as_millis()
+                .saturating_add(u64::from(last.duration_secs) * 1000),
+        );
+        self.cooldown_until(finished_at)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    fn attempts(scores: &[f32]) -> Vec<QuizAttempt> {
+        scores
+            .iter()
+            .zip(0u64..)
+            .map(|(&score, i)| QuizAttempt::new(score, 60, Timestamp::from_millis(i * 1000), false))
+            .collect()
+    }
+
+    #[test]
+    fn test_scoring_modes() {
+        let history = attempts(&[0.6, 0.9, 0.3]);
+        let policy = AttemptPolicy::new();
+        assert_eq!(policy.effective_score(&history), Some(0.9));
+
+        let latest = policy.with_scoring(AttemptScoring::Latest);
+        assert_eq!(latest.effective_score(&history), Some(0.3));
+
+        let average = policy.with_scoring(AttemptScoring::Average);
+        let score = average.effective_score(&history).unwrap_or_default();
+        assert!((score - 0.6).abs() < 1e-6);
+
+        for scoring in [
+            AttemptScoring::Best,
+            AttemptScoring::Latest,
+            AttemptScoring::Average,
+        ] {
+            assert!(policy.with_scoring(scoring).effective_score(&[]).is_none());
+        }
+    }
+
+    #[test]
+    fn test_retry_penalty() {
+        let policy = AttemptPolicy::new().with_retry_penalty(0.1);
+        assert!((policy.penalized_score(1.0, 1) - 1.0).abs() < 1e-6);
+        assert!((policy.penalized_score(1.0, 3) - 0.8).abs() < 1e-6);
+        assert_eq!(policy.penalized_score(1.0, 20), 0.0);
+
+        // A perfect retry is worth less than a good first attempt.
+        let history = attempts(&[0.85, 0.7, 1.0]);
+        let best = policy.effective_score(&history).unwrap_or_default();
+        assert!((best - 0.85).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_cooldown_from_end_of_last_attempt() {
+        let policy = AttemptPolicy::new().with_cooldown(3600);
+        assert!(policy.next_attempt_at(&[]).is_none());
+        assert!(AttemptPolicy::new()
+            .next_attempt_at(&attempts(&[0.5]))
+            .is_none());
+
+        let history = attempts(&[0.5, 0.4]);
+        assert_eq!(
+            policy.next_attempt_at(&history),
+            Some(Timestamp::from_millis(1000 + 60_000 + 3_600_000))
+        );
+        assert_eq!(
+            policy.cooldown_until(Timestamp::from_millis(u64::MAX - 1)),
+            Some(Timestamp::from_millis(u64::MAX))
+        );
+    }
+
+    #[test]
+    fn test_deserialized_penalty_is_clamped() {
+        let bonus: AttemptPolicy = serde_yaml::from_str("retry_penalty: -0.5\n").unwrap();
+        assert_eq!(bonus.penalized_score(0.8, 3), 0.8);
+        let harsh: AttemptPolicy = serde_yaml::from_str("retry_penalty: 2.0\n").unwrap();
+        assert_eq!(harsh.penalized_score(0.8, 2), 0.0);
+    }
+
+    #[test]
+    fn test_policy_defaults_when_missing() {
+        let policy: AttemptPolicy = serde_yaml::from_str("scoring: Latest\n").unwrap();
+        assert_eq!(policy.scoring, AttemptScoring::Latest);
+        assert_eq!(policy.cooldown_secs, None);
+        assert_eq!(policy.retry_penalty, 0.0);
+    }
+}
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_penalized_score_bounded(score in 0.0f32..=1.0, penalty in 0.0f32..=1.0, attempt in 1u32..50) {
+            let policy = AttemptPolicy::new().with_retry_penalty(penalty);
+            let penalized = policy.penalized_score(score, attempt);
+            prop_assert!((0.0..=score).contains(&penalized));
+        }
+    }
+}
@@ -0,0 +1,397 @@
+//! Question banks and randomized draws.
+//!
+//! A quiz can draw its questions from a bank of tagged items using rules such
+//! as "3 from ownership/easy, 2 from borrowing/hard". Draws are seeded so the
+//! same seed always reproduces the same selection for scoring and review.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+use crate::ids::QuestionId;
+use crate::quiz::Question;
+use crate::rng::SeededRng;
+use crate::template::TemplateError;
+
+/// Difficulty of a bank question.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+pub enum QuestionDifficulty {
+    /// Recall and recognition
+    Easy,
+    /// Application of a concept
+    #[default]
+    Medium,
+    /// Analysis or combination of concepts
+    Hard,
+}
+
+impl QuestionDifficulty {
+    /// Get a human-readable label.
+    #[must_use]
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Easy => "Easy",
+            Self::Medium => "Medium",
+            Self::Hard => "Hard",
+        }
+    }
+}
+
+/// A question in a bank, tagged with concepts and difficulty.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BankItem {
+    /// The question
+    pub question: Question,
+    /// Concept tags (e.g., "ownership")
+    pub tags: Vec<String>,
+    /// Difficulty level
+    pub difficulty: QuestionDifficulty,
+}
+
+impl BankItem {
+    /// Create a new bank item.
+    #[must_use]
+    pub fn new(question: Question, difficulty: QuestionDifficulty) -> Self {
+        Self {
+            question,
+            tags: Vec::new(),
+            difficulty,
+        }
+    }
+
+    /// Add a concept tag.
+    #[must_use]
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    /// Check if the item has a tag.
+    #[must_use]
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+}
+
+/// A pool of tagged questions.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct QuestionBank {
+    /// Items in the bank
+    pub items: Vec<BankItem>,
+}
+
+impl QuestionBank {
+    /// Create an empty bank.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an item.
+    #[must_use]
+    pub fn with_item(mut self, item: BankItem) -> Self {
+        self.items.push(item);
+        self
+    }
+
+    /// Get the number of items.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Check if the bank is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+/// A rule selecting questions from a bank.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DrawRule {
+    /// Number of questions to draw
+    pub count: usize,
+    /// Required tag (any tag if `None`)
+    pub tag: Option<String>,
+    /// Required difficulty (any difficulty if `None`)
+    pub difficulty: Option<QuestionDifficulty>,
+}
+
+impl DrawRule {
+    /// Draw `count` questions from anywhere in the bank.
+    #[must_use]
+    pub fn new(count: usize) -> Self {
+        Self {
+            count,
+            tag: None,
+            difficulty: None,
+        }
+    }
+
+    /// Require a tag.
+    #[must_use]
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.tag = Some(tag.into());
+        self
+    }
+
+    /// Require a difficulty.
+    #[must_use]
+    pub fn with_difficulty(mut self, difficulty: QuestionDifficulty) -> Self {
+        self.difficulty = Some(difficulty);
+        self
+    }
+
+    /// Check if a bank item satisfies this rule.
+    #[must_use]
+    pub fn matches(&self, item: &BankItem) -> bool {
+        self.tag.as_deref().map_or(true, |t| item.has_tag(t))
+            && self.difficulty.map_or(true, |d| item.difficulty == d)
+    }
+}
+
+/// Bank plus the rules a quiz uses to draw from it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct QuestionDraw {
+    /// Source bank
+    pub bank: QuestionBank,
+    /// Rules applied in order; an item is drawn at most once
+    pub rules: Vec<DrawRule>,
+}
+
+impl QuestionDraw {
+    /// Create a draw from a bank.
+    #[must_use]
+    pub fn new(bank: QuestionBank) -> Self {
+        Self {
+            bank,
+            rules: Vec::new(),
+        }
+    }
+
+    /// Add a rule.
+    #[must_use]
+    pub fn with_rule(mut self, rule: DrawRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Get the number of questions each draw produces.
+    #[must_use]
+    pub fn question_count(&self) -> usize {
+        self.rules.iter().map(|r| r.count).sum()
+    }
+
+    /// Draw questions with a seed.
+    ///
+    /// Returns an error if a rule has fewer matching items left than it asks for.
+    pub fn draw(&self, seed: u64) -> Result<Vec<Question>, DrawError> {
+        let mut rng = SeededRng::new(seed);
+        let mut taken = alloc::vec![false; self.bank.items.len()];
+        let mut questions = Vec::with_capacity(self.question_count());
+
+        for (rule_index, rule) in self.rules.iter().enumerate() {
+            let candidates: Vec<usize> = self
+                .bank
+                .items
+                .iter()
+                .enumerate()
+                .filter(|(i, item)| !taken[*i] && rule.matches(item))
+                .map(|(i, _)| i)
+                .collect();
+
+            if candidates.len() < rule.count {
+                return Err(DrawError::NotEnoughItems {
+                    rule_index,
+                    available: candidates.len(),
+                    requested: rule.count,
+                });
+            }
+
+            for pick in rng.sample_indices(candidates.len(), rule.count) {
+                let idx = candidates[pick];
+                taken[idx] = true;
+                questions.push(self.bank.items[idx].question.clone());
+            }
+        }
+
+        Ok(questions)
+    }
+}
+
+/// Errors from drawing questions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DrawError {
+    /// A rule matched fewer unused items than it requested
+    NotEnoughItems {
+        /// Index of the failing rule
+        rule_index: usize,
+        /// Matching items still available
+        available: usize,
+        /// Items requested by the rule
+        requested: usize,
+    },
+    /// A question template could not be instantiated
+    Template {
+        /// ID of the template question
+        question_id: QuestionId,
+        /// What went wrong
+        error: TemplateError,
+    },
+}
+
+impl core::fmt::Display for DrawError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::NotEnoughItems {
+                rule_index,
+                available,
+                requested,
+            } => write!(
+                f,
+                "Draw rule {} needs {} questions but only {} are available",
+                rule_index, requested, available
+            ),
+            Self::Template { question_id, error } => {
+                write!(
+                    f,
+                    "Question '{}' could not be generated: {}",
+                    question_id, error
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mc(id: &str) -> Question {
+        Question::MultipleChoice {
+            id: id.into(),
+            prompt: "Q".into(),
+            options: alloc::vec!["A".into(), "B".into()],
+            correct: 0,
+            explanation: "".into(),
+            points: 10,
+            option_explanations: Vec::new(),
+        }
+    }
+
+    fn bank() -> QuestionBank {
+        let mut bank = QuestionBank::new();
+        for i in 0..5 {
+            bank = bank.with_item(
+                BankItem::new(
+                    mc(&alloc::format!("own-easy-{}", i)),
+                    QuestionDifficulty::Easy,
+                )
+                .with_tag("ownership"),
+            );
+        }
+        for i in 0..3 {
+            bank = bank.with_item(
+                BankItem::new(
+                    mc(&alloc::format!("borrow-hard-{}", i)),
+                    QuestionDifficulty::Hard,
+                )
+                .with_tag("borrowing"),
+            );
+        }
+        bank
+    }
+
+    fn draw() -> QuestionDraw {
+        QuestionDraw::new(bank())
+            .with_rule(
+                DrawRule::new(3)
+                    .with_tag("ownership")
+                    .with_difficulty(QuestionDifficulty::Easy),
+            )
+            .with_rule(
+                DrawRule::new(2)
+                    .with_tag("borrowing")
+                    .with_difficulty(QuestionDifficulty::Hard),
+            )
+    }
+
+    #[test]
+    fn test_draw_follows_rules() {
+        let questions = draw().draw(42).unwrap();
+        assert_eq!(questions.len(), 5);
+        assert!(questions[..3]
+            .iter()
+            .all(|q| q.id().as_str().starts_with("own-easy")));
+        assert!(questions[3..]
+            .iter()
+            .all(|q| q.id().as_str().starts_with("borrow-hard")));
+    }
+
+    #[test]
+    fn test_draw_is_reproducible() {
+        let draw = draw();
+        assert_eq!(draw.draw(7), draw.draw(7));
+    }
+
+    #[test]
+    fn test_draw_varies_with_seed() {
+        let draw = draw();
+        let selections: Vec<Vec<Question>> = (0..10).map(|s| draw.draw(s).unwrap()).collect();
+        assert!(selections.iter().any(|s| s != &selections[0]));
+    }
+
+    #[test]
+    fn test_draw_never_repeats() {
+        let draw = QuestionDraw::new(bank())
+            .with_rule(DrawRule::new(4).with_tag("ownership"))
+            .with_rule(DrawRule::new(1).with_tag("ownership"));
+        let mut ids: Vec<String> = draw
+            .draw(3)
+            .unwrap()
+            .iter()
+            .map(|q| q.id().as_str().into())
+            .collect();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), 5);
+    }
+
+    #[test]
+    fn test_draw_not_enough_items() {
+        let draw = QuestionDraw::new(bank()).with_rule(DrawRule::new(4).with_tag("borrowing"));
+        assert_eq!(
+            draw.draw(0),
+            Err(DrawError::NotEnoughItems {
+                rule_index: 0,
+                available: 3,
+                requested: 4,
+            })
+        );
+    }
+
+    #[test]
+    fn test_rule_matching() {
+        let item = BankItem::new(mc("q"), QuestionDifficulty::Easy).with_tag("ownership");
+        assert!(DrawRule::new(1).matches(&item));
+        assert!(DrawRule::new(1).with_tag("ownership").matches(&item));
+        assert!(!DrawRule::new(1).with_tag("traits").matches(&item));
+        assert!(!DrawRule::new(1)
+            .with_difficulty(QuestionDifficulty::Hard)
+            .matches(&item));
+    }
+
+    #[test]
+    fn test_question_count() {
+        assert_eq!(draw().question_count(), 5);
+        assert_eq!(bank().len(), 8);
+        assert!(QuestionBank::new().is_empty());
+    }
+
+    #[test]
+    fn test_difficulty_label() {
+        assert_eq!(QuestionDifficulty::Easy.label(), "Easy");
+        assert_eq!(QuestionDifficulty::default(), QuestionDifficulty::Medium);
+    }
+}
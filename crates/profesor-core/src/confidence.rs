@@ -0,0 +1,186 @@
+//! Confidence-based marking.
+//!
+//! Learners may say how sure they are of an answer. A payoff matrix turns
+//! each (confidence, correctness) pair into a signed mark, rewarding honest
+//! confidence and penalising confident mistakes. The default matrix is the
+//! Gardner-Medwin scheme: low 1/0, medium 2/-2, high 3/-6.
+
+use serde::{Deserialize, Serialize};
+
+/// How sure a learner is of an answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Confidence {
+    /// A guess
+    Low,
+    /// Fairly sure
+    Medium,
+    /// Certain
+    High,
+}
+
+/// Marks for one confidence level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Payoff {
+    /// Mark for a correct answer
+    pub correct: i32,
+    /// Mark for an incorrect answer
+    pub incorrect: i32,
+}
+
+impl Payoff {
+    /// Create a payoff.
+    #[must_use]
+    pub const fn new(correct: i32, incorrect: i32) -> Self {
+        Self { correct, incorrect }
+    }
+}
+
+/// Marks for each confidence level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PayoffMatrix {
+    /// Payoff for low confidence
+    pub low: Payoff,
+    /// Payoff for medium confidence
+    pub medium: Payoff,
+    /// Payoff for high confidence
+    pub high: Payoff,
+}
+
+impl PayoffMatrix {
+    /// Create a matrix.
+    #[must_use]
+    pub const fn new(low: Payoff, medium: Payoff, high: Payoff) -> Self {
+        Self { low, medium, high }
+    }
+
+    /// Get the payoff for a confidence level.
+    #[must_use]
+    pub fn payoff(&self, confidence: Confidence) -> Payoff {
+        match confidence {
+            Confidence::Low => self.low,
+            Confidence::Medium => self.medium,
+            Confidence::High => self.high,
+        }
+    }
+
+    /// Get the mark for an answer.
+    #[must_use]
+    pub fn mark(&self, confidence: Confidence, correct: bool) -> i32 {
+        let payoff = self.payoff(confidence);
+        if correct {
+            payoff.correct
+        } else {
+            payoff.incorrect
+        }
+    }
+}
+
+impl Default for PayoffMatrix {
+    fn default() -> Self {
+        Self::new(Payoff::new(1, 0), Payoff::new(2, -2), Payoff::new(3, -6))
+    }
+}
+
+/// Answers given at one confidence level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ConfidenceTally {
+    /// Answers given
+    pub answered: usize,
+    /// Answers that were correct
+    pub correct: usize,
+}
+
+impl ConfidenceTally {
+    /// Get the share of answers that were correct, if any were given.
+    #[must_use]
+    pub fn accuracy(&self) -> Option<f32> {
+        (self.answered > 0).then(|| self.correct as f32 / self.answered as f32)
+    }
+}
+
+/// How well a learner's confidence matched their results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Calibration {
+    /// Sum of confidence marks
+    pub marks: i32,
+    /// Low-confidence answers
+    pub low: ConfidenceTally,
+    /// Medium-confidence answers
+    pub medium: ConfidenceTally,
+    /// High-confidence answers
+    pub high: ConfidenceTally,
+}
+
+impl Calibration {
+    /// Create an empty calibration.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a marked answer.
+    pub fn record(&mut self, confidence: Confidence, correct: bool, mark: i32) {
+        let tally = match confidence {
+            Confidence::Low => &mut self.low,
+            Confidence::Medium => &mut self.medium,
+            Confidence::High => &mut self.high,
+        };
+        tally.answered += 1;
+        if correct {
+            tally.correct += 1;
+        }
+        self.marks += mark;
+    }
+
+    /// Get the number of answers with a confidence level.
+    #[must_use]
+    pub fn answered(&self) -> usize {
+        self.low.answered + self.medium.answered + self.high.answered
+    }
+
+    /// Share of high-confidence answers that were wrong (0.0 - 1.0).
+    #[must_use]
+    pub fn over_confidence(&self) -> f32 {
+        self.high.accuracy().map_or(0.0, |accuracy| 1.0 - accuracy)
+    }
+
+    /// Share of low-confidence answers that were right (0.0 - 1.0).
+    #[must_use]
+    pub fn under_confidence(&self) -> f32 {
+        self.low.accuracy().unwrap_or(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_matrix() {
+        let matrix = PayoffMatrix::default();
+        assert_eq!(matrix.mark(Confidence::High, true), 3);
+        assert_eq!(matrix.mark(Confidence::High, false), -6);
+        assert_eq!(matrix.mark(Confidence::Medium, false), -2);
+        assert_eq!(matrix.mark(Confidence::Low, false), 0);
+        assert_eq!(matrix.payoff(Confidence::Low), Payoff::new(1, 0));
+    }
+
+    #[test]
+    fn test_calibration() {
+        let mut calibration = Calibration::new();
+        assert_eq!(calibration.over_confidence(), 0.0);
+        assert_eq!(calibration.under_confidence(), 0.0);
+
+        calibration.record(Confidence::High, true, 3);
+        calibration.record(Confidence::High, false, -6);
+        calibration.record(Confidence::High, false, -6);
+        calibration.record(Confidence::Low, true, 1);
+        calibration.record(Confidence::Medium, true, 2);
+
+        assert_eq!(calibration.marks, -6);
+        assert_eq!(calibration.answered(), 5);
+        assert!((calibration.over_confidence() - 2.0 / 3.0).abs() < 1e-6);
+        assert_eq!(calibration.under_confidence(), 1.0);
+        assert_eq!(calibration.medium.accuracy(), Some(1.0));
+    }
+}
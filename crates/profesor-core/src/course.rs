@@ -6,8 +6,9 @@ use alloc::string::String;
 use alloc::vec::Vec;
 use serde::{Deserialize, Serialize};
 
-use crate::ids::{CourseId, LessonId, ModuleId, SimulationId};
+use crate::ids::{CourseId, LessonId, ModuleId, QuizId, SimulationId};
 use crate::lab::Lab;
+use crate::progress::CourseProgress;
 use crate::quiz::Quiz;
 
 /// A complete course (e.g., "Rust Fundamentals").
@@ -86,6 +87,15 @@ impl Course {
     pub fn total_quizzes(&self) -> usize {
         self.modules.iter().filter(|m| m.quiz.is_some()).count()
     }
+
+    /// Find a module's quiz by ID.
+    #[must_use]
+    pub fn quiz(&self, quiz_id: &QuizId) -> Option<&Quiz> {
+        self.modules
+            .iter()
+            .filter_map(|m| m.quiz.as_ref())
+            .find(|quiz| &quiz.id == quiz_id)
+    }
 }
 
 /// Course difficulty level.
@@ -201,6 +211,29 @@ pub enum UnlockCriteria {
     },
 }
 
+impl UnlockCriteria {
+    /// Check whether a learner's progress meets the criteria.
+    ///
+    /// Quiz scores are evaluated with the quiz's attempt policy, or the
+    /// default best-score policy if the quiz is not part of `course`.
+    #[must_use]
+    pub fn is_met(&self, course: &Course, progress: &CourseProgress) -> bool {
+        match self {
+            Self::None => true,
+            Self::ModuleCompleted(module_id) => progress.modules_completed.contains(module_id),
+            Self::QuizScore { quiz_id, min_score } => {
+                let policy = course
+                    .quiz(quiz_id)
+                    .map(|quiz| quiz.attempt_policy)
+                    .unwrap_or_default();
+                progress
+                    .effective_quiz_score(quiz_id, &policy)
+                    .is_some_and(|score| score >= *min_score)
+            }
+        }
+    }
+}
+
 /// A single lesson within a module.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Lesson {
@@ -381,6 +414,50 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_unlock_criteria_is_met() {
+        use crate::attempts::{AttemptPolicy, AttemptScoring};
+        use crate::progress::{QuizAttempt, Timestamp};
+
+        let quiz = Quiz::new("q1", "Quiz")
+            .with_attempt_policy(AttemptPolicy::new().with_scoring(AttemptScoring::Latest));
+        let course =
+            Course::new("c", "Course").with_module(Module::new("m1", "M1").with_quiz(quiz));
+        let mut progress = CourseProgress::new(CourseId::new("c"), Timestamp::ZERO);
+        let criteria = |quiz_id: &str| UnlockCriteria::QuizScore {
+            quiz_id: QuizId::new(quiz_id),
+            min_score: 0.8,
+        };
+
+        assert!(UnlockCriteria::None.is_met(&course, &progress));
+        assert!(!criteria("q1").is_met(&course, &progress));
+
+        progress.record_quiz_attempt(
+            &QuizId::new("q1"),
+            QuizAttempt::new(0.9, 60, Timestamp::ZERO, true),
+        );
+        assert!(criteria("q1").is_met(&course, &progress));
+
+        // The latest attempt counts, not the best.
+        progress.record_quiz_attempt(
+            &QuizId::new("q1"),
+            QuizAttempt::new(0.6, 60, Timestamp::from_millis(1000), false),
+        );
+        assert!(!criteria("q1").is_met(&course, &progress));
+
+        // Unknown quizzes fall back to the best score.
+        progress.record_quiz_attempt(
+            &QuizId::new("other"),
+            QuizAttempt::new(0.9, 60, Timestamp::ZERO, true),
+        );
+        assert!(criteria("other").is_met(&course, &progress));
+
+        let after_m1 = UnlockCriteria::ModuleCompleted(ModuleId::new("m1"));
+        assert!(!after_m1.is_met(&course, &progress));
+        progress.complete_module(ModuleId::new("m1"));
+        assert!(after_m1.is_met(&course, &progress));
+    }
+
     #[test]
     fn test_lesson_with_content() {
         let lesson = Lesson::new("l1", "Lesson 1")
@@ -0,0 +1,144 @@
+//! Item response theory types for adaptive quizzes.
+//!
+//! Uses the three-parameter logistic (3PL) model: each question has a
+//! difficulty, a discrimination and a guessing floor, and the learner's
+//! ability is estimated on the same scale as difficulty.
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use serde::{Deserialize, Serialize};
+
+use crate::ids::QuestionId;
+
+/// 3PL parameters for one question.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct ItemParameters {
+    /// How sharply the question separates learners (a)
+    pub discrimination: f64,
+    /// Ability at which the learner is halfway above the guessing floor (b)
+    pub difficulty: f64,
+    /// Probability of answering correctly by guessing (c)
+    pub guessing: f64,
+}
+
+impl ItemParameters {
+    /// Create parameters, clamping guessing to `0.0..=0.99`.
+    #[must_use]
+    pub fn new(discrimination: f64, difficulty: f64, guessing: f64) -> Self {
+        Self {
+            discrimination,
+            difficulty,
+            guessing: guessing.clamp(0.0, 0.99),
+        }
+    }
+}
+
+impl Default for ItemParameters {
+    fn default() -> Self {
+        Self::new(1.0, 0.0, 0.0)
+    }
+}
+
+/// Configuration for computerized adaptive testing.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AdaptiveConfig {
+    /// Stop once the standard error of the ability estimate falls below this
+    pub se_threshold: f64,
+    /// Minimum number of questions before stopping on standard error
+    pub min_length: usize,
+    /// Maximum number of questions
+    pub max_length: usize,
+    /// Item parameters by question ID; unlisted questions use the defaults
+    pub items: BTreeMap<String, ItemParameters>,
+}
+
+impl AdaptiveConfig {
+    /// Create a configuration with a stopping threshold and maximum length.
+    #[must_use]
+    pub fn new(se_threshold: f64, max_length: usize) -> Self {
+        Self {
+            se_threshold,
+            min_length: 1,
+            max_length,
+            items: BTreeMap::new(),
+        }
+    }
+
+    /// Set the minimum number of questions.
+    #[must_use]
+    pub fn with_min_length(mut self, min_length: usize) -> Self {
+        self.min_length = min_length;
+        self
+    }
+
+    /// Set the parameters for a question.
+    #[must_use]
+    pub fn with_item(mut self, question_id: impl Into<QuestionId>, params: ItemParameters) -> Self {
+        self.items
+            .insert(question_id.into().as_str().into(), params);
+        self
+    }
+
+    /// Get the parameters for a question.
+    #[must_use]
+    pub fn parameters(&self, question_id: &QuestionId) -> ItemParameters {
+        self.items
+            .get(question_id.as_str())
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// An estimate of learner ability.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct AbilityEstimate {
+    /// Estimated ability (theta)
+    pub theta: f64,
+    /// Standard error of the estimate
+    pub standard_error: f64,
+}
+
+impl AbilityEstimate {
+    /// z-value for a 95% confidence interval.
+    pub const Z_95: f64 = 1.96;
+
+    /// Get the 95% confidence interval as `(low, high)`.
+    #[must_use]
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        let margin = Self::Z_95 * self.standard_error;
+        (self.theta - margin, self.theta + margin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guessing_clamped() {
+        assert_eq!(ItemParameters::new(1.0, 0.0, 1.5).guessing, 0.99);
+        assert_eq!(ItemParameters::new(1.0, 0.0, -0.2).guessing, 0.0);
+    }
+
+    #[test]
+    fn test_config_parameters() {
+        let config =
+            AdaptiveConfig::new(0.3, 20).with_item("q1", ItemParameters::new(1.5, 0.5, 0.2));
+        assert_eq!(config.parameters(&QuestionId::new("q1")).difficulty, 0.5);
+        assert_eq!(
+            config.parameters(&QuestionId::new("q2")),
+            ItemParameters::default()
+        );
+    }
+
+    #[test]
+    fn test_confidence_interval() {
+        let estimate = AbilityEstimate {
+            theta: 1.0,
+            standard_error: 0.5,
+        };
+        let (low, high) = estimate.confidence_interval();
+        assert!((low - 0.02).abs() < 1e-9);
+        assert!((high - 1.98).abs() < 1e-9);
+    }
+}
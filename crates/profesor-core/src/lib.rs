@@ -16,14 +16,33 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std as alloc;
 
+pub mod attempts;
+pub mod bank;
+pub mod confidence;
 pub mod course;
 pub mod ids;
+pub mod irt;
 pub mod lab;
 pub mod progress;
 pub mod quiz;
+pub mod review;
+pub mod rng;
+pub mod template;
 
+pub use attempts::{AttemptPolicy, AttemptScoring};
+pub use bank::{BankItem, DrawError, DrawRule, QuestionBank, QuestionDifficulty, QuestionDraw};
+pub use confidence::{Calibration, Confidence, ConfidenceTally, Payoff, PayoffMatrix};
 pub use course::{Course, CourseLevel, Lesson, LessonContent, Module, UnlockCriteria};
 pub use ids::{CourseId, LabId, LessonId, ModuleId, QuestionId, QuizId, SimulationId};
+pub use irt::{AbilityEstimate, AdaptiveConfig, ItemParameters};
 pub use lab::{Difficulty, Hint, Lab, LabStep, Language, StarterFile, StepValidation, TestSuite};
-pub use progress::{CourseProgress, CourseStatus, LabCompletion, LearnerProgress, QuizAttempt};
-pub use quiz::{Answer, Blank, Feedback, Question, Quiz, Score, TestCase};
+pub use progress::{
+    CourseProgress, CourseStatus, LabCompletion, LearnerProgress, QuestionOutcome, QuizAttempt,
+};
+pub use quiz::{
+    Answer, Blank, CodeFragment, Feedback, HotspotClick, HotspotRegion, HotspotTarget, Question,
+    QuestionHint, Quiz, Score, TestCase, TextAnswer, TextMatcher, Tolerance,
+};
+pub use review::{RecallQuality, ReviewCard, ReviewSchedule};
+pub use rng::SeededRng;
+pub use template::{QuestionTemplate, TemplateAnswer, TemplateError, Variable};
@@ -6,7 +6,10 @@ use alloc::collections::BTreeMap;
 use alloc::vec::Vec;
 use serde::{Deserialize, Serialize};
 
-use crate::ids::{CourseId, LabId, ModuleId, QuizId};
+use crate::attempts::AttemptPolicy;
+use crate::ids::{CourseId, LabId, ModuleId, QuestionId, QuizId};
+use crate::quiz::{Answer, Feedback};
+use crate::review::ReviewSchedule;
 
 /// A unique identifier for a learner.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -60,6 +63,9 @@ pub struct LearnerProgress {
     pub streak_days: u32,
     /// Timestamp of last activity
     pub last_activity: Timestamp,
+    /// Spaced-repetition schedule of answered questions
+    #[serde(default)]
+    pub review: ReviewSchedule,
 }
 
 impl LearnerProgress {
@@ -72,6 +78,7 @@ impl LearnerProgress {
             total_xp: 0,
             streak_days: 0,
             last_activity: Timestamp::ZERO,
+            review: ReviewSchedule::new(),
         }
     }
 
@@ -196,6 +203,20 @@ impl CourseProgress {
         })
     }
 
+    /// Get the recorded attempts for a quiz, oldest first.
+    #[must_use]
+    pub fn quiz_attempts(&self, quiz_id: &QuizId) -> &[QuizAttempt] {
+        self.quiz_scores
+            .get(quiz_id.as_str())
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Get the score that counts for a quiz under an attempt policy.
+    #[must_use]
+    pub fn effective_quiz_score(&self, quiz_id: &QuizId, policy: &AttemptPolicy) -> Option<f32> {
+        policy.effective_score(self.quiz_attempts(quiz_id))
+    }
+
     /// Mark the course as completed.
     pub fn complete(&mut self, now: Timestamp) {
         self.status = CourseStatus::Completed;
@@ -251,6 +272,15 @@ pub struct QuizAttempt {
     pub attempted_at: Timestamp,
     /// Whether the attempt passed
     pub passed: bool,
+    /// Seed used to draw and order the attempt's questions
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Questions presented in this attempt, in order
+    #[serde(default)]
+    pub question_ids: Vec<QuestionId>,
+    /// Per-question results
+    #[serde(default)]
+    pub outcomes: Vec<QuestionOutcome>,
 }
 
 impl QuizAttempt {
@@ -262,8 +292,66 @@ impl QuizAttempt {
             duration_secs,
             attempted_at,
             passed,
+            seed: None,
+            question_ids: Vec::new(),
+            outcomes: Vec::new(),
         }
     }
+
+    /// Record the seed and questions of a drawn attempt.
+    #[must_use]
+    pub fn with_draw(mut self, seed: u64, question_ids: Vec<QuestionId>) -> Self {
+        self.seed = Some(seed);
+        self.question_ids = question_ids;
+        self
+    }
+
+    /// Record per-question results.
+    #[must_use]
+    pub fn with_outcomes(mut self, outcomes: Vec<QuestionOutcome>) -> Self {
+        self.outcomes = outcomes;
+        self
+    }
+}
+
+/// The result of one question within a quiz attempt.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuestionOutcome {
+    /// Question answered
+    pub question_id: QuestionId,
+    /// Whether the answer was correct
+    pub correct: bool,
+    /// Points earned
+    pub points_earned: u32,
+    /// The submitted answer, if any
+    #[serde(default)]
+    pub answer: Option<Answer>,
+}
+
+impl QuestionOutcome {
+    /// Create an outcome.
+    #[must_use]
+    pub fn new(question_id: impl Into<QuestionId>, correct: bool, points_earned: u32) -> Self {
+        Self {
+            question_id: question_id.into(),
+            correct,
+            points_earned,
+            answer: None,
+        }
+    }
+
+    /// Create an outcome from graded feedback.
+    #[must_use]
+    pub fn from_feedback(question_id: impl Into<QuestionId>, feedback: &Feedback) -> Self {
+        Self::new(question_id, feedback.correct, feedback.points_earned)
+    }
+
+    /// Record the submitted answer.
+    #[must_use]
+    pub fn with_answer(mut self, answer: Answer) -> Self {
+        self.answer = Some(answer);
+        self
+    }
 }
 
 /// A lab completion record.
@@ -414,6 +502,50 @@ mod tests {
         assert_eq!(cp.best_quiz_score(&quiz_id), Some(0.8));
     }
 
+    #[test]
+    fn test_quiz_attempt_with_draw() {
+        let attempt = QuizAttempt::new(0.5, 60, Timestamp::from_millis(0), false).with_draw(
+            42,
+            alloc::vec![QuestionId::new("q3"), QuestionId::new("q1")],
+        );
+        assert_eq!(attempt.seed, Some(42));
+        assert_eq!(attempt.question_ids.len(), 2);
+    }
+
+    #[test]
+    fn test_quiz_attempt_without_draw_deserializes() {
+        let yaml = "score: 0.9\nduration_secs: 120\nattempted_at: 1000\npassed: true\n";
+        let attempt: QuizAttempt = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(attempt.seed, None);
+        assert!(attempt.question_ids.is_empty());
+    }
+
+    #[test]
+    fn test_effective_quiz_score_uses_policy() {
+        use crate::attempts::AttemptScoring;
+
+        let mut cp = CourseProgress::new(CourseId::new("test"), Timestamp::from_millis(0));
+        let quiz_id = QuizId::new("quiz-1");
+        assert!(cp.quiz_attempts(&quiz_id).is_empty());
+
+        cp.record_quiz_attempt(
+            &quiz_id,
+            QuizAttempt::new(0.9, 300, Timestamp::from_millis(1000), true),
+        );
+        cp.record_quiz_attempt(
+            &quiz_id,
+            QuizAttempt::new(0.5, 250, Timestamp::from_millis(2000), false),
+        );
+
+        let latest = AttemptPolicy::new().with_scoring(AttemptScoring::Latest);
+        assert_eq!(cp.quiz_attempts(&quiz_id).len(), 2);
+        assert_eq!(cp.effective_quiz_score(&quiz_id, &latest), Some(0.5));
+        assert_eq!(
+            cp.effective_quiz_score(&quiz_id, &AttemptPolicy::new()),
+            cp.best_quiz_score(&quiz_id)
+        );
+    }
+
     #[test]
     fn test_best_quiz_score_none() {
         let cp = CourseProgress::new(CourseId::new("test"), Timestamp::from_millis(0));
@@ -6,8 +6,15 @@ use alloc::string::String;
 use alloc::vec::Vec;
 use serde::{Deserialize, Serialize};
 
-use crate::ids::{QuestionId, QuizId};
+use crate::attempts::AttemptPolicy;
+use crate::bank::{DrawError, QuestionDraw};
+use crate::confidence::{Calibration, Confidence, PayoffMatrix};
+use crate::ids::{QuestionId, QuizId, SimulationId};
+use crate::irt::{AbilityEstimate, AdaptiveConfig};
 use crate::lab::Language;
+use crate::progress::{QuizAttempt, Timestamp};
+use crate::rng::SeededRng;
+use crate::template::{QuestionTemplate, TemplateError};
 
 /// A quiz with multiple questions.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -26,6 +33,21 @@ pub struct Quiz {
     pub shuffle: bool,
     /// Maximum number of attempts allowed
     pub max_attempts: Option<u32>,
+    /// Questions drawn from a bank on each attempt, after the fixed ones
+    #[serde(default)]
+    pub draw: Option<QuestionDraw>,
+    /// Adaptive testing configuration; questions become the item pool
+    #[serde(default)]
+    pub adaptive: Option<AdaptiveConfig>,
+    /// How repeated attempts are scored and paced
+    #[serde(default)]
+    pub attempt_policy: AttemptPolicy,
+    /// Marks for answers given with a confidence level
+    #[serde(default)]
+    pub confidence_payoff: PayoffMatrix,
+    /// Hide correct answers in review while attempts remain
+    #[serde(default)]
+    pub conceal_answers: bool,
 }
 
 impl Quiz {
@@ -40,6 +62,11 @@ impl Quiz {
             passing_score: 0.7,
             shuffle: false,
             max_attempts: None,
+            draw: None,
+            adaptive: None,
+            attempt_policy: AttemptPolicy::default(),
+            confidence_payoff: PayoffMatrix::default(),
+            conceal_answers: false,
         }
     }
 
@@ -78,16 +105,92 @@ impl Quiz {
         self
     }
 
-    /// Get the total possible points for the quiz.
+    /// Set how repeated attempts are scored and paced.
+    #[must_use]
+    pub fn with_attempt_policy(mut self, policy: AttemptPolicy) -> Self {
+        self.attempt_policy = policy;
+        self
+    }
+
+    /// Set the marks for answers given with a confidence level.
+    #[must_use]
+    pub fn with_confidence_payoff(mut self, payoff: PayoffMatrix) -> Self {
+        self.confidence_payoff = payoff;
+        self
+    }
+
+    /// Hide correct answers in review while attempts remain.
+    #[must_use]
+    pub fn with_concealed_answers(mut self, conceal: bool) -> Self {
+        self.conceal_answers = conceal;
+        self
+    }
+
+    /// Draw additional questions from a bank on each attempt.
+    #[must_use]
+    pub fn with_draw(mut self, draw: QuestionDraw) -> Self {
+        self.draw = Some(draw);
+        self
+    }
+
+    /// Run the quiz as an adaptive test.
+    #[must_use]
+    pub fn with_adaptive(mut self, config: AdaptiveConfig) -> Self {
+        self.adaptive = Some(config);
+        self
+    }
+
+    /// Check whether a learner with these previous attempts may start
+    /// another at `now`, given the attempt limit and cooldown.
+    #[must_use]
+    pub fn can_attempt(&self, attempts: &[QuizAttempt], now: Timestamp) -> bool {
+        let under_limit = self
+            .max_attempts
+            .map_or(true, |max| attempts.len() < max as usize);
+        let cooled_down = self
+            .attempt_policy
+            .next_attempt_at(attempts)
+            .map_or(true, |at| now >= at);
+        under_limit && cooled_down
+    }
+
+    /// Get the total possible points for the quiz's fixed questions.
+    ///
+    /// Drawn questions vary per attempt; score them against
+    /// [`Quiz::materialize`] instead.
     #[must_use]
     pub fn total_points(&self) -> u32 {
         self.questions.iter().map(|q| q.points()).sum()
     }
 
-    /// Get the number of questions.
+    /// Get the number of questions in each attempt.
     #[must_use]
     pub fn question_count(&self) -> usize {
-        self.questions.len()
+        self.questions.len() + self.draw.as_ref().map_or(0, QuestionDraw::question_count)
+    }
+
+    /// Build the questions for one attempt.
+    ///
+    /// Fixed questions come first, followed by any bank draw; the whole set is
+    /// shuffled if enabled and templates are instantiated. The same seed
+    /// always yields the same questions.
+    pub fn materialize(&self, seed: u64) -> Result<Vec<Question>, DrawError> {
+        let mut questions = self.questions.clone();
+        if let Some(draw) = &self.draw {
+            questions.extend(draw.draw(seed)?);
+        }
+        for question in &mut questions {
+            *question = question
+                .instantiate(seed)
+                .map_err(|error| DrawError::Template {
+                    question_id: question.id().clone(),
+                    error,
+                })?;
+        }
+        if self.shuffle {
+            SeededRng::derive(seed, "shuffle").shuffle(&mut questions);
+        }
+        Ok(questions)
     }
 }
 
@@ -108,6 +211,9 @@ pub enum Question {
         explanation: String,
         /// Points for correct answer
         points: u32,
+        /// Why each option is right or wrong, parallel to `options`
+        #[serde(default)]
+        option_explanations: Vec<String>,
     },
     /// Multiple select (multiple correct answers)
     MultipleSelect {
@@ -123,6 +229,9 @@ pub enum Question {
         explanation: String,
         /// Points for correct answer
         points: u32,
+        /// Why each option is right or wrong, parallel to `options`
+        #[serde(default)]
+        option_explanations: Vec<String>,
     },
     /// Code completion (fill in the blank)
     CodeCompletion {
@@ -138,6 +247,9 @@ pub enum Question {
         test_cases: Vec<TestCase>,
         /// Points for correct answer
         points: u32,
+        /// Explanation shown after answering
+        #[serde(default)]
+        explanation: String,
     },
     /// Drag and drop ordering
     Ordering {
@@ -168,6 +280,9 @@ pub enum Question {
         correct_pairs: Vec<(usize, usize)>,
         /// Points for correct answer
         points: u32,
+        /// Explanation shown after answering
+        #[serde(default)]
+        explanation: String,
     },
     /// Free-form code (graded by test cases)
     FreeformCode {
@@ -186,9 +301,178 @@ pub enum Question {
         /// Points for correct answer
         points: u32,
     },
+    /// Numeric answer with tolerance and optional units
+    Numeric {
+        /// Unique ID
+        id: QuestionId,
+        /// The question prompt
+        prompt: String,
+        /// Correct value, in `unit` if one is given
+        value: f64,
+        /// Accepted deviation from the correct value
+        tolerance: Tolerance,
+        /// Required number of significant figures
+        sig_figs: Option<u32>,
+        /// Expected unit (e.g., "m/s"); answers in convertible units are accepted
+        unit: Option<String>,
+        /// Explanation shown after answering
+        explanation: String,
+        /// Points for correct answer
+        points: u32,
+    },
+    /// Short free-text answer
+    ShortAnswer {
+        /// Unique ID
+        id: QuestionId,
+        /// The question prompt
+        prompt: String,
+        /// Accepted and known-wrong answers, checked in order
+        answers: Vec<TextAnswer>,
+        /// Explanation shown after answering
+        explanation: String,
+        /// Points for correct answer
+        points: u32,
+    },
+    /// Parsons problem (arrange code fragments)
+    Parsons {
+        /// Unique ID
+        id: QuestionId,
+        /// The question prompt
+        prompt: String,
+        /// Programming language of the assembled program
+        language: Language,
+        /// Code fragments in authored order, including distractors
+        fragments: Vec<CodeFragment>,
+        /// Accepted orderings (fragment indices); empty means authored order
+        valid_orderings: Vec<Vec<usize>>,
+        /// Tests run against the assembled program; empty means line-based grading
+        test_cases: Vec<TestCase>,
+        /// Explanation shown after answering
+        explanation: String,
+        /// Points for correct answer
+        points: u32,
+    },
+    /// Predict what a code snippet prints (answered with `Answer::Text`)
+    PredictOutput {
+        /// Unique ID
+        id: QuestionId,
+        /// The question prompt
+        prompt: String,
+        /// Programming language of the snippet
+        language: Language,
+        /// Snippet whose output is predicted; the expected answer comes from running it
+        code: String,
+        /// Standard input given to the snippet
+        input: String,
+        /// Explanation shown after answering
+        explanation: String,
+        /// Points for correct answer
+        points: u32,
+    },
+    /// Sort items into buckets (many-to-one)
+    Categorize {
+        /// Unique ID
+        id: QuestionId,
+        /// The question prompt
+        prompt: String,
+        /// Bucket labels
+        buckets: Vec<String>,
+        /// Items to sort
+        items: Vec<String>,
+        /// Correct bucket index for each item
+        correct: Vec<usize>,
+        /// Explanation shown after answering
+        explanation: String,
+        /// Points for correct answer
+        points: u32,
+    },
+    /// Click the right places in code, an image or a simulation render
+    Hotspot {
+        /// Unique ID
+        id: QuestionId,
+        /// The question prompt
+        prompt: String,
+        /// What the learner clicks on
+        target: HotspotTarget,
+        /// Regions the learner must find
+        regions: Vec<HotspotRegion>,
+        /// Explanation shown after answering
+        explanation: String,
+        /// Points for correct answer
+        points: u32,
+    },
+    /// Template instantiated with random parameters for each attempt
+    Template(QuestionTemplate),
+    /// A question with hints the learner can reveal at a cost
+    Hinted {
+        /// The question itself
+        question: alloc::boxed::Box<Question>,
+        /// Hints, revealed in order
+        hints: Vec<QuestionHint>,
+    },
 }
 
 impl Question {
+    /// Resolve a template into the concrete question for a seed.
+    ///
+    /// Other questions are returned unchanged.
+    pub fn instantiate(&self, seed: u64) -> Result<Self, TemplateError> {
+        match self {
+            Self::Template(template) => template.instantiate(seed),
+            Self::Hinted { question, hints } => Ok(Self::Hinted {
+                question: alloc::boxed::Box::new(question.instantiate(seed)?),
+                hints: hints.clone(),
+            }),
+            other => Ok(other.clone()),
+        }
+    }
+
+    /// Add a hint, revealed after any existing ones.
+    #[must_use]
+    pub fn with_hint(self, hint: QuestionHint) -> Self {
+        match self {
+            Self::Hinted {
+                question,
+                mut hints,
+            } => {
+                hints.push(hint);
+                Self::Hinted { question, hints }
+            }
+            question => Self::Hinted {
+                question: alloc::boxed::Box::new(question),
+                hints: alloc::vec![hint],
+            },
+        }
+    }
+
+    /// Get the question's hints, in reveal order.
+    #[must_use]
+    pub fn hints(&self) -> &[QuestionHint] {
+        match self {
+            Self::Hinted { hints, .. } => hints,
+            _ => &[],
+        }
+    }
+
+    /// Get the question without its hints.
+    #[must_use]
+    pub fn unhinted(&self) -> &Self {
+        match self {
+            Self::Hinted { question, .. } => question.unhinted(),
+            _ => self,
+        }
+    }
+
+    /// Get the points deducted after revealing the first `revealed` hints.
+    ///
+    /// Never exceeds the question's points.
+    #[must_use]
+    pub fn hint_deduction(&self, revealed: usize) -> u32 {
+        let share: f32 = self.hints().iter().take(revealed).map(|h| h.cost).sum();
+        let points = self.points();
+        (libm::roundf(points as f32 * share) as u32).min(points)
+    }
+
     /// Get the points value for this question.
     #[must_use]
     pub fn points(&self) -> u32 {
@@ -198,7 +482,15 @@ impl Question {
             | Self::CodeCompletion { points, .. }
             | Self::Ordering { points, .. }
             | Self::Matching { points, .. }
-            | Self::FreeformCode { points, .. } => *points,
+            | Self::FreeformCode { points, .. }
+            | Self::Numeric { points, .. }
+            | Self::ShortAnswer { points, .. }
+            | Self::Parsons { points, .. }
+            | Self::PredictOutput { points, .. }
+            | Self::Categorize { points, .. }
+            | Self::Hotspot { points, .. } => *points,
+            Self::Template(template) => template.points,
+            Self::Hinted { question, .. } => question.points(),
         }
     }
 
@@ -211,7 +503,15 @@ impl Question {
             | Self::CodeCompletion { id, .. }
             | Self::Ordering { id, .. }
             | Self::Matching { id, .. }
-            | Self::FreeformCode { id, .. } => id,
+            | Self::FreeformCode { id, .. }
+            | Self::Numeric { id, .. }
+            | Self::ShortAnswer { id, .. }
+            | Self::Parsons { id, .. }
+            | Self::PredictOutput { id, .. }
+            | Self::Categorize { id, .. }
+            | Self::Hotspot { id, .. } => id,
+            Self::Template(template) => &template.id,
+            Self::Hinted { question, .. } => question.id(),
         }
     }
 
@@ -224,9 +524,123 @@ impl Question {
             | Self::CodeCompletion { prompt, .. }
             | Self::Ordering { prompt, .. }
             | Self::Matching { prompt, .. }
-            | Self::FreeformCode { prompt, .. } => prompt,
+            | Self::FreeformCode { prompt, .. }
+            | Self::Numeric { prompt, .. }
+            | Self::ShortAnswer { prompt, .. }
+            | Self::Parsons { prompt, .. }
+            | Self::PredictOutput { prompt, .. }
+            | Self::Categorize { prompt, .. }
+            | Self::Hotspot { prompt, .. } => prompt,
+            Self::Template(template) => &template.prompt,
+            Self::Hinted { question, .. } => question.prompt(),
+        }
+    }
+}
+
+/// A quiz hint that costs a share of the question's points.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuestionHint {
+    /// The hint text
+    pub text: String,
+    /// Share of the question's points deducted when revealed (0.0 - 1.0)
+    pub cost: f32,
+}
+
+impl QuestionHint {
+    /// Create a new hint.
+    #[must_use]
+    pub fn new(text: impl Into<String>, cost: f32) -> Self {
+        Self {
+            text: text.into(),
+            cost: cost.clamp(0.0, 1.0),
+        }
+    }
+}
+
+/// Accepted deviation for numeric answers.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum Tolerance {
+    /// Within a fixed amount of the correct value
+    Absolute(f64),
+    /// Within a fraction of the correct value (e.g., 0.01 for 1%)
+    Relative(f64),
+}
+
+impl Tolerance {
+    /// Check if a value is close enough to the expected one.
+    #[must_use]
+    pub fn accepts(&self, expected: f64, actual: f64) -> bool {
+        let diff = (expected - actual).abs();
+        match self {
+            Self::Absolute(amount) => diff <= amount.abs(),
+            Self::Relative(fraction) => diff <= (expected * fraction).abs(),
+        }
+    }
+}
+
+/// How a short answer is compared with an expected one.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TextMatcher {
+    /// Identical after trimming surrounding whitespace
+    Exact(String),
+    /// Identical ignoring case and runs of whitespace
+    Normalized(String),
+    /// Regular expression matching the whole answer
+    Regex(String),
+    /// Normalized answer within an edit distance
+    Fuzzy {
+        /// Expected text
+        text: String,
+        /// Maximum number of single-character edits
+        max_distance: usize,
+    },
+}
+
+/// An expected short answer with its credit and feedback.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TextAnswer {
+    /// How to match the learner's answer
+    pub matcher: TextMatcher,
+    /// Fraction of the question's points awarded (0.0 - 1.0)
+    pub credit: f32,
+    /// Feedback shown instead of the question's explanation
+    pub feedback: Option<String>,
+}
+
+impl TextAnswer {
+    /// Create a fully-credited answer.
+    #[must_use]
+    pub fn new(matcher: TextMatcher) -> Self {
+        Self {
+            matcher,
+            credit: 1.0,
+            feedback: None,
+        }
+    }
+
+    /// Create a known wrong answer with targeted feedback.
+    #[must_use]
+    pub fn wrong(matcher: TextMatcher, feedback: impl Into<String>) -> Self {
+        Self {
+            matcher,
+            credit: 0.0,
+            feedback: Some(feedback.into()),
         }
     }
+
+    /// Set the credit awarded.
+    #[must_use]
+    pub fn with_credit(mut self, credit: f32) -> Self {
+        self.credit = credit.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the feedback shown when this answer matches.
+    #[must_use]
+    pub fn with_feedback(mut self, feedback: impl Into<String>) -> Self {
+        self.feedback = Some(feedback.into());
+        self
+    }
 }
 
 /// A blank in code completion questions.
@@ -238,6 +652,9 @@ pub struct Blank {
     pub acceptable_answers: Vec<String>,
     /// Optional hint
     pub hint: Option<String>,
+    /// Why a wrong answer to this blank is wrong
+    #[serde(default)]
+    pub explanation: String,
 }
 
 impl Blank {
@@ -248,6 +665,7 @@ impl Blank {
             id: id.into(),
             acceptable_answers: Vec::new(),
             hint: None,
+            explanation: String::new(),
         }
     }
 
@@ -265,6 +683,13 @@ impl Blank {
         self
     }
 
+    /// Set the explanation shown when the blank is answered wrongly.
+    #[must_use]
+    pub fn with_explanation(mut self, explanation: impl Into<String>) -> Self {
+        self.explanation = explanation.into();
+        self
+    }
+
     /// Check if an answer is acceptable.
     #[must_use]
     pub fn is_acceptable(&self, answer: &str) -> bool {
@@ -272,6 +697,126 @@ impl Blank {
     }
 }
 
+/// A line of code in a Parsons problem.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CodeFragment {
+    /// Code text, without leading indentation
+    pub code: String,
+    /// Indentation level in the solution
+    pub indent: usize,
+    /// Whether the line belongs in no solution
+    pub distractor: bool,
+}
+
+impl CodeFragment {
+    /// Create a fragment at an indentation level.
+    #[must_use]
+    pub fn new(code: impl Into<String>, indent: usize) -> Self {
+        Self {
+            code: code.into(),
+            indent,
+            distractor: false,
+        }
+    }
+
+    /// Create a distractor line.
+    #[must_use]
+    pub fn distractor(code: impl Into<String>) -> Self {
+        Self {
+            code: code.into(),
+            indent: 0,
+            distractor: true,
+        }
+    }
+}
+
+/// What a hotspot question is clicked on.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum HotspotTarget {
+    /// A code listing, clicked by line
+    Code {
+        /// Programming language
+        language: Language,
+        /// Code shown to the learner
+        code: String,
+    },
+    /// An image, clicked by point
+    Image {
+        /// Image URL or asset path
+        src: String,
+        /// Text alternative for the image
+        alt: String,
+    },
+    /// A rendered simulation frame, clicked by point
+    Simulation(SimulationId),
+}
+
+/// An area of a hotspot target.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum HotspotRegion {
+    /// Code lines `start..=end` (1-based)
+    Lines {
+        /// First line
+        start: usize,
+        /// Last line (inclusive)
+        end: usize,
+    },
+    /// Rectangle in image or render coordinates
+    Rect {
+        /// Left edge
+        x: f32,
+        /// Top edge
+        y: f32,
+        /// Width
+        width: f32,
+        /// Height
+        height: f32,
+    },
+}
+
+impl HotspotRegion {
+    /// Create a region covering a single code line.
+    #[must_use]
+    pub fn line(line: usize) -> Self {
+        Self::Lines {
+            start: line,
+            end: line,
+        }
+    }
+
+    /// Check if a click lands inside the region.
+    #[must_use]
+    pub fn contains(&self, click: &HotspotClick) -> bool {
+        match (*self, *click) {
+            (Self::Lines { start, end }, HotspotClick::Line(line)) => (start..=end).contains(&line),
+            (
+                Self::Rect {
+                    x,
+                    y,
+                    width,
+                    height,
+                },
+                HotspotClick::Point { x: px, y: py },
+            ) => px >= x && px <= x + width && py >= y && py <= y + height,
+            _ => false,
+        }
+    }
+}
+
+/// A learner's click on a hotspot target.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum HotspotClick {
+    /// A code line (1-based)
+    Line(usize),
+    /// A point in image or render coordinates
+    Point {
+        /// Horizontal position
+        x: f32,
+        /// Vertical position
+        y: f32,
+    },
+}
+
 /// Test case for code validation.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct TestCase {
@@ -334,6 +879,59 @@ pub enum Answer {
     Code(String),
     /// Filled blanks (blank_id -> answer)
     Blanks(Vec<(String, String)>),
+    /// Numeric answer as typed, optionally with a unit (e.g., "12.5 m/s")
+    Numeric(String),
+    /// Short free-text answer
+    Text(String),
+    /// Arranged code fragments as (fragment index, indentation level)
+    Arrangement(Vec<(usize, usize)>),
+    /// Items sorted into buckets as (item index, bucket index)
+    Categories(Vec<(usize, usize)>),
+    /// Clicks on a hotspot target
+    Hotspots(Vec<HotspotClick>),
+    /// An answer with the learner's confidence in it
+    Confident {
+        /// The answer itself
+        answer: alloc::boxed::Box<Answer>,
+        /// How sure the learner is
+        confidence: Confidence,
+    },
+}
+
+impl Answer {
+    /// Attach a confidence level, replacing any existing one.
+    #[must_use]
+    pub fn with_confidence(self, confidence: Confidence) -> Self {
+        Self::Confident {
+            answer: alloc::boxed::Box::new(self.into_response()),
+            confidence,
+        }
+    }
+
+    /// Get the attached confidence level.
+    #[must_use]
+    pub fn confidence(&self) -> Option<Confidence> {
+        match self {
+            Self::Confident { confidence, .. } => Some(*confidence),
+            _ => None,
+        }
+    }
+
+    /// Get the answer without its confidence level.
+    #[must_use]
+    pub fn response(&self) -> &Self {
+        match self {
+            Self::Confident { answer, .. } => answer.response(),
+            _ => self,
+        }
+    }
+
+    fn into_response(self) -> Self {
+        match self {
+            Self::Confident { answer, .. } => answer.into_response(),
+            other => other,
+        }
+    }
 }
 
 /// Feedback for an answered question.
@@ -345,6 +943,18 @@ pub struct Feedback {
     pub explanation: String,
     /// Points earned
     pub points_earned: u32,
+    /// Confidence the learner gave, if any
+    #[serde(default)]
+    pub confidence: Option<Confidence>,
+    /// Confidence-based mark from the quiz's payoff matrix
+    #[serde(default)]
+    pub confidence_mark: i32,
+    /// Whether the learner was sure of a wrong answer
+    #[serde(default)]
+    pub misconception: bool,
+    /// Points deducted for revealed hints
+    #[serde(default)]
+    pub hint_deduction: u32,
 }
 
 impl Feedback {
@@ -355,6 +965,10 @@ impl Feedback {
             correct: true,
             explanation: explanation.into(),
             points_earned: points,
+            confidence: None,
+            confidence_mark: 0,
+            misconception: false,
+            hint_deduction: 0,
         }
     }
 
@@ -365,7 +979,57 @@ impl Feedback {
             correct: false,
             explanation: explanation.into(),
             points_earned: 0,
+            confidence: None,
+            confidence_mark: 0,
+            misconception: false,
+            hint_deduction: 0,
+        }
+    }
+
+    /// Create feedback for a partially correct answer.
+    #[must_use]
+    pub fn partial(explanation: impl Into<String>, points: u32) -> Self {
+        Self {
+            correct: false,
+            explanation: explanation.into(),
+            points_earned: points,
+            confidence: None,
+            confidence_mark: 0,
+            misconception: false,
+            hint_deduction: 0,
+        }
+    }
+
+    /// Deduct points for revealed hints, replacing any earlier deduction.
+    ///
+    /// The deduction is capped at the points earned.
+    #[must_use]
+    pub fn with_hint_deduction(mut self, deduction: u32) -> Self {
+        let earned = self.points_earned + self.hint_deduction;
+        self.hint_deduction = deduction.min(earned);
+        self.points_earned = earned - self.hint_deduction;
+        self
+    }
+
+    /// Mark the answer by the learner's confidence.
+    ///
+    /// A wrong answer given with high confidence is flagged as a
+    /// misconception and the explanation says so.
+    #[must_use]
+    pub fn with_confidence(mut self, confidence: Confidence, payoff: &PayoffMatrix) -> Self {
+        self.confidence = Some(confidence);
+        self.confidence_mark = payoff.mark(confidence, self.correct);
+        self.misconception = confidence == Confidence::High && !self.correct;
+        if self.misconception {
+            let note = "You were sure of this answer, but it is wrong. \
+                        This may be a misconception worth revisiting.";
+            if self.explanation.is_empty() {
+                self.explanation = note.into();
+            } else {
+                self.explanation = alloc::format!("{}\n\n{}", self.explanation, note);
+            }
         }
+        self
     }
 }
 
@@ -384,6 +1048,12 @@ pub struct Score {
     pub total_questions: usize,
     /// Whether the quiz was passed
     pub passed: bool,
+    /// Ability estimate for adaptive quizzes
+    #[serde(default)]
+    pub ability: Option<AbilityEstimate>,
+    /// Confidence calibration, if any answers carried a confidence level
+    #[serde(default)]
+    pub calibration: Option<Calibration>,
 }
 
 impl Score {
@@ -409,8 +1079,24 @@ impl Score {
             correct_count,
             total_questions,
             passed: percentage >= passing_threshold,
+            ability: None,
+            calibration: None,
         }
     }
+
+    /// Attach the confidence calibration of the answers.
+    #[must_use]
+    pub fn with_calibration(mut self, calibration: Calibration) -> Self {
+        self.calibration = Some(calibration);
+        self
+    }
+
+    /// Attach an ability estimate.
+    #[must_use]
+    pub fn with_ability(mut self, ability: AbilityEstimate) -> Self {
+        self.ability = Some(ability);
+        self
+    }
 }
 
 #[cfg(test)]
@@ -430,6 +1116,25 @@ mod tests {
         assert_eq!(quiz.max_attempts, Some(3));
     }
 
+    #[test]
+    fn test_can_attempt_with_limit_and_cooldown() {
+        let quiz = Quiz::new("q", "Quiz")
+            .with_max_attempts(2)
+            .with_attempt_policy(AttemptPolicy::new().with_cooldown(60));
+        let first = QuizAttempt::new(0.5, 30, Timestamp::from_millis(0), false);
+        assert!(quiz.can_attempt(&[], Timestamp::ZERO));
+        assert!(!quiz.can_attempt(
+            core::slice::from_ref(&first),
+            Timestamp::from_millis(89_999)
+        ));
+        assert!(quiz.can_attempt(
+            core::slice::from_ref(&first),
+            Timestamp::from_millis(90_000)
+        ));
+        let both = [first.clone(), first];
+        assert!(!quiz.can_attempt(&both, Timestamp::from_millis(1_000_000)));
+    }
+
     #[test]
     fn test_quiz_default() {
         let quiz = Quiz::new("test", "Test");
@@ -449,6 +1154,7 @@ mod tests {
                 correct: 0,
                 explanation: "".into(),
                 points: 10,
+                option_explanations: Vec::new(),
             })
             .with_question(Question::MultipleChoice {
                 id: "q2".into(),
@@ -457,6 +1163,7 @@ mod tests {
                 correct: 1,
                 explanation: "".into(),
                 points: 20,
+                option_explanations: Vec::new(),
             });
 
         assert_eq!(quiz.total_points(), 30);
@@ -472,6 +1179,7 @@ mod tests {
             correct: 0,
             explanation: "".into(),
             points: 15,
+            option_explanations: Vec::new(),
         };
         assert_eq!(q.points(), 15);
     }
@@ -485,6 +1193,7 @@ mod tests {
             correct: 0,
             explanation: "".into(),
             points: 10,
+            option_explanations: Vec::new(),
         };
         assert_eq!(q.id().as_str(), "q1");
     }
@@ -498,6 +1207,7 @@ mod tests {
             correct: 0,
             explanation: "".into(),
             points: 10,
+            option_explanations: Vec::new(),
         };
         assert_eq!(q.prompt(), "What is 2+2?");
     }
@@ -511,6 +1221,7 @@ mod tests {
             correct: vec![0],
             explanation: "".into(),
             points: 5,
+            option_explanations: Vec::new(),
         };
         assert_eq!(multi_select.points(), 5);
 
@@ -521,6 +1232,7 @@ mod tests {
             blanks: vec![],
             test_cases: vec![],
             points: 10,
+            explanation: String::new(),
         };
         assert_eq!(code_completion.points(), 10);
 
@@ -541,6 +1253,7 @@ mod tests {
             right: vec!["1".into()],
             correct_pairs: vec![(0, 0)],
             points: 20,
+            explanation: String::new(),
         };
         assert_eq!(matching.points(), 20);
 
@@ -554,6 +1267,29 @@ mod tests {
             points: 25,
         };
         assert_eq!(freeform.points(), 25);
+
+        let numeric = Question::Numeric {
+            id: "q6".into(),
+            prompt: "Velocity after 2 s?".into(),
+            value: 19.6,
+            tolerance: Tolerance::Relative(0.01),
+            sig_figs: None,
+            unit: Some("m/s".into()),
+            explanation: "".into(),
+            points: 10,
+        };
+        assert_eq!(numeric.points(), 10);
+        assert_eq!(numeric.id().as_str(), "q6");
+        assert_eq!(numeric.prompt(), "Velocity after 2 s?");
+    }
+
+    #[test]
+    fn test_tolerance() {
+        assert!(Tolerance::Absolute(0.1).accepts(9.8, 9.85));
+        assert!(!Tolerance::Absolute(0.1).accepts(9.8, 9.95));
+        assert!(Tolerance::Relative(0.01).accepts(100.0, 100.9));
+        assert!(!Tolerance::Relative(0.01).accepts(100.0, 101.5));
+        assert!(Tolerance::Relative(0.01).accepts(-100.0, -100.9));
     }
 
     #[test]
@@ -565,6 +1301,7 @@ mod tests {
             correct: vec![],
             explanation: "".into(),
             points: 5,
+            option_explanations: Vec::new(),
         };
         assert_eq!(multi_select.id().as_str(), "ms");
 
@@ -588,6 +1325,7 @@ mod tests {
             correct: vec![],
             explanation: "".into(),
             points: 5,
+            option_explanations: Vec::new(),
         };
         assert_eq!(multi_select.prompt(), "Select prompt");
 
@@ -669,6 +1407,125 @@ mod tests {
         assert_eq!(fb.points_earned, 0);
     }
 
+    #[test]
+    fn test_question_hints() {
+        let question = Question::MultipleChoice {
+            id: "q1".into(),
+            prompt: "Pick".into(),
+            options: alloc::vec!["A".into(), "B".into()],
+            correct: 0,
+            explanation: "".into(),
+            points: 10,
+            option_explanations: Vec::new(),
+        };
+        assert!(question.hints().is_empty());
+        assert_eq!(question.hint_deduction(3), 0);
+
+        let hinted = question
+            .clone()
+            .with_hint(QuestionHint::new("Not B", 0.25))
+            .with_hint(QuestionHint::new("It's A", 0.9));
+        assert_eq!(hinted.hints().len(), 2);
+        assert_eq!(hinted.hints()[1].text, "It's A");
+        assert_eq!(hinted.unhinted(), &question);
+        assert_eq!(hinted.id().as_str(), "q1");
+        assert_eq!(hinted.points(), 10);
+        assert_eq!(hinted.prompt(), "Pick");
+        assert_eq!(hinted.hint_deduction(0), 0);
+        assert_eq!(hinted.hint_deduction(1), 3);
+        assert_eq!(hinted.hint_deduction(2), 10);
+        assert_eq!(hinted.instantiate(7).ok(), Some(hinted.clone()));
+    }
+
+    #[test]
+    fn test_feedback_hint_deduction() {
+        let feedback = Feedback::correct("", 10).with_hint_deduction(3);
+        assert_eq!(feedback.points_earned, 7);
+        let feedback = feedback.with_hint_deduction(5);
+        assert_eq!(feedback.points_earned, 5);
+        assert_eq!(feedback.hint_deduction, 5);
+        let feedback = Feedback::partial("", 4).with_hint_deduction(6);
+        assert_eq!(feedback.points_earned, 0);
+        assert_eq!(feedback.hint_deduction, 4);
+    }
+
+    #[test]
+    fn test_answer_confidence() {
+        let answer = Answer::Choice(2).with_confidence(Confidence::Low);
+        assert_eq!(answer.confidence(), Some(Confidence::Low));
+        assert_eq!(answer.response(), &Answer::Choice(2));
+
+        let answer = answer.with_confidence(Confidence::High);
+        assert_eq!(answer.confidence(), Some(Confidence::High));
+        assert_eq!(answer.response(), &Answer::Choice(2));
+        assert_eq!(Answer::Choice(2).confidence(), None);
+    }
+
+    #[test]
+    fn test_feedback_with_confidence() {
+        let payoff = PayoffMatrix::default();
+        let feedback = Feedback::correct("Right", 10).with_confidence(Confidence::High, &payoff);
+        assert_eq!(feedback.confidence_mark, 3);
+        assert!(!feedback.misconception);
+        assert_eq!(feedback.explanation, "Right");
+
+        let feedback = Feedback::incorrect("Wrong").with_confidence(Confidence::High, &payoff);
+        assert_eq!(feedback.confidence_mark, -6);
+        assert!(feedback.misconception);
+        assert!(feedback.explanation.starts_with("Wrong\n\nYou were sure"));
+
+        let feedback = Feedback::incorrect("").with_confidence(Confidence::Low, &payoff);
+        assert_eq!(feedback.confidence_mark, 0);
+        assert!(!feedback.misconception);
+    }
+
+    #[test]
+    fn test_feedback_partial() {
+        let fb = Feedback::partial("Close", 5);
+        assert!(!fb.correct);
+        assert_eq!(fb.points_earned, 5);
+    }
+
+    #[test]
+    fn test_text_answer_builders() {
+        let answer = TextAnswer::new(TextMatcher::Normalized("ownership".into()))
+            .with_credit(1.5)
+            .with_feedback("Right");
+        assert_eq!(answer.credit, 1.0);
+        assert_eq!(answer.feedback.as_deref(), Some("Right"));
+
+        let wrong = TextAnswer::wrong(TextMatcher::Exact("borrowing".into()), "Not quite");
+        assert_eq!(wrong.credit, 0.0);
+    }
+
+    #[test]
+    fn test_code_fragment_builders() {
+        let line = CodeFragment::new("return x", 1);
+        assert_eq!(line.indent, 1);
+        assert!(!line.distractor);
+        assert!(CodeFragment::distractor("return y").distractor);
+    }
+
+    #[test]
+    fn test_hotspot_region_contains() {
+        let lines = HotspotRegion::Lines { start: 3, end: 5 };
+        assert!(lines.contains(&HotspotClick::Line(3)));
+        assert!(lines.contains(&HotspotClick::Line(5)));
+        assert!(!lines.contains(&HotspotClick::Line(6)));
+        assert!(!lines.contains(&HotspotClick::Point { x: 4.0, y: 4.0 }));
+        assert!(HotspotRegion::line(2).contains(&HotspotClick::Line(2)));
+
+        let rect = HotspotRegion::Rect {
+            x: 10.0,
+            y: 20.0,
+            width: 5.0,
+            height: 5.0,
+        };
+        assert!(rect.contains(&HotspotClick::Point { x: 12.0, y: 25.0 }));
+        assert!(!rect.contains(&HotspotClick::Point { x: 16.0, y: 22.0 }));
+        assert!(!rect.contains(&HotspotClick::Line(12)));
+    }
+
     #[test]
     fn test_passing_score_clamped() {
         let quiz = Quiz::new("test", "Test").with_passing_score(1.5);
@@ -677,6 +1534,121 @@ mod tests {
         let quiz2 = Quiz::new("test", "Test").with_passing_score(-0.5);
         assert_eq!(quiz2.passing_score, 0.0);
     }
+
+    fn bank_quiz() -> Quiz {
+        use crate::bank::{BankItem, DrawRule, QuestionBank, QuestionDifficulty};
+
+        let mut bank = QuestionBank::new();
+        for i in 0..6 {
+            bank = bank.with_item(
+                BankItem::new(
+                    Question::MultipleChoice {
+                        id: alloc::format!("bank-{}", i).into(),
+                        prompt: "Q".into(),
+                        options: vec!["A".into(), "B".into()],
+                        correct: 0,
+                        explanation: "".into(),
+                        points: 5,
+                        option_explanations: Vec::new(),
+                    },
+                    QuestionDifficulty::Easy,
+                )
+                .with_tag("ownership"),
+            );
+        }
+
+        Quiz::new("test", "Test")
+            .with_question(Question::MultipleChoice {
+                id: "fixed".into(),
+                prompt: "Q".into(),
+                options: vec!["A".into(), "B".into()],
+                correct: 0,
+                explanation: "".into(),
+                points: 10,
+                option_explanations: Vec::new(),
+            })
+            .with_draw(QuestionDraw::new(bank).with_rule(DrawRule::new(3).with_tag("ownership")))
+    }
+
+    #[test]
+    fn test_materialize_with_draw() {
+        let quiz = bank_quiz();
+        assert_eq!(quiz.question_count(), 4);
+
+        let questions = quiz.materialize(9).unwrap();
+        assert_eq!(questions.len(), 4);
+        assert_eq!(questions[0].id().as_str(), "fixed");
+        assert_eq!(quiz.materialize(9).unwrap(), questions);
+    }
+
+    #[test]
+    fn test_materialize_shuffle_is_seeded() {
+        let quiz = bank_quiz().with_shuffle(true);
+        assert_eq!(quiz.materialize(5), quiz.materialize(5));
+        let orders: Vec<Vec<Question>> = (0..10).map(|s| quiz.materialize(s).unwrap()).collect();
+        assert!(orders.iter().any(|o| o[0].id().as_str() != "fixed"));
+    }
+
+    fn sum_template(answer: &str) -> Question {
+        Question::Template(
+            QuestionTemplate::new(
+                "sum",
+                "{a} + {b}",
+                crate::template::TemplateAnswer::Numeric {
+                    value: answer.into(),
+                    tolerance: Tolerance::Absolute(0.0),
+                    unit: None,
+                },
+                10,
+            )
+            .with_variable(crate::template::Variable::new("a", 1.0, 100.0))
+            .with_variable(crate::template::Variable::new("b", 1.0, 100.0)),
+        )
+    }
+
+    #[test]
+    fn test_materialize_instantiates_templates() {
+        let quiz = Quiz::new("test", "Test").with_question(sum_template("a+b"));
+        let first = quiz.materialize(1).unwrap();
+        assert!(matches!(first[0], Question::Numeric { .. }));
+        assert_eq!(first[0].id().as_str(), "sum");
+        assert_eq!(first[0].points(), 10);
+        assert_eq!(quiz.materialize(1).unwrap(), first);
+        assert!((2..12).any(|seed| quiz.materialize(seed).unwrap() != first));
+    }
+
+    #[test]
+    fn test_materialize_instantiates_hinted_templates() {
+        let quiz = Quiz::new("test", "Test")
+            .with_question(sum_template("a+b").with_hint(QuestionHint::new("Add them", 0.5)));
+        let questions = quiz.materialize(1).unwrap();
+        match &questions[0] {
+            Question::Hinted { question, hints } => {
+                assert!(matches!(**question, Question::Numeric { .. }));
+                assert_eq!(hints.len(), 1);
+            }
+            other => assert!(matches!(other, Question::Hinted { .. })),
+        }
+        let quiz = Quiz::new("test", "Test")
+            .with_question(sum_template("a+c").with_hint(QuestionHint::new("Add them", 0.5)));
+        assert!(quiz.materialize(0).is_err());
+    }
+
+    #[test]
+    fn test_materialize_template_error() {
+        let quiz = Quiz::new("test", "Test").with_question(sum_template("a+c"));
+        let err = quiz.materialize(0).unwrap_err();
+        assert_eq!(
+            alloc::format!("{}", err),
+            "Question 'sum' could not be generated: Unknown variable 'c'"
+        );
+    }
+
+    #[test]
+    fn test_materialize_without_draw() {
+        let quiz = Quiz::new("test", "Test");
+        assert_eq!(quiz.materialize(0), Ok(Vec::new()));
+    }
 }
 
 #[cfg(test)]
@@ -700,6 +1672,7 @@ mod proptests {
                     correct: 0,
                     explanation: "".into(),
                     points: p,
+                    option_explanations: Vec::new(),
                 });
             }
 
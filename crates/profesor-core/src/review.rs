@@ -0,0 +1,353 @@
+//! Spaced-repetition review scheduling.
+//!
+//! Each question a learner has answered becomes a review card scheduled with
+//! the SM-2 algorithm: correct answers push the next review further out,
+//! mistakes bring the question back the next day.
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+use crate::ids::{QuestionId, QuizId};
+use crate::progress::{QuizAttempt, Timestamp};
+use crate::quiz::{Feedback, Question, Quiz};
+
+/// Milliseconds in one day.
+const MILLIS_PER_DAY: u64 = 86_400_000;
+
+/// Starting ease factor for new cards.
+const INITIAL_EASE: f32 = 2.5;
+
+/// Lowest ease factor SM-2 allows.
+const MIN_EASE: f32 = 1.3;
+
+/// Recall quality on the SM-2 scale (0 = blackout, 5 = perfect).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct RecallQuality(u8);
+
+impl RecallQuality {
+    /// Complete failure to recall.
+    pub const FORGOTTEN: Self = Self(1);
+    /// Partially correct.
+    pub const PARTIAL: Self = Self(3);
+    /// Correct.
+    pub const CORRECT: Self = Self(4);
+
+    /// Create a quality, clamped to 0-5.
+    #[must_use]
+    pub fn new(quality: u8) -> Self {
+        Self(quality.min(5))
+    }
+
+    /// Derive a quality from graded feedback.
+    #[must_use]
+    pub fn from_feedback(feedback: &Feedback) -> Self {
+        Self::from_outcome(feedback.correct, feedback.points_earned)
+    }
+
+    /// Derive a quality from an answer's correctness and points.
+    #[must_use]
+    pub fn from_outcome(correct: bool, points_earned: u32) -> Self {
+        if correct {
+            Self::CORRECT
+        } else if points_earned > 0 {
+            Self::PARTIAL
+        } else {
+            Self::FORGOTTEN
+        }
+    }
+
+    /// Get the raw value.
+    #[must_use]
+    pub fn value(&self) -> u8 {
+        self.0
+    }
+
+    /// Check if the answer counts as recalled.
+    #[must_use]
+    pub fn is_pass(&self) -> bool {
+        self.0 >= 3
+    }
+}
+
+/// Scheduling state for one question.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReviewCard {
+    /// Question being reviewed
+    pub question_id: QuestionId,
+    /// Ease factor (≥ 1.3)
+    pub ease: f32,
+    /// Current interval in days
+    pub interval_days: u32,
+    /// Consecutive successful reviews
+    pub repetitions: u32,
+    /// Number of times the question was forgotten
+    pub lapses: u32,
+    /// When the question is next due
+    pub due: Timestamp,
+}
+
+impl ReviewCard {
+    /// Create a card that is due immediately.
+    #[must_use]
+    pub fn new(question_id: QuestionId, now: Timestamp) -> Self {
+        Self {
+            question_id,
+            ease: INITIAL_EASE,
+            interval_days: 0,
+            repetitions: 0,
+            lapses: 0,
+            due: now,
+        }
+    }
+
+    /// Apply a review using SM-2.
+    pub fn review(&mut self, quality: RecallQuality, now: Timestamp) {
+        if quality.is_pass() {
+            self.interval_days = match self.repetitions {
+                0 => 1,
+                1 => 6,
+                _ => round_days(self.interval_days as f32 * self.ease),
+            };
+            self.repetitions += 1;
+        } else {
+            self.repetitions = 0;
+            self.interval_days = 1;
+            self.lapses += 1;
+        }
+
+        let miss = f32::from(5 - quality.value());
+        self.ease = (self.ease + 0.1 - miss * (0.08 + miss * 0.02)).max(MIN_EASE);
+        self.due = Timestamp::from_millis(
+            now.as_millis()
+                .saturating_add(u64::from(self.interval_days) * MILLIS_PER_DAY),
+        );
+    }
+
+    /// Check if the card is due.
+    #[must_use]
+    pub fn is_due(&self, now: Timestamp) -> bool {
+        self.due <= now
+    }
+}
+
+/// Round a positive interval to whole days.
+fn round_days(days: f32) -> u32 {
+    (days + 0.5) as u32
+}
+
+/// A learner's review cards, keyed by question ID.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct ReviewSchedule {
+    /// Cards by question ID
+    pub cards: BTreeMap<String, ReviewCard>,
+}
+
+impl ReviewSchedule {
+    /// Create an empty schedule.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the card for a question.
+    #[must_use]
+    pub fn card(&self, question_id: &QuestionId) -> Option<&ReviewCard> {
+        self.cards.get(question_id.as_str())
+    }
+
+    /// Record a review of a question, creating its card if needed.
+    pub fn review(&mut self, question_id: &QuestionId, quality: RecallQuality, now: Timestamp) {
+        self.cards
+            .entry(question_id.as_str().into())
+            .or_insert_with(|| ReviewCard::new(question_id.clone(), now))
+            .review(quality, now);
+    }
+
+    /// Record graded feedback for a question.
+    pub fn record_feedback(
+        &mut self,
+        question_id: &QuestionId,
+        feedback: &Feedback,
+        now: Timestamp,
+    ) {
+        self.review(question_id, RecallQuality::from_feedback(feedback), now);
+    }
+
+    /// Record every question outcome of a quiz attempt.
+    pub fn record_attempt(&mut self, attempt: &QuizAttempt) {
+        for outcome in &attempt.outcomes {
+            let quality = RecallQuality::from_outcome(outcome.correct, outcome.points_earned);
+            self.review(&outcome.question_id, quality, attempt.attempted_at);
+        }
+    }
+
+    /// Get the due question IDs, most overdue first.
+    #[must_use]
+    pub fn due(&self, now: Timestamp) -> Vec<&QuestionId> {
+        let mut due: Vec<&ReviewCard> = self.cards.values().filter(|c| c.is_due(now)).collect();
+        due.sort_by_key(|c| c.due);
+        due.into_iter().map(|c| &c.question_id).collect()
+    }
+
+    /// Build a review quiz from the due questions found in `pool`.
+    ///
+    /// Due questions missing from the pool are skipped.
+    #[must_use]
+    pub fn daily_quiz(&self, pool: &[Question], now: Timestamp, max_questions: usize) -> Quiz {
+        let day = now.as_millis() / MILLIS_PER_DAY;
+        let mut quiz = Quiz::new(
+            QuizId::new(alloc::format!("review-{}", day)),
+            "Daily Review",
+        );
+        for id in self.due(now) {
+            if quiz.questions.len() >= max_questions {
+                break;
+            }
+            if let Some(question) = pool.iter().find(|q| q.id() == id) {
+                quiz = quiz.with_question(question.clone());
+            }
+        }
+        quiz
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::progress::QuestionOutcome;
+
+    fn day(n: u64) -> Timestamp {
+        Timestamp::from_millis(n * MILLIS_PER_DAY)
+    }
+
+    fn mc(id: &str) -> Question {
+        Question::MultipleChoice {
+            id: id.into(),
+            prompt: "Q".into(),
+            options: alloc::vec!["A".into(), "B".into()],
+            correct: 0,
+            explanation: "".into(),
+            points: 10,
+            option_explanations: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_sm2_intervals_grow() {
+        let mut card = ReviewCard::new("q1".into(), day(0));
+        card.review(RecallQuality::CORRECT, day(0));
+        assert_eq!(card.interval_days, 1);
+        card.review(RecallQuality::CORRECT, day(1));
+        assert_eq!(card.interval_days, 6);
+        card.review(RecallQuality::CORRECT, day(7));
+        assert_eq!(card.interval_days, 15);
+        assert_eq!(card.due, day(22));
+    }
+
+    #[test]
+    fn test_sm2_lapse_resets() {
+        let mut card = ReviewCard::new("q1".into(), day(0));
+        card.review(RecallQuality::CORRECT, day(0));
+        card.review(RecallQuality::CORRECT, day(1));
+        card.review(RecallQuality::FORGOTTEN, day(7));
+
+        assert_eq!(card.repetitions, 0);
+        assert_eq!(card.interval_days, 1);
+        assert_eq!(card.lapses, 1);
+        assert!(card.ease < INITIAL_EASE);
+    }
+
+    #[test]
+    fn test_ease_floor() {
+        let mut card = ReviewCard::new("q1".into(), day(0));
+        for i in 0..20 {
+            card.review(RecallQuality::new(0), day(i));
+        }
+        assert!((card.ease - MIN_EASE).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_quality_from_feedback() {
+        assert_eq!(
+            RecallQuality::from_feedback(&Feedback::correct("", 10)),
+            RecallQuality::CORRECT
+        );
+        assert_eq!(
+            RecallQuality::from_feedback(&Feedback::incorrect("")),
+            RecallQuality::FORGOTTEN
+        );
+        assert_eq!(
+            RecallQuality::from_outcome(false, 3),
+            RecallQuality::PARTIAL
+        );
+        assert_eq!(RecallQuality::new(9).value(), 5);
+    }
+
+    #[test]
+    fn test_due_ordering() {
+        let mut schedule = ReviewSchedule::new();
+        schedule.record_feedback(&"q1".into(), &Feedback::correct("", 10), day(0));
+        schedule.record_feedback(&"q2".into(), &Feedback::incorrect(""), day(0));
+        schedule.review(&"q3".into(), RecallQuality::CORRECT, day(0));
+        schedule.review(&"q3".into(), RecallQuality::CORRECT, day(1));
+
+        let due: Vec<&str> = schedule.due(day(1)).iter().map(|id| id.as_str()).collect();
+        assert_eq!(due, alloc::vec!["q1", "q2"]);
+        assert!(schedule.due(day(0)).is_empty());
+        assert_eq!(schedule.due(day(7)).len(), 3);
+    }
+
+    #[test]
+    fn test_record_attempt() {
+        let attempt = QuizAttempt::new(0.5, 60, day(3), false).with_outcomes(alloc::vec![
+            QuestionOutcome::new("q1", true, 10),
+            QuestionOutcome::new("q2", false, 0),
+        ]);
+        let mut schedule = ReviewSchedule::new();
+        schedule.record_attempt(&attempt);
+
+        assert_eq!(schedule.cards.len(), 2);
+        assert_eq!(schedule.card(&"q2".into()).map(|c| c.lapses), Some(1));
+        assert_eq!(schedule.card(&"q1".into()).map(|c| c.due), Some(day(4)));
+    }
+
+    #[test]
+    fn test_daily_quiz() {
+        let mut schedule = ReviewSchedule::new();
+        for id in ["q1", "q2", "q3"] {
+            schedule.review(&id.into(), RecallQuality::FORGOTTEN, day(0));
+        }
+        schedule.review(&"gone".into(), RecallQuality::FORGOTTEN, day(0));
+
+        let pool = [mc("q1"), mc("q2"), mc("q3")];
+        let quiz = schedule.daily_quiz(&pool, day(1), 2);
+        assert_eq!(quiz.id.as_str(), "review-1");
+        assert_eq!(quiz.question_count(), 2);
+
+        let quiz = schedule.daily_quiz(&pool, day(1), 10);
+        assert_eq!(quiz.question_count(), 3);
+        assert!(schedule.daily_quiz(&pool, day(0), 10).questions.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_review_always_schedules_ahead(qualities in proptest::collection::vec(0u8..6, 1..30)) {
+            let mut card = ReviewCard::new("q".into(), Timestamp::ZERO);
+            let mut now = Timestamp::ZERO;
+            for q in qualities {
+                card.review(RecallQuality::new(q), now);
+                prop_assert!(card.due > now);
+                prop_assert!(card.ease >= MIN_EASE);
+                now = card.due;
+            }
+        }
+    }
+}
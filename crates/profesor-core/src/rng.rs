@@ -0,0 +1,159 @@
+//! Deterministic random number generation.
+//!
+//! A small seeded generator (SplitMix64) used wherever content is randomized
+//! per learner or attempt, so the same seed always reproduces the same draw.
+
+use alloc::vec::Vec;
+
+/// Seeded pseudo-random number generator (SplitMix64).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    /// Create a generator from a seed.
+    #[must_use]
+    pub const fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Create a generator from a seed and a string, e.g. a question ID.
+    ///
+    /// Lets independent items derive their own streams from one attempt seed.
+    #[must_use]
+    pub fn derive(seed: u64, label: &str) -> Self {
+        let mut rng = Self::new(seed);
+        for byte in label.bytes() {
+            rng.state ^= u64::from(byte);
+            rng.next_u64();
+        }
+        rng
+    }
+
+    /// Get the next 64 random bits.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Get a random index in `0..bound` (returns 0 if `bound` is 0).
+    pub fn below(&mut self, bound: usize) -> usize {
+        if bound == 0 {
+            return 0;
+        }
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    /// Get a random integer in `min..=max`.
+    pub fn range_i64(&mut self, min: i64, max: i64) -> i64 {
+        if max <= min {
+            return min;
+        }
+        let span = max.abs_diff(min).saturating_add(1);
+        min.wrapping_add((self.next_u64() % span) as i64)
+    }
+
+    /// Get a random float in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Shuffle a slice in place (Fisher-Yates).
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i + 1);
+            items.swap(i, j);
+        }
+    }
+
+    /// Pick `count` distinct indices from `0..len`, in draw order.
+    pub fn sample_indices(&mut self, len: usize, count: usize) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..len).collect();
+        self.shuffle(&mut indices);
+        indices.truncate(count);
+        indices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_sequence() {
+        let mut a = SeededRng::new(42);
+        let mut b = SeededRng::new(42);
+        for _ in 0..10 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_different_seeds_differ() {
+        let mut a = SeededRng::new(1);
+        let mut b = SeededRng::new(2);
+        assert_ne!(a.next_u64(), b.next_u64());
+    }
+
+    #[test]
+    fn test_derive_by_label() {
+        let mut a = SeededRng::derive(7, "q1");
+        let mut b = SeededRng::derive(7, "q2");
+        let mut c = SeededRng::derive(7, "q1");
+        let first = a.next_u64();
+        assert_ne!(first, b.next_u64());
+        assert_eq!(first, c.next_u64());
+    }
+
+    #[test]
+    fn test_shuffle_is_permutation() {
+        let mut rng = SeededRng::new(3);
+        let mut items: Vec<u32> = (0..20).collect();
+        rng.shuffle(&mut items);
+        let mut sorted = items.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_below_zero() {
+        let mut rng = SeededRng::new(0);
+        assert_eq!(rng.below(0), 0);
+    }
+}
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_range_in_bounds(seed in any::<u64>(), min in -1000i64..1000, span in 0i64..1000) {
+            let mut rng = SeededRng::new(seed);
+            let value = rng.range_i64(min, min + span);
+            prop_assert!(value >= min && value <= min + span);
+        }
+
+        #[test]
+        fn test_f64_in_unit_interval(seed in any::<u64>()) {
+            let mut rng = SeededRng::new(seed);
+            let value = rng.next_f64();
+            prop_assert!((0.0..1.0).contains(&value));
+        }
+
+        #[test]
+        fn test_sample_indices_distinct(seed in any::<u64>(), len in 0usize..30, count in 0usize..30) {
+            let mut rng = SeededRng::new(seed);
+            let mut picked = rng.sample_indices(len, count);
+            prop_assert_eq!(picked.len(), count.min(len));
+            picked.sort_unstable();
+            picked.dedup();
+            prop_assert_eq!(picked.len(), count.min(len));
+        }
+    }
+}
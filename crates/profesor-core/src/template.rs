@@ -0,0 +1,655 @@
+//! Algorithmic question templates.
+//!
+//! A template declares variables drawn from ranges and computes its answer
+//! with a small expression language, so each attempt seed yields a different
+//! but reproducible variant of the question. Prompts and explanations embed
+//! expressions in braces (`"What is {a} × {b}?"`, `"{a*b}"`); write `{{` and
+//! `}}` for literal braces.
+//!
+//! Expressions support numbers, variables, `+ - * / %`, `^` (power),
+//! parentheses, unary minus and the functions `abs`, `sqrt`, `round`,
+//! `floor`, `ceil`, `min` and `max`.
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+use crate::ids::QuestionId;
+use crate::quiz::{Question, Tolerance};
+use crate::rng::SeededRng;
+
+/// Variable values for one instantiation, by name.
+pub type Bindings = BTreeMap<String, f64>;
+
+/// A template variable drawn uniformly from `min..=max` in steps of `step`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Variable {
+    /// Name used in expressions
+    pub name: String,
+    /// Smallest value
+    pub min: f64,
+    /// Largest value
+    pub max: f64,
+    /// Spacing between values
+    pub step: f64,
+}
+
+impl Variable {
+    /// Create an integer variable in `min..=max`.
+    #[must_use]
+    pub fn new(name: impl Into<String>, min: f64, max: f64) -> Self {
+        Self {
+            name: name.into(),
+            min,
+            max,
+            step: 1.0,
+        }
+    }
+
+    /// Set the spacing between values (e.g., 0.5).
+    #[must_use]
+    pub fn with_step(mut self, step: f64) -> Self {
+        self.step = step;
+        self
+    }
+
+    /// Draw a value.
+    pub fn sample(&self, rng: &mut SeededRng) -> f64 {
+        if self.step <= 0.0 || self.max <= self.min {
+            return self.min;
+        }
+        // Small epsilon so 0.1-style steps don't lose their last value.
+        let count = ((self.max - self.min) / self.step + 1e-9) as usize + 1;
+        let value = self.min + self.step * rng.below(count) as f64;
+        round_to(value, 1e9)
+    }
+}
+
+/// How a template's answer is computed.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum TemplateAnswer {
+    /// Numeric answer computed from an expression
+    Numeric {
+        /// Expression for the correct value
+        value: String,
+        /// Accepted deviation
+        tolerance: Tolerance,
+        /// Expected unit
+        unit: Option<String>,
+    },
+    /// Multiple choice with computed options, shuffled per variant
+    MultipleChoice {
+        /// Expression for the correct option
+        correct: String,
+        /// Expressions for the distractors (e.g., `"a+b+1"`)
+        distractors: Vec<String>,
+    },
+}
+
+/// A question with randomized parameters.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuestionTemplate {
+    /// ID shared by every variant
+    pub id: QuestionId,
+    /// Prompt with `{expression}` placeholders
+    pub prompt: String,
+    /// Variables, drawn in order
+    pub variables: Vec<Variable>,
+    /// Answer computation
+    pub answer: TemplateAnswer,
+    /// Explanation with `{expression}` placeholders
+    pub explanation: String,
+    /// Points for correct answer
+    pub points: u32,
+}
+
+impl QuestionTemplate {
+    /// Create a template.
+    #[must_use]
+    pub fn new(
+        id: impl Into<QuestionId>,
+        prompt: impl Into<String>,
+        answer: TemplateAnswer,
+        points: u32,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            prompt: prompt.into(),
+            variables: Vec::new(),
+            answer,
+            explanation: String::new(),
+            points,
+        }
+    }
+
+    /// Add a variable.
+    #[must_use]
+    pub fn with_variable(mut self, variable: Variable) -> Self {
+        self.variables.push(variable);
+        self
+    }
+
+    /// Set the explanation.
+    #[must_use]
+    pub fn with_explanation(mut self, explanation: impl Into<String>) -> Self {
+        self.explanation = explanation.into();
+        self
+    }
+
+    /// Draw the variable values for a seed.
+    #[must_use]
+    pub fn bindings(&self, seed: u64) -> Bindings {
+        let mut rng = SeededRng::derive(seed, self.id.as_str());
+        self.variables
+            .iter()
+            .map(|v| (v.name.clone(), v.sample(&mut rng)))
+            .collect()
+    }
+
+    /// Build the concrete question for a seed.
+    pub fn instantiate(&self, seed: u64) -> Result<Question, TemplateError> {
+        let vars = self.bindings(seed);
+        let prompt = fill(&self.prompt, &vars)?;
+        let explanation = fill(&self.explanation, &vars)?;
+
+        match &self.answer {
+            TemplateAnswer::Numeric {
+                value,
+                tolerance,
+                unit,
+            } => Ok(Question::Numeric {
+                id: self.id.clone(),
+                prompt,
+                value: evaluate(value, &vars)?,
+                tolerance: *tolerance,
+                sig_figs: None,
+                unit: unit.clone(),
+                explanation,
+                points: self.points,
+            }),
+            TemplateAnswer::MultipleChoice {
+                correct,
+                distractors,
+            } => {
+                let answer = format_number(evaluate(correct, &vars)?);
+                let mut options = alloc::vec![answer.clone()];
+                for expr in distractors {
+                    let option = format_number(evaluate(expr, &vars)?);
+                    if !options.contains(&option) {
+                        options.push(option);
+                    }
+                }
+                SeededRng::derive(seed, "options").shuffle(&mut options);
+                let correct = options.iter().position(|o| *o == answer).unwrap_or(0);
+                Ok(Question::MultipleChoice {
+                    id: self.id.clone(),
+                    prompt,
+                    options,
+                    correct,
+                    explanation,
+                    points: self.points,
+                    option_explanations: Vec::new(),
+                })
+            }
+        }
+    }
+}
+
+/// Errors from instantiating a template.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TemplateError {
+    /// The expression could not be parsed
+    Syntax {
+        /// The expression
+        expression: String,
+        /// Byte offset of the problem
+        position: usize,
+    },
+    /// A variable is not declared
+    UnknownVariable(String),
+    /// A function is not supported
+    UnknownFunction(String),
+    /// The result is not a finite number (e.g., division by zero)
+    NotFinite(String),
+    /// A placeholder brace is never closed
+    UnclosedPlaceholder,
+}
+
+impl core::fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Syntax {
+                expression,
+                position,
+            } => write!(
+                f,
+                "Syntax error in '{}' at position {}",
+                expression, position
+            ),
+            Self::UnknownVariable(name) => write!(f, "Unknown variable '{}'", name),
+            Self::UnknownFunction(name) => write!(f, "Unknown function '{}'", name),
+            Self::NotFinite(expr) => write!(f, "'{}' is not a finite number", expr),
+            Self::UnclosedPlaceholder => write!(f, "Unclosed '{{' placeholder"),
+        }
+    }
+}
+
+/// Evaluate an expression with the given variable values.
+pub fn evaluate(expression: &str, vars: &Bindings) -> Result<f64, TemplateError> {
+    let mut parser = Parser {
+        text: expression,
+        pos: 0,
+        vars,
+    };
+    let value = parser.expr()?;
+    parser.skip_spaces();
+    if parser.pos < expression.len() {
+        return Err(parser.syntax_error());
+    }
+    if value.is_finite() {
+        Ok(value)
+    } else {
+        Err(TemplateError::NotFinite(expression.into()))
+    }
+}
+
+/// Replace each `{expression}` in `text` with its formatted value.
+pub fn fill(text: &str, vars: &Bindings) -> Result<String, TemplateError> {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(i) = rest.find(['{', '}']) {
+        out.push_str(&rest[..i]);
+        let brace = &rest[i..=i];
+        rest = &rest[i + 1..];
+        if rest.starts_with(brace) {
+            // Escaped `{{` or `}}`.
+            out.push_str(brace);
+            rest = &rest[1..];
+        } else if brace == "{" {
+            let end = rest.find('}').ok_or(TemplateError::UnclosedPlaceholder)?;
+            out.push_str(&format_number(evaluate(&rest[..end], vars)?));
+            rest = &rest[end + 1..];
+        } else {
+            out.push_str(brace);
+        }
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Format a computed value: integers without a decimal point, other values
+/// rounded to six decimal places.
+#[must_use]
+pub fn format_number(value: f64) -> String {
+    let rounded = round_to(value, 1e6);
+    if rounded == libm::trunc(rounded) && libm::fabs(rounded) < 1e15 {
+        alloc::format!("{}", rounded as i64)
+    } else {
+        alloc::format!("{}", rounded)
+    }
+}
+
+fn round_to(value: f64, scale: f64) -> f64 {
+    libm::round(value * scale) / scale
+}
+
+/// Recursive-descent evaluator over the expression grammar:
+///
+/// ```text
+/// expr   = term (("+" | "-") term)*
+/// term   = unary (("*" | "/" | "%") unary)*
+/// unary  = "-" unary | power
+/// power  = atom ("^" unary)?
+/// atom   = number | name | name "(" expr ("," expr)* ")" | "(" expr ")"
+/// ```
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+    vars: &'a Bindings,
+}
+
+impl Parser<'_> {
+    fn syntax_error(&self) -> TemplateError {
+        TemplateError::Syntax {
+            expression: self.text.into(),
+            position: self.pos,
+        }
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.text[self.pos..].starts_with(' ') {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_spaces();
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expr(&mut self) -> Result<f64, TemplateError> {
+        let mut value = self.term()?;
+        loop {
+            if self.eat(b'+') {
+                value += self.term()?;
+            } else if self.eat(b'-') {
+                value -= self.term()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<f64, TemplateError> {
+        let mut value = self.unary()?;
+        loop {
+            if self.eat(b'*') {
+                value *= self.unary()?;
+            } else if self.eat(b'/') {
+                value /= self.unary()?;
+            } else if self.eat(b'%') {
+                value = libm::fmod(value, self.unary()?);
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<f64, TemplateError> {
+        if self.eat(b'-') {
+            Ok(-self.unary()?)
+        } else {
+            self.power()
+        }
+    }
+
+    fn power(&mut self) -> Result<f64, TemplateError> {
+        let base = self.atom()?;
+        if self.eat(b'^') {
+            Ok(libm::pow(base, self.unary()?))
+        } else {
+            Ok(base)
+        }
+    }
+
+    fn atom(&mut self) -> Result<f64, TemplateError> {
+        match self.peek() {
+            Some(b'(') => {
+                self.pos += 1;
+                let value = self.expr()?;
+                if self.eat(b')') {
+                    Ok(value)
+                } else {
+                    Err(self.syntax_error())
+                }
+            }
+            Some(b) if b.is_ascii_digit() || b == b'.' => self.number(),
+            Some(b) if b.is_ascii_alphabetic() || b == b'_' => self.name(),
+            _ => Err(self.syntax_error()),
+        }
+    }
+
+    fn number(&mut self) -> Result<f64, TemplateError> {
+        let start = self.pos;
+        let bytes = self.text.as_bytes();
+        while self.pos < bytes.len()
+            && (bytes[self.pos].is_ascii_digit() || bytes[self.pos] == b'.')
+        {
+            self.pos += 1;
+        }
+        self.text[start..self.pos]
+            .parse()
+            .map_err(|_| TemplateError::Syntax {
+                expression: self.text.into(),
+                position: start,
+            })
+    }
+
+    fn name(&mut self) -> Result<f64, TemplateError> {
+        let start = self.pos;
+        let bytes = self.text.as_bytes();
+        while self.pos < bytes.len()
+            && (bytes[self.pos].is_ascii_alphanumeric() || bytes[self.pos] == b'_')
+        {
+            self.pos += 1;
+        }
+        let name = &self.text[start..self.pos];
+
+        if !self.eat(b'(') {
+            return self
+                .vars
+                .get(name)
+                .copied()
+                .ok_or_else(|| TemplateError::UnknownVariable(name.into()));
+        }
+
+        let mut args = alloc::vec![self.expr()?];
+        while self.eat(b',') {
+            args.push(self.expr()?);
+        }
+        if !self.eat(b')') {
+            return Err(self.syntax_error());
+        }
+
+        let value = match (name, args.as_slice()) {
+            ("abs", [x]) => libm::fabs(*x),
+            ("sqrt", [x]) => libm::sqrt(*x),
+            ("round", [x]) => libm::round(*x),
+            ("floor", [x]) => libm::floor(*x),
+            ("ceil", [x]) => libm::ceil(*x),
+            ("min", [first, rest @ ..]) => rest.iter().fold(*first, |m, x| m.min(*x)),
+            ("max", [first, rest @ ..]) => rest.iter().fold(*first, |m, x| m.max(*x)),
+            _ => return Err(TemplateError::UnknownFunction(name.into())),
+        };
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::panic)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, f64)]) -> Bindings {
+        pairs.iter().map(|(k, v)| ((*k).into(), *v)).collect()
+    }
+
+    fn product_template() -> QuestionTemplate {
+        QuestionTemplate::new(
+            "mul",
+            "What is {a} × {b}?",
+            TemplateAnswer::Numeric {
+                value: "a*b".into(),
+                tolerance: Tolerance::Absolute(0.0),
+                unit: None,
+            },
+            10,
+        )
+        .with_variable(Variable::new("a", 2.0, 12.0))
+        .with_variable(Variable::new("b", 2.0, 12.0))
+        .with_explanation("{a} × {b} = {a*b}")
+    }
+
+    #[test]
+    fn test_evaluate_precedence() {
+        let v = vars(&[("a", 3.0), ("b", 4.0)]);
+        assert_eq!(evaluate("a + b * 2", &v), Ok(11.0));
+        assert_eq!(evaluate("(a + b) * 2", &v), Ok(14.0));
+        assert_eq!(evaluate("-a^2", &v), Ok(-9.0));
+        assert_eq!(evaluate("2^3^2", &v), Ok(512.0));
+        assert_eq!(evaluate("b % a", &v), Ok(1.0));
+        assert_eq!(evaluate("sqrt(a*a + b*b)", &v), Ok(5.0));
+        assert_eq!(evaluate("max(a, b, 1) - min(a, b)", &v), Ok(1.0));
+        assert_eq!(evaluate("round(2.5) + floor(1.9) + ceil(0.1)", &v), Ok(5.0));
+    }
+
+    #[test]
+    fn test_evaluate_errors() {
+        let v = vars(&[("a", 0.0)]);
+        assert_eq!(
+            evaluate("c + 1", &v),
+            Err(TemplateError::UnknownVariable("c".into()))
+        );
+        assert_eq!(
+            evaluate("log(2)", &v),
+            Err(TemplateError::UnknownFunction("log".into()))
+        );
+        assert_eq!(
+            evaluate("1 / a", &v),
+            Err(TemplateError::NotFinite("1 / a".into()))
+        );
+        assert_eq!(
+            evaluate("1 +", &v),
+            Err(TemplateError::Syntax {
+                expression: "1 +".into(),
+                position: 3,
+            })
+        );
+        assert!(evaluate("(1", &v).is_err());
+        assert!(evaluate("1 2", &v).is_err());
+    }
+
+    #[test]
+    fn test_fill_placeholders() {
+        let v = vars(&[("a", 3.0), ("b", 0.5)]);
+        assert_eq!(fill("{a} + {b} = {a+b}", &v), Ok("3 + 0.5 = 3.5".into()));
+        assert_eq!(fill("fn f() {{ {a} }}", &v), Ok("fn f() { 3 }".into()));
+        assert_eq!(fill("{a", &v), Err(TemplateError::UnclosedPlaceholder));
+    }
+
+    #[test]
+    fn test_format_number() {
+        assert_eq!(format_number(42.0), "42");
+        assert_eq!(format_number(-3.0), "-3");
+        assert_eq!(format_number(0.1 + 0.2), "0.3");
+        assert_eq!(format_number(1.0 / 3.0), "0.333333");
+    }
+
+    #[test]
+    fn test_variable_sample_in_range() {
+        let var = Variable::new("x", 0.0, 1.0).with_step(0.1);
+        let mut rng = SeededRng::new(7);
+        for _ in 0..100 {
+            let value = var.sample(&mut rng);
+            assert!((0.0..=1.0).contains(&value));
+            assert_eq!(value, round_to(value, 10.0));
+        }
+        assert_eq!(Variable::new("y", 5.0, 5.0).sample(&mut rng), 5.0);
+    }
+
+    #[test]
+    fn test_instantiate_numeric() {
+        let template = product_template();
+        let question = template.instantiate(1).unwrap();
+        let vars = template.bindings(1);
+        let (a, b) = (vars["a"], vars["b"]);
+
+        match question {
+            Question::Numeric {
+                id,
+                prompt,
+                value,
+                explanation,
+                ..
+            } => {
+                assert_eq!(id.as_str(), "mul");
+                assert_eq!(value, a * b);
+                assert_eq!(prompt, alloc::format!("What is {} × {}?", a, b));
+                assert!(explanation.ends_with(&format_number(a * b)));
+            }
+            other => panic!("expected numeric question, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_instantiate_is_seeded() {
+        let template = product_template();
+        assert_eq!(template.instantiate(3), template.instantiate(3));
+        let variants: Vec<Question> = (0..10).map(|s| template.instantiate(s).unwrap()).collect();
+        assert!(variants.iter().any(|q| q.prompt() != variants[0].prompt()));
+    }
+
+    #[test]
+    fn test_instantiate_multiple_choice() {
+        let template = QuestionTemplate::new(
+            "add",
+            "{a} + {b} = ?",
+            TemplateAnswer::MultipleChoice {
+                correct: "a+b".into(),
+                distractors: alloc::vec!["a+b+1".into(), "a+b-1".into(), "a+b".into()],
+            },
+            5,
+        )
+        .with_variable(Variable::new("a", 1.0, 9.0))
+        .with_variable(Variable::new("b", 1.0, 9.0));
+
+        for seed in 0..20 {
+            let vars = template.bindings(seed);
+            match template.instantiate(seed).unwrap() {
+                Question::MultipleChoice {
+                    options, correct, ..
+                } => {
+                    assert_eq!(options.len(), 3);
+                    assert_eq!(options[correct], format_number(vars["a"] + vars["b"]));
+                }
+                other => panic!("expected multiple choice, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_template_error_display() {
+        assert_eq!(
+            alloc::format!("{}", TemplateError::UnknownVariable("z".into())),
+            "Unknown variable 'z'"
+        );
+        assert_eq!(
+            alloc::format!("{}", TemplateError::UnclosedPlaceholder),
+            "Unclosed '{' placeholder"
+        );
+    }
+}
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_integer_arithmetic(a in -1000i32..1000, b in 1i32..1000) {
+            let v: Bindings = [("a".into(), f64::from(a)), ("b".into(), f64::from(b))]
+                .into_iter()
+                .collect();
+            prop_assert_eq!(evaluate("a * b - a", &v), Ok(f64::from(a * b - a)));
+            prop_assert_eq!(fill("{a+b}", &v), Ok(alloc::format!("{}", a + b)));
+        }
+
+        #[test]
+        fn test_bindings_within_ranges(seed in any::<u64>()) {
+            let template = QuestionTemplate::new(
+                "t",
+                "",
+                TemplateAnswer::Numeric {
+                    value: "x".into(),
+                    tolerance: Tolerance::Absolute(0.0),
+                    unit: None,
+                },
+                1,
+            )
+            .with_variable(Variable::new("x", -5.0, 5.0).with_step(0.25));
+            let x = template.bindings(seed)["x"];
+            prop_assert!((-5.0..=5.0).contains(&x));
+        }
+    }
+}
@@ -0,0 +1,372 @@
+//! Moodle Aiken format.
+//!
+//! Aiken holds only single-answer multiple choice questions: a one-line
+//! prompt, lettered options and an `ANSWER:` line. It has no IDs, points
+//! or feedback, so imported questions get positional IDs (`q1`, `q2`, ...)
+//! and are worth 1 point.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use profesor_core::{Question, QuestionId, Quiz, QuizId};
+
+use crate::diagnostic::{question_kind, settings_dropped, Converted, Diagnostic};
+
+/// Option letters, in order.
+const LETTERS: &[u8; 26] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// A question being read.
+struct Pending {
+    line: usize,
+    prompt: String,
+    options: Vec<String>,
+}
+
+/// Parse Aiken text into a quiz.
+///
+/// Malformed questions are skipped with an error diagnostic.
+pub fn import(input: &str, id: impl Into<QuizId>, title: impl Into<String>) -> Converted<Quiz> {
+    let mut quiz = Quiz::new(id, title);
+    let mut diagnostics = Vec::new();
+    let mut pending: Option<Pending> = None;
+
+    for (number, line) in input.lines().enumerate() {
+        let number = number + 1;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(letter) = line.strip_prefix("ANSWER:") {
+            let Some(question) = pending.take() else {
+                diagnostics.push(Diagnostic::error("answer without a question").at_line(number));
+                continue;
+            };
+            let id = QuestionId::new(alloc::format!("q{}", quiz.questions.len() + 1));
+            match answer_index(letter.trim(), question.options.len()) {
+                Some(correct) => quiz.questions.push(Question::MultipleChoice {
+                    id,
+                    prompt: question.prompt,
+                    options: question.options,
+                    correct,
+                    explanation: String::new(),
+                    points: 1,
+                    option_explanations: Vec::new(),
+                }),
+                None => diagnostics.push(
+                    Diagnostic::error(alloc::format!("invalid answer '{}'", letter.trim()))
+                        .at_line(number)
+                        .for_question(&id),
+                ),
+            }
+            continue;
+        }
+
+        match &mut pending {
+            Some(question) => match option_text(line, question.options.len()) {
+                Some(text) => question.options.push(String::from(text)),
+                None if question.options.is_empty() => {
+                    question.prompt.push(' ');
+                    question.prompt.push_str(line);
+                }
+                None => {
+                    diagnostics.push(
+                        Diagnostic::error("expected the next option or an ANSWER line")
+                            .at_line(question.line),
+                    );
+                    pending = None;
+                }
+            },
+            None => {
+                pending = Some(Pending {
+                    line: number,
+                    prompt: String::from(line),
+                    options: Vec::new(),
+                });
+            }
+        }
+    }
+
+    if let Some(question) = pending {
+        diagnostics.push(Diagnostic::error("question has no ANSWER line").at_line(question.line));
+    }
+
+    Converted {
+        value: quiz,
+        diagnostics,
+    }
+}
+
+/// Write a quiz as Aiken text.
+///
+/// Only multiple choice questions can be written; others are skipped with
+/// an error diagnostic.
+#[must_use]
+pub fn export(quiz: &Quiz) -> Converted<String> {
+    let mut diagnostics = settings_dropped(quiz, "Aiken");
+    let mut out = String::new();
+    let mut written = 0;
+
+    for question in &quiz.questions {
+        let mut warnings = Vec::new();
+        let result = write_question(question, written + 1, &mut warnings);
+        diagnostics.extend(
+            warnings
+                .into_iter()
+                .map(|w| Diagnostic::warning(w).for_question(question.id())),
+        );
+        match result {
+            Ok(text) => {
+                out.push_str(&text);
+                out.push('\n');
+                written += 1;
+            }
+            Err(err) => diagnostics.push(Diagnostic::error(err).for_question(question.id())),
+        }
+    }
+
+    Converted {
+        value: out,
+        diagnostics,
+    }
+}
+
+fn write_question(
+    question: &Question,
+    position: usize,
+    warnings: &mut Vec<String>,
+) -> Result<String, String> {
+    let question = match question {
+        Question::Hinted { question, hints } => {
+            if !hints.is_empty() {
+                warnings.push(String::from("hints are dropped"));
+            }
+            question.as_ref()
+        }
+        other => other,
+    };
+    let Question::MultipleChoice {
+        id,
+        prompt,
+        options,
+        correct,
+        explanation,
+        points,
+        option_explanations,
+    } = question
+    else {
+        return Err(alloc::format!(
+            "{} questions have no Aiken equivalent",
+            question_kind(question)
+        ));
+    };
+
+    if options.len() > LETTERS.len() {
+        return Err(alloc::format!("more than {} options", LETTERS.len()));
+    }
+    let letter = LETTERS
+        .get(*correct)
+        .filter(|_| *correct < options.len())
+        .ok_or_else(|| String::from("correct option is out of range"))?;
+
+    if id.as_str() != alloc::format!("q{}", position) {
+        warnings.push(String::from("question ID is dropped"));
+    }
+    if *points != 1 {
+        warnings.push(alloc::format!(
+            "worth {} points; Aiken questions have no points",
+            points
+        ));
+    }
+    if !explanation.is_empty() || option_explanations.iter().any(|e| !e.is_empty()) {
+        warnings.push(String::from("explanations are dropped"));
+    }
+
+    let mut text = single_line(prompt, "prompt", warnings);
+    for (option, letter) in options.iter().zip(LETTERS) {
+        text.push_str(&alloc::format!(
+            "\n{}. {}",
+            char::from(*letter),
+            single_line(option, "option", warnings)
+        ));
+    }
+    text.push_str(&alloc::format!("\nANSWER: {}\n", char::from(*letter)));
+    Ok(text)
+}
+
+/// Join lines with spaces, warning if there were several.
+fn single_line(text: &str, what: &str, warnings: &mut Vec<String>) -> String {
+    let trimmed = text.trim();
+    if trimmed.contains('\n') {
+        warnings.push(alloc::format!("multi-line {} joined into one line", what));
+        return trimmed.lines().map(str::trim).collect::<Vec<_>>().join(" ");
+    }
+    String::from(trimmed)
+}
+
+/// Get the text of option number `index` (`A. text` or `A) text`).
+fn option_text(line: &str, index: usize) -> Option<&str> {
+    let letter = *LETTERS.get(index)?;
+    let rest = line.strip_prefix(char::from(letter))?;
+    let rest = rest.strip_prefix('.').or_else(|| rest.strip_prefix(')'))?;
+    rest.strip_prefix(' ').map(str::trim)
+}
+
+/// Get the index of an answer letter.
+fn answer_index(letter: &str, options: usize) -> Option<usize> {
+    let [letter] = letter.as_bytes() else {
+        return None;
+    };
+    LETTERS
+        .iter()
+        .position(|l| l == letter)
+        .filter(|&i| i < options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Severity;
+
+    const SAMPLE: &str = "\
+Which type owns its data?
+A. &str
+B) String
+C. &[u8]
+ANSWER: B
+
+What does `?` do on an Err?
+A. Panics
+B. Returns early
+ANSWER: B
+";
+
+    #[test]
+    fn test_import() {
+        let converted = import(SAMPLE, "rust", "Rust");
+        assert!(converted.is_lossless());
+        let quiz = converted.value;
+        assert_eq!(quiz.questions.len(), 2);
+        match &quiz.questions[0] {
+            Question::MultipleChoice {
+                id,
+                options,
+                correct,
+                ..
+            } => {
+                assert_eq!(id.as_str(), "q1");
+                assert_eq!(options[1], "String");
+                assert_eq!(*correct, 1);
+            }
+            other => assert_eq!(question_kind(other), "multiple choice"),
+        }
+    }
+
+    #[test]
+    fn test_import_errors() {
+        let converted = import(
+            "First?\nA. yes\nB. no\nANSWER: E\n\nSecond?\nA. yes\nC. skipped\n\nThird?\nA. one\n",
+            "q",
+            "Q",
+        );
+        assert!(converted.value.questions.is_empty());
+        let lines: Vec<Option<usize>> = converted.errors().map(|d| d.line).collect();
+        assert_eq!(lines, alloc::vec![Some(4), Some(6), Some(10)]);
+    }
+
+    #[test]
+    fn test_export_round_trip() {
+        let quiz = import(SAMPLE, "rust", "Rust").value;
+        let exported = export(&quiz);
+        assert!(exported.is_lossless());
+        assert!(exported.value.contains("B. String\nC. &[u8]\nANSWER: B"));
+        assert_eq!(import(&exported.value, "rust", "Rust").value, quiz);
+    }
+
+    #[test]
+    fn test_export_reports_losses() {
+        let quiz = Quiz::new("q", "Q")
+            .with_question(Question::MultipleChoice {
+                id: "ownership".into(),
+                prompt: "Which owns?\nPick one".into(),
+                options: alloc::vec!["&str".into(), "String".into()],
+                correct: 1,
+                explanation: "String owns its buffer".into(),
+                points: 5,
+                option_explanations: Vec::new(),
+            })
+            .with_question(Question::Ordering {
+                id: "order".into(),
+                prompt: "Order".into(),
+                items: alloc::vec!["a".into()],
+                correct_order: alloc::vec![0],
+                explanation: "".into(),
+                points: 1,
+            });
+        let exported = export(&quiz);
+        let messages: Vec<String> = exported
+            .diagnostics
+            .iter()
+            .map(|d| alloc::format!("{}", d))
+            .collect();
+        assert_eq!(
+            messages,
+            alloc::vec![
+                String::from("warning: question 'ownership': question ID is dropped"),
+                String::from(
+                    "warning: question 'ownership': worth 5 points; Aiken questions have no points"
+                ),
+                String::from("warning: question 'ownership': explanations are dropped"),
+                String::from(
+                    "warning: question 'ownership': multi-line prompt joined into one line"
+                ),
+                String::from(
+                    "error: question 'order': ordering questions have no Aiken equivalent"
+                ),
+            ]
+        );
+        assert_eq!(exported.errors().count(), 1);
+        assert!(exported.value.starts_with("Which owns? Pick one\nA. &str"));
+        assert!(exported
+            .diagnostics
+            .iter()
+            .all(|d| d.severity == Severity::Error || d.line.is_none()));
+    }
+}
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn text() -> impl Strategy<Value = String> {
+        "[a-zA-Z0-9?&*(){}#=~:. -]{0,20}[a-zA-Z0-9?]".prop_map(|s| String::from(s.trim_start()))
+    }
+
+    proptest! {
+        #[test]
+        fn test_round_trip(
+            questions in proptest::collection::vec(
+                (text(), proptest::collection::vec(text(), 1..8), any::<prop::sample::Index>()),
+                0..5,
+            )
+        ) {
+            let mut quiz = Quiz::new("q", "Q");
+            for (i, (prompt, options, correct)) in questions.into_iter().enumerate() {
+                quiz = quiz.with_question(Question::MultipleChoice {
+                    id: QuestionId::new(alloc::format!("q{}", i + 1)),
+                    prompt,
+                    correct: correct.index(options.len()),
+                    options,
+                    explanation: String::new(),
+                    points: 1,
+                    option_explanations: Vec::new(),
+                });
+            }
+            let exported = export(&quiz);
+            prop_assert!(exported.is_lossless());
+            let imported = import(&exported.value, "q", "Q");
+            prop_assert!(imported.is_lossless());
+            prop_assert_eq!(imported.value, quiz);
+        }
+    }
+}
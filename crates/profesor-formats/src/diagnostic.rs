@@ -0,0 +1,192 @@
+//! Conversion diagnostics.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use profesor_core::{AttemptPolicy, PayoffMatrix, Question, QuestionId, Quiz};
+
+/// How serious a diagnostic is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The item was converted, but something was approximated or dropped
+    Warning,
+    /// The item could not be converted and was skipped
+    Error,
+}
+
+/// Something that could not be converted exactly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// How serious it is
+    pub severity: Severity,
+    /// Line of the input it refers to (1-based), when importing
+    pub line: Option<usize>,
+    /// Question it refers to
+    pub question_id: Option<QuestionId>,
+    /// What happened
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// Create a warning.
+    #[must_use]
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            line: None,
+            question_id: None,
+            message: message.into(),
+        }
+    }
+
+    /// Create an error.
+    #[must_use]
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            ..Self::warning(message)
+        }
+    }
+
+    /// Set the input line.
+    #[must_use]
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    /// Set the question.
+    #[must_use]
+    pub fn for_question(mut self, question_id: &QuestionId) -> Self {
+        self.question_id = Some(question_id.clone());
+        self
+    }
+}
+
+impl core::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.severity {
+            Severity::Warning => write!(f, "warning: ")?,
+            Severity::Error => write!(f, "error: ")?,
+        }
+        if let Some(line) = self.line {
+            write!(f, "line {}: ", line)?;
+        }
+        if let Some(id) = &self.question_id {
+            write!(f, "question '{}': ", id)?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+/// A converted value and what could not be converted exactly.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Converted<T> {
+    /// The result
+    pub value: T,
+    /// Problems found, in input order
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl<T> Converted<T> {
+    /// Check whether the conversion was exact.
+    #[must_use]
+    pub fn is_lossless(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    /// Get the items that were skipped.
+    pub fn errors(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.severity == Severity::Error)
+    }
+}
+
+/// Warn about quiz settings a format cannot store.
+pub(crate) fn settings_dropped(quiz: &Quiz, format: &str) -> Vec<Diagnostic> {
+    let defaults = Quiz::new(quiz.id.clone(), "");
+    let settings = [
+        ("time limit", quiz.time_limit_secs.is_some()),
+        (
+            "passing score",
+            quiz.passing_score != defaults.passing_score,
+        ),
+        ("question shuffling", quiz.shuffle),
+        ("attempt limit", quiz.max_attempts.is_some()),
+        ("question bank draw", quiz.draw.is_some()),
+        ("adaptive testing", quiz.adaptive.is_some()),
+        (
+            "attempt policy",
+            quiz.attempt_policy != AttemptPolicy::default(),
+        ),
+        (
+            "confidence payoff",
+            quiz.confidence_payoff != PayoffMatrix::default(),
+        ),
+        ("concealed answers", quiz.conceal_answers),
+    ];
+    settings
+        .into_iter()
+        .filter(|(_, set)| *set)
+        .map(|(name, _)| {
+            Diagnostic::warning(alloc::format!("{} has no {} equivalent", name, format))
+        })
+        .collect()
+}
+
+/// Name a question type for messages.
+pub(crate) fn question_kind(question: &Question) -> &'static str {
+    match question {
+        Question::MultipleChoice { .. } => "multiple choice",
+        Question::MultipleSelect { .. } => "multiple select",
+        Question::CodeCompletion { .. } => "code completion",
+        Question::Ordering { .. } => "ordering",
+        Question::Matching { .. } => "matching",
+        Question::FreeformCode { .. } => "free-form code",
+        Question::Numeric { .. } => "numeric",
+        Question::ShortAnswer { .. } => "short answer",
+        Question::Parsons { .. } => "Parsons",
+        Question::PredictOutput { .. } => "predict-the-output",
+        Question::Categorize { .. } => "categorize",
+        Question::Hotspot { .. } => "hotspot",
+        Question::Template(_) => "template",
+        Question::Hinted { question, .. } => question_kind(question),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let diagnostic = Diagnostic::error("no correct answer")
+            .at_line(3)
+            .for_question(&"q1".into());
+        assert_eq!(
+            alloc::format!("{}", diagnostic),
+            "error: line 3: question 'q1': no correct answer"
+        );
+        assert_eq!(
+            alloc::format!("{}", Diagnostic::warning("dropped")),
+            "warning: dropped"
+        );
+    }
+
+    #[test]
+    fn test_settings_dropped() {
+        assert!(settings_dropped(&Quiz::new("q", "Q"), "GIFT").is_empty());
+        let quiz = Quiz::new("q", "Q").with_time_limit(60).with_shuffle(true);
+        let messages: Vec<String> = settings_dropped(&quiz, "GIFT")
+            .into_iter()
+            .map(|d| d.message)
+            .collect();
+        assert_eq!(
+            messages,
+            alloc::vec![
+                String::from("time limit has no GIFT equivalent"),
+                String::from("question shuffling has no GIFT equivalent"),
+            ]
+        );
+    }
+}
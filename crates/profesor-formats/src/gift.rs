@@ -0,0 +1,1002 @@
+//! Moodle GIFT format.
+//!
+//! Supports multiple choice (including weighted multiple answers),
+//! true/false, short answer, matching and numeric questions. Question
+//! titles (`::title::`) become question IDs and general feedback (`####`)
+//! becomes the explanation. GIFT has no points, so imported questions are
+//! worth 1 point.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use profesor_core::{Question, QuestionId, Quiz, QuizId, TextAnswer, TextMatcher, Tolerance};
+
+use crate::diagnostic::{question_kind, settings_dropped, Converted, Diagnostic};
+
+/// Options of an imported true/false question.
+const TRUE_FALSE: [&str; 2] = ["True", "False"];
+
+/// Parse GIFT text into a quiz.
+///
+/// Items that cannot be represented are skipped with an error diagnostic;
+/// approximations produce warnings.
+pub fn import(input: &str, id: impl Into<QuizId>, title: impl Into<String>) -> Converted<Quiz> {
+    let mut quiz = Quiz::new(id, title);
+    let mut diagnostics = Vec::new();
+
+    for (line, block) in blocks(input) {
+        if block.starts_with("$CATEGORY:") {
+            diagnostics.push(Diagnostic::warning("categories are not imported").at_line(line));
+            continue;
+        }
+
+        let (title, text) = split_title(&block);
+        let mut id = match title.filter(|t| !t.is_empty()) {
+            Some(title) => QuestionId::new(title),
+            None => QuestionId::new(alloc::format!("q{}", quiz.questions.len() + 1)),
+        };
+        if quiz.questions.iter().any(|q| q.id() == &id) {
+            let renamed = QuestionId::new(alloc::format!("{}-{}", id, quiz.questions.len() + 1));
+            diagnostics.push(
+                Diagnostic::warning(alloc::format!("duplicate title; renamed to '{}'", renamed))
+                    .at_line(line)
+                    .for_question(&id),
+            );
+            id = renamed;
+        }
+
+        let mut warnings = Vec::new();
+        let result = parse_question(&id, text, &mut warnings);
+        diagnostics.extend(
+            warnings
+                .into_iter()
+                .map(|w| Diagnostic::warning(w).at_line(line).for_question(&id)),
+        );
+        match result {
+            Ok(question) => quiz.questions.push(question),
+            Err(err) => {
+                diagnostics.push(Diagnostic::error(err).at_line(line).for_question(&id));
+            }
+        }
+    }
+
+    Converted {
+        value: quiz,
+        diagnostics,
+    }
+}
+
+/// Write a quiz as GIFT text.
+///
+/// Questions with no GIFT equivalent are skipped with an error diagnostic.
+#[must_use]
+pub fn export(quiz: &Quiz) -> Converted<String> {
+    let mut diagnostics = settings_dropped(quiz, "GIFT");
+    let mut out = String::new();
+
+    for question in &quiz.questions {
+        let mut warnings = Vec::new();
+        let result = write_question(question, &mut warnings);
+        diagnostics.extend(
+            warnings
+                .into_iter()
+                .map(|w| Diagnostic::warning(w).for_question(question.id())),
+        );
+        match result {
+            Ok(text) => {
+                out.push_str(&text);
+                out.push_str("\n\n");
+            }
+            Err(err) => diagnostics.push(Diagnostic::error(err).for_question(question.id())),
+        }
+    }
+
+    Converted {
+        value: out,
+        diagnostics,
+    }
+}
+
+/// Split input into question blocks at blank lines, with their first line number.
+fn blocks(input: &str) -> Vec<(usize, String)> {
+    let mut blocks = Vec::new();
+    let mut current: Option<(usize, String)> = None;
+
+    for (number, line) in input.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("//") {
+            continue;
+        }
+        if trimmed.is_empty() || trimmed.starts_with("$CATEGORY:") {
+            blocks.extend(current.take());
+            if !trimmed.is_empty() {
+                blocks.push((number + 1, String::from(trimmed)));
+            }
+            continue;
+        }
+        match &mut current {
+            Some((_, text)) => {
+                text.push('\n');
+                text.push_str(line);
+            }
+            None => current = Some((number + 1, String::from(line))),
+        }
+    }
+    blocks.extend(current);
+    blocks
+}
+
+/// Split off a leading `::title::`.
+fn split_title(block: &str) -> (Option<String>, &str) {
+    let trimmed = block.trim_start();
+    if let Some(rest) = trimmed.strip_prefix("::") {
+        if let (title, Some(text)) = split_unescaped(rest, "::") {
+            return (Some(unescape(title.trim())), text);
+        }
+    }
+    (None, trimmed)
+}
+
+fn parse_question(
+    id: &QuestionId,
+    text: &str,
+    warnings: &mut Vec<String>,
+) -> Result<Question, String> {
+    let (before, rest) = split_unescaped(text, "{");
+    let (body, after) = match rest.map(|rest| split_unescaped(rest, "}")) {
+        Some((body, Some(after))) => (body, after),
+        Some((_, None)) => return Err(String::from("missing closing '}'")),
+        None => return Err(String::from("description items have no equivalent")),
+    };
+
+    let before = strip_format(before.trim(), warnings);
+    let prompt = if after.trim().is_empty() {
+        unescape(before)
+    } else {
+        warnings.push(String::from(
+            "missing-word question imported with a blank in the prompt",
+        ));
+        alloc::format!("{} _____ {}", unescape(before), unescape(after.trim()))
+    };
+
+    let (answers, general) = split_unescaped(body, "####");
+    let explanation = general.map(|g| unescape(g.trim())).unwrap_or_default();
+    let answers = answers.trim();
+    let id = id.clone();
+
+    if answers.is_empty() {
+        return Err(String::from("essay questions have no equivalent"));
+    }
+    if let Some(numeric) = answers.strip_prefix('#') {
+        return parse_numeric(id, prompt, numeric, explanation, warnings);
+    }
+    let (head, feedback) = split_unescaped(answers, "#");
+    match head.trim().to_ascii_uppercase().as_str() {
+        "T" | "TRUE" => return Ok(true_false(id, prompt, true, feedback, explanation)),
+        "F" | "FALSE" => return Ok(true_false(id, prompt, false, feedback, explanation)),
+        _ => {}
+    }
+
+    let answers = split_answers(answers)?;
+    if answers.iter().all(|a| a.correct) && answers.iter().any(|a| a.text.contains("->")) {
+        return parse_matching(id, prompt, &answers, explanation, warnings);
+    }
+    if answers.iter().all(|a| a.correct) {
+        return Ok(Question::ShortAnswer {
+            id,
+            prompt,
+            answers: answers
+                .iter()
+                .map(|a| TextAnswer {
+                    matcher: TextMatcher::Normalized(unescape(a.text)),
+                    credit: a.weight.map_or(1.0, |w| (w / 100.0).clamp(0.0, 1.0)),
+                    feedback: a.feedback.clone(),
+                })
+                .collect(),
+            explanation,
+            points: 1,
+        });
+    }
+    parse_choice(id, prompt, &answers, explanation, warnings)
+}
+
+/// Strip a leading `[format]` tag.
+fn strip_format<'a>(text: &'a str, warnings: &mut Vec<String>) -> &'a str {
+    for tag in ["[html]", "[moodle]", "[plain]", "[markdown]"] {
+        if let Some(rest) = text.strip_prefix(tag) {
+            if tag == "[html]" {
+                warnings.push(String::from("HTML formatting is kept as plain text"));
+            }
+            return rest.trim_start();
+        }
+    }
+    text
+}
+
+fn true_false(
+    id: QuestionId,
+    prompt: String,
+    answer: bool,
+    feedback: Option<&str>,
+    explanation: String,
+) -> Question {
+    // `{T#wrong#right}`: the first feedback is for the wrong option.
+    let (wrong, right) = match feedback.map(|f| split_unescaped(f, "#")) {
+        Some((wrong, right)) => (unescape(wrong.trim()), right.map(|r| unescape(r.trim()))),
+        None => (String::new(), None),
+    };
+    let right = right.unwrap_or_default();
+    let option_explanations = match (wrong.is_empty() && right.is_empty(), answer) {
+        (true, _) => Vec::new(),
+        (false, true) => alloc::vec![right, wrong],
+        (false, false) => alloc::vec![wrong, right],
+    };
+    Question::MultipleChoice {
+        id,
+        prompt,
+        options: TRUE_FALSE.iter().map(|&o| String::from(o)).collect(),
+        correct: usize::from(!answer),
+        explanation,
+        points: 1,
+        option_explanations,
+    }
+}
+
+fn parse_choice(
+    id: QuestionId,
+    prompt: String,
+    answers: &[RawAnswer<'_>],
+    explanation: String,
+    warnings: &mut Vec<String>,
+) -> Result<Question, String> {
+    let weight = |a: &RawAnswer<'_>| a.weight.unwrap_or(if a.correct { 100.0 } else { 0.0 });
+    let full: Vec<usize> = (0..answers.len())
+        .filter(|&i| weight(&answers[i]) >= 100.0)
+        .collect();
+    let partial: Vec<usize> = (0..answers.len())
+        .filter(|&i| (0.0..100.0).contains(&weight(&answers[i])) && weight(&answers[i]) > 0.0)
+        .collect();
+
+    let options = answers.iter().map(|a| unescape(a.text)).collect();
+    let mut option_explanations: Vec<String> = answers
+        .iter()
+        .map(|a| a.feedback.clone().unwrap_or_default())
+        .collect();
+    if option_explanations.iter().all(String::is_empty) {
+        option_explanations.clear();
+    }
+
+    match (full.as_slice(), partial.is_empty()) {
+        ([correct], true) => Ok(Question::MultipleChoice {
+            id,
+            prompt,
+            options,
+            correct: *correct,
+            explanation,
+            points: 1,
+            option_explanations,
+        }),
+        ([correct], false) => {
+            warnings.push(String::from("partial credit for other options is dropped"));
+            Ok(Question::MultipleChoice {
+                id,
+                prompt,
+                options,
+                correct: *correct,
+                explanation,
+                points: 1,
+                option_explanations,
+            })
+        }
+        ([], true) => Err(String::from("no correct answer")),
+        _ => {
+            warnings.push(String::from(
+                "answer weights are not kept; multiple answers are graded all-or-nothing",
+            ));
+            let mut correct: Vec<usize> = full.into_iter().chain(partial).collect();
+            correct.sort_unstable();
+            Ok(Question::MultipleSelect {
+                id,
+                prompt,
+                options,
+                correct,
+                explanation,
+                points: 1,
+                option_explanations,
+            })
+        }
+    }
+}
+
+fn parse_matching(
+    id: QuestionId,
+    prompt: String,
+    answers: &[RawAnswer<'_>],
+    explanation: String,
+    warnings: &mut Vec<String>,
+) -> Result<Question, String> {
+    let mut left = Vec::new();
+    let mut right: Vec<String> = Vec::new();
+    let mut correct_pairs = Vec::new();
+
+    for answer in answers {
+        let (l, r) = match answer.text.split_once("->") {
+            Some((l, r)) => (unescape(l.trim()), unescape(r.trim())),
+            None => {
+                return Err(alloc::format!(
+                    "matching answer '{}' has no '->'",
+                    answer.text
+                ))
+            }
+        };
+        if answer.feedback.is_some() || answer.weight.is_some() {
+            warnings.push(String::from("matching feedback and weights are dropped"));
+        }
+        let r_index = match right.iter().position(|x| x == &r) {
+            Some(index) => index,
+            None => {
+                right.push(r);
+                right.len() - 1
+            }
+        };
+        // An empty left side adds a distractor on the right.
+        if !l.is_empty() {
+            correct_pairs.push((left.len(), r_index));
+            left.push(l);
+        }
+    }
+
+    Ok(Question::Matching {
+        id,
+        prompt,
+        left,
+        right,
+        correct_pairs,
+        points: 1,
+        explanation,
+    })
+}
+
+fn parse_numeric(
+    id: QuestionId,
+    prompt: String,
+    body: &str,
+    explanation: String,
+    warnings: &mut Vec<String>,
+) -> Result<Question, String> {
+    let body = body.trim();
+    let specs = if body.starts_with('=') {
+        split_answers(body)?
+    } else {
+        let (text, feedback) = split_unescaped(body, "#");
+        alloc::vec![RawAnswer {
+            correct: true,
+            weight: None,
+            text: text.trim(),
+            feedback: feedback.map(|f| unescape(f.trim())),
+        }]
+    };
+
+    let full = specs
+        .iter()
+        .find(|s| s.correct && s.weight.map_or(true, |w| w >= 100.0))
+        .ok_or_else(|| String::from("no fully correct numeric answer"))?;
+    if specs.len() > 1 {
+        warnings.push(String::from(
+            "only the fully correct numeric answer is kept",
+        ));
+    }
+    if specs.iter().any(|s| s.feedback.is_some()) {
+        warnings.push(String::from("numeric answer feedback is dropped"));
+    }
+
+    let (value, tolerance) = parse_range(full.text)
+        .ok_or_else(|| alloc::format!("invalid numeric answer '{}'", full.text))?;
+    Ok(Question::Numeric {
+        id,
+        prompt,
+        value,
+        tolerance: Tolerance::Absolute(tolerance),
+        sig_figs: None,
+        unit: None,
+        explanation,
+        points: 1,
+    })
+}
+
+/// Parse `value`, `value:tolerance` or `min..max`.
+fn parse_range(text: &str) -> Option<(f64, f64)> {
+    let text = text.trim();
+    if let (value, Some(tolerance)) = split_unescaped(text, ":") {
+        return Some((
+            value.trim().parse().ok()?,
+            tolerance.trim().parse::<f64>().ok()?.abs(),
+        ));
+    }
+    if let Some((min, max)) = text.split_once("..") {
+        let (min, max): (f64, f64) = (min.trim().parse().ok()?, max.trim().parse().ok()?);
+        return Some(((min + max) / 2.0, (max - min).abs() / 2.0));
+    }
+    Some((text.parse().ok()?, 0.0))
+}
+
+/// One `=` or `~` answer, with the text still escaped.
+struct RawAnswer<'a> {
+    correct: bool,
+    weight: Option<f32>,
+    text: &'a str,
+    feedback: Option<String>,
+}
+
+/// Split an answer list at unescaped `=` and `~`.
+fn split_answers(body: &str) -> Result<Vec<RawAnswer<'_>>, String> {
+    let mut markers = Vec::new();
+    let mut escaped = false;
+    for (i, c) in body.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == '=' || c == '~' {
+            markers.push((i, c == '='));
+        }
+    }
+
+    match markers.first() {
+        Some(&(first, _)) if body[..first].trim().is_empty() => {}
+        _ => return Err(String::from("answers must start with '=' or '~'")),
+    }
+
+    let ends = markers.iter().skip(1).map(|&(i, _)| i).chain([body.len()]);
+    markers
+        .iter()
+        .zip(ends)
+        .map(|(&(start, correct), end)| raw_answer(correct, &body[start + 1..end]))
+        .collect()
+}
+
+fn raw_answer(correct: bool, text: &str) -> Result<RawAnswer<'_>, String> {
+    let mut text = text.trim();
+    let mut weight = None;
+    // Like Moodle, only a number between percent signs is a weight.
+    if let Some((value, rest)) = text.strip_prefix('%').and_then(|t| t.split_once('%')) {
+        if let Ok(value) = value.trim().parse::<f32>() {
+            weight = Some(value);
+            text = rest;
+        }
+    }
+    let (text, feedback) = split_unescaped(text, "#");
+    Ok(RawAnswer {
+        correct,
+        weight,
+        text: text.trim(),
+        feedback: feedback.map(|f| unescape(f.trim())),
+    })
+}
+
+fn write_question(question: &Question, warnings: &mut Vec<String>) -> Result<String, String> {
+    let question = match question {
+        Question::Hinted { question, hints } => {
+            if !hints.is_empty() {
+                warnings.push(String::from("hints are dropped"));
+            }
+            question.as_ref()
+        }
+        other => other,
+    };
+    if question.points() != 1 {
+        warnings.push(alloc::format!(
+            "worth {} points; GIFT questions have no points",
+            question.points()
+        ));
+    }
+
+    let mut body = String::new();
+    let explanation = match question {
+        Question::MultipleChoice {
+            options,
+            correct,
+            explanation,
+            option_explanations,
+            ..
+        } => {
+            let note = |i: usize| option_explanations.get(i).map_or("", String::as_str);
+            if *correct >= options.len() {
+                return Err(String::from("correct option is out of range"));
+            }
+            if options.iter().map(String::as_str).eq(TRUE_FALSE) {
+                body.push_str(if *correct == 0 { "TRUE" } else { "FALSE" });
+                let (wrong, right) = (note(1 - correct), note(*correct));
+                if !wrong.is_empty() || !right.is_empty() {
+                    body.push_str(&alloc::format!("#{}#{}", escape(wrong), escape(right)));
+                }
+            } else {
+                for (i, option) in options.iter().enumerate() {
+                    let marker = if i == *correct { '=' } else { '~' };
+                    body.push_str(&answer_line(marker, "", option, note(i)));
+                }
+            }
+            explanation
+        }
+        Question::MultipleSelect {
+            options,
+            correct,
+            explanation,
+            option_explanations,
+            ..
+        } => {
+            let count = options
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| correct.contains(i))
+                .count();
+            if count == 0 {
+                return Err(String::from("no correct option"));
+            }
+            let weight = alloc::format!("%{}%", percent(100.0 / count as f32));
+            for (i, option) in options.iter().enumerate() {
+                let note = option_explanations.get(i).map_or("", String::as_str);
+                let weight = if correct.contains(&i) {
+                    weight.as_str()
+                } else {
+                    "%-100%"
+                };
+                body.push_str(&answer_line('~', weight, option, note));
+            }
+            explanation
+        }
+        Question::ShortAnswer {
+            answers,
+            explanation,
+            ..
+        } => {
+            for answer in answers {
+                let text = match &answer.matcher {
+                    TextMatcher::Normalized(text) => text,
+                    TextMatcher::Exact(text) => {
+                        warnings.push(alloc::format!("'{}' will be matched ignoring case", text));
+                        text
+                    }
+                    TextMatcher::Fuzzy { text, .. } => {
+                        warnings.push(alloc::format!(
+                            "'{}' will be matched without typo tolerance",
+                            text
+                        ));
+                        text
+                    }
+                    TextMatcher::Regex(pattern) => {
+                        warnings.push(alloc::format!(
+                            "regular expression '{}' is dropped",
+                            pattern
+                        ));
+                        continue;
+                    }
+                };
+                let weight = if answer.credit >= 1.0 {
+                    String::new()
+                } else {
+                    alloc::format!("%{}%", percent(answer.credit * 100.0))
+                };
+                let feedback = answer.feedback.as_deref().unwrap_or("");
+                body.push_str(&answer_line('=', &weight, text, feedback));
+            }
+            if body.is_empty() {
+                return Err(String::from("no answer can be written in GIFT"));
+            }
+            explanation
+        }
+        Question::Matching {
+            left,
+            right,
+            correct_pairs,
+            explanation,
+            ..
+        } => {
+            let mut used = alloc::vec![false; right.len()];
+            for (l, item) in left.iter().enumerate() {
+                let mut pairs = correct_pairs.iter().filter(|(pl, _)| *pl == l);
+                let Some(&(_, r)) = pairs.next() else {
+                    warnings.push(alloc::format!("'{}' has no match and is dropped", item));
+                    continue;
+                };
+                if pairs.next().is_some() {
+                    warnings.push(alloc::format!("only the first match of '{}' is kept", item));
+                }
+                let Some(target) = right.get(r) else {
+                    return Err(String::from("matching pair is out of range"));
+                };
+                used[r] = true;
+                body.push_str(&alloc::format!("\n={} -> {}", escape(item), escape(target)));
+            }
+            for (item, _) in right.iter().zip(&used).filter(|(_, used)| !**used) {
+                body.push_str(&alloc::format!("\n= -> {}", escape(item)));
+            }
+            explanation
+        }
+        Question::Numeric {
+            value,
+            tolerance,
+            sig_figs,
+            unit,
+            explanation,
+            ..
+        } => {
+            let tolerance = match tolerance {
+                Tolerance::Absolute(amount) => amount.abs(),
+                Tolerance::Relative(fraction) => {
+                    warnings.push(String::from(
+                        "relative tolerance is written as an absolute tolerance",
+                    ));
+                    (value * fraction).abs()
+                }
+            };
+            if sig_figs.is_some() {
+                warnings.push(String::from("significant figures are dropped"));
+            }
+            if let Some(unit) = unit {
+                warnings.push(alloc::format!("unit '{}' is dropped", unit));
+            }
+            body.push_str(&alloc::format!("#{}:{}", value, tolerance));
+            explanation
+        }
+        other => {
+            return Err(alloc::format!(
+                "{} questions have no GIFT equivalent",
+                question_kind(other)
+            ))
+        }
+    };
+
+    if !explanation.is_empty() {
+        body.push_str(&alloc::format!("\n####{}", escape(explanation)));
+    }
+    if body.starts_with('\n') {
+        body.push('\n');
+    }
+    Ok(alloc::format!(
+        "::{}:: {} {{{}}}",
+        escape(question.id().as_str()),
+        escape(question.prompt()),
+        body
+    ))
+}
+
+fn answer_line(marker: char, weight: &str, text: &str, feedback: &str) -> String {
+    let mut text = escape(text);
+    // Keep a leading `%` from being read as a weight.
+    if weight.is_empty() && text.starts_with('%') {
+        text.insert(0, '\\');
+    }
+    if feedback.is_empty() {
+        alloc::format!("\n{}{}{}", marker, weight, text)
+    } else {
+        alloc::format!("\n{}{}{}#{}", marker, weight, text, escape(feedback))
+    }
+}
+
+/// Format a percentage with at most five decimals, as Moodle expects.
+fn percent(value: f32) -> String {
+    let text = alloc::format!("{:.5}", value);
+    String::from(text.trim_end_matches('0').trim_end_matches('.'))
+}
+
+/// Split at the first unescaped occurrence of `pattern`.
+fn split_unescaped<'a>(text: &'a str, pattern: &str) -> (&'a str, Option<&'a str>) {
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if text[i..].starts_with(pattern) {
+            return (&text[..i], Some(&text[i + pattern.len()..]));
+        }
+    }
+    (text, None)
+}
+
+/// Escape GIFT's special characters and newlines.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\n' => out.push_str("\\n"),
+            '\\' | '~' | '=' | '#' | '{' | '}' | ':' => {
+                out.push('\\');
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some(next) => out.push(next),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Severity;
+
+    const SAMPLE: &str = r"// Rust basics
+$CATEGORY: rust/basics
+
+::ownership:: Which type owns its data? {
+=String#It owns a heap buffer
+~&str#A slice borrows
+~&[u8]
+####Owned types free their memory on drop.
+}
+
+::copy:: Which types are Copy? {
+~%50%i32
+~%50%bool
+~%-100%String
+}
+
+::panic:: `unwrap` on `None` panics. {T#It does panic#Right}
+
+::keyword:: Which keyword declares a constant? {
+=const
+=%50%static#Close, but static is a global
+}
+
+::pairs:: Match each type to its kind. {
+=Vec -> growable
+=[T; 3] -> fixed
+= -> borrowed
+}
+
+::pi:: Give pi to two decimals. {#3.14:0.005}
+
+::range:: Pick a number from 1 to 5. {#1..5}
+
+::essay:: Explain borrowing. {}
+
+Ownership is a Rust feature.
+
+The {=borrow checker ~compiler} enforces aliasing rules.
+";
+
+    fn question(quiz: &Quiz, id: &str) -> Question {
+        quiz.questions
+            .iter()
+            .find(|q| q.id().as_str() == id)
+            .cloned()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_import_question_types() {
+        let converted = import(SAMPLE, "rust", "Rust");
+        let quiz = &converted.value;
+        assert_eq!(quiz.questions.len(), 8);
+
+        assert_eq!(
+            question(quiz, "ownership"),
+            Question::MultipleChoice {
+                id: "ownership".into(),
+                prompt: "Which type owns its data?".into(),
+                options: alloc::vec!["String".into(), "&str".into(), "&[u8]".into()],
+                correct: 0,
+                explanation: "Owned types free their memory on drop.".into(),
+                points: 1,
+                option_explanations: alloc::vec![
+                    "It owns a heap buffer".into(),
+                    "A slice borrows".into(),
+                    "".into(),
+                ],
+            }
+        );
+        match question(quiz, "copy") {
+            Question::MultipleSelect { correct, .. } => assert_eq!(correct, alloc::vec![0, 1]),
+            other => assert_eq!(question_kind(&other), "multiple select"),
+        }
+        match question(quiz, "panic") {
+            Question::MultipleChoice {
+                correct,
+                option_explanations,
+                ..
+            } => {
+                assert_eq!(correct, 0);
+                assert_eq!(
+                    option_explanations,
+                    alloc::vec![String::from("Right"), "It does panic".into()]
+                );
+            }
+            other => assert_eq!(question_kind(&other), "multiple choice"),
+        }
+        match question(quiz, "keyword") {
+            Question::ShortAnswer { answers, .. } => {
+                assert_eq!(answers[1].credit, 0.5);
+                assert_eq!(
+                    answers[1].feedback.as_deref(),
+                    Some("Close, but static is a global")
+                );
+            }
+            other => assert_eq!(question_kind(&other), "short answer"),
+        }
+        match question(quiz, "pairs") {
+            Question::Matching {
+                left,
+                right,
+                correct_pairs,
+                ..
+            } => {
+                assert_eq!(left, alloc::vec![String::from("Vec"), "[T; 3]".into()]);
+                assert_eq!(right.len(), 3);
+                assert_eq!(correct_pairs, alloc::vec![(0, 0), (1, 1)]);
+            }
+            other => assert_eq!(question_kind(&other), "matching"),
+        }
+        match question(quiz, "range") {
+            Question::Numeric {
+                value, tolerance, ..
+            } => {
+                assert_eq!(value, 3.0);
+                assert_eq!(tolerance, Tolerance::Absolute(2.0));
+            }
+            other => assert_eq!(question_kind(&other), "numeric"),
+        }
+        match question(quiz, "q8") {
+            Question::MultipleChoice { prompt, .. } => {
+                assert_eq!(prompt, "The _____ enforces aliasing rules.");
+            }
+            other => assert_eq!(question_kind(&other), "multiple choice"),
+        }
+    }
+
+    #[test]
+    fn test_import_diagnostics() {
+        let converted = import(SAMPLE, "rust", "Rust");
+        let messages: Vec<String> = converted
+            .diagnostics
+            .iter()
+            .map(|d| alloc::format!("{}", d))
+            .collect();
+        assert_eq!(
+            messages,
+            alloc::vec![
+                String::from("warning: line 2: categories are not imported"),
+                String::from("warning: line 11: question 'copy': answer weights are not kept; multiple answers are graded all-or-nothing"),
+                String::from("error: line 34: question 'essay': essay questions have no equivalent"),
+                String::from("error: line 36: question 'q8': description items have no equivalent"),
+                String::from("warning: line 38: question 'q8': missing-word question imported with a blank in the prompt"),
+            ]
+        );
+        assert_eq!(converted.errors().count(), 2);
+    }
+
+    #[test]
+    fn test_round_trip_is_lossless() {
+        let quiz = import(SAMPLE, "rust", "Rust").value;
+        let exported = export(&quiz);
+        assert!(exported.is_lossless(), "{:?}", exported.diagnostics);
+
+        let reimported = import(&exported.value, "rust", "Rust");
+        assert_eq!(reimported.value, quiz);
+        assert!(reimported
+            .diagnostics
+            .iter()
+            .all(|d| d.severity == Severity::Warning && d.question_id == Some("copy".into())));
+    }
+
+    #[test]
+    fn test_export_reports_losses() {
+        let quiz = Quiz::new("q", "Q")
+            .with_time_limit(600)
+            .with_question(Question::Numeric {
+                id: "g".into(),
+                prompt: "g?".into(),
+                value: 9.8,
+                tolerance: Tolerance::Relative(0.5),
+                sig_figs: Some(2),
+                unit: Some("m/s^2".into()),
+                explanation: "".into(),
+                points: 3,
+            })
+            .with_question(Question::ShortAnswer {
+                id: "s".into(),
+                prompt: "Name?".into(),
+                answers: alloc::vec![TextAnswer::new(TextMatcher::Regex("ab+".into()))],
+                explanation: "".into(),
+                points: 1,
+            })
+            .with_question(Question::Ordering {
+                id: "o".into(),
+                prompt: "Order".into(),
+                items: alloc::vec!["a".into()],
+                correct_order: alloc::vec![0],
+                explanation: "".into(),
+                points: 1,
+            });
+        let exported = export(&quiz);
+        let messages: Vec<String> = exported
+            .diagnostics
+            .iter()
+            .map(|d| alloc::format!("{}", d))
+            .collect();
+        assert_eq!(
+            messages,
+            alloc::vec![
+                String::from("warning: time limit has no GIFT equivalent"),
+                String::from(
+                    "warning: question 'g': worth 3 points; GIFT questions have no points"
+                ),
+                String::from(
+                    "warning: question 'g': relative tolerance is written as an absolute tolerance"
+                ),
+                String::from("warning: question 'g': significant figures are dropped"),
+                String::from("warning: question 'g': unit 'm/s^2' is dropped"),
+                String::from("warning: question 's': regular expression 'ab+' is dropped"),
+                String::from("error: question 's': no answer can be written in GIFT"),
+                String::from("error: question 'o': ordering questions have no GIFT equivalent"),
+            ]
+        );
+        assert_eq!(exported.value, "::g:: g? {#9.8:4.9}\n\n");
+    }
+
+    #[test]
+    fn test_escaping() {
+        let text = "a = b {c} ~d #e: f\\g\nh";
+        assert_eq!(escape(text), "a \\= b \\{c\\} \\~d \\#e\\: f\\\\g\\nh");
+        assert_eq!(unescape(&escape(text)), text);
+    }
+}
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn text() -> impl Strategy<Value = String> {
+        "[a-zA-Z0-9{}#=~:\\\\%? -]{0,20}".prop_map(|s| String::from(s.trim()))
+    }
+
+    proptest! {
+        #[test]
+        fn test_multiple_choice_round_trip(
+            prompt in text(),
+            options in proptest::collection::vec(text(), 2..6),
+            notes in proptest::collection::vec(text(), 0..6),
+            correct in any::<prop::sample::Index>(),
+            explanation in proptest::option::of(text()),
+        ) {
+            let mut option_explanations = notes;
+            option_explanations.truncate(options.len());
+            option_explanations.resize(options.len(), String::new());
+            // All-empty notes are the same as none.
+            if option_explanations.iter().all(String::is_empty) {
+                option_explanations.clear();
+            }
+            let quiz = Quiz::new("q", "Q").with_question(Question::MultipleChoice {
+                id: "mc".into(),
+                prompt,
+                correct: correct.index(options.len()),
+                options,
+                explanation: explanation.unwrap_or_default(),
+                points: 1,
+                option_explanations,
+            });
+            let exported = export(&quiz);
+            prop_assert!(exported.is_lossless());
+            let imported = import(&exported.value, "q", "Q");
+            prop_assert!(imported.is_lossless());
+            prop_assert_eq!(imported.value, quiz);
+        }
+    }
+}
@@ -0,0 +1,25 @@
+//! # Profesor Formats
+//!
+//! Import and export of quizzes in formats used by other learning
+//! management systems.
+//!
+//! Every conversion returns the converted value together with
+//! [`Diagnostic`]s for anything that had no equivalent in the target
+//! format, so nothing is lost silently.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(feature = "std")]
+extern crate std as alloc;
+
+pub mod aiken;
+mod diagnostic;
+pub mod gift;
+pub mod qti;
+
+pub use diagnostic::{Converted, Diagnostic, Severity};
@@ -0,0 +1,1581 @@
+//! IMS Question and Test Interoperability (QTI) 2.1 and 3.0.
+//!
+//! A quiz is written as a content package: an `imsmanifest.xml`, an
+//! `assessment.xml` test and one item per question under `items/`.
+//! Multiple choice and multiple select questions become choice
+//! interactions, ordering questions order interactions, matching questions
+//! match interactions and code completion questions text entry
+//! interactions inside the code. QTI 3.0 uses the same structure with
+//! `qti-` prefixed, kebab-case names; import reads either.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use profesor_core::{Blank, Question, QuestionId, Quiz};
+use roxmltree::{Document, Node};
+
+use crate::diagnostic::{question_kind, settings_dropped, Converted, Diagnostic};
+
+/// Path of the package manifest.
+const MANIFEST: &str = "imsmanifest.xml";
+
+/// Path of the assessment test.
+const TEST: &str = "assessment.xml";
+
+/// Identifier of single-response declarations.
+const RESPONSE: &str = "RESPONSE";
+
+/// Prefix of code completion blank responses.
+const BLANK_PREFIX: &str = "RESPONSE_";
+
+/// A QTI version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QtiVersion {
+    /// QTI 2.1
+    V2_1,
+    /// QTI 3.0
+    V3_0,
+}
+
+impl QtiVersion {
+    fn namespace(self) -> &'static str {
+        match self {
+            Self::V2_1 => "http://www.imsglobal.org/xsd/imsqti_v2p1",
+            Self::V3_0 => "http://www.imsglobal.org/xsd/imsqtiasi_v3p0",
+        }
+    }
+
+    fn manifest_namespace(self) -> &'static str {
+        match self {
+            Self::V2_1 => "http://www.imsglobal.org/xsd/imscp_v1p1",
+            Self::V3_0 => "http://www.imsglobal.org/xsd/qti/qtiv3p0/imscp_v1p1",
+        }
+    }
+
+    fn schema_version(self) -> &'static str {
+        match self {
+            Self::V2_1 => "2.1",
+            Self::V3_0 => "3.0.0",
+        }
+    }
+
+    fn resource_type(self, kind: &str) -> String {
+        match self {
+            Self::V2_1 => alloc::format!("imsqti_{}_xmlv2p1", kind),
+            Self::V3_0 => alloc::format!("imsqti_{}_xmlv3p0", kind),
+        }
+    }
+}
+
+/// A file in a content package.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageFile {
+    /// Path inside the package, `/`-separated
+    pub path: String,
+    /// XML text
+    pub content: String,
+}
+
+/// A QTI content package, ready to be zipped.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QtiPackage {
+    /// Files, manifest first
+    pub files: Vec<PackageFile>,
+}
+
+impl QtiPackage {
+    /// Add a file.
+    #[must_use]
+    pub fn with_file(mut self, path: impl Into<String>, content: impl Into<String>) -> Self {
+        self.files.push(PackageFile {
+            path: path.into(),
+            content: content.into(),
+        });
+        self
+    }
+
+    /// Get the content of a file.
+    #[must_use]
+    pub fn file(&self, path: &str) -> Option<&str> {
+        self.files
+            .iter()
+            .find(|f| f.path == path)
+            .map(|f| f.content.as_str())
+    }
+}
+
+/// Write a quiz as a QTI package.
+///
+/// Questions without a QTI interaction are skipped with an error
+/// diagnostic.
+#[must_use]
+pub fn export(quiz: &Quiz, version: QtiVersion) -> Converted<QtiPackage> {
+    let stored = Quiz {
+        time_limit_secs: None,
+        shuffle: false,
+        questions: Vec::new(),
+        ..quiz.clone()
+    };
+    let mut diagnostics = settings_dropped(&stored, "QTI");
+    let test_id = identifier(quiz.id.as_str());
+    if test_id != quiz.id.as_str() {
+        diagnostics.push(Diagnostic::warning(alloc::format!(
+            "quiz ID is stored as '{}'",
+            test_id
+        )));
+    }
+
+    let mut items: Vec<(String, Element)> = Vec::new();
+    for question in &quiz.questions {
+        let mut item_id = identifier(question.id().as_str());
+        if items.iter().any(|(id, _)| *id == item_id) {
+            item_id = alloc::format!("{}-{}", item_id, items.len() + 1);
+        }
+        let mut warnings = Vec::new();
+        let result = write_item(question, &item_id, &mut warnings);
+        diagnostics.extend(
+            warnings
+                .into_iter()
+                .map(|w| Diagnostic::warning(w).for_question(question.id())),
+        );
+        match result {
+            Ok(item) => items.push((item_id, item)),
+            Err(err) => diagnostics.push(Diagnostic::error(err).for_question(question.id())),
+        }
+    }
+
+    let mut section = Element::qti("assessmentSection")
+        .attr("identifier", "section1")
+        .attr("title", quiz.title.as_str())
+        .attr("visible", "true");
+    if quiz.shuffle {
+        section = section.child(Element::qti("ordering").attr("shuffle", "true"));
+    }
+    section = section.children(items.iter().map(|(id, _)| {
+        Element::qti("assessmentItemRef")
+            .attr("identifier", id.as_str())
+            .attr("href", item_path(id))
+    }));
+    let mut test = Element::qti("assessmentTest")
+        .attr("xmlns", version.namespace())
+        .attr("identifier", test_id.as_str())
+        .attr("title", quiz.title.as_str());
+    if let Some(secs) = quiz.time_limit_secs {
+        test = test.child(Element::qti("timeLimits").attr("maxTime", secs.to_string()));
+    }
+    test = test.child(
+        Element::qti("testPart")
+            .attr("identifier", "part1")
+            .attr("navigationMode", "linear")
+            .attr("submissionMode", "individual")
+            .child(section),
+    );
+
+    let resources = Element::plain("resources")
+        .child(
+            Element::plain("resource")
+                .attr("identifier", "test")
+                .attr("type", version.resource_type("test"))
+                .attr("href", TEST)
+                .child(Element::plain("file").attr("href", TEST))
+                .children(items.iter().map(|(id, _)| {
+                    Element::plain("dependency")
+                        .attr("identifierref", alloc::format!("item-{}", id))
+                })),
+        )
+        .children(items.iter().map(|(id, _)| {
+            Element::plain("resource")
+                .attr("identifier", alloc::format!("item-{}", id))
+                .attr("type", version.resource_type("item"))
+                .attr("href", item_path(id))
+                .child(Element::plain("file").attr("href", item_path(id)))
+        }));
+    let manifest = Element::plain("manifest")
+        .attr("xmlns", version.manifest_namespace())
+        .attr("identifier", alloc::format!("manifest-{}", test_id))
+        .child(
+            Element::plain("metadata")
+                .child(Element::plain("schema").text("QTI Package"))
+                .child(Element::plain("schemaversion").text(version.schema_version())),
+        )
+        .child(Element::plain("organizations"))
+        .child(resources);
+
+    let mut package = QtiPackage::default()
+        .with_file(MANIFEST, document(&manifest, version))
+        .with_file(TEST, document(&test, version));
+    for (id, item) in items {
+        let mut item = item;
+        item.attrs
+            .insert(0, ("xmlns", String::from(version.namespace())));
+        package = package.with_file(item_path(&id), document(&item, version));
+    }
+
+    Converted {
+        value: package,
+        diagnostics,
+    }
+}
+
+/// Read a QTI 2.1 or 3.0 package.
+///
+/// Items come in test order, or manifest order for packages without a
+/// test. Items that cannot be read are skipped with an error diagnostic.
+pub fn import(package: &QtiPackage) -> Converted<Quiz> {
+    let mut diagnostics = Vec::new();
+    let Some(manifest) = parse(package, MANIFEST, &mut diagnostics) else {
+        return Converted {
+            value: Quiz::new("", ""),
+            diagnostics,
+        };
+    };
+
+    let resources: Vec<(&str, &str)> = manifest
+        .descendants()
+        .filter(|n| n.has_tag_name("resource"))
+        .filter_map(|n| Some((n.attribute("type")?, n.attribute("href")?)))
+        .collect();
+    let test_path = resources
+        .iter()
+        .find(|(kind, _)| kind.starts_with("imsqti_test_"))
+        .map(|(_, href)| *href);
+
+    let mut quiz;
+    let mut item_paths = Vec::new();
+    match test_path.and_then(|path| Some((path, parse(package, path, &mut diagnostics)?))) {
+        Some((path, test)) => {
+            let root = test.root_element();
+            quiz = Quiz::new(
+                attr(root, "identifier").unwrap_or_default(),
+                attr(root, "title").unwrap_or_default(),
+            );
+            for node in root.descendants().filter(|n| n.is_element()) {
+                match name(node).as_str() {
+                    "timeLimits" => {
+                        quiz.time_limit_secs = attr(node, "maxTime")
+                            .and_then(|t| t.parse::<f64>().ok())
+                            .map(|t| t.round() as u32);
+                    }
+                    "ordering" => quiz.shuffle |= attr(node, "shuffle") == Some("true"),
+                    "assessmentItemRef" => {
+                        if let Some(href) = attr(node, "href") {
+                            item_paths.push(resolve(path, href));
+                        }
+                    }
+                    "selection" => diagnostics.push(
+                        Diagnostic::warning(alloc::format!(
+                            "{}: section selection is dropped",
+                            path
+                        ))
+                        .at_line(line(&test, node)),
+                    ),
+                    _ => {}
+                }
+            }
+        }
+        None => {
+            let root = manifest.root_element();
+            quiz = Quiz::new(root.attribute("identifier").unwrap_or_default(), "");
+            item_paths.extend(
+                resources
+                    .iter()
+                    .filter(|(kind, _)| kind.starts_with("imsqti_item_"))
+                    .map(|(_, href)| resolve(MANIFEST, href)),
+            );
+        }
+    }
+
+    for path in &item_paths {
+        let Some(item) = parse(package, path, &mut diagnostics) else {
+            continue;
+        };
+        let root = item.root_element();
+        let id = QuestionId::new(
+            attr(root, "title")
+                .filter(|t| !t.is_empty())
+                .or_else(|| attr(root, "identifier"))
+                .unwrap_or_default(),
+        );
+        let mut warnings = Vec::new();
+        let result = read_item(&item, id.clone(), &mut warnings);
+        diagnostics.extend(warnings.into_iter().map(|(at, message)| {
+            Diagnostic::warning(alloc::format!("{}: {}", path, message))
+                .at_line(at)
+                .for_question(&id)
+        }));
+        match result {
+            Ok(question) => quiz.questions.push(question),
+            Err((at, message)) => diagnostics.push(
+                Diagnostic::error(alloc::format!("{}: {}", path, message))
+                    .at_line(at)
+                    .for_question(&id),
+            ),
+        }
+    }
+
+    Converted {
+        value: quiz,
+        diagnostics,
+    }
+}
+
+fn write_item(
+    question: &Question,
+    item_id: &str,
+    warnings: &mut Vec<String>,
+) -> Result<Element, String> {
+    let question = match question {
+        Question::Hinted { question, hints } => {
+            if !hints.is_empty() {
+                warnings.push(String::from("hints are dropped"));
+            }
+            question.as_ref()
+        }
+        other => other,
+    };
+
+    let mut declarations = Vec::new();
+    let mut conditions = Vec::new();
+    let body;
+    let (points, explanation) = match question {
+        Question::MultipleChoice {
+            prompt,
+            options,
+            explanation,
+            points,
+            option_explanations,
+            ..
+        }
+        | Question::MultipleSelect {
+            prompt,
+            options,
+            explanation,
+            points,
+            option_explanations,
+            ..
+        } => {
+            if option_explanations.iter().any(|e| !e.is_empty()) {
+                warnings.push(String::from("option explanations are dropped"));
+            }
+            let (cardinality, max_choices, correct) = match question {
+                Question::MultipleSelect { correct, .. } => {
+                    ("multiple", options.len(), correct.clone())
+                }
+                Question::MultipleChoice { correct, .. } => ("single", 1, alloc::vec![*correct]),
+                _ => ("multiple", options.len(), Vec::new()),
+            };
+            if correct.iter().any(|&c| c >= options.len()) {
+                return Err(String::from("correct option is out of range"));
+            }
+            declarations.push(declaration(
+                RESPONSE,
+                cardinality,
+                "identifier",
+                correct.iter().map(|c| alloc::format!("C{}", c)),
+            ));
+            conditions.push(match_correct(RESPONSE));
+            body = Element::qti("itemBody").child(
+                Element::qti("choiceInteraction")
+                    .attr("responseIdentifier", RESPONSE)
+                    .attr("shuffle", "false")
+                    .attr("maxChoices", max_choices.to_string())
+                    .child(Element::qti("prompt").text(prompt.as_str()))
+                    .children(options.iter().enumerate().map(|(i, option)| {
+                        Element::qti("simpleChoice")
+                            .attr("identifier", alloc::format!("C{}", i))
+                            .text(option.as_str())
+                    })),
+            );
+            (*points, explanation)
+        }
+        Question::Ordering {
+            prompt,
+            items,
+            correct_order,
+            explanation,
+            points,
+            ..
+        } => {
+            if correct_order.iter().any(|&i| i >= items.len()) {
+                return Err(String::from("correct order is out of range"));
+            }
+            declarations.push(declaration(
+                RESPONSE,
+                "ordered",
+                "identifier",
+                correct_order.iter().map(|i| alloc::format!("I{}", i)),
+            ));
+            conditions.push(match_correct(RESPONSE));
+            body = Element::qti("itemBody").child(
+                Element::qti("orderInteraction")
+                    .attr("responseIdentifier", RESPONSE)
+                    .attr("shuffle", "false")
+                    .child(Element::qti("prompt").text(prompt.as_str()))
+                    .children(items.iter().enumerate().map(|(i, item)| {
+                        Element::qti("simpleChoice")
+                            .attr("identifier", alloc::format!("I{}", i))
+                            .text(item.as_str())
+                    })),
+            );
+            (*points, explanation)
+        }
+        Question::Matching {
+            prompt,
+            left,
+            right,
+            correct_pairs,
+            points,
+            explanation,
+            ..
+        } => {
+            if correct_pairs
+                .iter()
+                .any(|&(l, r)| l >= left.len() || r >= right.len())
+            {
+                return Err(String::from("correct pair is out of range"));
+            }
+            declarations.push(declaration(
+                RESPONSE,
+                "multiple",
+                "directedPair",
+                correct_pairs
+                    .iter()
+                    .map(|(l, r)| alloc::format!("L{} R{}", l, r)),
+            ));
+            conditions.push(match_correct(RESPONSE));
+            let set = |prefix: &str, texts: &[String], match_max: &str| {
+                Element::qti("simpleMatchSet").children(texts.iter().enumerate().map(
+                    |(i, text)| {
+                        Element::qti("simpleAssociableChoice")
+                            .attr("identifier", alloc::format!("{}{}", prefix, i))
+                            .attr("matchMax", match_max)
+                            .text(text.as_str())
+                    },
+                ))
+            };
+            body = Element::qti("itemBody").child(
+                Element::qti("matchInteraction")
+                    .attr("responseIdentifier", RESPONSE)
+                    .attr("shuffle", "false")
+                    .attr("maxAssociations", left.len().to_string())
+                    .child(Element::qti("prompt").text(prompt.as_str()))
+                    .child(set("L", left, "1"))
+                    .child(set("R", right, "0")),
+            );
+            (*points, explanation)
+        }
+        Question::CodeCompletion {
+            prompt,
+            code_template,
+            blanks,
+            test_cases,
+            points,
+            explanation,
+            ..
+        } => {
+            if blanks.iter().any(|b| b.hint.is_some()) {
+                warnings.push(String::from("blank hints are dropped"));
+            }
+            if blanks.iter().any(|b| !b.explanation.is_empty()) {
+                warnings.push(String::from("blank explanations are dropped"));
+            }
+            if !test_cases.is_empty() {
+                warnings.push(String::from("test cases are dropped"));
+            }
+            if blanks.is_empty() {
+                return Err(String::from(
+                    "code completion without blanks has no interaction",
+                ));
+            }
+            let mut responses = Vec::new();
+            for blank in blanks {
+                let response = alloc::format!("{}{}", BLANK_PREFIX, identifier(&blank.id));
+                if response[BLANK_PREFIX.len()..] != *blank.id {
+                    warnings.push(alloc::format!(
+                        "blank '{}' is stored as '{}'",
+                        blank.id,
+                        &response[BLANK_PREFIX.len()..]
+                    ));
+                }
+                declarations.push(blank_declaration(&response, blank));
+                if !blank.acceptable_answers.is_empty() {
+                    conditions.push(
+                        Element::qti("gte")
+                            .child(
+                                Element::qti("mapResponse").attr("identifier", response.as_str()),
+                            )
+                            .child(base_value("float", "1")),
+                    );
+                }
+                responses.push((blank, response));
+            }
+
+            let mut code = Element::plain("pre");
+            let mut rest = code_template.as_str();
+            while let Some(start) = rest.find("{{") {
+                let blank = rest[start + 2..].find("}}").and_then(|end| {
+                    let id = &rest[start + 2..start + 2 + end];
+                    let (_, response) = responses.iter().find(|(b, _)| b.id == id)?;
+                    Some((start + 2 + end + 2, response))
+                });
+                match blank {
+                    Some((end, response)) => {
+                        code = code.text(&rest[..start]).child(
+                            Element::qti("textEntryInteraction")
+                                .attr("responseIdentifier", response.as_str())
+                                .attr("expectedLength", expected_length(response, blanks)),
+                        );
+                        rest = &rest[end..];
+                    }
+                    None => {
+                        code = code.text(&rest[..start + 2]);
+                        rest = &rest[start + 2..];
+                    }
+                }
+            }
+            code = code.text(rest);
+
+            let mut item_body = Element::qti("itemBody");
+            if !prompt.is_empty() {
+                item_body = item_body.child(Element::plain("p").text(prompt.as_str()));
+            }
+            body = item_body.child(code);
+            (*points, explanation)
+        }
+        other => {
+            return Err(alloc::format!(
+                "{} questions have no QTI equivalent",
+                question_kind(other)
+            ))
+        }
+    };
+
+    let feedback = !explanation.is_empty();
+    let mut item = Element::qti("assessmentItem")
+        .attr("identifier", item_id)
+        .attr("title", question.id().as_str())
+        .attr("adaptive", "false")
+        .attr("timeDependent", "false")
+        .children(declarations)
+        .child(outcome("SCORE", "float", Some("0")))
+        .child(outcome("MAXSCORE", "float", Some(&points.to_string())));
+    if feedback {
+        item = item.child(outcome("FEEDBACK", "identifier", None));
+    }
+    item = item
+        .child(body)
+        .child(response_processing(conditions, points, feedback));
+    if feedback {
+        item = item.child(
+            Element::qti("modalFeedback")
+                .attr("outcomeIdentifier", "FEEDBACK")
+                .attr("identifier", "EXPLANATION")
+                .attr("showHide", "show")
+                .text(explanation.as_str()),
+        );
+    }
+    Ok(item)
+}
+
+fn declaration(
+    identifier: &str,
+    cardinality: &str,
+    base_type: &str,
+    correct: impl Iterator<Item = String>,
+) -> Element {
+    let values: Vec<Element> = correct.map(|v| Element::qti("value").text(v)).collect();
+    let declaration = Element::qti("responseDeclaration")
+        .attr("identifier", identifier)
+        .attr("cardinality", cardinality)
+        .attr("baseType", base_type);
+    if values.is_empty() {
+        return declaration;
+    }
+    declaration.child(Element::qti("correctResponse").children(values))
+}
+
+fn blank_declaration(response: &str, blank: &Blank) -> Element {
+    let declaration = declaration(
+        response,
+        "single",
+        "string",
+        blank.acceptable_answers.iter().take(1).cloned(),
+    );
+    if blank.acceptable_answers.is_empty() {
+        return declaration;
+    }
+    declaration.child(Element::qti("mapping").attr("defaultValue", "0").children(
+        blank.acceptable_answers.iter().map(|answer| {
+            Element::qti("mapEntry")
+                .attr("mapKey", answer.as_str())
+                .attr("mappedValue", "1")
+                .attr("caseSensitive", "true")
+        }),
+    ))
+}
+
+fn expected_length(response: &str, blanks: &[Blank]) -> String {
+    blanks
+        .iter()
+        .find(|b| response.ends_with(identifier(&b.id).as_str()))
+        .and_then(|b| b.acceptable_answers.iter().map(|a| a.chars().count()).max())
+        .unwrap_or(0)
+        .max(1)
+        .to_string()
+}
+
+fn outcome(identifier: &str, base_type: &str, default: Option<&str>) -> Element {
+    let outcome = Element::qti("outcomeDeclaration")
+        .attr("identifier", identifier)
+        .attr("cardinality", "single")
+        .attr("baseType", base_type);
+    match default {
+        Some(value) => {
+            outcome.child(Element::qti("defaultValue").child(Element::qti("value").text(value)))
+        }
+        None => outcome,
+    }
+}
+
+fn base_value(base_type: &str, value: &str) -> Element {
+    Element::qti("baseValue")
+        .attr("baseType", base_type)
+        .text(value)
+}
+
+fn set_outcome(identifier: &str, value: Element) -> Element {
+    Element::qti("setOutcomeValue")
+        .attr("identifier", identifier)
+        .child(value)
+}
+
+fn match_correct(response: &str) -> Element {
+    Element::qti("match")
+        .child(Element::qti("variable").attr("identifier", response))
+        .child(Element::qti("correct").attr("identifier", response))
+}
+
+/// Award the points when every condition holds and show the explanation.
+fn response_processing(mut conditions: Vec<Element>, points: u32, feedback: bool) -> Element {
+    let score = set_outcome("SCORE", base_value("float", &points.to_string()));
+    let mut processing = Element::qti("responseProcessing");
+    processing = match conditions.len() {
+        0 => processing.child(score),
+        1 => processing.child(
+            Element::qti("responseCondition").child(
+                Element::qti("responseIf")
+                    .children(conditions.pop())
+                    .child(score),
+            ),
+        ),
+        _ => processing.child(
+            Element::qti("responseCondition").child(
+                Element::qti("responseIf")
+                    .child(Element::qti("and").children(conditions))
+                    .child(score),
+            ),
+        ),
+    };
+    if feedback {
+        processing = processing.child(set_outcome(
+            "FEEDBACK",
+            base_value("identifier", "EXPLANATION"),
+        ));
+    }
+    processing
+}
+
+/// A response declaration being read.
+struct Declaration {
+    identifier: String,
+    cardinality: String,
+    base_type: String,
+    correct: Vec<String>,
+    mapping: Vec<String>,
+}
+
+fn read_item(
+    doc: &Document,
+    id: QuestionId,
+    warnings: &mut Vec<(usize, String)>,
+) -> Result<Question, (usize, String)> {
+    let root = doc.root_element();
+    if name(root) != "assessmentItem" {
+        return Err((line(doc, root), String::from("expected an assessment item")));
+    }
+
+    let declarations: Vec<Declaration> = children(root, "responseDeclaration")
+        .map(|node| Declaration {
+            identifier: String::from(attr(node, "identifier").unwrap_or_default()),
+            cardinality: String::from(attr(node, "cardinality").unwrap_or_default()),
+            base_type: String::from(attr(node, "baseType").unwrap_or_default()),
+            correct: child(node, "correctResponse")
+                .map(|c| children(c, "value").map(text).collect())
+                .unwrap_or_default(),
+            mapping: child(node, "mapping")
+                .map(|m| {
+                    children(m, "mapEntry")
+                        .filter_map(|e| attr(e, "mapKey").map(String::from))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        })
+        .collect();
+
+    let mut points = 1;
+    for node in children(root, "outcomeDeclaration") {
+        if attr(node, "identifier") != Some("MAXSCORE") {
+            continue;
+        }
+        let value = child(node, "defaultValue")
+            .and_then(|d| child(d, "value"))
+            .map(text)
+            .and_then(|v| v.trim().parse::<f64>().ok());
+        if let Some(value) = value {
+            if value.fract() != 0.0 || value < 0.0 {
+                warnings.push((
+                    line(doc, node),
+                    alloc::format!("{} points rounded to a whole number", value),
+                ));
+            }
+            points = value.max(0.0).round() as u32;
+        }
+    }
+
+    let explanation = children(root, "modalFeedback")
+        .map(|f| String::from(text(f).trim()))
+        .filter(|t| !t.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    let Some(body) = child(root, "itemBody") else {
+        return Err((line(doc, root), String::from("item has no body")));
+    };
+    let interactions: Vec<Node> = body
+        .descendants()
+        .filter(|n| n.is_element() && name(*n).ends_with("Interaction"))
+        .collect();
+    let Some(&first) = interactions.first() else {
+        return Err((line(doc, body), String::from("item has no interaction")));
+    };
+    let kind = name(first);
+    for &interaction in &interactions {
+        let other = name(interaction);
+        if !matches!(
+            other.as_str(),
+            "choiceInteraction" | "orderInteraction" | "matchInteraction" | "textEntryInteraction"
+        ) {
+            return Err((
+                line(doc, interaction),
+                alloc::format!("{} is not supported", other),
+            ));
+        }
+        if other != kind || (kind != "textEntryInteraction" && interaction != first) {
+            return Err((
+                line(doc, interaction),
+                String::from("items with several interactions are not supported"),
+            ));
+        }
+    }
+
+    let response = |interaction: Node| {
+        let identifier = attr(interaction, "responseIdentifier").unwrap_or_default();
+        declarations
+            .iter()
+            .find(|d| d.identifier == identifier)
+            .ok_or_else(|| {
+                (
+                    line(doc, interaction),
+                    alloc::format!("response '{}' is not declared", identifier),
+                )
+            })
+    };
+    let index = |choices: &[&str], value: &str| {
+        choices.iter().position(|c| *c == value).ok_or_else(|| {
+            (
+                line(doc, first),
+                alloc::format!("correct response '{}' is not a choice", value),
+            )
+        })
+    };
+
+    if kind == "textEntryInteraction" {
+        let container = first.parent().unwrap_or(body);
+        let mut code_template = String::new();
+        for node in container.children() {
+            if node.is_text() {
+                code_template.push_str(node.text().unwrap_or_default());
+            } else if name(node) == "textEntryInteraction" {
+                let declaration = response(node)?;
+                let blank = declaration
+                    .identifier
+                    .strip_prefix(BLANK_PREFIX)
+                    .unwrap_or(&declaration.identifier);
+                code_template.push_str(&alloc::format!("{{{{{}}}}}", blank));
+            } else {
+                code_template.push_str(&text(node));
+            }
+        }
+        if let Some(&stray) = interactions.iter().find(|n| n.parent() != first.parent()) {
+            return Err((
+                line(doc, stray),
+                String::from("text entries in several blocks are not supported"),
+            ));
+        }
+        let blanks = declarations
+            .iter()
+            .filter(|d| d.base_type == "string")
+            .map(|d| Blank {
+                id: String::from(
+                    d.identifier
+                        .strip_prefix(BLANK_PREFIX)
+                        .unwrap_or(&d.identifier),
+                ),
+                acceptable_answers: if d.mapping.is_empty() {
+                    d.correct.clone()
+                } else {
+                    d.mapping.clone()
+                },
+                hint: None,
+                explanation: String::new(),
+            })
+            .collect();
+        return Ok(Question::CodeCompletion {
+            id,
+            prompt: prompt(body, container),
+            code_template,
+            blanks,
+            test_cases: Vec::new(),
+            points,
+            explanation,
+        });
+    }
+
+    let declaration = response(first)?;
+    if !declaration.mapping.is_empty() {
+        warnings.push((
+            line(doc, first),
+            String::from("partial-credit mapping is dropped"),
+        ));
+    }
+    let prompt = prompt(body, first);
+    match kind.as_str() {
+        "choiceInteraction" | "orderInteraction" => {
+            let ids: Vec<&str> = children(first, "simpleChoice")
+                .map(|c| attr(c, "identifier").unwrap_or_default())
+                .collect();
+            let options: Vec<String> = children(first, "simpleChoice")
+                .map(|c| String::from(text(c).trim()))
+                .collect();
+            let correct = declaration
+                .correct
+                .iter()
+                .map(|v| index(&ids, v.trim()))
+                .collect::<Result<Vec<_>, _>>()?;
+            if kind == "orderInteraction" {
+                return Ok(Question::Ordering {
+                    id,
+                    prompt,
+                    items: options,
+                    correct_order: correct,
+                    explanation,
+                    points,
+                });
+            }
+            match (declaration.cardinality.as_str(), correct.as_slice()) {
+                ("single", [correct]) => Ok(Question::MultipleChoice {
+                    id,
+                    prompt,
+                    options,
+                    correct: *correct,
+                    explanation,
+                    points,
+                    option_explanations: Vec::new(),
+                }),
+                ("single", _) => Err((
+                    line(doc, first),
+                    String::from("single choice needs exactly one correct response"),
+                )),
+                _ => Ok(Question::MultipleSelect {
+                    id,
+                    prompt,
+                    options,
+                    correct,
+                    explanation,
+                    points,
+                    option_explanations: Vec::new(),
+                }),
+            }
+        }
+        _ => {
+            let sets: Vec<Node> = children(first, "simpleMatchSet").collect();
+            let [left, right] = sets.as_slice() else {
+                return Err((
+                    line(doc, first),
+                    String::from("match interaction needs two match sets"),
+                ));
+            };
+            let (left_ids, left) = match_set(*left);
+            let (right_ids, right) = match_set(*right);
+            let mut correct_pairs = Vec::new();
+            for pair in &declaration.correct {
+                let mut ends = pair.split_whitespace();
+                let (Some(l), Some(r), None) = (ends.next(), ends.next(), ends.next()) else {
+                    return Err((line(doc, first), alloc::format!("'{}' is not a pair", pair)));
+                };
+                correct_pairs.push((index(&left_ids, l)?, index(&right_ids, r)?));
+            }
+            Ok(Question::Matching {
+                id,
+                prompt,
+                left,
+                right,
+                correct_pairs,
+                points,
+                explanation,
+            })
+        }
+    }
+}
+
+/// Read the choice identifiers and texts of a match set.
+fn match_set<'a>(set: Node<'a, '_>) -> (Vec<&'a str>, Vec<String>) {
+    let choices = || children(set, "simpleAssociableChoice");
+    (
+        choices()
+            .map(|c| attr(c, "identifier").unwrap_or_default())
+            .collect(),
+        choices().map(|c| String::from(text(c).trim())).collect(),
+    )
+}
+
+/// Collect the prompt: an interaction's `prompt` and the body text outside
+/// the interaction.
+fn prompt(body: Node, interaction: Node) -> String {
+    let mut parts: Vec<String> = body
+        .children()
+        .filter(|n| n.is_element() && !n.descendants().any(|d| d == interaction))
+        .map(|n| String::from(text(n).trim()))
+        .collect();
+    parts.extend(child(interaction, "prompt").map(|p| String::from(text(p).trim())));
+    parts.retain(|p| !p.is_empty());
+    parts.join("\n\n")
+}
+
+/// Parse a package file, reporting it if missing or malformed.
+fn parse<'a>(
+    package: &'a QtiPackage,
+    path: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Option<Document<'a>> {
+    let Some(content) = package.file(path) else {
+        diagnostics.push(Diagnostic::error(alloc::format!(
+            "{}: missing from the package",
+            path
+        )));
+        return None;
+    };
+    match Document::parse(content) {
+        Ok(doc) => Some(doc),
+        Err(err) => {
+            diagnostics.push(
+                Diagnostic::error(alloc::format!("{}: {}", path, err))
+                    .at_line(err.pos().row as usize),
+            );
+            None
+        }
+    }
+}
+
+/// Get an element's QTI 2.1 name, converting QTI 3.0 names.
+fn name(node: Node) -> String {
+    let name = node.tag_name().name();
+    match name.strip_prefix("qti-") {
+        Some(kebab) => {
+            let mut camel = String::new();
+            let mut upper = false;
+            for c in kebab.chars() {
+                if c == '-' {
+                    upper = true;
+                } else if upper {
+                    camel.extend(c.to_uppercase());
+                    upper = false;
+                } else {
+                    camel.push(c);
+                }
+            }
+            camel
+        }
+        None => String::from(name),
+    }
+}
+
+/// Get an attribute by its QTI 2.1 name or the QTI 3.0 equivalent.
+fn attr<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    node.attribute(name)
+        .or_else(|| node.attribute(kebab(name).as_str()))
+}
+
+fn children<'a, 'input>(
+    node: Node<'a, 'input>,
+    tag: &'static str,
+) -> impl Iterator<Item = Node<'a, 'input>> {
+    node.children()
+        .filter(move |n| n.is_element() && name(*n) == tag)
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, tag: &'static str) -> Option<Node<'a, 'input>> {
+    children(node, tag).next()
+}
+
+/// Get all text inside a node.
+fn text(node: Node) -> String {
+    node.descendants()
+        .filter(Node::is_text)
+        .filter_map(|n| n.text())
+        .collect()
+}
+
+fn line(doc: &Document, node: Node) -> usize {
+    doc.text_pos_at(node.range().start).row as usize
+}
+
+/// Resolve an `href` relative to the file that contains it.
+fn resolve(base: &str, href: &str) -> String {
+    let mut parts: Vec<&str> = base.split('/').collect();
+    parts.pop();
+    for part in href.split('/') {
+        match part {
+            "." | "" => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+fn item_path(id: &str) -> String {
+    alloc::format!("items/{}.xml", id)
+}
+
+/// Turn an ID into a QTI identifier.
+fn identifier(id: &str) -> String {
+    let mut out: String = id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if !out.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        out.insert(0, '_');
+    }
+    out
+}
+
+fn kebab(name: &str) -> String {
+    let mut out = String::new();
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            out.push('-');
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Element or text content.
+enum Content {
+    Element(Element),
+    Text(String),
+}
+
+/// An XML element being written.
+struct Element {
+    name: &'static str,
+    /// Whether QTI 3.0 renames it
+    qti: bool,
+    attrs: Vec<(&'static str, String)>,
+    content: Vec<Content>,
+}
+
+impl Element {
+    fn qti(name: &'static str) -> Self {
+        Self {
+            name,
+            qti: true,
+            attrs: Vec::new(),
+            content: Vec::new(),
+        }
+    }
+
+    fn plain(name: &'static str) -> Self {
+        Self {
+            qti: false,
+            ..Self::qti(name)
+        }
+    }
+
+    fn attr(mut self, name: &'static str, value: impl Into<String>) -> Self {
+        self.attrs.push((name, value.into()));
+        self
+    }
+
+    fn child(mut self, child: Element) -> Self {
+        self.content.push(Content::Element(child));
+        self
+    }
+
+    fn children(mut self, children: impl IntoIterator<Item = Element>) -> Self {
+        self.content
+            .extend(children.into_iter().map(Content::Element));
+        self
+    }
+
+    fn text(mut self, text: impl Into<String>) -> Self {
+        let text = text.into();
+        if !text.is_empty() {
+            self.content.push(Content::Text(text));
+        }
+        self
+    }
+
+    /// Write the element, indenting only elements without text or code.
+    fn write(&self, version: QtiVersion, out: &mut String, depth: Option<usize>) {
+        let renamed = self.qti && version == QtiVersion::V3_0;
+        out.push('<');
+        if renamed {
+            out.push_str("qti-");
+            out.push_str(&kebab(self.name));
+        } else {
+            out.push_str(self.name);
+        }
+        for (name, value) in &self.attrs {
+            out.push(' ');
+            if renamed {
+                out.push_str(&kebab(name));
+            } else {
+                out.push_str(name);
+            }
+            out.push_str("=\"");
+            escape(value, true, out);
+            out.push('"');
+        }
+        if self.content.is_empty() {
+            out.push_str("/>");
+            return;
+        }
+        out.push('>');
+
+        let depth = depth.filter(|_| {
+            self.name != "pre"
+                && self
+                    .content
+                    .iter()
+                    .all(|c| matches!(c, Content::Element(_)))
+        });
+        for content in &self.content {
+            match content {
+                Content::Element(element) => {
+                    if let Some(depth) = depth {
+                        indent(out, depth + 1);
+                    }
+                    element.write(version, out, depth.map(|d| d + 1));
+                }
+                Content::Text(text) => escape(text, false, out),
+            }
+        }
+        if let Some(depth) = depth {
+            indent(out, depth);
+        }
+
+        out.push_str("</");
+        if renamed {
+            out.push_str("qti-");
+            out.push_str(&kebab(self.name));
+        } else {
+            out.push_str(self.name);
+        }
+        out.push('>');
+    }
+}
+
+fn indent(out: &mut String, depth: usize) {
+    out.push('\n');
+    for _ in 0..depth {
+        out.push_str("  ");
+    }
+}
+
+fn escape(text: &str, attribute: bool, out: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' if attribute => out.push_str("&quot;"),
+            '\n' if attribute => out.push_str("&#10;"),
+            '\t' if attribute => out.push_str("&#9;"),
+            '\r' => out.push_str("&#13;"),
+            c => out.push(c),
+        }
+    }
+}
+
+fn document(root: &Element, version: QtiVersion) -> String {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    root.write(version, &mut out, Some(0));
+    out.push('\n');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Severity;
+    use profesor_core::TestCase;
+
+    fn sample() -> Quiz {
+        Quiz::new("rust-basics", "Rust & Friends")
+            .with_time_limit(600)
+            .with_shuffle(true)
+            .with_question(Question::MultipleChoice {
+                id: "owns".into(),
+                prompt: "Which type owns <its> data?".into(),
+                options: alloc::vec!["&str".into(), "String".into()],
+                correct: 1,
+                explanation: "String owns its buffer".into(),
+                points: 2,
+                option_explanations: Vec::new(),
+            })
+            .with_question(Question::MultipleSelect {
+                id: "copy".into(),
+                prompt: "Which are Copy?".into(),
+                options: alloc::vec!["u8".into(), "Vec<u8>".into(), "char".into()],
+                correct: alloc::vec![0, 2],
+                explanation: String::new(),
+                points: 1,
+                option_explanations: Vec::new(),
+            })
+            .with_question(Question::Ordering {
+                id: "build".into(),
+                prompt: "Order the steps".into(),
+                items: alloc::vec!["parse".into(), "check".into(), "codegen".into()],
+                correct_order: alloc::vec![0, 1, 2],
+                explanation: String::new(),
+                points: 3,
+            })
+            .with_question(Question::Matching {
+                id: "traits".into(),
+                prompt: "Match each trait".into(),
+                left: alloc::vec!["Clone".into(), "Debug".into()],
+                right: alloc::vec!["{:?}".into(), ".clone()".into()],
+                correct_pairs: alloc::vec![(0, 1), (1, 0)],
+                points: 2,
+                explanation: "Derive both".into(),
+            })
+            .with_question(Question::CodeCompletion {
+                id: "add".into(),
+                prompt: "Complete the function".into(),
+                code_template: "fn add(a: i32, b: i32) -> {{ret}} {\n    a {{op}} b\n}".into(),
+                blanks: alloc::vec![
+                    Blank::new("ret").with_answer("i32"),
+                    Blank::new("op").with_answer("+").with_answer("+ 0 +"),
+                ],
+                test_cases: Vec::new(),
+                points: 4,
+                explanation: String::new(),
+            })
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for version in [QtiVersion::V2_1, QtiVersion::V3_0] {
+            let exported = export(&sample(), version);
+            assert!(exported.is_lossless(), "{:?}", exported.diagnostics);
+            let imported = import(&exported.value);
+            assert!(imported.is_lossless(), "{:?}", imported.diagnostics);
+            assert_eq!(imported.value, sample());
+        }
+    }
+
+    #[test]
+    fn test_package_layout() {
+        let package = export(&sample(), QtiVersion::V2_1).value;
+        let paths: Vec<&str> = package.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            alloc::vec![
+                "imsmanifest.xml",
+                "assessment.xml",
+                "items/owns.xml",
+                "items/copy.xml",
+                "items/build.xml",
+                "items/traits.xml",
+                "items/add.xml",
+            ]
+        );
+        let manifest = package.file(MANIFEST).unwrap();
+        assert!(manifest.contains("type=\"imsqti_item_xmlv2p1\" href=\"items/owns.xml\""));
+        let item = package.file("items/owns.xml").unwrap();
+        assert!(item.contains("<choiceInteraction responseIdentifier=\"RESPONSE\""));
+        assert!(item.contains("<prompt>Which type owns &lt;its&gt; data?</prompt>"));
+        let code = package.file("items/add.xml").unwrap();
+        assert!(code.contains(
+            "-&gt; <textEntryInteraction responseIdentifier=\"RESPONSE_ret\" expectedLength=\"3\"/> {"
+        ));
+
+        let package = export(&sample(), QtiVersion::V3_0).value;
+        assert!(package
+            .file(MANIFEST)
+            .unwrap()
+            .contains("type=\"imsqti_test_xmlv3p0\""));
+        let item = package.file("items/traits.xml").unwrap();
+        assert!(item.contains("<qti-match-interaction response-identifier=\"RESPONSE\""));
+        assert!(item.contains("<qti-simple-associable-choice identifier=\"R0\" match-max=\"0\">"));
+    }
+
+    #[test]
+    fn test_export_reports_losses() {
+        let quiz = Quiz::new("q 1", "Q")
+            .with_max_attempts(2)
+            .with_question(Question::Hinted {
+                question: alloc::boxed::Box::new(Question::CodeCompletion {
+                    id: "hello".into(),
+                    prompt: "Print".into(),
+                    code_template: "println!(\"{{msg}}\")".into(),
+                    blanks: alloc::vec![Blank::new("msg").with_answer("hi").with_hint("greet")],
+                    test_cases: alloc::vec![TestCase {
+                        name: "runs".into(),
+                        input: String::new(),
+                        expected_output: "hi".into(),
+                        timeout_ms: 1000,
+                    }],
+                    points: 1,
+                    explanation: String::new(),
+                }),
+                hints: alloc::vec![profesor_core::QuestionHint::new("Use a string", 0.5)],
+            })
+            .with_question(Question::ShortAnswer {
+                id: "name".into(),
+                prompt: "Name it".into(),
+                answers: Vec::new(),
+                explanation: String::new(),
+                points: 1,
+            });
+        let exported = export(&quiz, QtiVersion::V2_1);
+        let messages: Vec<String> = exported
+            .diagnostics
+            .iter()
+            .map(|d| alloc::format!("{}", d))
+            .collect();
+        assert_eq!(
+            messages,
+            alloc::vec![
+                String::from("warning: attempt limit has no QTI equivalent"),
+                String::from("warning: quiz ID is stored as 'q_1'"),
+                String::from("warning: question 'hello': hints are dropped"),
+                String::from("warning: question 'hello': blank hints are dropped"),
+                String::from("warning: question 'hello': test cases are dropped"),
+                String::from(
+                    "error: question 'name': short answer questions have no QTI equivalent"
+                ),
+            ]
+        );
+        assert_eq!(exported.value.files.len(), 3);
+    }
+
+    #[test]
+    fn test_import_reports_unsupported() {
+        let item = "<?xml version=\"1.0\"?>
+<assessmentItem xmlns=\"http://www.imsglobal.org/xsd/imsqti_v2p1\" identifier=\"i1\" title=\"Slider\">
+  <responseDeclaration identifier=\"RESPONSE\" cardinality=\"single\" baseType=\"integer\"/>
+  <itemBody>
+    <sliderInteraction responseIdentifier=\"RESPONSE\" lowerBound=\"0\" upperBound=\"10\"/>
+  </itemBody>
+</assessmentItem>";
+        let choice = "<assessmentItem identifier=\"i2\" title=\"\">
+  <responseDeclaration identifier=\"RESPONSE\" cardinality=\"single\" baseType=\"identifier\">
+    <correctResponse><value>A</value></correctResponse>
+    <mapping defaultValue=\"0\"><mapEntry mapKey=\"A\" mappedValue=\"1\"/></mapping>
+  </responseDeclaration>
+  <itemBody>
+    <p>Pick one</p>
+    <choiceInteraction responseIdentifier=\"RESPONSE\" maxChoices=\"1\">
+      <simpleChoice identifier=\"A\">Yes</simpleChoice>
+      <simpleChoice identifier=\"B\">No</simpleChoice>
+    </choiceInteraction>
+  </itemBody>
+</assessmentItem>";
+        let manifest = "<manifest identifier=\"bank\"><resources>
+  <resource type=\"imsqti_item_xmlv2p1\" href=\"slider.xml\"/>
+  <resource type=\"imsqti_item_xmlv2p1\" href=\"./choice.xml\"/>
+  <resource type=\"imsqti_item_xmlv2p1\" href=\"missing.xml\"/>
+</resources></manifest>";
+        let package = QtiPackage::default()
+            .with_file(MANIFEST, manifest)
+            .with_file("slider.xml", item)
+            .with_file("choice.xml", choice);
+        let imported = import(&package);
+        let messages: Vec<String> = imported
+            .diagnostics
+            .iter()
+            .map(|d| alloc::format!("{}", d))
+            .collect();
+        assert_eq!(
+            messages,
+            alloc::vec![
+                String::from(
+                    "error: line 5: question 'Slider': slider.xml: sliderInteraction is not supported"
+                ),
+                String::from(
+                    "warning: line 8: question 'i2': choice.xml: partial-credit mapping is dropped"
+                ),
+                String::from("error: missing.xml: missing from the package"),
+            ]
+        );
+        assert_eq!(imported.value.id.as_str(), "bank");
+        assert_eq!(
+            imported.value.questions,
+            alloc::vec![Question::MultipleChoice {
+                id: "i2".into(),
+                prompt: "Pick one".into(),
+                options: alloc::vec!["Yes".into(), "No".into()],
+                correct: 0,
+                explanation: String::new(),
+                points: 1,
+                option_explanations: Vec::new(),
+            }]
+        );
+        assert!(imported
+            .diagnostics
+            .iter()
+            .all(|d| d.severity == Severity::Error || d.line.is_some()));
+    }
+
+    #[test]
+    fn test_import_malformed() {
+        let package =
+            QtiPackage::default().with_file(MANIFEST, "<manifest>\n<resources></manifest>");
+        let imported = import(&package);
+        assert_eq!(imported.errors().count(), 1);
+        assert_eq!(imported.diagnostics[0].line, Some(2));
+    }
+}
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn text() -> impl Strategy<Value = String> {
+        "[a-zA-Z0-9<>&\"' {}\n-]{0,16}[a-zA-Z0-9?<>&]".prop_map(|s| String::from(s.trim_start()))
+    }
+
+    fn question() -> impl Strategy<Value = Question> {
+        // Duplicate IDs are fine: items are renamed and titles keep the ID.
+        let id = QuestionId::new("q");
+        let choices = (
+            text(),
+            proptest::collection::vec(text(), 1..5),
+            any::<prop::sample::Index>(),
+            any::<bool>(),
+            0..10u32,
+        )
+            .prop_map({
+                let id = id.clone();
+                move |(prompt, options, correct, multiple, points)| {
+                    if multiple {
+                        Question::MultipleSelect {
+                            id: id.clone(),
+                            prompt,
+                            correct: (0..options.len()).step_by(2).collect(),
+                            options,
+                            explanation: String::new(),
+                            points,
+                            option_explanations: Vec::new(),
+                        }
+                    } else {
+                        Question::MultipleChoice {
+                            id: id.clone(),
+                            prompt,
+                            correct: correct.index(options.len()),
+                            options,
+                            explanation: String::new(),
+                            points,
+                            option_explanations: Vec::new(),
+                        }
+                    }
+                }
+            });
+        let matching = (
+            text(),
+            proptest::collection::vec(text(), 1..4),
+            proptest::collection::vec(text(), 1..4),
+            text(),
+        )
+            .prop_map({
+                let id = id.clone();
+                move |(prompt, left, right, explanation)| Question::Matching {
+                    id: id.clone(),
+                    prompt,
+                    correct_pairs: (0..left.len()).map(|l| (l, l % right.len())).collect(),
+                    left,
+                    right,
+                    points: 1,
+                    explanation,
+                }
+            });
+        let code = (
+            text(),
+            proptest::collection::vec(("[a-z ;(){}\n]{0,10}", text()), 1..4),
+            "[a-z ;\n]{0,10}",
+        )
+            .prop_map(move |(prompt, segments, tail)| {
+                let mut code_template = String::new();
+                let mut blanks = Vec::new();
+                for (i, (code, answer)) in segments.into_iter().enumerate() {
+                    let blank = alloc::format!("b{}", i);
+                    code_template.push_str(&code.replace('{', "("));
+                    code_template.push_str(&alloc::format!("{{{{{}}}}}", blank));
+                    blanks.push(Blank::new(blank).with_answer(answer));
+                }
+                code_template.push_str(&tail);
+                Question::CodeCompletion {
+                    id: id.clone(),
+                    prompt,
+                    code_template,
+                    blanks,
+                    test_cases: Vec::new(),
+                    points: 2,
+                    explanation: String::new(),
+                }
+            });
+        prop_oneof![choices, matching, code]
+    }
+
+    proptest! {
+        #[test]
+        fn test_round_trip(
+            questions in proptest::collection::vec(question(), 0..5),
+            v3 in any::<bool>(),
+        ) {
+            let version = if v3 { QtiVersion::V3_0 } else { QtiVersion::V2_1 };
+            let mut quiz = Quiz::new("q", "Q <&>");
+            for question in questions {
+                quiz = quiz.with_question(question);
+            }
+            let exported = export(&quiz, version);
+            prop_assert!(exported.is_lossless());
+            let imported = import(&exported.value);
+            prop_assert!(imported.is_lossless(), "{:?}", imported.diagnostics);
+            prop_assert_eq!(imported.value, quiz);
+        }
+    }
+}
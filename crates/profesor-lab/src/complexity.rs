@@ -0,0 +1,380 @@
+//! Empirical complexity checks.
+//!
+//! Runs a test case at increasing input sizes, records the interpreter step
+//! counts and fits them against common growth curves, so algorithm labs can
+//! require O(n log n) rather than accepting any correct O(n²) solution.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use profesor_core::{Language, TestCase};
+use serde::{Deserialize, Serialize};
+
+use crate::sandbox::Sandbox;
+
+/// Placeholder in a test input that is replaced with the input size.
+pub const SIZE_PLACEHOLDER: &str = "{n}";
+
+/// Asymptotic growth classes, ordered from slowest to fastest growth.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ComplexityClass {
+    /// O(1)
+    Constant,
+    /// O(log n)
+    Logarithmic,
+    /// O(n)
+    Linear,
+    /// O(n log n)
+    Linearithmic,
+    /// O(n²)
+    Quadratic,
+    /// O(n³)
+    Cubic,
+    /// O(2ⁿ)
+    Exponential,
+}
+
+impl ComplexityClass {
+    /// All classes, slowest growth first.
+    pub const ALL: [Self; 7] = [
+        Self::Constant,
+        Self::Logarithmic,
+        Self::Linear,
+        Self::Linearithmic,
+        Self::Quadratic,
+        Self::Cubic,
+        Self::Exponential,
+    ];
+
+    /// Get the big-O notation for this class.
+    #[must_use]
+    pub fn notation(&self) -> &'static str {
+        match self {
+            Self::Constant => "O(1)",
+            Self::Logarithmic => "O(log n)",
+            Self::Linear => "O(n)",
+            Self::Linearithmic => "O(n log n)",
+            Self::Quadratic => "O(n²)",
+            Self::Cubic => "O(n³)",
+            Self::Exponential => "O(2ⁿ)",
+        }
+    }
+
+    /// Natural log of the growth function at `n` (n ≥ 2).
+    ///
+    /// Working in log space keeps 2ⁿ from overflowing for large inputs.
+    fn ln_growth(&self, n: f64) -> f64 {
+        let ln_n = libm::log(n);
+        match self {
+            Self::Constant => 0.0,
+            Self::Logarithmic => libm::log(ln_n / core::f64::consts::LN_2),
+            Self::Linear => ln_n,
+            Self::Linearithmic => ln_n + libm::log(ln_n / core::f64::consts::LN_2),
+            Self::Quadratic => 2.0 * ln_n,
+            Self::Cubic => 3.0 * ln_n,
+            Self::Exponential => n * core::f64::consts::LN_2,
+        }
+    }
+}
+
+/// A step-count measurement at one input size.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Sample {
+    /// Input size
+    pub size: usize,
+    /// Interpreter steps executed
+    pub steps: u64,
+}
+
+/// Fit step counts against each growth class.
+///
+/// Each class is fitted as `steps ≈ c · f(n)` by least squares in log space;
+/// the class with the smallest residual wins, preferring slower growth on
+/// near-ties. Returns `None` with fewer than three usable samples.
+#[must_use]
+pub fn fit_growth(samples: &[Sample]) -> Option<ComplexityClass> {
+    let points: Vec<(f64, f64)> = samples
+        .iter()
+        .filter(|s| s.size >= 2 && s.steps > 0)
+        .map(|s| (s.size as f64, libm::log(s.steps as f64)))
+        .collect();
+
+    if points.len() < 3 {
+        return None;
+    }
+
+    let mut best: Option<(ComplexityClass, f64)> = None;
+    for class in ComplexityClass::ALL {
+        let offsets: Vec<f64> = points
+            .iter()
+            .map(|&(n, ln_y)| ln_y - class.ln_growth(n))
+            .collect();
+        let ln_c = offsets.iter().sum::<f64>() / offsets.len() as f64;
+        let residual: f64 = offsets.iter().map(|o| (o - ln_c) * (o - ln_c)).sum();
+
+        // Classes are visited in growth order, so a faster-growing class
+        // must beat the current best by a clear margin to replace it.
+        if best.map_or(true, |(_, r)| residual < r - NEAR_TIE) {
+            best = Some((class, residual));
+        }
+    }
+
+    best.map(|(class, _)| class)
+}
+
+/// Residual difference below which two fits are considered equal.
+const NEAR_TIE: f64 = 1e-3;
+
+/// A lab requirement on the growth rate of a submission.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ComplexityCheck {
+    /// Test case to scale; `{n}` in its input is replaced with the size
+    pub test: TestCase,
+    /// Input sizes to measure, in increasing order
+    pub sizes: Vec<usize>,
+    /// Fastest-growing class that is still acceptable
+    pub max_allowed: ComplexityClass,
+}
+
+impl ComplexityCheck {
+    /// Create a check with default sizes (16 to 1024, doubling).
+    #[must_use]
+    pub fn new(test: TestCase, max_allowed: ComplexityClass) -> Self {
+        Self {
+            test,
+            sizes: alloc::vec![16, 32, 64, 128, 256, 512, 1024],
+            max_allowed,
+        }
+    }
+
+    /// Set the input sizes to measure.
+    #[must_use]
+    pub fn with_sizes(mut self, sizes: Vec<usize>) -> Self {
+        self.sizes = sizes;
+        self
+    }
+
+    /// Get the input for a given size.
+    #[must_use]
+    pub fn input_for(&self, size: usize) -> String {
+        self.test
+            .input
+            .replace(SIZE_PLACEHOLDER, &alloc::format!("{}", size))
+    }
+
+    /// Run the check against a submission.
+    pub fn run(
+        &self,
+        sandbox: &Sandbox,
+        code: &str,
+        language: Language,
+    ) -> Result<ComplexityReport, ComplexityError> {
+        let mut samples = Vec::with_capacity(self.sizes.len());
+
+        for &size in &self.sizes {
+            let result = sandbox.execute(code, language, &self.input_for(size));
+            if !result.is_success() {
+                return Err(ComplexityError::ExecutionFailed {
+                    size,
+                    message: result
+                        .error_message()
+                        .unwrap_or("execution did not complete")
+                        .into(),
+                });
+            }
+            samples.push(Sample {
+                size,
+                steps: result.usage().steps,
+            });
+        }
+
+        let fitted = fit_growth(&samples).ok_or(ComplexityError::NotMeasurable)?;
+
+        Ok(ComplexityReport {
+            passed: fitted <= self.max_allowed,
+            fitted,
+            max_allowed: self.max_allowed,
+            samples,
+        })
+    }
+}
+
+/// Outcome of a complexity check.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ComplexityReport {
+    /// Best-fitting growth class
+    pub fitted: ComplexityClass,
+    /// Fastest-growing class that is still acceptable
+    pub max_allowed: ComplexityClass,
+    /// Whether the submission grows no faster than allowed
+    pub passed: bool,
+    /// Raw measurements
+    pub samples: Vec<Sample>,
+}
+
+impl ComplexityReport {
+    /// Get a summary of the result.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        if self.passed {
+            alloc::format!("✓ Grows as {}", self.fitted.notation())
+        } else {
+            alloc::format!(
+                "✗ Grows as {}, expected {} or better",
+                self.fitted.notation(),
+                self.max_allowed.notation()
+            )
+        }
+    }
+}
+
+/// Errors from running a complexity check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ComplexityError {
+    /// The submission failed at one of the sizes
+    ExecutionFailed {
+        /// Input size that failed
+        size: usize,
+        /// Error message
+        message: String,
+    },
+    /// Too few sizes or no step counts to fit a curve
+    NotMeasurable,
+}
+
+impl core::fmt::Display for ComplexityError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::ExecutionFailed { size, message } => {
+                write!(f, "Execution failed at n = {}: {}", size, message)
+            }
+            Self::NotMeasurable => write!(f, "Not enough measurements to fit a growth curve"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples(f: impl Fn(f64) -> f64) -> Vec<Sample> {
+        [16usize, 32, 64, 128, 256, 512, 1024]
+            .iter()
+            .map(|&size| Sample {
+                size,
+                steps: f(size as f64) as u64,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fit_linear() {
+        let fitted = fit_growth(&samples(|n| 3.0 * n + 5.0));
+        assert_eq!(fitted, Some(ComplexityClass::Linear));
+    }
+
+    #[test]
+    fn test_fit_linearithmic() {
+        let fitted = fit_growth(&samples(|n| 2.0 * n * libm::log2(n)));
+        assert_eq!(fitted, Some(ComplexityClass::Linearithmic));
+    }
+
+    #[test]
+    fn test_fit_quadratic() {
+        let fitted = fit_growth(&samples(|n| n * (n - 1.0) / 2.0));
+        assert_eq!(fitted, Some(ComplexityClass::Quadratic));
+    }
+
+    #[test]
+    fn test_fit_constant() {
+        let fitted = fit_growth(&samples(|_| 42.0));
+        assert_eq!(fitted, Some(ComplexityClass::Constant));
+    }
+
+    #[test]
+    fn test_fit_exponential() {
+        let points: Vec<Sample> = (4..12)
+            .map(|size| Sample {
+                size,
+                steps: 1u64 << size,
+            })
+            .collect();
+        assert_eq!(fit_growth(&points), Some(ComplexityClass::Exponential));
+    }
+
+    #[test]
+    fn test_fit_needs_samples() {
+        assert_eq!(fit_growth(&[]), None);
+        let zero_steps = samples(|_| 0.0);
+        assert_eq!(fit_growth(&zero_steps), None);
+    }
+
+    #[test]
+    fn test_class_ordering() {
+        assert!(ComplexityClass::Linearithmic < ComplexityClass::Quadratic);
+        assert_eq!(ComplexityClass::Linearithmic.notation(), "O(n log n)");
+    }
+
+    #[test]
+    fn test_input_for_size() {
+        let check = ComplexityCheck::new(
+            TestCase::new("sort").with_input("random {n}"),
+            ComplexityClass::Linearithmic,
+        );
+        assert_eq!(check.input_for(64), "random 64");
+    }
+
+    #[test]
+    fn test_run_without_step_counts() {
+        let check = ComplexityCheck::new(TestCase::new("sort"), ComplexityClass::Linear)
+            .with_sizes(alloc::vec![8, 16, 32]);
+        let result = check.run(&Sandbox::new(), "fn main() {}", Language::Rust);
+        assert_eq!(result, Err(ComplexityError::NotMeasurable));
+    }
+
+    #[test]
+    fn test_run_tells_linear_from_quadratic() {
+        let check = ComplexityCheck::new(
+            TestCase::new("count pairs").with_input("{n}"),
+            ComplexityClass::Linear,
+        )
+        .with_sizes(alloc::vec![16, 32, 64, 128, 256]);
+        let linear =
+            "n = int(input())\ntotal = 0\nfor i in range(n):\n    total += i\nprint(total)";
+        let quadratic = "n = int(input())\ntotal = 0\nfor i in range(n):\n    for j in range(i):\n        total += 1\nprint(total)";
+
+        let report = check
+            .run(&Sandbox::new(), linear, Language::Python)
+            .unwrap();
+        assert_eq!(report.fitted, ComplexityClass::Linear);
+        assert!(report.passed);
+        assert_eq!(report.samples.len(), 5);
+
+        let report = check
+            .run(&Sandbox::new(), quadratic, Language::Python)
+            .unwrap();
+        assert_eq!(report.fitted, ComplexityClass::Quadratic);
+        assert!(!report.passed);
+    }
+
+    #[test]
+    fn test_run_execution_failure() {
+        let check = ComplexityCheck::new(TestCase::new("sort"), ComplexityClass::Linear);
+        let result = check.run(&Sandbox::new(), "", Language::Rust);
+        assert!(matches!(
+            result,
+            Err(ComplexityError::ExecutionFailed { size: 16, .. })
+        ));
+    }
+
+    #[test]
+    fn test_report_summary() {
+        let report = ComplexityReport {
+            fitted: ComplexityClass::Quadratic,
+            max_allowed: ComplexityClass::Linearithmic,
+            passed: false,
+            samples: Vec::new(),
+        };
+        assert!(report.summary().contains("O(n²)"));
+        assert!(report.summary().contains("O(n log n) or better"));
+    }
+}
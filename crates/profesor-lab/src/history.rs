@@ -0,0 +1,510 @@
+//! Submission history for labs.
+//!
+//! Records every run and submission of a lab as a line-based delta against
+//! the previous snapshot, together with its test results, so learners can
+//! restore earlier versions and instructors can see how the pass rate evolved.
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use profesor_core::progress::{LearnerId, Timestamp};
+use profesor_core::LabId;
+use serde::{Deserialize, Serialize};
+
+use crate::runner::TestResults;
+
+/// Largest LCS table (changed old lines × changed new lines) built per
+/// snapshot; bigger edits are stored as a full replacement.
+const MAX_DIFF_CELLS: usize = 1 << 20;
+
+/// Why a snapshot was recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SubmissionKind {
+    /// Tests were run while working on the lab
+    Run,
+    /// The lab was submitted for grading
+    Submit,
+}
+
+/// One edit operation in a line-based delta.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeltaOp {
+    /// Copy lines unchanged from the previous snapshot
+    Keep(usize),
+    /// Skip lines from the previous snapshot
+    Delete(usize),
+    /// Insert new lines
+    Insert(Vec<String>),
+}
+
+/// Line-based edit script that turns one snapshot into the next.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct Delta {
+    /// Edit operations in order
+    pub ops: Vec<DeltaOp>,
+}
+
+impl Delta {
+    /// Compute the delta from `old` to `new`.
+    #[must_use]
+    pub fn between(old: &str, new: &str) -> Self {
+        let old: Vec<&str> = old.split('\n').collect();
+        let new: Vec<&str> = new.split('\n').collect();
+
+        let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+        let suffix = old[prefix..]
+            .iter()
+            .rev()
+            .zip(new[prefix..].iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        let mut delta = Self::default();
+        delta.keep(prefix);
+        delta.diff_middle(
+            &old[prefix..old.len() - suffix],
+            &new[prefix..new.len() - suffix],
+        );
+        delta.keep(suffix);
+        delta
+    }
+
+    /// Apply the delta to the previous snapshot.
+    ///
+    /// Returns `None` if the delta does not fit the snapshot.
+    #[must_use]
+    pub fn apply(&self, old: &str) -> Option<String> {
+        let old: Vec<&str> = old.split('\n').collect();
+        let mut lines: Vec<&str> = Vec::with_capacity(old.len());
+        let mut pos = 0;
+
+        for op in &self.ops {
+            match op {
+                DeltaOp::Keep(n) => {
+                    lines.extend(old.get(pos..pos + n)?);
+                    pos += n;
+                }
+                DeltaOp::Delete(n) => {
+                    old.get(pos..pos + n)?;
+                    pos += n;
+                }
+                DeltaOp::Insert(new) => lines.extend(new.iter().map(String::as_str)),
+            }
+        }
+
+        (pos == old.len()).then(|| lines.join("\n"))
+    }
+
+    /// Check if the delta leaves the snapshot unchanged.
+    #[must_use]
+    pub fn is_unchanged(&self) -> bool {
+        self.ops.iter().all(|op| matches!(op, DeltaOp::Keep(_)))
+    }
+
+    /// Diff the changed middle section with a longest-common-subsequence table.
+    fn diff_middle(&mut self, old: &[&str], new: &[&str]) {
+        if old.len().saturating_mul(new.len()) > MAX_DIFF_CELLS {
+            self.delete(old.len());
+            for line in new {
+                self.insert(line);
+            }
+            return;
+        }
+
+        let width = new.len() + 1;
+        let mut lcs = alloc::vec![0u32; (old.len() + 1) * width];
+        for i in (0..old.len()).rev() {
+            for j in (0..new.len()).rev() {
+                lcs[i * width + j] = if old[i] == new[j] {
+                    lcs[(i + 1) * width + j + 1] + 1
+                } else {
+                    lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+        while i < old.len() && j < new.len() {
+            if old[i] == new[j] {
+                self.keep(1);
+                i += 1;
+                j += 1;
+            } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+                self.delete(1);
+                i += 1;
+            } else {
+                self.insert(new[j]);
+                j += 1;
+            }
+        }
+        self.delete(old.len() - i);
+        for line in &new[j..] {
+            self.insert(line);
+        }
+    }
+
+    fn keep(&mut self, n: usize) {
+        if n == 0 {
+            return;
+        }
+        match self.ops.last_mut() {
+            Some(DeltaOp::Keep(k)) => *k += n,
+            _ => self.ops.push(DeltaOp::Keep(n)),
+        }
+    }
+
+    fn delete(&mut self, n: usize) {
+        if n == 0 {
+            return;
+        }
+        match self.ops.last_mut() {
+            Some(DeltaOp::Delete(d)) => *d += n,
+            _ => self.ops.push(DeltaOp::Delete(n)),
+        }
+    }
+
+    fn insert(&mut self, line: &str) {
+        match self.ops.last_mut() {
+            Some(DeltaOp::Insert(lines)) => lines.push(line.into()),
+            _ => self.ops.push(DeltaOp::Insert(alloc::vec![line.into()])),
+        }
+    }
+}
+
+/// A recorded run or submission.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Submission {
+    /// Version number (1-indexed)
+    pub version: u32,
+    /// Whether this was a test run or a submission
+    pub kind: SubmissionKind,
+    /// When it was recorded
+    pub recorded_at: Timestamp,
+    /// Changes since the previous version (or since empty code for version 1)
+    pub delta: Delta,
+    /// Test results for this version
+    pub results: TestResults,
+}
+
+/// A point on the pass-rate timeline.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PassRatePoint {
+    /// Version number
+    pub version: u32,
+    /// Whether this was a test run or a submission
+    pub kind: SubmissionKind,
+    /// When it was recorded
+    pub recorded_at: Timestamp,
+    /// Pass rate (0.0 - 1.0)
+    pub pass_rate: f32,
+}
+
+/// Version history of one learner's work on one lab.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubmissionHistory {
+    /// Lab identifier
+    pub lab_id: LabId,
+    /// Recorded versions, oldest first
+    pub submissions: Vec<Submission>,
+    /// Content of the latest version, kept to diff the next one against
+    latest: String,
+}
+
+impl SubmissionHistory {
+    /// Create an empty history for a lab.
+    #[must_use]
+    pub fn new(lab_id: impl Into<LabId>) -> Self {
+        Self {
+            lab_id: lab_id.into(),
+            submissions: Vec::new(),
+            latest: String::new(),
+        }
+    }
+
+    /// Record a new version and return its version number.
+    pub fn record(
+        &mut self,
+        code: &str,
+        kind: SubmissionKind,
+        results: TestResults,
+        now: Timestamp,
+    ) -> u32 {
+        let version = self.submissions.len() as u32 + 1;
+        self.submissions.push(Submission {
+            version,
+            kind,
+            recorded_at: now,
+            delta: Delta::between(&self.latest, code),
+            results,
+        });
+        self.latest = code.into();
+        version
+    }
+
+    /// Get the number of recorded versions.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.submissions.len()
+    }
+
+    /// Check if nothing has been recorded.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.submissions.is_empty()
+    }
+
+    /// Get the code of the latest version.
+    #[must_use]
+    pub fn latest(&self) -> Option<&str> {
+        (!self.submissions.is_empty()).then_some(self.latest.as_str())
+    }
+
+    /// Get a recorded version.
+    #[must_use]
+    pub fn submission(&self, version: u32) -> Option<&Submission> {
+        self.submissions
+            .get((version as usize).checked_sub(1)?)
+            .filter(|s| s.version == version)
+    }
+
+    /// Reconstruct the code of an earlier version.
+    #[must_use]
+    pub fn restore(&self, version: u32) -> Option<String> {
+        let count = (version as usize).checked_sub(1)? + 1;
+        if count > self.submissions.len() {
+            return None;
+        }
+
+        self.submissions[..count]
+            .iter()
+            .try_fold(String::new(), |code, s| s.delta.apply(&code))
+    }
+
+    /// Get the pass rate of every version, oldest first.
+    #[must_use]
+    pub fn pass_rate_timeline(&self) -> Vec<PassRatePoint> {
+        self.submissions
+            .iter()
+            .map(|s| PassRatePoint {
+                version: s.version,
+                kind: s.kind,
+                recorded_at: s.recorded_at,
+                pass_rate: s.results.pass_rate(),
+            })
+            .collect()
+    }
+
+    /// Get the most recent formal submission.
+    #[must_use]
+    pub fn last_submitted(&self) -> Option<&Submission> {
+        self.submissions
+            .iter()
+            .rev()
+            .find(|s| s.kind == SubmissionKind::Submit)
+    }
+}
+
+/// Submission histories for all labs of one learner.
+///
+/// Serialized alongside the learner's `LearnerProgress`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LabHistory {
+    /// Learner identifier
+    pub learner_id: LearnerId,
+    /// Histories per lab (lab_id -> history)
+    pub labs: BTreeMap<String, SubmissionHistory>,
+}
+
+impl LabHistory {
+    /// Create an empty history for a learner.
+    #[must_use]
+    pub fn new(learner_id: impl Into<LearnerId>) -> Self {
+        Self {
+            learner_id: learner_id.into(),
+            labs: BTreeMap::new(),
+        }
+    }
+
+    /// Get the history of a lab.
+    #[must_use]
+    pub fn lab(&self, lab_id: &LabId) -> Option<&SubmissionHistory> {
+        self.labs.get(lab_id.as_str())
+    }
+
+    /// Record a version of a lab and return its version number.
+    pub fn record(
+        &mut self,
+        lab_id: &LabId,
+        code: &str,
+        kind: SubmissionKind,
+        results: TestResults,
+        now: Timestamp,
+    ) -> u32 {
+        self.labs
+            .entry(lab_id.as_str().into())
+            .or_insert_with(|| SubmissionHistory::new(lab_id.clone()))
+            .record(code, kind, results, now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results(passed: usize, total: usize) -> TestResults {
+        TestResults {
+            results: Vec::new(),
+            all_passed: passed == total,
+            passed_count: passed,
+            total_count: total,
+        }
+    }
+
+    #[test]
+    fn test_delta_roundtrip() {
+        let old = "fn main() {\n    let x = 1;\n    println!(\"{}\", x);\n}\n";
+        let new = "fn main() {\n    let x = 2;\n    let y = 3;\n    println!(\"{}\", x + y);\n}\n";
+
+        let delta = Delta::between(old, new);
+        assert_eq!(delta.apply(old).as_deref(), Some(new));
+        assert!(!delta.is_unchanged());
+    }
+
+    #[test]
+    fn test_delta_is_compact() {
+        let old = "a\nb\nc\nd\ne";
+        let new = "a\nb\nX\nd\ne";
+        let delta = Delta::between(old, new);
+        assert_eq!(
+            delta.ops,
+            alloc::vec![
+                DeltaOp::Keep(2),
+                DeltaOp::Delete(1),
+                DeltaOp::Insert(alloc::vec!["X".into()]),
+                DeltaOp::Keep(2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_delta_unchanged_and_empty() {
+        assert!(Delta::between("same\n", "same\n").is_unchanged());
+        let delta = Delta::between("", "x");
+        assert_eq!(delta.apply("").as_deref(), Some("x"));
+        let delta = Delta::between("x", "");
+        assert_eq!(delta.apply("x").as_deref(), Some(""));
+    }
+
+    #[test]
+    fn test_large_edit_is_full_replacement() {
+        let old: Vec<String> = (0..2000).map(|i| alloc::format!("old {}", i)).collect();
+        let mut new: Vec<String> = (0..2000).map(|i| alloc::format!("new {}", i)).collect();
+        new[1000] = old[1000].clone();
+        let (old, new) = (old.join("\n"), new.join("\n"));
+
+        let delta = Delta::between(&old, &new);
+        assert_eq!(delta.ops.len(), 2);
+        assert_eq!(delta.ops[0], DeltaOp::Delete(2000));
+        assert_eq!(delta.apply(&old).as_deref(), Some(new.as_str()));
+    }
+
+    #[test]
+    fn test_delta_rejects_wrong_base() {
+        let delta = Delta::between("a\nb\nc", "a\nc");
+        assert!(delta.apply("a").is_none());
+    }
+
+    #[test]
+    fn test_history_restore() {
+        let mut history = SubmissionHistory::new("lab-1");
+        let v1 = "fn main() {}";
+        let v2 = "fn main() {\n    println!(\"hi\");\n}";
+        let v3 = "fn main() {\n    println!(\"hello\");\n}";
+
+        assert_eq!(
+            history.record(v1, SubmissionKind::Run, results(0, 2), Timestamp::ZERO),
+            1
+        );
+        history.record(
+            v2,
+            SubmissionKind::Run,
+            results(1, 2),
+            Timestamp::from_millis(10),
+        );
+        history.record(
+            v3,
+            SubmissionKind::Submit,
+            results(2, 2),
+            Timestamp::from_millis(20),
+        );
+
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.restore(1).as_deref(), Some(v1));
+        assert_eq!(history.restore(2).as_deref(), Some(v2));
+        assert_eq!(history.restore(3).as_deref(), Some(v3));
+        assert_eq!(history.latest(), Some(v3));
+        assert!(history.restore(0).is_none());
+        assert!(history.restore(4).is_none());
+    }
+
+    #[test]
+    fn test_history_pass_rate_timeline() {
+        let mut history = SubmissionHistory::new("lab-1");
+        history.record("a", SubmissionKind::Run, results(1, 4), Timestamp::ZERO);
+        history.record("b", SubmissionKind::Run, results(3, 4), Timestamp::ZERO);
+        history.record("c", SubmissionKind::Submit, results(4, 4), Timestamp::ZERO);
+
+        let rates: Vec<f32> = history
+            .pass_rate_timeline()
+            .iter()
+            .map(|p| p.pass_rate)
+            .collect();
+        assert_eq!(rates, alloc::vec![0.25, 0.75, 1.0]);
+        assert_eq!(history.last_submitted().map(|s| s.version), Some(3));
+    }
+
+    #[test]
+    fn test_empty_history() {
+        let history = SubmissionHistory::new("lab-1");
+        assert!(history.is_empty());
+        assert!(history.latest().is_none());
+        assert!(history.submission(1).is_none());
+        assert!(history.last_submitted().is_none());
+    }
+
+    #[test]
+    fn test_lab_history_per_lab() {
+        let mut history = LabHistory::new("learner-1");
+        let lab_a = LabId::new("lab-a");
+        let lab_b = LabId::new("lab-b");
+
+        history.record(
+            &lab_a,
+            "a1",
+            SubmissionKind::Run,
+            results(0, 1),
+            Timestamp::ZERO,
+        );
+        history.record(
+            &lab_a,
+            "a2",
+            SubmissionKind::Run,
+            results(1, 1),
+            Timestamp::ZERO,
+        );
+        history.record(
+            &lab_b,
+            "b1",
+            SubmissionKind::Submit,
+            results(1, 1),
+            Timestamp::ZERO,
+        );
+
+        assert_eq!(history.lab(&lab_a).map(SubmissionHistory::len), Some(2));
+        assert_eq!(history.lab(&lab_b).map(SubmissionHistory::len), Some(1));
+        assert_eq!(
+            history.lab(&lab_a).and_then(|h| h.restore(1)).as_deref(),
+            Some("a1")
+        );
+    }
+}
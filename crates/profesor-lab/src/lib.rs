@@ -15,12 +15,26 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std as alloc;
 
+mod complexity;
 mod feedback;
+mod history;
+mod mutation;
+mod python;
 mod runner;
 mod sandbox;
+mod trace;
 
+pub use complexity::{
+    fit_growth, ComplexityCheck, ComplexityClass, ComplexityError, ComplexityReport, Sample,
+    SIZE_PLACEHOLDER,
+};
 pub use feedback::{
     DifferenceType, ErrorCategory, ErrorExplanation, FeedbackGenerator, OutputComparison,
 };
-pub use runner::{TestResult, TestResults, TestRunner};
-pub use sandbox::{ExecutionResult, Sandbox, SandboxConfig};
+pub use history::{
+    Delta, DeltaOp, LabHistory, PassRatePoint, Submission, SubmissionHistory, SubmissionKind,
+};
+pub use mutation::{BugSeeder, Mutation, MutationOperator, SeedError, SeededLab};
+pub use runner::{RunMode, TestResult, TestResults, TestRunner};
+pub use sandbox::{ExecutionResult, ResourceUsage, Sandbox, SandboxConfig};
+pub use trace::{ExecutionTrace, StackFrame, TraceCursor, TraceStep, TracedExecution, Variable};
@@ -0,0 +1,606 @@
+//! Seeded-bug generation for debugging labs.
+//!
+//! Applies mutation operators to a working reference solution to produce a
+//! buggy starter file. The reference must pass the lab's test suite, and every
+//! generated variant is checked against it so learners never receive a "bug"
+//! that the tests cannot detect.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use profesor_core::{Lab, Language, SeededRng, StarterFile};
+use serde::{Deserialize, Serialize};
+
+use crate::runner::TestRunner;
+
+/// A kind of bug that can be injected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MutationOperator {
+    /// Shift an integer literal by one (`n` → `n ± 1`)
+    OffByOne,
+    /// Flip a comparison (`<` ↔ `>`, `<=` ↔ `>=`, `==` ↔ `!=`)
+    FlipComparison,
+    /// Swap the two arguments of a call (`f(a, b)` → `f(b, a)`)
+    SwapArguments,
+}
+
+impl MutationOperator {
+    /// All available operators.
+    pub const ALL: [Self; 3] = [Self::OffByOne, Self::FlipComparison, Self::SwapArguments];
+
+    /// Get a human-readable label.
+    #[must_use]
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::OffByOne => "Off-by-one",
+            Self::FlipComparison => "Flipped comparison",
+            Self::SwapArguments => "Swapped arguments",
+        }
+    }
+}
+
+/// A bug injected into the reference solution.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Mutation {
+    /// Operator that produced the bug
+    pub operator: MutationOperator,
+    /// Line of the bug (1-indexed)
+    pub line: u32,
+    /// Original source text
+    pub original: String,
+    /// Replacement source text
+    pub mutated: String,
+}
+
+/// A debugging lab generated from a reference solution.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeededLab {
+    /// The lab with the buggy code as its starter file
+    pub lab: Lab,
+    /// The buggy code
+    pub buggy_code: String,
+    /// Injected bugs (the answer key for authors)
+    pub mutations: Vec<Mutation>,
+}
+
+/// Errors from seeding bugs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SeedError {
+    /// The reference solution has no place where the chosen operators apply
+    NoMutationSites,
+    /// The reference solution itself fails the lab's tests
+    ReferenceFails {
+        /// Names of the failing tests
+        failed: Vec<String>,
+    },
+    /// Every tried variant still passed all tests
+    NoFailingVariant {
+        /// Number of variants tried
+        attempts: u32,
+    },
+}
+
+impl core::fmt::Display for SeedError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::NoMutationSites => write!(f, "No mutation sites found in reference solution"),
+            Self::ReferenceFails { failed } => {
+                write!(f, "Reference solution fails tests: {}", failed.join(", "))
+            }
+            Self::NoFailingVariant { attempts } => {
+                write!(f, "No variant failed the tests after {} attempts", attempts)
+            }
+        }
+    }
+}
+
+/// A place in the source where an operator applies.
+#[derive(Debug, Clone)]
+struct Site {
+    operator: MutationOperator,
+    start: usize,
+    end: usize,
+    replacement: String,
+}
+
+/// Generates buggy variants of a reference solution.
+#[derive(Debug, Clone)]
+pub struct BugSeeder {
+    seed: u64,
+    operators: Vec<MutationOperator>,
+    bug_count: usize,
+    max_attempts: u32,
+    runner: TestRunner,
+}
+
+impl BugSeeder {
+    /// Create a seeder using all operators and injecting one bug.
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            operators: MutationOperator::ALL.to_vec(),
+            bug_count: 1,
+            max_attempts: 16,
+            runner: TestRunner::new(),
+        }
+    }
+
+    /// Restrict the operators used.
+    #[must_use]
+    pub fn with_operators(mut self, operators: Vec<MutationOperator>) -> Self {
+        self.operators = operators;
+        self
+    }
+
+    /// Set the number of bugs to inject.
+    #[must_use]
+    pub fn with_bug_count(mut self, count: usize) -> Self {
+        self.bug_count = count.max(1);
+        self
+    }
+
+    /// Set how many variants to try before giving up.
+    #[must_use]
+    pub fn with_max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = attempts.max(1);
+        self
+    }
+
+    /// Set the runner used to check variants.
+    #[must_use]
+    pub fn with_runner(mut self, runner: TestRunner) -> Self {
+        self.runner = runner;
+        self
+    }
+
+    /// Inject bugs without checking them against tests.
+    pub fn mutate(
+        &self,
+        reference: &str,
+        language: Language,
+    ) -> Result<(String, Vec<Mutation>), SeedError> {
+        let sites = self.find_sites(reference, language);
+        if sites.is_empty() {
+            return Err(SeedError::NoMutationSites);
+        }
+        let mut rng = SeededRng::new(self.seed);
+        Ok(Self::apply(reference, &sites, &mut rng, self.bug_count))
+    }
+
+    /// Generate a debugging lab whose starter file fails at least one test.
+    ///
+    /// The reference must pass every test first, so a failing variant is
+    /// failing because of the injected bug. The buggy code replaces the first
+    /// editable starter file, or is added as `main.<ext>` if the lab has none.
+    pub fn generate(&self, lab: &Lab, reference: &str) -> Result<SeededLab, SeedError> {
+        let sites = self.find_sites(reference, lab.language);
+        if sites.is_empty() {
+            return Err(SeedError::NoMutationSites);
+        }
+
+        let baseline = self.runner.run_tests(reference, lab);
+        if !baseline.all_passed {
+            return Err(SeedError::ReferenceFails {
+                failed: baseline
+                    .results
+                    .into_iter()
+                    .filter(|r| !r.passed)
+                    .map(|r| r.name)
+                    .collect(),
+            });
+        }
+
+        let mut rng = SeededRng::new(self.seed);
+        for _ in 0..self.max_attempts {
+            let (buggy_code, mutations) = Self::apply(reference, &sites, &mut rng, self.bug_count);
+            if self.runner.run_tests(&buggy_code, lab).all_passed {
+                continue;
+            }
+
+            let mut seeded = lab.clone();
+            match seeded.starter_files.iter_mut().find(|f| !f.readonly) {
+                Some(file) => file.content.clone_from(&buggy_code),
+                None => seeded.starter_files.push(StarterFile::new(
+                    alloc::format!("main.{}", lab.language.extension()),
+                    buggy_code.clone(),
+                )),
+            }
+
+            return Ok(SeededLab {
+                lab: seeded,
+                buggy_code,
+                mutations,
+            });
+        }
+
+        Err(SeedError::NoFailingVariant {
+            attempts: self.max_attempts,
+        })
+    }
+
+    /// Apply up to `count` non-overlapping mutations chosen at random.
+    fn apply(
+        reference: &str,
+        sites: &[Site],
+        rng: &mut SeededRng,
+        count: usize,
+    ) -> (String, Vec<Mutation>) {
+        let mut chosen: Vec<&Site> = Vec::new();
+        for idx in rng.sample_indices(sites.len(), sites.len()) {
+            let site = &sites[idx];
+            if chosen
+                .iter()
+                .all(|c| site.end <= c.start || site.start >= c.end)
+            {
+                chosen.push(site);
+            }
+            if chosen.len() == count {
+                break;
+            }
+        }
+        chosen.sort_by_key(|s| s.start);
+
+        let mut code = String::with_capacity(reference.len());
+        let mut mutations = Vec::with_capacity(chosen.len());
+        let mut pos = 0;
+        for site in chosen {
+            code.push_str(&reference[pos..site.start]);
+            code.push_str(&site.replacement);
+            pos = site.end;
+            mutations.push(Mutation {
+                operator: site.operator,
+                line: reference[..site.start].matches('\n').count() as u32 + 1,
+                original: reference[site.start..site.end].into(),
+                mutated: site.replacement.clone(),
+            });
+        }
+        code.push_str(&reference[pos..]);
+
+        (code, mutations)
+    }
+
+    fn find_sites(&self, code: &str, language: Language) -> Vec<Site> {
+        let mut sites = Vec::new();
+        let mut offset = 0;
+
+        for line in code.split_inclusive('\n') {
+            let trimmed = line.trim_start();
+            let is_comment = trimmed.starts_with("//") || trimmed.starts_with('#');
+            if !is_comment {
+                let literals = string_literals(line, language);
+                for &operator in &self.operators {
+                    let found = match operator {
+                        MutationOperator::OffByOne => off_by_one_sites(line),
+                        MutationOperator::FlipComparison => comparison_sites(line),
+                        MutationOperator::SwapArguments => swap_argument_sites(line),
+                    };
+                    let outside = found.into_iter().filter(|&(start, end, _)| {
+                        literals.iter().all(|&(s, e)| end <= s || start >= e)
+                    });
+                    sites.extend(outside.map(|(start, end, replacement)| Site {
+                        operator,
+                        start: offset + start,
+                        end: offset + end,
+                        replacement,
+                    }));
+                }
+            }
+            offset += line.len();
+        }
+
+        sites
+    }
+}
+
+fn is_ident_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+/// Byte ranges of the string and character literals on a line.
+///
+/// In Rust a single quote only opens a character literal, since it also
+/// starts lifetimes and labels; elsewhere it opens a string.
+fn string_literals(line: &str, language: Language) -> Vec<(usize, usize)> {
+    let bytes = line.as_bytes();
+    let mut literals = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let quote = bytes[i];
+        let start = i;
+        i += 1;
+        if quote == b'\'' && language == Language::Rust {
+            // 'x' or '\n', but not 'a in &'a str
+            let width = line[i..].chars().next().map_or(0, char::len_utf8);
+            let end = if bytes.get(i) == Some(&b'\\') {
+                line.get(i + 2..)
+                    .and_then(|rest| rest.find('\''))
+                    .map(|p| i + 2 + p + 1)
+            } else {
+                (bytes.get(i + width) == Some(&b'\'')).then_some(i + width + 1)
+            };
+            if let Some(end) = end {
+                literals.push((start, end));
+                i = end;
+            }
+        } else if quote == b'"' || quote == b'\'' {
+            while i < bytes.len() && bytes[i] != quote {
+                i += if bytes[i] == b'\\' { 2 } else { 1 };
+            }
+            i = (i + 1).min(bytes.len());
+            literals.push((start, i));
+        }
+    }
+
+    literals
+}
+
+/// Standalone integer literals, shifted down by one (up for zero).
+fn off_by_one_sites(line: &str) -> Vec<(usize, usize, String)> {
+    let bytes = line.as_bytes();
+    let mut sites = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        let at = |p: Option<usize>| p.and_then(|p| bytes.get(p).copied());
+        let before = at(start.checked_sub(1));
+        let after = at(Some(i));
+        // A '.' next to a literal is a float or tuple field unless it is a range.
+        let dotted_before = before == Some(b'.') && at(start.checked_sub(2)) != Some(b'.');
+        let dotted_after = after == Some(b'.') && at(Some(i + 1)) != Some(b'.');
+        let standalone = !before.is_some_and(is_ident_byte)
+            && !after.is_some_and(is_ident_byte)
+            && !dotted_before
+            && !dotted_after;
+
+        if let (true, Ok(value)) = (standalone, line[start..i].parse::<u64>()) {
+            let shifted = if value == 0 { 1 } else { value - 1 };
+            sites.push((start, i, alloc::format!("{}", shifted)));
+        }
+    }
+
+    sites
+}
+
+/// Space-delimited comparison operators, so generics and arrows are skipped.
+fn comparison_sites(line: &str) -> Vec<(usize, usize, String)> {
+    const FLIPS: [(&str, &str); 6] = [
+        (" <= ", " >= "),
+        (" >= ", " <= "),
+        (" == ", " != "),
+        (" != ", " == "),
+        (" < ", " > "),
+        (" > ", " < "),
+    ];
+
+    let mut sites: Vec<(usize, usize, String)> = Vec::new();
+    for (from, to) in FLIPS {
+        for (pos, _) in line.match_indices(from) {
+            let (start, end) = (pos + 1, pos + from.len() - 1);
+            if sites.iter().all(|&(s, e, _)| end <= s || start >= e) {
+                sites.push((start, end, to.trim().into()));
+            }
+        }
+    }
+    sites
+}
+
+/// Calls with exactly two distinct top-level arguments.
+fn swap_argument_sites(line: &str) -> Vec<(usize, usize, String)> {
+    let bytes = line.as_bytes();
+    let mut sites = Vec::new();
+
+    for (open, _) in line.match_indices('(') {
+        let is_call = open > 0 && is_ident_byte(bytes[open - 1]);
+        let head = line[..open].trim_end_matches(|c: char| c.is_alphanumeric() || c == '_');
+        let is_definition = ["fn ", "def ", "function "]
+            .iter()
+            .any(|kw| head.ends_with(kw));
+        if !is_call || is_definition {
+            continue;
+        }
+
+        let mut depth = 0usize;
+        let mut comma = None;
+        let mut close = None;
+        for (i, &b) in bytes.iter().enumerate().skip(open + 1) {
+            match b {
+                b'(' | b'[' | b'{' => depth += 1,
+                b')' if depth == 0 => {
+                    close = Some(i);
+                    break;
+                }
+                b')' | b']' | b'}' => depth = depth.saturating_sub(1),
+                b',' if depth == 0 => {
+                    if comma.is_some() {
+                        comma = None;
+                        break;
+                    }
+                    comma = Some(i);
+                }
+                _ => {}
+            }
+        }
+
+        if let (Some(comma), Some(close)) = (comma, close) {
+            let first = line[open + 1..comma].trim();
+            let second = line[comma + 1..close].trim();
+            if !first.is_empty() && !second.is_empty() && first != second {
+                sites.push((open + 1, close, alloc::format!("{}, {}", second, first)));
+            }
+        }
+    }
+
+    sites
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use profesor_core::{TestCase, TestSuite};
+
+    const REFERENCE: &str = "fn sum_to(n: u32) -> u32 {\n    let mut total = 0;\n    for i in 1..n + 1 {\n        total += i;\n    }\n    total\n}\n";
+    const PY_REFERENCE: &str =
+        "n = int(input())\ntotal = 0\nfor i in range(1, n + 1):\n    total += i\nprint(total)\n";
+
+    fn sum_lab() -> Lab {
+        Lab::new("debug", "Debug")
+            .with_language(Language::Python)
+            .with_starter_file(StarterFile::readonly("README.md", "Fix the sum"))
+            .with_test_suite(
+                TestSuite::new()
+                    .with_test(TestCase::new("sum").with_input("3").with_expected("6"))
+                    .with_test(TestCase::new("one").with_input("1").with_expected("1")),
+            )
+    }
+
+    #[test]
+    fn test_off_by_one_sites() {
+        let sites = off_by_one_sites("let x = arr[10] + y2 + 3.5;");
+        assert_eq!(sites.len(), 1);
+        assert_eq!(sites[0].2, "9");
+    }
+
+    #[test]
+    fn test_off_by_one_ranges() {
+        let sites = off_by_one_sites("for i in 1..n + 1 {");
+        let replacements: Vec<&str> = sites.iter().map(|s| s.2.as_str()).collect();
+        assert_eq!(replacements, alloc::vec!["0", "0"]);
+        assert!(off_by_one_sites("let t = pair.0;").is_empty());
+    }
+
+    #[test]
+    fn test_comparison_sites_skip_generics() {
+        let sites = comparison_sites("let v: Vec<u32> = if a <= b { x } else { y };");
+        assert_eq!(sites.len(), 1);
+        assert_eq!(sites[0].2, ">=");
+    }
+
+    #[test]
+    fn test_swap_argument_sites() {
+        let line = "    let d = distance(origin, point(1, 2));";
+        let sites = swap_argument_sites(line);
+        assert_eq!(sites.len(), 2);
+        assert_eq!(sites[0].2, "point(1, 2), origin");
+        assert_eq!(sites[1].2, "2, 1");
+    }
+
+    #[test]
+    fn test_swap_skips_definitions() {
+        assert!(swap_argument_sites("fn add(a: i32, b: i32) -> i32 {").is_empty());
+        assert!(swap_argument_sites("def add(a, b):").is_empty());
+    }
+
+    #[test]
+    fn test_mutate_is_deterministic() {
+        let seeder = BugSeeder::new(7);
+        let first = seeder.mutate(REFERENCE, Language::Rust);
+        let second = seeder.mutate(REFERENCE, Language::Rust);
+        assert_eq!(first, second);
+
+        let (code, mutations) = first.unwrap();
+        assert_ne!(code, REFERENCE);
+        assert_eq!(mutations.len(), 1);
+    }
+
+    #[test]
+    fn test_mutate_respects_operators() {
+        let seeder = BugSeeder::new(1).with_operators(alloc::vec![MutationOperator::OffByOne]);
+        let (_, mutations) = seeder.mutate(REFERENCE, Language::Rust).unwrap();
+        assert_eq!(mutations[0].operator, MutationOperator::OffByOne);
+    }
+
+    #[test]
+    fn test_mutate_multiple_bugs() {
+        let seeder = BugSeeder::new(3).with_bug_count(2);
+        let (_, mutations) = seeder.mutate(REFERENCE, Language::Rust).unwrap();
+        assert_eq!(mutations.len(), 2);
+        assert!(mutations[0].line <= mutations[1].line);
+    }
+
+    #[test]
+    fn test_no_sites() {
+        let seeder = BugSeeder::new(0).with_operators(alloc::vec![MutationOperator::SwapArguments]);
+        assert_eq!(
+            seeder.mutate("x", Language::Rust),
+            Err(SeedError::NoMutationSites)
+        );
+    }
+
+    #[test]
+    fn test_string_literals_are_not_mutated() {
+        let line = "print(\"1 < 2\", f(a, 'b, c'), x < 3)";
+        let seeder = BugSeeder::new(0);
+        let (_, mutations) = seeder
+            .with_bug_count(10)
+            .mutate(line, Language::Python)
+            .unwrap();
+        let originals: Vec<&str> = mutations.iter().map(|m| m.original.as_str()).collect();
+        assert_eq!(originals, ["<", "3"]);
+
+        // Rust lifetimes are not quotes; character literals are.
+        let literals = string_literals("fn f<'a>(s: &'a str) -> char { '<' }", Language::Rust);
+        assert_eq!(literals, [(31, 34)]);
+        assert_eq!(string_literals(r"let q = '\'';", Language::Rust), [(8, 12)]);
+    }
+
+    #[test]
+    fn test_generate_emits_starter_file() {
+        let seeded = BugSeeder::new(11)
+            .generate(&sum_lab(), PY_REFERENCE)
+            .unwrap();
+        assert_eq!(seeded.lab.starter_files.len(), 2);
+        assert_eq!(seeded.lab.starter_files[1].path, "main.py");
+        assert_eq!(seeded.lab.starter_files[1].content, seeded.buggy_code);
+        assert!(!seeded.mutations.is_empty());
+        assert!(
+            !TestRunner::new()
+                .run_tests(&seeded.buggy_code, &sum_lab())
+                .all_passed
+        );
+    }
+
+    #[test]
+    fn test_generate_requires_passing_reference() {
+        let broken = PY_REFERENCE.replace("n + 1", "n");
+        let result = BugSeeder::new(11).generate(&sum_lab(), &broken);
+        assert_eq!(
+            result,
+            Err(SeedError::ReferenceFails {
+                failed: alloc::vec!["sum".into(), "one".into()]
+            })
+        );
+    }
+
+    #[test]
+    fn test_generate_rejects_undetected_bugs() {
+        // The output is never checked, so no bug is detectable.
+        let lab = Lab::new("debug", "Debug")
+            .with_language(Language::Python)
+            .with_test_suite(TestSuite::new().with_test(TestCase::new("runs").with_input("3")));
+        let silent = PY_REFERENCE.replace("print(total)\n", "");
+
+        let result = BugSeeder::new(5)
+            .with_max_attempts(3)
+            .generate(&lab, &silent);
+        assert_eq!(result, Err(SeedError::NoFailingVariant { attempts: 3 }));
+    }
+
+    #[test]
+    fn test_seed_error_display() {
+        assert!(alloc::format!("{}", SeedError::NoMutationSites).contains("No mutation sites"));
+        let fails = SeedError::ReferenceFails {
+            failed: alloc::vec!["sum".into()],
+        };
+        assert!(alloc::format!("{}", fails).ends_with("tests: sum"));
+        assert!(alloc::format!("{}", SeedError::NoFailingVariant { attempts: 4 }).contains("4"));
+    }
+}
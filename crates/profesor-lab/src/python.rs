@@ -0,0 +1,2147 @@
+//! Interpreter for the Python subset run by the sandbox.
+//!
+//! Covers what introductory labs use: integers, floats, strings, booleans,
+//! lists, `if`/`while`/`for`, functions, `print` and `input`. Every executed
+//! statement (and every loop test) is one step; when tracing, each step is
+//! recorded with the variables in scope.
+
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::cell::{Cell, RefCell};
+use core::cmp::Ordering;
+
+use crate::sandbox::{ExecutionResult, ResourceUsage};
+use crate::trace::{ExecutionTrace, StackFrame, TraceStep};
+
+/// Deepest allowed function call nesting.
+const MAX_DEPTH: usize = 64;
+
+/// Deepest allowed expression nesting, so parsing cannot overflow the stack.
+const MAX_NESTING: usize = 100;
+
+/// Steps counted as one millisecond of execution time.
+pub(crate) const STEPS_PER_MS: u64 = 1_000;
+
+/// Execution budget for one run.
+pub(crate) struct Limits {
+    /// Steps before the run times out
+    pub max_steps: u64,
+    /// Bytes of lists and strings the program may hold at once
+    pub max_memory: usize,
+}
+
+/// Run a program, returning its result and resource usage.
+pub(crate) fn run(
+    code: &str,
+    input: &str,
+    limits: &Limits,
+    trace: Option<&mut ExecutionTrace>,
+) -> ExecutionResult {
+    let mut interpreter = Interpreter {
+        input: input.lines(),
+        output: String::new(),
+        steps: 0,
+        meter: Rc::default(),
+        limits,
+        globals: Vec::new(),
+        frames: Vec::new(),
+        module_line: 0,
+        trace,
+    };
+    let outcome = parse(code).and_then(|program| interpreter.exec_block(&program).map(|_| ()));
+
+    let usage = ResourceUsage {
+        steps: interpreter.steps,
+        peak_memory_bytes: interpreter.meter.peak.get(),
+        output_bytes: interpreter.output.len(),
+    };
+    match outcome {
+        Ok(()) => ExecutionResult::Success {
+            duration_ms: u32::try_from(interpreter.steps / STEPS_PER_MS).unwrap_or(u32::MAX),
+            output: interpreter.output,
+            usage,
+        },
+        Err(Stop::Error { message, line }) => ExecutionResult::RuntimeError {
+            error: message,
+            line: Some(line),
+            usage,
+        },
+        Err(Stop::Timeout) => ExecutionResult::Timeout {
+            partial_output: interpreter.output,
+            usage,
+        },
+        Err(Stop::Memory(used_bytes)) => ExecutionResult::MemoryExceeded { used_bytes, usage },
+    }
+}
+
+/// Why execution stopped early.
+enum Stop {
+    Error {
+        message: String,
+        line: u32,
+    },
+    Timeout,
+    /// Live bytes the program needed
+    Memory(usize),
+}
+
+fn error_at(line: u32, message: impl Into<String>) -> Stop {
+    Stop::Error {
+        message: message.into(),
+        line,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Syntax
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Name(String),
+    Op(&'static str),
+}
+
+/// Operators, longest first so `//=` wins over `//` and `/`.
+const OPERATORS: [&str; 29] = [
+    "**=", "//=", "==", "!=", "<=", ">=", "+=", "-=", "*=", "/=", "%=", "//", "**", "+", "-", "*",
+    "/", "%", "<", ">", "=", "(", ")", "[", "]", ",", ":", ".", ";",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    FloorDiv,
+    Mod,
+    Pow,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    In,
+    NotIn,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Bool(bool),
+    None,
+    Name(String),
+    List(Vec<Expr>),
+    Index(Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>, Vec<(String, Expr)>),
+    Method(Box<Expr>, String, Vec<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+    Compare(Box<Expr>, Vec<(CmpOp, Expr)>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Neg(Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Target {
+    Name(String),
+    Index(Expr, Expr),
+}
+
+#[derive(Debug, PartialEq)]
+struct FunctionDef {
+    name: String,
+    params: Vec<String>,
+    body: Vec<Line>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Stmt {
+    Expr(Expr),
+    Assign(Vec<Target>, Vec<Expr>),
+    AugAssign(Target, BinOp, Expr),
+    If(Vec<(Expr, Vec<Line>)>, Vec<Line>),
+    While(Expr, Vec<Line>),
+    For(String, Expr, Vec<Line>),
+    Def(Rc<FunctionDef>),
+    Return(Option<Expr>),
+    Break,
+    Continue,
+    Pass,
+}
+
+#[derive(Debug, PartialEq)]
+struct Line {
+    number: u32,
+    stmt: Stmt,
+}
+
+/// A non-blank source line, tokenized.
+struct RawLine {
+    number: u32,
+    indent: usize,
+    tokens: Vec<Token>,
+}
+
+/// Statement head: either a whole simple statement or a block header.
+enum Head {
+    Simple(Stmt),
+    If(Expr),
+    Elif(Expr),
+    Else,
+    While(Expr),
+    For(String, Expr),
+    Def(String, Vec<String>),
+}
+
+fn parse(code: &str) -> Result<Vec<Line>, Stop> {
+    let mut lines = Vec::new();
+    for (index, text) in code.lines().enumerate() {
+        let number = u32::try_from(index + 1).unwrap_or(u32::MAX);
+        let indent = text
+            .chars()
+            .take_while(|c| *c == ' ' || *c == '\t')
+            .map(|c| if c == '\t' { 4 } else { 1 })
+            .sum();
+        let tokens = tokenize(text, number)?;
+        if !tokens.is_empty() {
+            lines.push(RawLine {
+                number,
+                indent,
+                tokens,
+            });
+        }
+    }
+    let mut position = 0;
+    let block = parse_block(&lines, &mut position, 0)?;
+    match lines.get(position) {
+        Some(line) => Err(error_at(line.number, "IndentationError: unexpected indent")),
+        None => Ok(block),
+    }
+}
+
+fn tokenize(text: &str, line: u32) -> Result<Vec<Token>, Stop> {
+    let syntax = || error_at(line, "SyntaxError: invalid syntax");
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while let Some(&c) = chars.get(i) {
+        if c == '#' {
+            break;
+        } else if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while chars
+                .get(i)
+                .is_some_and(|c| c.is_ascii_digit() || *c == '_' || *c == '.')
+            {
+                i += 1;
+            }
+            let number: String = chars[start..i].iter().filter(|c| **c != '_').collect();
+            tokens.push(if number.contains('.') {
+                Token::Float(number.parse().map_err(|_| syntax())?)
+            } else {
+                Token::Int(
+                    number.parse().map_err(|_| {
+                        error_at(line, "OverflowError: integer literal is too large")
+                    })?,
+                )
+            });
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while chars
+                .get(i)
+                .is_some_and(|c| c.is_alphanumeric() || *c == '_')
+            {
+                i += 1;
+            }
+            tokens.push(Token::Name(chars[start..i].iter().collect()));
+        } else if c == '\'' || c == '"' {
+            i += 1;
+            let mut value = String::new();
+            loop {
+                match chars.get(i) {
+                    None => return Err(error_at(line, "SyntaxError: unterminated string literal")),
+                    Some(&q) if q == c => break,
+                    Some('\\') => {
+                        i += 1;
+                        match chars.get(i) {
+                            Some('n') => value.push('\n'),
+                            Some('t') => value.push('\t'),
+                            Some(&other) => value.push(other),
+                            None => {
+                                return Err(error_at(
+                                    line,
+                                    "SyntaxError: unterminated string literal",
+                                ))
+                            }
+                        }
+                    }
+                    Some(&other) => value.push(other),
+                }
+                i += 1;
+            }
+            i += 1;
+            tokens.push(Token::Str(value));
+        } else {
+            let rest: String = chars[i..].iter().take(3).collect();
+            let op = OPERATORS
+                .iter()
+                .find(|op| rest.starts_with(**op))
+                .ok_or_else(syntax)?;
+            tokens.push(Token::Op(op));
+            i += op.len();
+        }
+    }
+    Ok(tokens)
+}
+
+fn parse_block(lines: &[RawLine], position: &mut usize, indent: usize) -> Result<Vec<Line>, Stop> {
+    let mut block = Vec::new();
+    while let Some(raw) = lines.get(*position) {
+        if raw.indent < indent {
+            break;
+        }
+        if raw.indent > indent {
+            return Err(error_at(raw.number, "IndentationError: unexpected indent"));
+        }
+        let (head, inline) = parse_head(raw)?;
+        *position += 1;
+        let stmt = match head {
+            Head::Simple(stmt) => stmt,
+            Head::If(condition) => {
+                let mut branches = alloc::vec![(condition, body(lines, position, raw, inline)?)];
+                let mut otherwise = Vec::new();
+                while let Some(next) = lines.get(*position).filter(|l| l.indent == indent) {
+                    match parse_head(next)? {
+                        (Head::Elif(condition), inline) => {
+                            *position += 1;
+                            branches.push((condition, body(lines, position, next, inline)?));
+                        }
+                        (Head::Else, inline) => {
+                            *position += 1;
+                            otherwise = body(lines, position, next, inline)?;
+                            break;
+                        }
+                        _ => break,
+                    }
+                }
+                Stmt::If(branches, otherwise)
+            }
+            Head::Elif(_) | Head::Else => {
+                return Err(error_at(raw.number, "SyntaxError: invalid syntax"))
+            }
+            Head::While(condition) => Stmt::While(condition, body(lines, position, raw, inline)?),
+            Head::For(name, iterable) => {
+                Stmt::For(name, iterable, body(lines, position, raw, inline)?)
+            }
+            Head::Def(name, params) => Stmt::Def(Rc::new(FunctionDef {
+                name,
+                params,
+                body: body(lines, position, raw, inline)?,
+            })),
+        };
+        block.push(Line {
+            number: raw.number,
+            stmt,
+        });
+    }
+    Ok(block)
+}
+
+/// Read the body of a block header: inline after the colon or indented below.
+fn body(
+    lines: &[RawLine],
+    position: &mut usize,
+    header: &RawLine,
+    inline: Option<Stmt>,
+) -> Result<Vec<Line>, Stop> {
+    if let Some(stmt) = inline {
+        return Ok(alloc::vec![Line {
+            number: header.number,
+            stmt,
+        }]);
+    }
+    match lines.get(*position) {
+        Some(next) if next.indent > header.indent => parse_block(lines, position, next.indent),
+        _ => Err(error_at(
+            header.number,
+            "IndentationError: expected an indented block",
+        )),
+    }
+}
+
+fn parse_head(raw: &RawLine) -> Result<(Head, Option<Stmt>), Stop> {
+    let mut p = Parser {
+        tokens: &raw.tokens,
+        position: 0,
+        line: raw.number,
+        depth: 0,
+    };
+    let keyword = match raw.tokens.first() {
+        Some(Token::Name(name)) => name.as_str(),
+        _ => "",
+    };
+    let head = match keyword {
+        "if" | "elif" | "while" => {
+            p.position += 1;
+            let condition = p.expr()?;
+            match keyword {
+                "if" => Head::If(condition),
+                "elif" => Head::Elif(condition),
+                _ => Head::While(condition),
+            }
+        }
+        "else" => {
+            p.position += 1;
+            Head::Else
+        }
+        "for" => {
+            p.position += 1;
+            let name = p.name()?;
+            p.keyword("in")?;
+            Head::For(name, p.expr()?)
+        }
+        "def" => {
+            p.position += 1;
+            let name = p.name()?;
+            p.op("(")?;
+            let mut params = Vec::new();
+            while !p.eat(")") {
+                params.push(p.name()?);
+                if !p.eat(",") {
+                    p.op(")")?;
+                    break;
+                }
+            }
+            Head::Def(name, params)
+        }
+        _ => {
+            let stmt = p.simple()?;
+            p.end()?;
+            return Ok((Head::Simple(stmt), None));
+        }
+    };
+    p.op(":")?;
+    if p.at_end() {
+        return Ok((head, None));
+    }
+    let inline = p.simple()?;
+    p.end()?;
+    Ok((head, Some(inline)))
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    position: usize,
+    line: u32,
+    /// Nesting of the expression being parsed
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn syntax(&self) -> Stop {
+        error_at(self.line, "SyntaxError: invalid syntax")
+    }
+
+    /// Go one level deeper into an expression.
+    ///
+    /// Parenthesized expressions, operator chains and prefix operators each
+    /// add a level; callers restore the depth once their expression is built.
+    fn deeper(&mut self) -> Result<(), Stop> {
+        self.depth += 1;
+        if self.depth > MAX_NESTING {
+            return Err(error_at(
+                self.line,
+                "SyntaxError: too many nested expressions",
+            ));
+        }
+        Ok(())
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn at_end(&self) -> bool {
+        self.position >= self.tokens.len()
+    }
+
+    fn end(&self) -> Result<(), Stop> {
+        if self.at_end() {
+            Ok(())
+        } else {
+            Err(self.syntax())
+        }
+    }
+
+    fn eat(&mut self, op: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Op(o)) if *o == op) {
+            self.position += 1;
+            return true;
+        }
+        false
+    }
+
+    fn op(&mut self, op: &str) -> Result<(), Stop> {
+        if self.eat(op) {
+            Ok(())
+        } else {
+            Err(self.syntax())
+        }
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Name(name)) if name == keyword) {
+            self.position += 1;
+            return true;
+        }
+        false
+    }
+
+    fn keyword(&mut self, keyword: &str) -> Result<(), Stop> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.syntax())
+        }
+    }
+
+    fn name(&mut self) -> Result<String, Stop> {
+        match self.peek() {
+            Some(Token::Name(name)) if !is_keyword(name) => {
+                let name = name.clone();
+                self.position += 1;
+                Ok(name)
+            }
+            _ => Err(self.syntax()),
+        }
+    }
+
+    fn simple(&mut self) -> Result<Stmt, Stop> {
+        if self.eat_keyword("pass") {
+            return Ok(Stmt::Pass);
+        }
+        if self.eat_keyword("break") {
+            return Ok(Stmt::Break);
+        }
+        if self.eat_keyword("continue") {
+            return Ok(Stmt::Continue);
+        }
+        if self.eat_keyword("return") {
+            if self.at_end() {
+                return Ok(Stmt::Return(None));
+            }
+            return Ok(Stmt::Return(Some(self.expr()?)));
+        }
+
+        let targets = self.expr_list()?;
+        let augmented = [
+            ("+=", BinOp::Add),
+            ("-=", BinOp::Sub),
+            ("*=", BinOp::Mul),
+            ("/=", BinOp::Div),
+            ("//=", BinOp::FloorDiv),
+            ("%=", BinOp::Mod),
+            ("**=", BinOp::Pow),
+        ];
+        for (op, binop) in augmented {
+            if self.eat(op) {
+                let [target] = targets.as_slice() else {
+                    return Err(self.syntax());
+                };
+                let target = self.target(target.clone())?;
+                return Ok(Stmt::AugAssign(target, binop, self.expr()?));
+            }
+        }
+        if self.eat("=") {
+            let targets = targets
+                .into_iter()
+                .map(|t| self.target(t))
+                .collect::<Result<Vec<_>, _>>()?;
+            return Ok(Stmt::Assign(targets, self.expr_list()?));
+        }
+        match <[Expr; 1]>::try_from(targets) {
+            Ok([expr]) => Ok(Stmt::Expr(expr)),
+            Err(_) => Err(self.syntax()),
+        }
+    }
+
+    fn target(&self, expr: Expr) -> Result<Target, Stop> {
+        match expr {
+            Expr::Name(name) => Ok(Target::Name(name)),
+            Expr::Index(list, index) => Ok(Target::Index(*list, *index)),
+            _ => Err(error_at(
+                self.line,
+                "SyntaxError: cannot assign to expression",
+            )),
+        }
+    }
+
+    fn expr_list(&mut self) -> Result<Vec<Expr>, Stop> {
+        let mut exprs = alloc::vec![self.expr()?];
+        while self.eat(",") {
+            exprs.push(self.expr()?);
+        }
+        Ok(exprs)
+    }
+
+    fn expr(&mut self) -> Result<Expr, Stop> {
+        let depth = self.depth;
+        self.deeper()?;
+        let mut left = self.and()?;
+        while self.eat_keyword("or") {
+            self.deeper()?;
+            left = Expr::Or(Box::new(left), Box::new(self.and()?));
+        }
+        self.depth = depth;
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, Stop> {
+        let depth = self.depth;
+        let mut left = self.not()?;
+        while self.eat_keyword("and") {
+            self.deeper()?;
+            left = Expr::And(Box::new(left), Box::new(self.not()?));
+        }
+        self.depth = depth;
+        Ok(left)
+    }
+
+    fn not(&mut self) -> Result<Expr, Stop> {
+        if self.eat_keyword("not") {
+            let depth = self.depth;
+            self.deeper()?;
+            let operand = self.not()?;
+            self.depth = depth;
+            return Ok(Expr::Not(Box::new(operand)));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, Stop> {
+        let left = self.sum()?;
+        let mut rest = Vec::new();
+        loop {
+            let op = if self.eat("==") {
+                CmpOp::Eq
+            } else if self.eat("!=") {
+                CmpOp::Ne
+            } else if self.eat("<=") {
+                CmpOp::Le
+            } else if self.eat(">=") {
+                CmpOp::Ge
+            } else if self.eat("<") {
+                CmpOp::Lt
+            } else if self.eat(">") {
+                CmpOp::Gt
+            } else if self.eat_keyword("in") {
+                CmpOp::In
+            } else if self.eat_keyword("not") {
+                self.keyword("in")?;
+                CmpOp::NotIn
+            } else {
+                break;
+            };
+            rest.push((op, self.sum()?));
+        }
+        if rest.is_empty() {
+            return Ok(left);
+        }
+        Ok(Expr::Compare(Box::new(left), rest))
+    }
+
+    fn sum(&mut self) -> Result<Expr, Stop> {
+        let depth = self.depth;
+        let mut left = self.term()?;
+        loop {
+            let op = if self.eat("+") {
+                BinOp::Add
+            } else if self.eat("-") {
+                BinOp::Sub
+            } else {
+                self.depth = depth;
+                return Ok(left);
+            };
+            self.deeper()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(self.term()?));
+        }
+    }
+
+    fn term(&mut self) -> Result<Expr, Stop> {
+        let depth = self.depth;
+        let mut left = self.unary()?;
+        loop {
+            let op = if self.eat("*") {
+                BinOp::Mul
+            } else if self.eat("//") {
+                BinOp::FloorDiv
+            } else if self.eat("/") {
+                BinOp::Div
+            } else if self.eat("%") {
+                BinOp::Mod
+            } else {
+                self.depth = depth;
+                return Ok(left);
+            };
+            self.deeper()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, Stop> {
+        let negate = self.eat("-");
+        if !negate && !self.eat("+") {
+            return self.power();
+        }
+        let depth = self.depth;
+        self.deeper()?;
+        let operand = self.unary()?;
+        self.depth = depth;
+        Ok(if negate {
+            Expr::Neg(Box::new(operand))
+        } else {
+            operand
+        })
+    }
+
+    fn power(&mut self) -> Result<Expr, Stop> {
+        let base = self.primary()?;
+        if self.eat("**") {
+            return Ok(Expr::Binary(
+                BinOp::Pow,
+                Box::new(base),
+                Box::new(self.unary()?),
+            ));
+        }
+        Ok(base)
+    }
+
+    fn primary(&mut self) -> Result<Expr, Stop> {
+        let depth = self.depth;
+        let mut expr = self.atom()?;
+        loop {
+            if matches!(self.peek(), Some(Token::Op("[" | "." | "("))) {
+                self.deeper()?;
+            }
+            if self.eat("[") {
+                let index = self.expr()?;
+                self.op("]")?;
+                expr = Expr::Index(Box::new(expr), Box::new(index));
+            } else if self.eat(".") {
+                let method = self.name()?;
+                self.op("(")?;
+                let (args, kwargs) = self.arguments()?;
+                if !kwargs.is_empty() {
+                    return Err(self.syntax());
+                }
+                expr = Expr::Method(Box::new(expr), method, args);
+            } else if self.eat("(") {
+                let Expr::Name(name) = expr else {
+                    return Err(error_at(self.line, "TypeError: object is not callable"));
+                };
+                let (args, kwargs) = self.arguments()?;
+                expr = Expr::Call(name, args, kwargs);
+            } else {
+                self.depth = depth;
+                return Ok(expr);
+            }
+        }
+    }
+
+    /// Parse call arguments after the opening parenthesis.
+    #[allow(clippy::type_complexity)]
+    fn arguments(&mut self) -> Result<(Vec<Expr>, Vec<(String, Expr)>), Stop> {
+        let mut args = Vec::new();
+        let mut kwargs = Vec::new();
+        while !self.eat(")") {
+            let keyword = match (self.peek(), self.tokens.get(self.position + 1)) {
+                (Some(Token::Name(name)), Some(Token::Op("="))) => Some(name.clone()),
+                _ => None,
+            };
+            match keyword {
+                Some(name) => {
+                    self.position += 2;
+                    kwargs.push((name, self.expr()?));
+                }
+                None => args.push(self.expr()?),
+            }
+            if !self.eat(",") {
+                self.op(")")?;
+                break;
+            }
+        }
+        Ok((args, kwargs))
+    }
+
+    fn atom(&mut self) -> Result<Expr, Stop> {
+        let token = self.peek().cloned().ok_or_else(|| self.syntax())?;
+        self.position += 1;
+        match token {
+            Token::Int(value) => Ok(Expr::Int(value)),
+            Token::Float(value) => Ok(Expr::Float(value)),
+            Token::Str(value) => Ok(Expr::Str(value)),
+            Token::Name(name) => match name.as_str() {
+                "True" => Ok(Expr::Bool(true)),
+                "False" => Ok(Expr::Bool(false)),
+                "None" => Ok(Expr::None),
+                _ if is_keyword(&name) => Err(self.syntax()),
+                _ => Ok(Expr::Name(name)),
+            },
+            Token::Op("(") => {
+                let expr = self.expr()?;
+                self.op(")")?;
+                Ok(expr)
+            }
+            Token::Op("[") => {
+                let mut items = Vec::new();
+                while !self.eat("]") {
+                    items.push(self.expr()?);
+                    if !self.eat(",") {
+                        self.op("]")?;
+                        break;
+                    }
+                }
+                Ok(Expr::List(items))
+            }
+            Token::Op(_) => Err(self.syntax()),
+        }
+    }
+}
+
+fn is_keyword(name: &str) -> bool {
+    matches!(
+        name,
+        "if" | "elif"
+            | "else"
+            | "while"
+            | "for"
+            | "in"
+            | "def"
+            | "return"
+            | "break"
+            | "continue"
+            | "pass"
+            | "and"
+            | "or"
+            | "not"
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Values
+// ---------------------------------------------------------------------------
+
+/// Bytes charged for each list slot.
+const SLOT_BYTES: usize = core::mem::size_of::<Value>();
+
+/// Bytes held by live lists and strings.
+#[derive(Debug, Default)]
+struct Meter {
+    live: Cell<usize>,
+    peak: Cell<usize>,
+}
+
+impl Meter {
+    fn add(&self, bytes: usize) {
+        let live = self.live.get().saturating_add(bytes);
+        self.live.set(live);
+        self.peak.set(self.peak.get().max(live));
+    }
+
+    fn release(&self, bytes: usize) {
+        self.live.set(self.live.get().saturating_sub(bytes));
+    }
+}
+
+/// List or string data, charged to the meter until the last reference
+/// drops.
+#[derive(Debug)]
+struct Metered<T> {
+    data: T,
+    bytes: Cell<usize>,
+    meter: Rc<Meter>,
+}
+
+impl<T> Metered<T> {
+    fn new(data: T, bytes: usize, meter: &Rc<Meter>) -> Rc<Self> {
+        meter.add(bytes);
+        Rc::new(Self {
+            data,
+            bytes: Cell::new(bytes),
+            meter: Rc::clone(meter),
+        })
+    }
+
+    /// Replace the charge after the data grew or shrank.
+    fn recharge(&self, bytes: usize) {
+        self.meter.release(self.bytes.replace(bytes));
+        self.meter.add(bytes);
+    }
+}
+
+impl<T> core::ops::Deref for Metered<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.data
+    }
+}
+
+impl<T> Drop for Metered<T> {
+    fn drop(&mut self) {
+        self.meter.release(self.bytes.get());
+    }
+}
+
+type List = Metered<RefCell<Vec<Value>>>;
+
+impl List {
+    /// Charge the list for its current length.
+    fn resized(&self) {
+        self.recharge(self.borrow().len().saturating_mul(SLOT_BYTES));
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Value {
+    None,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(Rc<Metered<String>>),
+    List(Rc<List>),
+    Range(i64, i64, i64),
+    Function(Rc<FunctionDef>),
+}
+
+impl Value {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Self::None => "NoneType",
+            Self::Bool(_) => "bool",
+            Self::Int(_) => "int",
+            Self::Float(_) => "float",
+            Self::Str(_) => "str",
+            Self::List(_) => "list",
+            Self::Range(..) => "range",
+            Self::Function(_) => "function",
+        }
+    }
+
+    fn truthy(&self) -> bool {
+        match self {
+            Self::None => false,
+            Self::Bool(b) => *b,
+            Self::Int(i) => *i != 0,
+            Self::Float(f) => *f != 0.0,
+            Self::Str(s) => !s.is_empty(),
+            Self::List(items) => !items.borrow().is_empty(),
+            Self::Range(..) => range_len(self) > 0,
+            Self::Function(_) => true,
+        }
+    }
+
+    /// Integer value of ints and bools.
+    fn as_int(&self) -> Option<i64> {
+        match self {
+            Self::Bool(b) => Some(i64::from(*b)),
+            Self::Int(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    fn as_float(&self) -> Option<f64> {
+        match self {
+            Self::Float(f) => Some(*f),
+            other => other.as_int().map(|i| i as f64),
+        }
+    }
+
+    /// Render as `str()` would.
+    fn display(&self) -> String {
+        match self {
+            Self::Str(s) => s.to_string(),
+            other => other.repr(),
+        }
+    }
+
+    /// Render as `repr()` would.
+    fn repr(&self) -> String {
+        match self {
+            Self::None => String::from("None"),
+            Self::Bool(true) => String::from("True"),
+            Self::Bool(false) => String::from("False"),
+            Self::Int(i) => i.to_string(),
+            Self::Float(f) => alloc::format!("{:?}", f),
+            Self::Str(s) => {
+                let quote = if s.contains('\'') && !s.contains('"') {
+                    '"'
+                } else {
+                    '\''
+                };
+                let mut out = String::from(quote);
+                for c in s.chars() {
+                    match c {
+                        '\n' => out.push_str("\\n"),
+                        '\t' => out.push_str("\\t"),
+                        '\\' => out.push_str("\\\\"),
+                        c if c == quote => {
+                            out.push('\\');
+                            out.push(c);
+                        }
+                        c => out.push(c),
+                    }
+                }
+                out.push(quote);
+                out
+            }
+            Self::List(items) => {
+                let items: Vec<String> = items.borrow().iter().map(Value::repr).collect();
+                alloc::format!("[{}]", items.join(", "))
+            }
+            Self::Range(start, stop, 1) => alloc::format!("range({}, {})", start, stop),
+            Self::Range(start, stop, step) => {
+                alloc::format!("range({}, {}, {})", start, stop, step)
+            }
+            Self::Function(def) => alloc::format!("<function {}>", def.name),
+        }
+    }
+}
+
+fn range_len(value: &Value) -> i64 {
+    match *value {
+        Value::Range(start, stop, step) if step > 0 && start < stop => {
+            (stop - start - 1) / step + 1
+        }
+        Value::Range(start, stop, step) if step < 0 && start > stop => {
+            (start - stop - 1) / -step + 1
+        }
+        _ => 0,
+    }
+}
+
+fn equals(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::None, Value::None) => true,
+        (Value::Str(a), Value::Str(b)) => a.as_str() == b.as_str(),
+        (Value::List(a), Value::List(b)) => {
+            let (a, b) = (a.borrow(), b.borrow());
+            a.len() == b.len() && a.iter().zip(b.iter()).all(|(x, y)| equals(x, y))
+        }
+        (Value::Range(..), Value::Range(..)) => {
+            range_items(a).eq(range_items(b)) && range_len(a) == range_len(b)
+        }
+        (Value::Function(a), Value::Function(b)) => Rc::ptr_eq(a, b),
+        _ => match (a.as_int(), b.as_int()) {
+            (Some(a), Some(b)) => a == b,
+            _ => matches!((a.as_float(), b.as_float()), (Some(a), Some(b)) if a == b),
+        },
+    }
+}
+
+fn range_items(value: &Value) -> impl Iterator<Item = i64> {
+    let (start, step) = match *value {
+        Value::Range(start, _, step) => (start, step),
+        _ => (0, 1),
+    };
+    (0..range_len(value)).map(move |k| start + k * step)
+}
+
+fn compare(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Str(a), Value::Str(b)) => Some(a.cmp(b)),
+        (Value::List(a), Value::List(b)) => {
+            let (a, b) = (a.borrow(), b.borrow());
+            for (x, y) in a.iter().zip(b.iter()) {
+                match compare(x, y)? {
+                    Ordering::Equal => {}
+                    other => return Some(other),
+                }
+            }
+            Some(a.len().cmp(&b.len()))
+        }
+        _ => match (a.as_int(), b.as_int()) {
+            (Some(a), Some(b)) => Some(a.cmp(&b)),
+            _ => a.as_float()?.partial_cmp(&b.as_float()?),
+        },
+    }
+}
+
+fn floor_div(a: i64, b: i64) -> Option<i64> {
+    let q = a.checked_div(b)?;
+    if a % b != 0 && ((a < 0) != (b < 0)) {
+        return q.checked_sub(1);
+    }
+    Some(q)
+}
+
+// ---------------------------------------------------------------------------
+// Execution
+// ---------------------------------------------------------------------------
+
+enum Flow {
+    Normal,
+    Break,
+    Continue,
+    Return(Value),
+}
+
+struct Frame {
+    function: String,
+    locals: Vec<(String, Value)>,
+    line: u32,
+}
+
+struct Interpreter<'a, 't> {
+    input: core::str::Lines<'a>,
+    output: String,
+    steps: u64,
+    meter: Rc<Meter>,
+    limits: &'a Limits,
+    globals: Vec<(String, Value)>,
+    frames: Vec<Frame>,
+    module_line: u32,
+    trace: Option<&'t mut ExecutionTrace>,
+}
+
+impl Interpreter<'_, '_> {
+    fn line(&self) -> u32 {
+        self.frames.last().map_or(self.module_line, |f| f.line)
+    }
+
+    fn error(&self, message: impl Into<String>) -> Stop {
+        error_at(self.line(), message)
+    }
+
+    fn type_error(&self, message: impl Into<String>) -> Stop {
+        self.error(alloc::format!("TypeError: {}", message.into()))
+    }
+
+    fn scope(&self) -> &Vec<(String, Value)> {
+        self.frames.last().map_or(&self.globals, |f| &f.locals)
+    }
+
+    /// Count a step at `line` and record it in the trace.
+    fn step(&mut self, line: u32) -> Result<(), Stop> {
+        if self.steps >= self.limits.max_steps {
+            return Err(Stop::Timeout);
+        }
+        // Values built without a reservation are caught here.
+        self.reserve(0)?;
+        self.steps += 1;
+        match self.frames.last_mut() {
+            Some(frame) => frame.line = line,
+            None => self.module_line = line,
+        }
+        if self.trace.is_some() {
+            let mut step = TraceStep::new(line);
+            for (name, value) in self.scope() {
+                if !matches!(value, Value::Function(_)) {
+                    step = step.with_variable(name.as_str(), value.repr());
+                }
+            }
+            step = step.with_frame(StackFrame::new("<module>", self.module_line));
+            for frame in &self.frames {
+                step = step.with_frame(StackFrame::new(frame.function.as_str(), frame.line));
+            }
+            if let Some(trace) = self.trace.as_deref_mut() {
+                trace.record(step);
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that `bytes` more of list or string data fit in the limit.
+    fn reserve(&self, bytes: usize) -> Result<(), Stop> {
+        let needed = self.meter.live.get().saturating_add(bytes);
+        if needed > self.limits.max_memory {
+            return Err(Stop::Memory(needed));
+        }
+        Ok(())
+    }
+
+    fn list(&self, items: Vec<Value>) -> Value {
+        let bytes = items.len().saturating_mul(SLOT_BYTES);
+        Value::List(Metered::new(RefCell::new(items), bytes, &self.meter))
+    }
+
+    fn str(&self, text: impl Into<String>) -> Value {
+        let text = text.into();
+        let bytes = text.len();
+        Value::Str(Metered::new(text, bytes, &self.meter))
+    }
+
+    fn write(&mut self, text: &str) {
+        self.output.push_str(text);
+        if let Some(trace) = self.trace.as_deref_mut() {
+            trace.write_stdout(text);
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Result<Value, Stop> {
+        self.scope()
+            .iter()
+            .chain(self.globals.iter())
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.clone())
+            .ok_or_else(|| self.error(alloc::format!("NameError: name '{}' is not defined", name)))
+    }
+
+    fn set(&mut self, name: &str, value: Value) {
+        let scope = match self.frames.last_mut() {
+            Some(frame) => &mut frame.locals,
+            None => &mut self.globals,
+        };
+        match scope.iter_mut().find(|(n, _)| n == name) {
+            Some(slot) => slot.1 = value,
+            None => scope.push((String::from(name), value)),
+        }
+    }
+
+    fn exec_block(&mut self, block: &[Line]) -> Result<Flow, Stop> {
+        for line in block {
+            match self.exec(line)? {
+                Flow::Normal => {}
+                flow => return Ok(flow),
+            }
+        }
+        Ok(Flow::Normal)
+    }
+
+    fn exec(&mut self, line: &Line) -> Result<Flow, Stop> {
+        self.step(line.number)?;
+        match &line.stmt {
+            Stmt::Expr(expr) => {
+                self.eval(expr)?;
+            }
+            Stmt::Assign(targets, exprs) => {
+                let values = exprs
+                    .iter()
+                    .map(|e| self.eval(e))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let values = match (targets.len(), <[Value; 1]>::try_from(values)) {
+                    (1, Ok(value)) => Vec::from(value),
+                    (_, Ok([Value::List(items)])) => items.borrow().clone(),
+                    (_, Ok([other])) => {
+                        return Err(self.type_error(alloc::format!(
+                            "cannot unpack non-sequence {}",
+                            other.type_name()
+                        )))
+                    }
+                    (_, Err(values)) => values,
+                };
+                if values.len() != targets.len() {
+                    return Err(self.error(alloc::format!(
+                        "ValueError: expected {} values to unpack, got {}",
+                        targets.len(),
+                        values.len()
+                    )));
+                }
+                for (target, value) in targets.iter().zip(values) {
+                    self.assign(target, value)?;
+                }
+            }
+            Stmt::AugAssign(target, op, expr) => {
+                let current = match target {
+                    Target::Name(name) => self.lookup(name)?,
+                    Target::Index(list, index) => {
+                        let list = self.eval(list)?;
+                        let index = self.eval(index)?;
+                        self.index(&list, &index)?
+                    }
+                };
+                let right = self.eval(expr)?;
+                let value = self.binary(*op, &current, &right)?;
+                self.assign(target, value)?;
+            }
+            Stmt::If(branches, otherwise) => {
+                for (condition, body) in branches {
+                    if self.eval(condition)?.truthy() {
+                        return self.exec_block(body);
+                    }
+                }
+                return self.exec_block(otherwise);
+            }
+            Stmt::While(condition, body) => {
+                let mut first = true;
+                loop {
+                    if !core::mem::take(&mut first) {
+                        self.step(line.number)?;
+                    }
+                    if !self.eval(condition)?.truthy() {
+                        break;
+                    }
+                    match self.exec_block(body)? {
+                        Flow::Break => break,
+                        Flow::Return(value) => return Ok(Flow::Return(value)),
+                        Flow::Normal | Flow::Continue => {}
+                    }
+                }
+            }
+            Stmt::For(name, iterable, body) => {
+                let items: Vec<Value> = match self.eval(iterable)? {
+                    range @ Value::Range(..) => range_items(&range).map(Value::Int).collect(),
+                    Value::List(items) => items.borrow().clone(),
+                    Value::Str(s) => s
+                        .chars()
+                        .map(|c| self.str(c.encode_utf8(&mut [0; 4])))
+                        .collect(),
+                    other => {
+                        return Err(self.type_error(alloc::format!(
+                            "'{}' object is not iterable",
+                            other.type_name()
+                        )))
+                    }
+                };
+                for (k, item) in items.into_iter().enumerate() {
+                    if k > 0 {
+                        self.step(line.number)?;
+                    }
+                    self.set(name, item);
+                    match self.exec_block(body)? {
+                        Flow::Break => break,
+                        Flow::Return(value) => return Ok(Flow::Return(value)),
+                        Flow::Normal | Flow::Continue => {}
+                    }
+                }
+            }
+            Stmt::Def(def) => self.set(&def.name, Value::Function(Rc::clone(def))),
+            Stmt::Return(expr) => {
+                if self.frames.is_empty() {
+                    return Err(self.error("SyntaxError: 'return' outside function"));
+                }
+                let value = match expr {
+                    Some(expr) => self.eval(expr)?,
+                    None => Value::None,
+                };
+                return Ok(Flow::Return(value));
+            }
+            Stmt::Break => return Ok(Flow::Break),
+            Stmt::Continue => return Ok(Flow::Continue),
+            Stmt::Pass => {}
+        }
+        Ok(Flow::Normal)
+    }
+
+    fn assign(&mut self, target: &Target, value: Value) -> Result<(), Stop> {
+        match target {
+            Target::Name(name) => {
+                self.set(name, value);
+                Ok(())
+            }
+            Target::Index(list, index) => {
+                let list = self.eval(list)?;
+                let index = self.eval(index)?;
+                let Value::List(items) = list else {
+                    return Err(self.type_error(alloc::format!(
+                        "'{}' object does not support item assignment",
+                        list.type_name()
+                    )));
+                };
+                let position = self.position(&index, items.borrow().len())?;
+                if let Some(slot) = items.borrow_mut().get_mut(position) {
+                    *slot = value;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Resolve a (possibly negative) index into a sequence of `len` items.
+    fn position(&self, index: &Value, len: usize) -> Result<usize, Stop> {
+        let Some(index) = index.as_int() else {
+            return Err(self.type_error(alloc::format!(
+                "indices must be integers, not {}",
+                index.type_name()
+            )));
+        };
+        let len = i64::try_from(len).unwrap_or(i64::MAX);
+        let resolved = if index < 0 { index + len } else { index };
+        if (0..len).contains(&resolved) {
+            return Ok(usize::try_from(resolved).unwrap_or(0));
+        }
+        Err(self.error("IndexError: index out of range"))
+    }
+
+    fn index(&self, value: &Value, index: &Value) -> Result<Value, Stop> {
+        match value {
+            Value::List(items) => {
+                let items = items.borrow();
+                let position = self.position(index, items.len())?;
+                Ok(items.get(position).cloned().unwrap_or(Value::None))
+            }
+            Value::Str(s) => {
+                let position = self.position(index, s.chars().count())?;
+                Ok(s.chars()
+                    .nth(position)
+                    .map_or(Value::None, |c| self.str(c.encode_utf8(&mut [0; 4]))))
+            }
+            range @ Value::Range(..) => {
+                let len = usize::try_from(range_len(range)).unwrap_or(0);
+                let position = self.position(index, len)?;
+                Ok(range_items(range)
+                    .nth(position)
+                    .map_or(Value::None, Value::Int))
+            }
+            other => Err(self.type_error(alloc::format!(
+                "'{}' object is not subscriptable",
+                other.type_name()
+            ))),
+        }
+    }
+
+    fn eval(&mut self, expr: &Expr) -> Result<Value, Stop> {
+        match expr {
+            Expr::Int(i) => Ok(Value::Int(*i)),
+            Expr::Float(f) => Ok(Value::Float(*f)),
+            Expr::Str(s) => Ok(self.str(s)),
+            Expr::Bool(b) => Ok(Value::Bool(*b)),
+            Expr::None => Ok(Value::None),
+            Expr::Name(name) => self.lookup(name),
+            Expr::List(items) => {
+                let items = items
+                    .iter()
+                    .map(|e| self.eval(e))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.reserve(items.len() * core::mem::size_of::<Value>())?;
+                Ok(self.list(items))
+            }
+            Expr::Index(value, index) => {
+                let value = self.eval(value)?;
+                let index = self.eval(index)?;
+                self.index(&value, &index)
+            }
+            Expr::Call(name, args, kwargs) => {
+                let args = args
+                    .iter()
+                    .map(|e| self.eval(e))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let mut evaluated = Vec::with_capacity(kwargs.len());
+                for (key, value) in kwargs {
+                    evaluated.push((key.as_str(), self.eval(value)?));
+                }
+                self.call(name, args, &evaluated)
+            }
+            Expr::Method(receiver, method, args) => {
+                let receiver = self.eval(receiver)?;
+                let args = args
+                    .iter()
+                    .map(|e| self.eval(e))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.method(&receiver, method, args)
+            }
+            Expr::Binary(op, left, right) => {
+                let left = self.eval(left)?;
+                let right = self.eval(right)?;
+                self.binary(*op, &left, &right)
+            }
+            Expr::Compare(first, rest) => {
+                let mut left = self.eval(first)?;
+                for (op, right) in rest {
+                    let right = self.eval(right)?;
+                    if !self.compare(*op, &left, &right)? {
+                        return Ok(Value::Bool(false));
+                    }
+                    left = right;
+                }
+                Ok(Value::Bool(true))
+            }
+            Expr::And(left, right) => {
+                let left = self.eval(left)?;
+                if !left.truthy() {
+                    return Ok(left);
+                }
+                self.eval(right)
+            }
+            Expr::Or(left, right) => {
+                let left = self.eval(left)?;
+                if left.truthy() {
+                    return Ok(left);
+                }
+                self.eval(right)
+            }
+            Expr::Not(value) => Ok(Value::Bool(!self.eval(value)?.truthy())),
+            Expr::Neg(value) => {
+                let value = self.eval(value)?;
+                self.binary(BinOp::Sub, &Value::Int(0), &value)
+            }
+        }
+    }
+
+    fn compare(&self, op: CmpOp, left: &Value, right: &Value) -> Result<bool, Stop> {
+        let ordering = || {
+            compare(left, right).ok_or_else(|| {
+                self.type_error(alloc::format!(
+                    "'<' not supported between instances of '{}' and '{}'",
+                    left.type_name(),
+                    right.type_name()
+                ))
+            })
+        };
+        Ok(match op {
+            CmpOp::Eq => equals(left, right),
+            CmpOp::Ne => !equals(left, right),
+            CmpOp::Lt => ordering()? == Ordering::Less,
+            CmpOp::Le => ordering()? != Ordering::Greater,
+            CmpOp::Gt => ordering()? == Ordering::Greater,
+            CmpOp::Ge => ordering()? != Ordering::Less,
+            CmpOp::In | CmpOp::NotIn => {
+                let found = match right {
+                    Value::List(items) => items.borrow().iter().any(|i| equals(i, left)),
+                    Value::Str(haystack) => match left {
+                        Value::Str(needle) => haystack.contains(needle.as_str()),
+                        other => {
+                            return Err(self.type_error(alloc::format!(
+                                "'in <string>' requires string as left operand, not {}",
+                                other.type_name()
+                            )))
+                        }
+                    },
+                    range @ Value::Range(..) => left
+                        .as_int()
+                        .is_some_and(|i| range_items(range).any(|r| r == i)),
+                    other => {
+                        return Err(self.type_error(alloc::format!(
+                            "argument of type '{}' is not iterable",
+                            other.type_name()
+                        )))
+                    }
+                };
+                found == (op == CmpOp::In)
+            }
+        })
+    }
+
+    fn binary(&mut self, op: BinOp, left: &Value, right: &Value) -> Result<Value, Stop> {
+        let overflow = || self.error("OverflowError: integer overflow");
+        let unsupported = || {
+            let symbol = match op {
+                BinOp::Add => "+",
+                BinOp::Sub => "-",
+                BinOp::Mul => "*",
+                BinOp::Div => "/",
+                BinOp::FloorDiv => "//",
+                BinOp::Mod => "%",
+                BinOp::Pow => "**",
+            };
+            self.type_error(alloc::format!(
+                "unsupported operand type(s) for {}: '{}' and '{}'",
+                symbol,
+                left.type_name(),
+                right.type_name()
+            ))
+        };
+
+        match (op, left, right) {
+            (BinOp::Add, Value::Str(a), Value::Str(b)) => {
+                self.reserve(a.len() + b.len())?;
+                return Ok(self.str(alloc::format!("{}{}", a.as_str(), b.as_str())));
+            }
+            (BinOp::Add, Value::List(a), Value::List(b)) => {
+                let mut items = a.borrow().clone();
+                items.extend(b.borrow().iter().cloned());
+                self.reserve(items.len() * core::mem::size_of::<Value>())?;
+                return Ok(self.list(items));
+            }
+            (BinOp::Mul, Value::Str(s), n) | (BinOp::Mul, n, Value::Str(s))
+                if n.as_int().is_some() =>
+            {
+                let count = usize::try_from(n.as_int().unwrap_or(0)).unwrap_or(0);
+                self.reserve(s.len().saturating_mul(count))?;
+                return Ok(self.str(s.repeat(count)));
+            }
+            (BinOp::Mul, Value::List(items), n) | (BinOp::Mul, n, Value::List(items))
+                if n.as_int().is_some() =>
+            {
+                let count = usize::try_from(n.as_int().unwrap_or(0)).unwrap_or(0);
+                let len = items.borrow().len();
+                self.reserve(
+                    len.saturating_mul(count)
+                        .saturating_mul(core::mem::size_of::<Value>()),
+                )?;
+                let items = items.borrow();
+                let mut repeated = Vec::with_capacity(len * count);
+                for _ in 0..count {
+                    repeated.extend(items.iter().cloned());
+                }
+                return Ok(self.list(repeated));
+            }
+            _ => {}
+        }
+
+        if let (Some(a), Some(b)) = (left.as_int(), right.as_int()) {
+            let zero = || self.error("ZeroDivisionError: integer division or modulo by zero");
+            return match op {
+                BinOp::Add => a.checked_add(b).map(Value::Int).ok_or_else(overflow),
+                BinOp::Sub => a.checked_sub(b).map(Value::Int).ok_or_else(overflow),
+                BinOp::Mul => a.checked_mul(b).map(Value::Int).ok_or_else(overflow),
+                BinOp::Div if b == 0 => Err(self.error("ZeroDivisionError: division by zero")),
+                BinOp::Div => Ok(Value::Float(a as f64 / b as f64)),
+                BinOp::FloorDiv | BinOp::Mod if b == 0 => Err(zero()),
+                BinOp::FloorDiv => floor_div(a, b).map(Value::Int).ok_or_else(overflow),
+                BinOp::Mod => floor_div(a, b)
+                    .and_then(|q| q.checked_mul(b))
+                    .and_then(|p| a.checked_sub(p))
+                    .map(Value::Int)
+                    .ok_or_else(overflow),
+                BinOp::Pow if b < 0 => Ok(Value::Float(libm::pow(a as f64, b as f64))),
+                BinOp::Pow => u32::try_from(b)
+                    .ok()
+                    .and_then(|b| a.checked_pow(b))
+                    .map(Value::Int)
+                    .ok_or_else(overflow),
+            };
+        }
+
+        let (Some(a), Some(b)) = (left.as_float(), right.as_float()) else {
+            return Err(unsupported());
+        };
+        let zero = || self.error("ZeroDivisionError: float division by zero");
+        Ok(Value::Float(match op {
+            BinOp::Add => a + b,
+            BinOp::Sub => a - b,
+            BinOp::Mul => a * b,
+            BinOp::Div | BinOp::FloorDiv | BinOp::Mod if b == 0.0 => return Err(zero()),
+            BinOp::Div => a / b,
+            BinOp::FloorDiv => libm::floor(a / b),
+            BinOp::Mod => a - b * libm::floor(a / b),
+            BinOp::Pow => libm::pow(a, b),
+        }))
+    }
+
+    fn call(
+        &mut self,
+        name: &str,
+        args: Vec<Value>,
+        kwargs: &[(&str, Value)],
+    ) -> Result<Value, Stop> {
+        let defined = self
+            .scope()
+            .iter()
+            .chain(self.globals.iter())
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.clone());
+        if let Some(value) = defined {
+            let Value::Function(def) = value else {
+                return Err(self.type_error(alloc::format!(
+                    "'{}' object is not callable",
+                    value.type_name()
+                )));
+            };
+            return self.call_function(&def, args);
+        }
+        if !kwargs.is_empty() && name != "print" {
+            return Err(self.type_error(alloc::format!("{}() takes no keyword arguments", name)));
+        }
+
+        let arity = |expected: core::ops::RangeInclusive<usize>| {
+            if expected.contains(&args.len()) {
+                Ok(())
+            } else {
+                Err(self.type_error(alloc::format!(
+                    "{}() takes {} argument(s) ({} given)",
+                    name,
+                    expected.end(),
+                    args.len()
+                )))
+            }
+        };
+        match name {
+            "print" => {
+                let mut sep = String::from(" ");
+                let mut end = String::from("\n");
+                for (key, value) in kwargs {
+                    match *key {
+                        "sep" => sep = value.display(),
+                        "end" => end = value.display(),
+                        other => {
+                            return Err(self.type_error(alloc::format!(
+                                "'{}' is an invalid keyword argument for print()",
+                                other
+                            )))
+                        }
+                    }
+                }
+                let text: Vec<String> = args.iter().map(Value::display).collect();
+                let text = alloc::format!("{}{}", text.join(&sep), end);
+                self.write(&text);
+                Ok(Value::None)
+            }
+            "input" => {
+                arity(0..=1)?;
+                if let Some(prompt) = args.first() {
+                    let prompt = prompt.display();
+                    self.write(&prompt);
+                }
+                match self.input.next() {
+                    Some(line) => Ok(self.str(line)),
+                    None => Err(self.error("EOFError: EOF when reading a line")),
+                }
+            }
+            "len" => {
+                arity(1..=1)?;
+                match &args[0] {
+                    Value::Str(s) => Ok(Value::Int(s.chars().count() as i64)),
+                    Value::List(items) => Ok(Value::Int(items.borrow().len() as i64)),
+                    range @ Value::Range(..) => Ok(Value::Int(range_len(range))),
+                    other => Err(self.type_error(alloc::format!(
+                        "object of type '{}' has no len()",
+                        other.type_name()
+                    ))),
+                }
+            }
+            "range" => {
+                arity(1..=3)?;
+                let bounds = args
+                    .iter()
+                    .map(|a| {
+                        a.as_int().ok_or_else(|| {
+                            self.type_error(alloc::format!(
+                                "'{}' object cannot be interpreted as an integer",
+                                a.type_name()
+                            ))
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                match bounds.as_slice() {
+                    [stop] => Ok(Value::Range(0, *stop, 1)),
+                    [start, stop] => Ok(Value::Range(*start, *stop, 1)),
+                    [_, _, 0] => Err(self.error("ValueError: range() arg 3 must not be zero")),
+                    [start, stop, step] => Ok(Value::Range(*start, *stop, *step)),
+                    _ => Err(self.type_error("range expected at most 3 arguments")),
+                }
+            }
+            "int" => {
+                arity(1..=1)?;
+                match &args[0] {
+                    Value::Str(s) => s.trim().parse().map(Value::Int).map_err(|_| {
+                        self.error(alloc::format!(
+                            "ValueError: invalid literal for int() with base 10: {}",
+                            args[0].repr()
+                        ))
+                    }),
+                    Value::Float(f) => Ok(Value::Int(*f as i64)),
+                    other => other.as_int().map(Value::Int).ok_or_else(|| {
+                        self.type_error(alloc::format!(
+                            "int() argument must be a string or a number, not '{}'",
+                            other.type_name()
+                        ))
+                    }),
+                }
+            }
+            "float" => {
+                arity(1..=1)?;
+                match &args[0] {
+                    Value::Str(s) => s.trim().parse().map(Value::Float).map_err(|_| {
+                        self.error(alloc::format!(
+                            "ValueError: could not convert string to float: {}",
+                            args[0].repr()
+                        ))
+                    }),
+                    other => other.as_float().map(Value::Float).ok_or_else(|| {
+                        self.type_error(alloc::format!(
+                            "float() argument must be a string or a number, not '{}'",
+                            other.type_name()
+                        ))
+                    }),
+                }
+            }
+            "str" => {
+                arity(1..=1)?;
+                Ok(self.str(args[0].display()))
+            }
+            "abs" => {
+                arity(1..=1)?;
+                match &args[0] {
+                    Value::Float(f) => Ok(Value::Float(libm::fabs(*f))),
+                    other => other
+                        .as_int()
+                        .and_then(i64::checked_abs)
+                        .map(Value::Int)
+                        .ok_or_else(|| {
+                            self.type_error(alloc::format!(
+                                "bad operand type for abs(): '{}'",
+                                other.type_name()
+                            ))
+                        }),
+                }
+            }
+            "list" | "sorted" | "min" | "max" | "sum" => {
+                let items = match (name, args.as_slice()) {
+                    ("min" | "max", [_, _, ..]) => args.clone(),
+                    (_, [iterable]) => self.items(iterable)?,
+                    _ => {
+                        arity(1..=1)?;
+                        Vec::new()
+                    }
+                };
+                self.reserve(items.len() * core::mem::size_of::<Value>())?;
+                match name {
+                    "list" => Ok(self.list(items)),
+                    "sorted" => Ok(self.list(self.sorted(items)?)),
+                    "sum" => items.iter().try_fold(Value::Int(0), |total, item| {
+                        self.binary(BinOp::Add, &total, item)
+                    }),
+                    _ => {
+                        let sorted = self.sorted(items)?;
+                        let picked = if name == "min" {
+                            sorted.into_iter().next()
+                        } else {
+                            sorted.into_iter().last()
+                        };
+                        picked.ok_or_else(|| {
+                            self.error(alloc::format!(
+                                "ValueError: {}() arg is an empty sequence",
+                                name
+                            ))
+                        })
+                    }
+                }
+            }
+            _ => Err(self.error(alloc::format!("NameError: name '{}' is not defined", name))),
+        }
+    }
+
+    /// Get the items of an iterable.
+    fn items(&self, value: &Value) -> Result<Vec<Value>, Stop> {
+        match value {
+            Value::List(items) => Ok(items.borrow().clone()),
+            Value::Str(s) => Ok(s
+                .chars()
+                .map(|c| self.str(c.encode_utf8(&mut [0; 4])))
+                .collect()),
+            range @ Value::Range(..) => Ok(range_items(range).map(Value::Int).collect()),
+            other => Err(self.type_error(alloc::format!(
+                "'{}' object is not iterable",
+                other.type_name()
+            ))),
+        }
+    }
+
+    fn sorted(&self, mut items: Vec<Value>) -> Result<Vec<Value>, Stop> {
+        if let Some(pair) = items
+            .windows(2)
+            .find(|pair| compare(&pair[0], &pair[1]).is_none())
+        {
+            return Err(self.type_error(alloc::format!(
+                "'<' not supported between instances of '{}' and '{}'",
+                pair[0].type_name(),
+                pair[1].type_name()
+            )));
+        }
+        items.sort_by(|a, b| compare(a, b).unwrap_or(Ordering::Equal));
+        Ok(items)
+    }
+
+    fn call_function(&mut self, def: &Rc<FunctionDef>, args: Vec<Value>) -> Result<Value, Stop> {
+        if args.len() != def.params.len() {
+            return Err(self.type_error(alloc::format!(
+                "{}() takes {} positional argument(s) but {} were given",
+                def.name,
+                def.params.len(),
+                args.len()
+            )));
+        }
+        if self.frames.len() >= MAX_DEPTH {
+            return Err(self.error("RecursionError: maximum recursion depth exceeded"));
+        }
+        self.frames.push(Frame {
+            function: def.name.clone(),
+            locals: def.params.iter().cloned().zip(args).collect(),
+            line: self.line(),
+        });
+        let flow = self.exec_block(&def.body);
+        self.frames.pop();
+        match flow? {
+            Flow::Return(value) => Ok(value),
+            _ => Ok(Value::None),
+        }
+    }
+
+    fn method(&mut self, receiver: &Value, method: &str, args: Vec<Value>) -> Result<Value, Stop> {
+        match (receiver, method, args.as_slice()) {
+            (Value::List(items), "append", [value]) => {
+                self.reserve(core::mem::size_of::<Value>())?;
+                items.borrow_mut().push(value.clone());
+                items.resized();
+                Ok(Value::None)
+            }
+            (Value::List(items), "pop", []) => {
+                let popped = items.borrow_mut().pop();
+                items.resized();
+                popped.ok_or_else(|| self.error("IndexError: pop from empty list"))
+            }
+            (Value::List(items), "pop", [index]) => {
+                let position = self.position(index, items.borrow().len())?;
+                let removed = items.borrow_mut().remove(position);
+                items.resized();
+                Ok(removed)
+            }
+            (Value::List(items), "insert", [index, value]) => {
+                let len = items.borrow().len();
+                let index = index.as_int().unwrap_or(0);
+                let len_i = i64::try_from(len).unwrap_or(i64::MAX);
+                let at = if index < 0 { index + len_i } else { index };
+                let at = usize::try_from(at.clamp(0, len_i)).unwrap_or(len);
+                self.reserve(core::mem::size_of::<Value>())?;
+                items.borrow_mut().insert(at, value.clone());
+                items.resized();
+                Ok(Value::None)
+            }
+            (Value::Str(s), "split", []) => {
+                Ok(self.list(s.split_whitespace().map(|part| self.str(part)).collect()))
+            }
+            (Value::Str(s), "split", [Value::Str(sep)]) if !sep.is_empty() => {
+                Ok(self.list(s.split(sep.as_str()).map(|part| self.str(part)).collect()))
+            }
+            (Value::Str(s), "strip", []) => Ok(self.str(s.trim())),
+            (Value::Str(s), "upper", []) => Ok(self.str(s.to_uppercase())),
+            (Value::Str(s), "lower", []) => Ok(self.str(s.to_lowercase())),
+            (Value::Str(sep), "join", [iterable]) => {
+                let parts = self
+                    .items(iterable)?
+                    .iter()
+                    .map(|part| match part {
+                        Value::Str(s) => Ok(s.to_string()),
+                        other => Err(self.type_error(alloc::format!(
+                            "sequence item: expected str instance, {} found",
+                            other.type_name()
+                        ))),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(self.str(parts.join(sep)))
+            }
+            _ => Err(self.error(alloc::format!(
+                "AttributeError: '{}' object has no attribute '{}'",
+                receiver.type_name(),
+                method
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits() -> Limits {
+        Limits {
+            max_steps: 100_000,
+            max_memory: 1 << 20,
+        }
+    }
+
+    fn output(code: &str, input: &str) -> String {
+        match run(code, input, &limits(), None) {
+            ExecutionResult::Success { output, .. } => output,
+            other => alloc::format!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_arithmetic_and_print() {
+        assert_eq!(
+            output("print(1 + 2 * 3, 7 // 2, -7 // 2, -7 % 3)", ""),
+            "7 3 -4 2\n"
+        );
+        assert_eq!(
+            output("print(2 ** 10, 7 / 2, 'a' + 'b', 'ab' * 2)", ""),
+            "1024 3.5 ab abab\n"
+        );
+        assert_eq!(
+            output("print([1, 'x', None, True])", ""),
+            "[1, 'x', None, True]\n"
+        );
+        assert_eq!(output("print(1, 2, sep='-', end='!')", ""), "1-2!");
+    }
+
+    #[test]
+    fn test_control_flow() {
+        let code = "\
+total = 0
+for i in range(10):
+    if i % 2 == 0:
+        continue
+    elif i > 7:
+        break
+    total += i
+n = 3
+while n > 0: n -= 1
+print(total, n)
+";
+        assert_eq!(output(code, ""), "16 0\n");
+        assert_eq!(output("for i in range(3): print(i)", ""), "0\n1\n2\n");
+    }
+
+    #[test]
+    fn test_functions_and_lists() {
+        let code = "\
+def fib(n):
+    if n < 2:
+        return n
+    return fib(n - 1) + fib(n - 2)
+
+xs = []
+for i in range(8):
+    xs.append(fib(i))
+a, b = xs[-1], len(xs)
+xs[0] = 99
+print(a, b, xs, sorted(xs)[0], max(xs), sum(xs))
+print(' '.join(['a', 'b']), 3 in xs, 4 not in xs)
+";
+        assert_eq!(
+            output(code, ""),
+            "13 8 [99, 1, 1, 2, 3, 5, 8, 13] 1 99 132\na b True True\n"
+        );
+    }
+
+    #[test]
+    fn test_input() {
+        let code = "n = int(input())\nwords = input().split()\nprint(n * 2, len(words))";
+        assert_eq!(output(code, "21\nsee spot run\n"), "42 3\n");
+    }
+
+    #[test]
+    fn test_errors_carry_line() {
+        let result = run("x = 1\nprint(y)", "", &limits(), None);
+        assert_eq!(
+            result.error_message(),
+            Some("NameError: name 'y' is not defined")
+        );
+        assert!(matches!(
+            result,
+            ExecutionResult::RuntimeError { line: Some(2), .. }
+        ));
+
+        let result = run("if True\n    pass", "", &limits(), None);
+        assert_eq!(result.error_message(), Some("SyntaxError: invalid syntax"));
+        let result = run("print(1 // 0)", "", &limits(), None);
+        assert_eq!(
+            result.error_message(),
+            Some("ZeroDivisionError: integer division or modulo by zero")
+        );
+        let result = run("x = int(input())", "", &limits(), None);
+        assert_eq!(
+            result.error_message(),
+            Some("EOFError: EOF when reading a line")
+        );
+    }
+
+    #[test]
+    fn test_limits() {
+        let result = run("while True:\n    pass", "", &limits(), None);
+        assert!(matches!(result, ExecutionResult::Timeout { .. }));
+        assert_eq!(result.usage().steps, limits().max_steps);
+
+        let result = run("s = 'x' * 10000000", "", &limits(), None);
+        assert!(matches!(result, ExecutionResult::MemoryExceeded { .. }));
+
+        let result = run("def f():\n    return f()\nf()", "", &limits(), None);
+        assert_eq!(
+            result.error_message(),
+            Some("RecursionError: maximum recursion depth exceeded")
+        );
+    }
+
+    #[test]
+    fn test_memory_counts_live_values() {
+        let limits = Limits {
+            max_steps: 1_000_000,
+            max_memory: 1 << 12,
+        };
+        let code = "for i in range(10000):\n    xs = [i] * 10\n    s = 'ab' * 20";
+        let result = run(code, "", &limits, None);
+        assert!(matches!(result, ExecutionResult::Success { .. }));
+        let peak = result.usage().peak_memory_bytes;
+        assert!(peak >= 10 * SLOT_BYTES + 40);
+        assert!(peak <= 2 * (10 * SLOT_BYTES + 40));
+
+        let code = "xs = []\nfor i in range(10000):\n    xs.append(i)";
+        let result = run(code, "", &limits, None);
+        assert!(matches!(
+            result,
+            ExecutionResult::MemoryExceeded { used_bytes, .. } if used_bytes > 1 << 12
+        ));
+
+        let result = run("xs = [1, 2, 3]\nxs.pop()\nxs.pop()", "", &limits, None);
+        assert_eq!(result.usage().peak_memory_bytes, 3 * SLOT_BYTES);
+    }
+
+    #[test]
+    fn test_deep_nesting_is_a_syntax_error() {
+        let nested =
+            |depth: usize| alloc::format!("x = {}1{}", "(".repeat(depth), ")".repeat(depth));
+        assert_eq!(
+            run(&(nested(90) + "\nprint(x)"), "", &limits(), None).output(),
+            Some("1\n")
+        );
+
+        let too_deep = [
+            nested(200_000),
+            alloc::format!("x = 1{}", " + 1".repeat(100_000)),
+            alloc::format!("x = {}1", "-".repeat(100_000)),
+            alloc::format!("x = {}True", "not ".repeat(100_000)),
+            alloc::format!("x = [[1]]{}", "[0]".repeat(100_000)),
+        ];
+        for code in &too_deep {
+            let result = run(code, "", &limits(), None);
+            assert_eq!(
+                result.error_message(),
+                Some("SyntaxError: too many nested expressions")
+            );
+            assert_eq!(result.usage().steps, 0);
+        }
+    }
+
+    #[test]
+    fn test_steps_count_statements_and_loop_tests() {
+        let result = run("x = 0\nfor i in range(5):\n    x += i", "", &limits(), None);
+        // 1 assignment, 5 loop headers, 5 body statements
+        assert_eq!(result.usage().steps, 11);
+    }
+}
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_integer_arithmetic_matches(a in -1000i64..1000, b in 1i64..100) {
+            let code = alloc::format!("print({a} + {b}, {a} - {b}, {a} * {b}, {a} // {b}, {a} % {b})");
+            let expected = alloc::format!(
+                "{} {} {} {} {}\n",
+                a + b,
+                a - b,
+                a * b,
+                a.div_euclid(b),
+                a.rem_euclid(b)
+            );
+            let result = run(&code, "", &Limits { max_steps: 10, max_memory: 1 << 10 }, None);
+            prop_assert_eq!(result.output(), Some(expected.as_str()));
+        }
+
+        #[test]
+        fn test_never_panics(code in "[a-z0-9 ()\\[\\]+*/%=:,.'\\n-]{0,60}") {
+            let _ = run(&code, "", &Limits { max_steps: 1000, max_memory: 1 << 16 }, None);
+        }
+    }
+}
@@ -1,54 +1,221 @@
 //! Lab test runner.
 //!
 //! Runs test suites for lab submissions and provides detailed feedback.
+//!
+//! With the `std` feature, independent test cases run in parallel across
+//! threads. Results for the latest submission can be cached so
+//! keystroke-triggered reruns of unchanged code return instantly.
 
+use alloc::collections::BTreeMap;
 use alloc::string::String;
 use alloc::vec::Vec;
-use profesor_core::{Lab, TestCase};
+use profesor_core::{Lab, Language, TestCase};
 use serde::{Deserialize, Serialize};
 
 use crate::sandbox::{ExecutionResult, Sandbox};
 
+/// How much of a test suite to run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+pub enum RunMode {
+    /// Run every test (used for submissions)
+    #[default]
+    Full,
+    /// Stop after the first failing test (used for quick feedback while editing)
+    QuickCheck,
+}
+
 /// Test runner for lab submissions.
 #[derive(Debug, Clone)]
 pub struct TestRunner {
     sandbox: Sandbox,
+    cache: ResultCache,
+}
+
+/// Results cached for the most recent submission only.
+#[derive(Debug, Clone, Default)]
+struct ResultCache {
+    code: String,
+    language: Option<Language>,
+    /// Test case and result, by cache key
+    entries: BTreeMap<u64, (TestCase, TestResult)>,
+}
+
+impl ResultCache {
+    /// Drop every entry that does not belong to this submission and suite.
+    fn retain(&mut self, code: &str, language: Language, keys: &[u64]) {
+        if self.code != code || self.language != Some(language) {
+            self.entries.clear();
+            self.code = code.into();
+            self.language = Some(language);
+        }
+        self.entries.retain(|key, _| keys.contains(key));
+    }
+
+    /// Look up a result, checking the stored test so hash collisions miss.
+    fn get(&self, key: u64, test: &TestCase) -> Option<&TestResult> {
+        self.entries
+            .get(&key)
+            .filter(|(cached, _)| cached == test)
+            .map(|(_, result)| result)
+    }
+
+    fn insert(&mut self, key: u64, test: &TestCase, result: TestResult) {
+        self.entries.insert(key, (test.clone(), result));
+    }
 }
 
 impl TestRunner {
     /// Create a new test runner with default sandbox.
     #[must_use]
     pub fn new() -> Self {
-        Self {
-            sandbox: Sandbox::new(),
-        }
+        Self::with_sandbox(Sandbox::new())
     }
 
     /// Create a test runner with a custom sandbox.
     #[must_use]
     pub fn with_sandbox(sandbox: Sandbox) -> Self {
-        Self { sandbox }
+        Self {
+            sandbox,
+            cache: ResultCache::default(),
+        }
     }
 
     /// Run all tests for a lab submission.
     #[must_use]
     pub fn run_tests(&self, code: &str, lab: &Lab) -> TestResults {
+        let tests: Vec<&TestCase> = lab.test_suite.tests.iter().collect();
+        let results = self.run_batch(code, lab, &tests);
+        TestResults::from_results(results, lab.test_suite.test_count())
+    }
+
+    /// Run tests in order, stopping after the first failure.
+    #[must_use]
+    pub fn quick_check(&self, code: &str, lab: &Lab) -> TestResults {
         let mut results = Vec::new();
 
         for test in &lab.test_suite.tests {
             let result = self.run_single_test(code, lab, test);
+            let failed = result.is_failed();
             results.push(result);
+            if failed {
+                break;
+            }
         }
 
-        let passed_count = results.iter().filter(|r| r.passed).count();
-        let all_passed = passed_count == results.len();
+        TestResults::from_results(results, lab.test_suite.test_count())
+    }
 
-        TestResults {
-            results,
-            all_passed,
-            passed_count,
-            total_count: lab.test_suite.test_count(),
+    /// Run tests, reusing cached results for unchanged code and tests.
+    ///
+    /// Only the latest submission is cached: changing the code or language
+    /// discards every entry, and editing a test reruns just that test.
+    pub fn run_tests_cached(&mut self, code: &str, lab: &Lab, mode: RunMode) -> TestResults {
+        let keys: Vec<u64> = lab
+            .test_suite
+            .tests
+            .iter()
+            .map(|test| cache_key(code, lab, test))
+            .collect();
+        self.cache.retain(code, lab.language, &keys);
+
+        let results = match mode {
+            RunMode::Full => {
+                let misses: Vec<(u64, &TestCase)> = keys
+                    .iter()
+                    .zip(&lab.test_suite.tests)
+                    .filter(|(key, test)| self.cache.get(**key, test).is_none())
+                    .map(|(key, test)| (*key, test))
+                    .collect();
+                let tests: Vec<&TestCase> = misses.iter().map(|(_, test)| *test).collect();
+                let fresh = self.run_batch(code, lab, &tests);
+                for ((key, test), result) in misses.into_iter().zip(fresh) {
+                    self.cache.insert(key, test, result);
+                }
+                keys.iter()
+                    .zip(&lab.test_suite.tests)
+                    .filter_map(|(key, test)| self.cache.get(*key, test).cloned())
+                    .collect()
+            }
+            RunMode::QuickCheck => {
+                let mut results = Vec::new();
+                for (key, test) in keys.iter().zip(&lab.test_suite.tests) {
+                    let result = match self.cache.get(*key, test) {
+                        Some(cached) => cached.clone(),
+                        None => {
+                            let fresh = self.run_single_test(code, lab, test);
+                            self.cache.insert(*key, test, fresh.clone());
+                            fresh
+                        }
+                    };
+                    let failed = result.is_failed();
+                    results.push(result);
+                    if failed {
+                        break;
+                    }
+                }
+                results
+            }
+        };
+
+        TestResults::from_results(results, lab.test_suite.test_count())
+    }
+
+    /// Get the number of cached test results.
+    #[must_use]
+    pub fn cached_count(&self) -> usize {
+        self.cache.entries.len()
+    }
+
+    /// Discard all cached test results.
+    pub fn clear_cache(&mut self) {
+        self.cache.entries.clear();
+    }
+
+    /// Run a batch of independent tests, in parallel when threads are available.
+    #[cfg(feature = "std")]
+    fn run_batch(&self, code: &str, lab: &Lab, tests: &[&TestCase]) -> Vec<TestResult> {
+        let workers = std::thread::available_parallelism()
+            .map_or(1, core::num::NonZeroUsize::get)
+            .min(tests.len());
+        if workers < 2 {
+            return self.run_sequential(code, lab, tests);
         }
+
+        let chunk_size = tests.len().div_ceil(workers);
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = tests
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    let handle = scope.spawn(move || self.run_sequential(code, lab, chunk));
+                    (chunk, handle)
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .flat_map(|(chunk, handle)| {
+                    handle.join().unwrap_or_else(|_| {
+                        chunk
+                            .iter()
+                            .map(|test| TestResult::errored(test, "Test worker crashed"))
+                            .collect()
+                    })
+                })
+                .collect()
+        })
+    }
+
+    /// Run a batch of independent tests.
+    #[cfg(not(feature = "std"))]
+    fn run_batch(&self, code: &str, lab: &Lab, tests: &[&TestCase]) -> Vec<TestResult> {
+        self.run_sequential(code, lab, tests)
+    }
+
+    fn run_sequential(&self, code: &str, lab: &Lab, tests: &[&TestCase]) -> Vec<TestResult> {
+        tests
+            .iter()
+            .map(|test| self.run_single_test(code, lab, test))
+            .collect()
     }
 
     /// Run a single test case.
@@ -59,6 +226,7 @@ impl TestRunner {
             ExecutionResult::Success {
                 output,
                 duration_ms,
+                ..
             } => {
                 let passed = output.trim() == test.expected_output.trim();
                 TestResult {
@@ -70,7 +238,7 @@ impl TestRunner {
                     error: None,
                 }
             }
-            ExecutionResult::RuntimeError { error, line } => TestResult {
+            ExecutionResult::RuntimeError { error, line, .. } => TestResult {
                 name: test.name.clone(),
                 passed: false,
                 expected: test.expected_output.clone(),
@@ -82,7 +250,7 @@ impl TestRunner {
                     error
                 )),
             },
-            ExecutionResult::Timeout { partial_output } => TestResult {
+            ExecutionResult::Timeout { partial_output, .. } => TestResult {
                 name: test.name.clone(),
                 passed: false,
                 expected: test.expected_output.clone(),
@@ -90,7 +258,7 @@ impl TestRunner {
                 duration_ms: None,
                 error: Some("Execution timed out".into()),
             },
-            ExecutionResult::MemoryExceeded { used_bytes } => TestResult {
+            ExecutionResult::MemoryExceeded { used_bytes, .. } => TestResult {
                 name: test.name.clone(),
                 passed: false,
                 expected: test.expected_output.clone(),
@@ -101,7 +269,7 @@ impl TestRunner {
                     used_bytes
                 )),
             },
-            ExecutionResult::Error { message } => TestResult {
+            ExecutionResult::Error { message, .. } => TestResult {
                 name: test.name.clone(),
                 passed: false,
                 expected: test.expected_output.clone(),
@@ -130,6 +298,30 @@ impl Default for TestRunner {
     }
 }
 
+/// Hash a submission and test case into a cache key (64-bit FNV-1a).
+fn cache_key(code: &str, lab: &Lab, test: &TestCase) -> u64 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let timeout = test.timeout_ms.to_le_bytes();
+    let fields: [&[u8]; 6] = [
+        code.as_bytes(),
+        lab.language.extension().as_bytes(),
+        test.name.as_bytes(),
+        test.input.as_bytes(),
+        test.expected_output.as_bytes(),
+        &timeout,
+    ];
+
+    fields.iter().fold(OFFSET, |hash, field| {
+        // Length prefix keeps ("ab", "c") and ("a", "bc") distinct.
+        let len = (field.len() as u64).to_le_bytes();
+        len.iter()
+            .chain(field.iter())
+            .fold(hash, |h, &b| (h ^ u64::from(b)).wrapping_mul(PRIME))
+    })
+}
+
 /// Result of running a single test.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct TestResult {
@@ -148,6 +340,19 @@ pub struct TestResult {
 }
 
 impl TestResult {
+    /// Create a failed result for a test that could not run.
+    #[cfg(feature = "std")]
+    fn errored(test: &TestCase, error: &str) -> Self {
+        Self {
+            name: test.name.clone(),
+            passed: false,
+            expected: test.expected_output.clone(),
+            actual: String::new(),
+            duration_ms: None,
+            error: Some(error.into()),
+        }
+    }
+
     /// Check if the test failed.
     #[must_use]
     pub fn is_failed(&self) -> bool {
@@ -186,6 +391,24 @@ pub struct TestResults {
 }
 
 impl TestResults {
+    /// Summarize individual results for a suite of `total_count` tests.
+    fn from_results(results: Vec<TestResult>, total_count: usize) -> Self {
+        let passed_count = results.iter().filter(|r| r.passed).count();
+
+        Self {
+            all_passed: passed_count == total_count,
+            results,
+            passed_count,
+            total_count,
+        }
+    }
+
+    /// Check if the run stopped before every test was executed.
+    #[must_use]
+    pub fn is_partial(&self) -> bool {
+        self.results.len() < self.total_count
+    }
+
     /// Get the pass rate as a percentage (0.0 - 1.0).
     #[must_use]
     pub fn pass_rate(&self) -> f32 {
@@ -269,6 +492,103 @@ mod tests {
         assert_eq!(results.total_count, 2);
     }
 
+    #[test]
+    fn test_run_tests_preserves_order() {
+        let runner = TestRunner::new();
+        let mut suite = TestSuite::new();
+        for i in 0..16 {
+            suite = suite.with_test(TestCase::new(alloc::format!("t{}", i)));
+        }
+        let lab = Lab::new("many", "Many").with_test_suite(suite);
+
+        let results = runner.run_tests("fn main() {}", &lab);
+        let names: Vec<String> = results.results.iter().map(|r| r.name.clone()).collect();
+        let expected: Vec<String> = (0..16).map(|i| alloc::format!("t{}", i)).collect();
+        assert_eq!(names, expected);
+        assert!(results.all_passed);
+    }
+
+    #[test]
+    fn test_quick_check_stops_at_first_failure() {
+        let runner = TestRunner::new();
+        let lab = create_test_lab();
+        let results = runner.quick_check("fn main() {}", &lab);
+
+        assert_eq!(results.results.len(), 1);
+        assert_eq!(results.total_count, 2);
+        assert!(results.is_partial());
+        assert!(!results.all_passed);
+    }
+
+    #[test]
+    fn test_run_tests_cached_reuses_results() {
+        let mut runner = TestRunner::new();
+        let lab = create_test_lab();
+
+        let first = runner.run_tests_cached("fn main() {}", &lab, RunMode::Full);
+        assert_eq!(runner.cached_count(), 2);
+
+        let second = runner.run_tests_cached("fn main() {}", &lab, RunMode::Full);
+        assert_eq!(first, second);
+        assert_eq!(runner.cached_count(), 2);
+
+        // Only the latest submission is kept.
+        runner.run_tests_cached("fn main() { }", &lab, RunMode::Full);
+        assert_eq!(runner.cached_count(), 2);
+
+        let mut fewer = lab.clone();
+        fewer.test_suite.tests.truncate(1);
+        runner.run_tests_cached("fn main() { }", &fewer, RunMode::Full);
+        assert_eq!(runner.cached_count(), 1);
+
+        runner.clear_cache();
+        assert_eq!(runner.cached_count(), 0);
+    }
+
+    #[test]
+    fn test_run_tests_cached_quick_check() {
+        let mut runner = TestRunner::new();
+        let lab = create_test_lab();
+
+        let quick = runner.run_tests_cached("fn main() {}", &lab, RunMode::QuickCheck);
+        assert!(quick.is_partial());
+        assert_eq!(runner.cached_count(), 1);
+
+        let full = runner.run_tests_cached("fn main() {}", &lab, RunMode::Full);
+        assert!(!full.is_partial());
+        assert_eq!(full.results[0], quick.results[0]);
+    }
+
+    #[test]
+    fn test_run_tests_cached_checks_stored_test() {
+        let mut runner = TestRunner::new();
+        let lab = create_test_lab();
+        let code = "fn main() {}";
+        runner.run_tests_cached(code, &lab, RunMode::Full);
+
+        // Plant another test's result under the first test's key, as a hash
+        // collision would.
+        let key = cache_key(code, &lab, &lab.test_suite.tests[0]);
+        let other = TestCase::new("other");
+        let planted = TestResult::errored(&other, "collision");
+        runner.cache.insert(key, &other, planted.clone());
+
+        let results = runner.run_tests_cached(code, &lab, RunMode::Full);
+        assert_ne!(results.results[0], planted);
+        assert_eq!(results.results[0].name, lab.test_suite.tests[0].name);
+    }
+
+    #[test]
+    fn test_cache_key_distinguishes_inputs() {
+        let lab = create_test_lab();
+        let test = TestCase::new("t").with_input("ab").with_expected("c");
+        let shifted = TestCase::new("t").with_input("a").with_expected("bc");
+
+        assert_eq!(cache_key("x", &lab, &test), cache_key("x", &lab, &test));
+        assert_ne!(cache_key("x", &lab, &test), cache_key("y", &lab, &test));
+        assert_ne!(cache_key("x", &lab, &test), cache_key("x", &lab, &shifted));
+    }
+
     #[test]
     fn test_result_is_failed() {
         let passed = TestResult {
@@ -6,6 +6,9 @@ use alloc::string::String;
 use profesor_core::Language;
 use serde::{Deserialize, Serialize};
 
+use crate::python::{self, Limits};
+use crate::trace::{ExecutionTrace, TracedExecution};
+
 /// Configuration for the sandbox.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SandboxConfig {
@@ -15,6 +18,8 @@ pub struct SandboxConfig {
     pub timeout_ms: u32,
     /// Maximum output size in bytes
     pub max_output_bytes: usize,
+    /// Maximum number of steps kept when tracing
+    pub max_trace_steps: usize,
 }
 
 impl Default for SandboxConfig {
@@ -23,6 +28,7 @@ impl Default for SandboxConfig {
             memory_limit_bytes: 64 * 1024 * 1024, // 64 MB
             timeout_ms: 5000,                     // 5 seconds
             max_output_bytes: 1024 * 1024,        // 1 MB
+            max_trace_steps: 10_000,
         }
     }
 }
@@ -66,20 +72,67 @@ impl Sandbox {
         self
     }
 
+    /// Set the maximum number of steps kept when tracing.
+    #[must_use]
+    pub fn with_max_trace_steps(mut self, steps: usize) -> Self {
+        self.config.max_trace_steps = steps;
+        self
+    }
+
     /// Execute code in the sandbox.
     ///
     /// Note: In the browser, this delegates to a WASM interpreter.
     /// The actual execution depends on the language and runtime environment.
     #[must_use]
     pub fn execute(&self, code: &str, language: Language, input: &str) -> ExecutionResult {
+        self.run(code, language, input, None)
+    }
+
+    /// Execute code and record a step-by-step trace.
+    ///
+    /// The trace is capped at `max_trace_steps`; languages the sandbox does
+    /// not interpret return no trace.
+    #[must_use]
+    pub fn execute_traced(&self, code: &str, language: Language, input: &str) -> TracedExecution {
+        if !Self::can_trace(language) {
+            return TracedExecution {
+                result: self.execute(code, language, input),
+                trace: None,
+            };
+        }
+
+        let mut trace = ExecutionTrace::new(self.config.max_trace_steps);
+        let result = self.run(code, language, input, Some(&mut trace));
+        TracedExecution {
+            result,
+            trace: Some(trace),
+        }
+    }
+
+    /// Check if the sandbox can trace a language.
+    ///
+    /// Only Python is interpreted statement by statement.
+    #[must_use]
+    pub fn can_trace(language: Language) -> bool {
+        matches!(language, Language::Python)
+    }
+
+    fn run(
+        &self,
+        code: &str,
+        language: Language,
+        input: &str,
+        trace: Option<&mut ExecutionTrace>,
+    ) -> ExecutionResult {
         // In pure WASM, we can only interpret simple languages
         // or delegate to pre-compiled WASM modules
         match language {
             Language::Rust => self.execute_rust_subset(code, input),
-            Language::Python => self.execute_python_subset(code, input),
+            Language::Python => self.execute_python_subset(code, input, trace),
             Language::JavaScript => self.execute_js_subset(code, input),
             _ => ExecutionResult::Error {
                 message: alloc::format!("Language {:?} not yet supported in sandbox", language),
+                usage: ResourceUsage::default(),
             },
         }
     }
@@ -93,6 +146,7 @@ impl Sandbox {
         if code.is_empty() {
             return ExecutionResult::Error {
                 message: "Empty code".into(),
+                usage: ResourceUsage::default(),
             };
         }
 
@@ -100,22 +154,31 @@ impl Sandbox {
         ExecutionResult::Success {
             output: String::new(),
             duration_ms: 0,
+            usage: ResourceUsage::default(),
         }
     }
 
     /// Execute a subset of Python.
-    fn execute_python_subset(&self, code: &str, _input: &str) -> ExecutionResult {
+    ///
+    /// The timeout is enforced as a step budget so runs are deterministic.
+    fn execute_python_subset(
+        &self,
+        code: &str,
+        input: &str,
+        trace: Option<&mut ExecutionTrace>,
+    ) -> ExecutionResult {
         if code.is_empty() {
             return ExecutionResult::Error {
                 message: "Empty code".into(),
+                usage: ResourceUsage::default(),
             };
         }
 
-        // Stub: Would use ruchy (Rust Python interpreter)
-        ExecutionResult::Success {
-            output: String::new(),
-            duration_ms: 0,
-        }
+        let limits = Limits {
+            max_steps: u64::from(self.config.timeout_ms) * python::STEPS_PER_MS,
+            max_memory: self.config.memory_limit_bytes,
+        };
+        python::run(code, input, &limits, trace)
     }
 
     /// Execute a subset of JavaScript.
@@ -123,6 +186,7 @@ impl Sandbox {
         if code.is_empty() {
             return ExecutionResult::Error {
                 message: "Empty code".into(),
+                usage: ResourceUsage::default(),
             };
         }
 
@@ -130,6 +194,7 @@ impl Sandbox {
         ExecutionResult::Success {
             output: String::new(),
             duration_ms: 0,
+            usage: ResourceUsage::default(),
         }
     }
 }
@@ -149,6 +214,8 @@ pub enum ExecutionResult {
         output: String,
         /// Execution time in milliseconds
         duration_ms: u32,
+        /// Resources consumed
+        usage: ResourceUsage,
     },
     /// Runtime error during execution
     RuntimeError {
@@ -156,21 +223,29 @@ pub enum ExecutionResult {
         error: String,
         /// Line number where error occurred (if known)
         line: Option<u32>,
+        /// Resources consumed before the error
+        usage: ResourceUsage,
     },
     /// Execution timed out
     Timeout {
         /// Partial output before timeout
         partial_output: String,
+        /// Resources consumed before the timeout
+        usage: ResourceUsage,
     },
     /// Memory limit exceeded
     MemoryExceeded {
         /// Memory used in bytes
         used_bytes: usize,
+        /// Resources consumed before the limit was hit
+        usage: ResourceUsage,
     },
     /// General error
     Error {
         /// Error message
         message: String,
+        /// Resources consumed (zero if execution never started)
+        usage: ResourceUsage,
     },
 }
 
@@ -195,10 +270,44 @@ impl ExecutionResult {
     pub fn error_message(&self) -> Option<&str> {
         match self {
             Self::RuntimeError { error, .. } => Some(error),
-            Self::Error { message } => Some(message),
+            Self::Error { message, .. } => Some(message),
             _ => None,
         }
     }
+
+    /// Get the resources consumed by the run.
+    #[must_use]
+    pub fn usage(&self) -> &ResourceUsage {
+        match self {
+            Self::Success { usage, .. }
+            | Self::RuntimeError { usage, .. }
+            | Self::Timeout { usage, .. }
+            | Self::MemoryExceeded { usage, .. }
+            | Self::Error { usage, .. } => usage,
+        }
+    }
+}
+
+/// Resources consumed by a sandboxed run.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct ResourceUsage {
+    /// Interpreter steps (instructions or statements) executed
+    pub steps: u64,
+    /// Peak memory in bytes
+    pub peak_memory_bytes: usize,
+    /// Bytes written to stdout
+    pub output_bytes: usize,
+}
+
+impl ResourceUsage {
+    /// Usage of a run that produced only the given output.
+    #[must_use]
+    pub fn for_output(output: &str) -> Self {
+        Self {
+            output_bytes: output.len(),
+            ..Self::default()
+        }
+    }
 }
 
 #[cfg(test)]
@@ -225,6 +334,7 @@ mod tests {
             memory_limit_bytes: 32 * 1024 * 1024,
             timeout_ms: 1000,
             max_output_bytes: 512 * 1024,
+            max_trace_steps: 100,
         };
         let sandbox = Sandbox::with_config(config);
         assert_eq!(sandbox.config().timeout_ms, 1000);
@@ -296,10 +406,12 @@ mod tests {
         let success = ExecutionResult::Success {
             output: "Hello".into(),
             duration_ms: 10,
+            usage: ResourceUsage::for_output("Hello"),
         };
         assert!(success.is_success());
         assert_eq!(success.output(), Some("Hello"));
         assert!(success.error_message().is_none());
+        assert_eq!(success.usage().output_bytes, 5);
     }
 
     #[test]
@@ -307,6 +419,7 @@ mod tests {
         let error = ExecutionResult::RuntimeError {
             error: "Divide by zero".into(),
             line: Some(5),
+            usage: ResourceUsage::default(),
         };
         assert!(!error.is_success());
         assert!(error.output().is_none());
@@ -317,16 +430,26 @@ mod tests {
     fn test_execution_result_timeout() {
         let timeout = ExecutionResult::Timeout {
             partial_output: "partial".into(),
+            usage: ResourceUsage {
+                steps: 1_000_000,
+                peak_memory_bytes: 4096,
+                output_bytes: 7,
+            },
         };
         assert!(!timeout.is_success());
         assert!(timeout.output().is_none());
         assert!(timeout.error_message().is_none());
+        assert_eq!(timeout.usage().steps, 1_000_000);
     }
 
     #[test]
     fn test_execution_result_memory_exceeded() {
         let mem_error = ExecutionResult::MemoryExceeded {
             used_bytes: 100_000_000,
+            usage: ResourceUsage {
+                peak_memory_bytes: 100_000_000,
+                ..ResourceUsage::default()
+            },
         };
         assert!(!mem_error.is_success());
         assert!(mem_error.output().is_none());
@@ -337,6 +460,7 @@ mod tests {
     fn test_execution_result_error() {
         let error = ExecutionResult::Error {
             message: "Failed".into(),
+            usage: ResourceUsage::default(),
         };
         assert!(!error.is_success());
         assert_eq!(error.error_message(), Some("Failed"));
@@ -354,6 +478,68 @@ mod tests {
             .contains("not yet supported"));
     }
 
+    #[test]
+    fn test_execute_python_output_and_usage() {
+        let sandbox = Sandbox::new();
+        let result = sandbox.execute("n = int(input())\nprint(n * 2)", Language::Python, "21");
+        assert_eq!(result.output(), Some("42\n"));
+        assert_eq!(result.usage().steps, 2);
+        assert_eq!(result.usage().output_bytes, 3);
+
+        let result = sandbox.clone().with_timeout_ms(1).execute(
+            "while True:\n    pass",
+            Language::Python,
+            "",
+        );
+        assert!(matches!(result, ExecutionResult::Timeout { .. }));
+    }
+
+    #[test]
+    fn test_execute_traced() {
+        let sandbox = Sandbox::new().with_max_trace_steps(50);
+        let code = "def double(x):\n    return x * 2\ntotal = 0\nfor i in range(2):\n    total += double(i)\nprint(total)";
+        let traced = sandbox.execute_traced(code, Language::Python, "");
+        assert_eq!(traced.result.output(), Some("2\n"));
+        let trace = traced.trace.unwrap();
+        assert_eq!(trace.max_steps, 50);
+        assert_eq!(trace.stdout, "2\n");
+
+        let lines: Vec<u32> = trace.steps.iter().map(|s| s.line).collect();
+        assert_eq!(lines, [1, 3, 4, 5, 2, 4, 5, 2, 6]);
+        // Inside the second call to double: its frame and argument.
+        let call = &trace.steps[7];
+        assert_eq!(call.variable("x"), Some("1"));
+        assert_eq!(call.variable("total"), None);
+        let frames: Vec<&str> = call
+            .call_stack
+            .iter()
+            .map(|f| f.function.as_str())
+            .collect();
+        assert_eq!(frames, ["<module>", "double"]);
+        assert_eq!(call.call_stack[0].line, 5);
+        // Back at module level before printing.
+        let last = &trace.steps[8];
+        assert_eq!(last.variable("total"), Some("2"));
+        assert_eq!(last.variable("i"), Some("1"));
+        assert_eq!(last.stdout_len, 0);
+    }
+
+    #[test]
+    fn test_execute_traced_untraceable_language() {
+        let traced = Sandbox::new().execute_traced("fn main() {}", Language::Rust, "");
+        assert!(traced.result.is_success());
+        assert!(traced.trace.is_none());
+    }
+
+    #[test]
+    fn test_execute_traced_unsupported_language() {
+        let sandbox = Sandbox::new();
+        let traced = sandbox.execute_traced("SELECT 1", Language::Sql, "");
+        assert!(!traced.result.is_success());
+        assert!(traced.trace.is_none());
+        assert!(!Sandbox::can_trace(Language::Sql));
+    }
+
     #[test]
     fn test_unsupported_languages() {
         let sandbox = Sandbox::new();
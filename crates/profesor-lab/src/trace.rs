@@ -0,0 +1,348 @@
+//! Execution tracing for step-through debugging.
+//!
+//! Records line-by-line execution with variable snapshots so a learner can
+//! replay a run forwards and backwards and see exactly where it diverged.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+use crate::sandbox::ExecutionResult;
+
+/// A variable binding captured at a trace step.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Variable {
+    /// Variable name
+    pub name: String,
+    /// Rendered value
+    pub value: String,
+}
+
+impl Variable {
+    /// Create a new variable snapshot.
+    #[must_use]
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+}
+
+/// A frame on the call stack.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StackFrame {
+    /// Function name
+    pub function: String,
+    /// Line the frame is currently executing
+    pub line: u32,
+}
+
+impl StackFrame {
+    /// Create a new stack frame.
+    #[must_use]
+    pub fn new(function: impl Into<String>, line: u32) -> Self {
+        Self {
+            function: function.into(),
+            line,
+        }
+    }
+}
+
+/// A single recorded execution step.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TraceStep {
+    /// Line about to execute (1-indexed)
+    pub line: u32,
+    /// Variables in scope at this step
+    pub variables: Vec<Variable>,
+    /// Call stack, outermost frame first
+    pub call_stack: Vec<StackFrame>,
+    /// Number of stdout bytes written before this step
+    pub stdout_len: usize,
+}
+
+impl TraceStep {
+    /// Create a step at the given line with no captured state.
+    #[must_use]
+    pub fn new(line: u32) -> Self {
+        Self {
+            line,
+            variables: Vec::new(),
+            call_stack: Vec::new(),
+            stdout_len: 0,
+        }
+    }
+
+    /// Add a variable snapshot.
+    #[must_use]
+    pub fn with_variable(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.variables.push(Variable::new(name, value));
+        self
+    }
+
+    /// Add a frame to the call stack.
+    #[must_use]
+    pub fn with_frame(mut self, frame: StackFrame) -> Self {
+        self.call_stack.push(frame);
+        self
+    }
+
+    /// Look up a variable by name.
+    #[must_use]
+    pub fn variable(&self, name: &str) -> Option<&str> {
+        self.variables
+            .iter()
+            .find(|v| v.name == name)
+            .map(|v| v.value.as_str())
+    }
+}
+
+/// Bounded record of a traced execution.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ExecutionTrace {
+    /// Recorded steps in execution order
+    pub steps: Vec<TraceStep>,
+    /// Everything the program wrote to stdout
+    pub stdout: String,
+    /// Maximum number of steps kept
+    pub max_steps: usize,
+    /// Whether steps were dropped after reaching `max_steps`
+    pub truncated: bool,
+}
+
+impl ExecutionTrace {
+    /// Create an empty trace that keeps at most `max_steps` steps.
+    #[must_use]
+    pub fn new(max_steps: usize) -> Self {
+        Self {
+            steps: Vec::new(),
+            stdout: String::new(),
+            max_steps,
+            truncated: false,
+        }
+    }
+
+    /// Record a step, stamping it with the current stdout position.
+    ///
+    /// Returns `false` once the trace is full; the step is dropped.
+    pub fn record(&mut self, mut step: TraceStep) -> bool {
+        if self.steps.len() >= self.max_steps {
+            self.truncated = true;
+            return false;
+        }
+        step.stdout_len = self.stdout.len();
+        self.steps.push(step);
+        true
+    }
+
+    /// Append program output.
+    pub fn write_stdout(&mut self, text: &str) {
+        self.stdout.push_str(text);
+    }
+
+    /// Get the number of recorded steps.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Check if no steps were recorded.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Get a cursor positioned at the first step.
+    #[must_use]
+    pub fn cursor(&self) -> TraceCursor<'_> {
+        TraceCursor {
+            trace: self,
+            position: 0,
+        }
+    }
+}
+
+/// Navigates an [`ExecutionTrace`] forwards and backwards.
+#[derive(Debug, Clone)]
+pub struct TraceCursor<'a> {
+    trace: &'a ExecutionTrace,
+    position: usize,
+}
+
+impl<'a> TraceCursor<'a> {
+    /// Get the current position (0-based step index).
+    #[must_use]
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Get the step at the current position.
+    #[must_use]
+    pub fn current(&self) -> Option<&'a TraceStep> {
+        self.trace.steps.get(self.position)
+    }
+
+    /// Advance one step.
+    ///
+    /// Returns `None` (without moving) when already at the last step.
+    pub fn step_forward(&mut self) -> Option<&'a TraceStep> {
+        if self.position + 1 >= self.trace.steps.len() {
+            return None;
+        }
+        self.position += 1;
+        self.current()
+    }
+
+    /// Go back one step.
+    ///
+    /// Returns `None` (without moving) when already at the first step.
+    pub fn step_back(&mut self) -> Option<&'a TraceStep> {
+        if self.position == 0 {
+            return None;
+        }
+        self.position -= 1;
+        self.current()
+    }
+
+    /// Jump to a specific step, clamped to the trace bounds.
+    pub fn seek(&mut self, position: usize) -> Option<&'a TraceStep> {
+        self.position = position.min(self.trace.steps.len().saturating_sub(1));
+        self.current()
+    }
+
+    /// Get the output written before the current step executed.
+    #[must_use]
+    pub fn stdout(&self) -> &'a str {
+        let len = self.current().map_or(0, |s| s.stdout_len);
+        self.trace.stdout.get(..len).unwrap_or(&self.trace.stdout)
+    }
+
+    /// Get the variables whose value differs from the previous step.
+    #[must_use]
+    pub fn changed_variables(&self) -> Vec<&'a Variable> {
+        let Some(step) = self.current() else {
+            return Vec::new();
+        };
+        let previous = self
+            .position
+            .checked_sub(1)
+            .and_then(|i| self.trace.steps.get(i));
+
+        step.variables
+            .iter()
+            .filter(|v| previous.and_then(|p| p.variable(&v.name)) != Some(v.value.as_str()))
+            .collect()
+    }
+}
+
+/// Execution result paired with its trace.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TracedExecution {
+    /// Outcome of the run
+    pub result: ExecutionResult,
+    /// Recorded trace, or `None` if the language cannot be traced
+    pub trace: Option<ExecutionTrace>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn loop_trace() -> ExecutionTrace {
+        let mut trace = ExecutionTrace::new(100);
+        for i in 0..3 {
+            trace.record(
+                TraceStep::new(2)
+                    .with_variable("i", alloc::format!("{}", i))
+                    .with_frame(StackFrame::new("main", 2)),
+            );
+            trace.write_stdout(&alloc::format!("{}\n", i));
+        }
+        trace
+    }
+
+    #[test]
+    fn test_record_stamps_stdout_position() {
+        let trace = loop_trace();
+        assert_eq!(trace.len(), 3);
+        assert_eq!(trace.steps[0].stdout_len, 0);
+        assert_eq!(trace.steps[1].stdout_len, 2);
+        assert_eq!(trace.steps[2].stdout_len, 4);
+    }
+
+    #[test]
+    fn test_record_truncates_at_limit() {
+        let mut trace = ExecutionTrace::new(2);
+        assert!(trace.record(TraceStep::new(1)));
+        assert!(trace.record(TraceStep::new(2)));
+        assert!(!trace.record(TraceStep::new(3)));
+        assert_eq!(trace.len(), 2);
+        assert!(trace.truncated);
+    }
+
+    #[test]
+    fn test_cursor_steps_both_ways() {
+        let trace = loop_trace();
+        let mut cursor = trace.cursor();
+
+        assert_eq!(cursor.position(), 0);
+        assert!(cursor.step_back().is_none());
+
+        assert_eq!(
+            cursor.step_forward().and_then(|s| s.variable("i")),
+            Some("1")
+        );
+        assert_eq!(
+            cursor.step_forward().and_then(|s| s.variable("i")),
+            Some("2")
+        );
+        assert!(cursor.step_forward().is_none());
+        assert_eq!(cursor.position(), 2);
+
+        assert_eq!(cursor.step_back().and_then(|s| s.variable("i")), Some("1"));
+    }
+
+    #[test]
+    fn test_cursor_stdout_so_far() {
+        let trace = loop_trace();
+        let mut cursor = trace.cursor();
+        assert_eq!(cursor.stdout(), "");
+
+        cursor.seek(2);
+        assert_eq!(cursor.stdout(), "0\n1\n");
+
+        cursor.seek(99);
+        assert_eq!(cursor.position(), 2);
+    }
+
+    #[test]
+    fn test_cursor_changed_variables() {
+        let mut trace = ExecutionTrace::new(10);
+        trace.record(TraceStep::new(1).with_variable("n", "3"));
+        trace.record(
+            TraceStep::new(2)
+                .with_variable("n", "3")
+                .with_variable("i", "0"),
+        );
+
+        let mut cursor = trace.cursor();
+        assert_eq!(cursor.changed_variables().len(), 1);
+
+        cursor.step_forward();
+        let changed = cursor.changed_variables();
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].name, "i");
+    }
+
+    #[test]
+    fn test_empty_trace_cursor() {
+        let trace = ExecutionTrace::new(10);
+        let mut cursor = trace.cursor();
+        assert!(trace.is_empty());
+        assert!(cursor.current().is_none());
+        assert!(cursor.step_forward().is_none());
+        assert!(cursor.seek(5).is_none());
+        assert_eq!(cursor.stdout(), "");
+    }
+}
@@ -0,0 +1,188 @@
+//! Adaptive testing.
+//!
+//! Ability estimation and item selection under the three-parameter logistic
+//! model. Ability is estimated as the expected a posteriori (EAP) value over
+//! a fixed grid with a standard normal prior, which stays finite even when
+//! every answer so far is correct or incorrect.
+
+use alloc::vec::Vec;
+use profesor_core::{AbilityEstimate, ItemParameters};
+
+/// Lower and upper bounds of the ability grid.
+const THETA_RANGE: (f64, f64) = (-4.0, 4.0);
+
+/// Number of grid points used for estimation.
+const GRID_POINTS: usize = 81;
+
+/// Probability of a correct answer at ability `theta`.
+#[must_use]
+pub fn probability(params: &ItemParameters, theta: f64) -> f64 {
+    let logistic = 1.0 / (1.0 + libm::exp(-params.discrimination * (theta - params.difficulty)));
+    params.guessing + (1.0 - params.guessing) * logistic
+}
+
+/// Fisher information of a question at ability `theta`.
+#[must_use]
+pub fn information(params: &ItemParameters, theta: f64) -> f64 {
+    let p = probability(params, theta);
+    let q = 1.0 - p;
+    if p <= 0.0 || q <= 0.0 {
+        return 0.0;
+    }
+    let c = params.guessing;
+    let a = params.discrimination;
+    a * a * (q / p) * ((p - c) / (1.0 - c)) * ((p - c) / (1.0 - c))
+}
+
+/// Estimate ability from scored responses.
+///
+/// With no responses this returns the prior (θ = 0, SE = 1).
+#[must_use]
+pub fn estimate_ability(responses: &[(ItemParameters, bool)]) -> AbilityEstimate {
+    let (low, high) = THETA_RANGE;
+    let step = (high - low) / (GRID_POINTS - 1) as f64;
+    let grid: Vec<f64> = (0..GRID_POINTS).map(|i| low + step * i as f64).collect();
+
+    let log_posterior: Vec<f64> = grid
+        .iter()
+        .map(|&theta| {
+            let prior = -0.5 * theta * theta;
+            responses.iter().fold(prior, |acc, (params, correct)| {
+                let p = probability(params, theta).clamp(1e-12, 1.0 - 1e-12);
+                acc + if *correct {
+                    libm::log(p)
+                } else {
+                    libm::log(1.0 - p)
+                }
+            })
+        })
+        .collect();
+
+    // Normalize in log space so long tests don't underflow.
+    let max = log_posterior
+        .iter()
+        .copied()
+        .fold(f64::NEG_INFINITY, f64::max);
+    let weights: Vec<f64> = log_posterior.iter().map(|l| libm::exp(l - max)).collect();
+    let total: f64 = weights.iter().sum();
+
+    let theta = grid.iter().zip(&weights).map(|(t, w)| t * w).sum::<f64>() / total;
+    let variance = grid
+        .iter()
+        .zip(&weights)
+        .map(|(t, w)| (t - theta) * (t - theta) * w)
+        .sum::<f64>()
+        / total;
+
+    AbilityEstimate {
+        theta,
+        standard_error: libm::sqrt(variance),
+    }
+}
+
+/// Pick the question with the most information at ability `theta`.
+///
+/// Ties go to the earliest candidate. Returns `None` if there are none.
+#[must_use]
+pub fn select_item(candidates: &[ItemParameters], theta: f64) -> Option<usize> {
+    let mut best: Option<(usize, f64)> = None;
+    for (i, params) in candidates.iter().enumerate() {
+        let info = information(params, theta);
+        if best.map_or(true, |(_, b)| info > b) {
+            best = Some((i, info));
+        }
+    }
+    best.map(|(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_probability_at_difficulty() {
+        let params = ItemParameters::new(1.0, 0.5, 0.0);
+        assert!((probability(&params, 0.5) - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_probability_guessing_floor() {
+        let params = ItemParameters::new(2.0, 0.0, 0.25);
+        assert!(probability(&params, -10.0) >= 0.25);
+        assert!(probability(&params, 10.0) <= 1.0);
+    }
+
+    #[test]
+    fn test_information_peaks_near_difficulty() {
+        let params = ItemParameters::new(1.5, 1.0, 0.0);
+        assert!(information(&params, 1.0) > information(&params, -1.0));
+        assert!(information(&params, 1.0) > information(&params, 3.0));
+    }
+
+    #[test]
+    fn test_estimate_prior() {
+        let estimate = estimate_ability(&[]);
+        assert!(estimate.theta.abs() < 1e-9);
+        assert!((estimate.standard_error - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_estimate_moves_with_answers() {
+        let item = ItemParameters::default();
+        let high = estimate_ability(&[(item, true), (item, true), (item, true)]);
+        let low = estimate_ability(&[(item, false), (item, false), (item, false)]);
+        assert!(high.theta > 0.5);
+        assert!(low.theta < -0.5);
+    }
+
+    #[test]
+    fn test_standard_error_shrinks() {
+        let item = ItemParameters::new(2.0, 0.0, 0.0);
+        let few = estimate_ability(&[(item, true), (item, false)]);
+        let responses: Vec<(ItemParameters, bool)> = (0..20).map(|i| (item, i % 2 == 0)).collect();
+        let many = estimate_ability(&responses);
+        assert!(many.standard_error < few.standard_error);
+    }
+
+    #[test]
+    fn test_select_item_matches_ability() {
+        let candidates = [
+            ItemParameters::new(1.0, -2.0, 0.0),
+            ItemParameters::new(1.0, 0.0, 0.0),
+            ItemParameters::new(1.0, 2.0, 0.0),
+        ];
+        assert_eq!(select_item(&candidates, 1.8), Some(2));
+        assert_eq!(select_item(&candidates, 0.1), Some(1));
+        assert_eq!(select_item(&[], 0.0), None);
+    }
+}
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_probability_in_unit_interval(
+            a in 0.1f64..3.0,
+            b in -3.0f64..3.0,
+            c in 0.0f64..0.5,
+            theta in -4.0f64..4.0
+        ) {
+            let p = probability(&ItemParameters::new(a, b, c), theta);
+            prop_assert!((0.0..=1.0).contains(&p));
+        }
+
+        #[test]
+        fn test_estimate_within_grid(answers in proptest::collection::vec(any::<bool>(), 0..30)) {
+            let responses: Vec<(ItemParameters, bool)> = answers
+                .into_iter()
+                .map(|correct| (ItemParameters::default(), correct))
+                .collect();
+            let estimate = estimate_ability(&responses);
+            prop_assert!(estimate.theta >= THETA_RANGE.0 && estimate.theta <= THETA_RANGE.1);
+            prop_assert!(estimate.standard_error >= 0.0);
+        }
+    }
+}
@@ -0,0 +1,453 @@
+//! Item analysis.
+//!
+//! Classical test theory statistics over many attempts of a quiz, used to
+//! find broken questions: items everyone gets right, items that strong
+//! learners miss more often than weak ones, and distractors nobody picks.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use profesor_core::{Answer, Question, QuestionId, Quiz, QuizAttempt, QuizId};
+use serde::{Deserialize, Serialize};
+
+/// Difficulty index above which an item is flagged as too easy.
+pub const TOO_EASY_THRESHOLD: f64 = 0.9;
+
+/// A problem detected for an item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ItemFlag {
+    /// Almost every learner answers correctly
+    TooEasy,
+    /// Low scorers do better on this item than high scorers
+    NegativeDiscrimination,
+    /// A multiple-choice distractor that nobody selected (option index)
+    UnusedDistractor(usize),
+}
+
+impl ItemFlag {
+    /// Get a short machine-readable label.
+    #[must_use]
+    pub fn label(&self) -> String {
+        match self {
+            Self::TooEasy => "too_easy".into(),
+            Self::NegativeDiscrimination => "negative_discrimination".into(),
+            Self::UnusedDistractor(option) => alloc::format!("unused_distractor:{}", option),
+        }
+    }
+}
+
+/// Statistics for one question.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ItemStatistics {
+    /// Question analyzed
+    pub question_id: QuestionId,
+    /// Number of attempts that included the question
+    pub responses: usize,
+    /// Difficulty index: mean fraction of points earned (0.0 - 1.0)
+    pub difficulty: Option<f64>,
+    /// Point-biserial correlation with the rest of the attempt's score
+    pub discrimination: Option<f64>,
+    /// Selection frequency of each option (multiple choice only)
+    pub option_frequencies: Vec<f64>,
+    /// Detected problems
+    pub flags: Vec<ItemFlag>,
+}
+
+/// Item analysis report for a quiz.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ItemAnalysis {
+    /// Quiz analyzed
+    pub quiz_id: QuizId,
+    /// Number of attempts analyzed
+    pub attempts: usize,
+    /// Per-question statistics
+    pub items: Vec<ItemStatistics>,
+    /// Internal consistency of the quiz's fixed questions
+    pub cronbach_alpha: Option<f64>,
+}
+
+impl ItemAnalysis {
+    /// Analyze attempts of a quiz.
+    ///
+    /// Covers the quiz's fixed questions and any bank questions. Attempts
+    /// without per-question outcomes are ignored.
+    #[must_use]
+    pub fn compute(quiz: &Quiz, attempts: &[QuizAttempt]) -> Self {
+        let attempts: Vec<&QuizAttempt> =
+            attempts.iter().filter(|a| !a.outcomes.is_empty()).collect();
+        let questions: Vec<&Question> = quiz
+            .questions
+            .iter()
+            .chain(
+                quiz.draw
+                    .iter()
+                    .flat_map(|d| d.bank.items.iter().map(|i| &i.question)),
+            )
+            .collect();
+
+        // Item score matrix: one row per attempt, `None` where not presented.
+        let matrix: Vec<Vec<Option<f64>>> = attempts
+            .iter()
+            .map(|attempt| questions.iter().map(|q| item_score(q, attempt)).collect())
+            .collect();
+
+        let items = questions
+            .iter()
+            .enumerate()
+            .map(|(col, question)| item_statistics(question, col, &matrix, &attempts))
+            .collect();
+
+        let fixed: Vec<usize> = (0..quiz.questions.len()).collect();
+
+        Self {
+            quiz_id: quiz.id.clone(),
+            attempts: attempts.len(),
+            items,
+            cronbach_alpha: cronbach_alpha(&matrix, &fixed),
+        }
+    }
+
+    /// Get the statistics for a question.
+    #[must_use]
+    pub fn item(&self, question_id: &QuestionId) -> Option<&ItemStatistics> {
+        self.items.iter().find(|i| &i.question_id == question_id)
+    }
+
+    /// Get the items with at least one flag.
+    #[must_use]
+    pub fn flagged(&self) -> Vec<&ItemStatistics> {
+        self.items.iter().filter(|i| !i.flags.is_empty()).collect()
+    }
+
+    /// Export the per-item statistics as CSV.
+    ///
+    /// Option frequencies and flags are `;`-separated within their column.
+    #[must_use]
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "question_id,responses,difficulty,discrimination,option_frequencies,flags\n",
+        );
+        for item in &self.items {
+            let frequencies: Vec<String> = item
+                .option_frequencies
+                .iter()
+                .map(|f| alloc::format!("{:.3}", f))
+                .collect();
+            let flags: Vec<String> = item.flags.iter().map(ItemFlag::label).collect();
+            csv.push_str(&alloc::format!(
+                "{},{},{},{},{},{}\n",
+                csv_field(item.question_id.as_str()),
+                item.responses,
+                format_stat(item.difficulty),
+                format_stat(item.discrimination),
+                frequencies.join(";"),
+                flags.join(";"),
+            ));
+        }
+        csv
+    }
+}
+
+/// Fraction of points earned on a question in one attempt.
+fn item_score(question: &Question, attempt: &QuizAttempt) -> Option<f64> {
+    let outcome = attempt
+        .outcomes
+        .iter()
+        .find(|o| &o.question_id == question.id())?;
+    Some(if question.points() > 0 {
+        (f64::from(outcome.points_earned) / f64::from(question.points())).min(1.0)
+    } else if outcome.correct {
+        1.0
+    } else {
+        0.0
+    })
+}
+
+fn item_statistics(
+    question: &Question,
+    col: usize,
+    matrix: &[Vec<Option<f64>>],
+    attempts: &[&QuizAttempt],
+) -> ItemStatistics {
+    // Pair each presented item score with the rest of that attempt's score.
+    let pairs: Vec<(f64, f64)> = matrix
+        .iter()
+        .filter_map(|row| {
+            let score = row[col]?;
+            let rest: f64 = row
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != col)
+                .filter_map(|(_, s)| *s)
+                .sum();
+            Some((score, rest))
+        })
+        .collect();
+
+    let difficulty = mean(pairs.iter().map(|p| p.0));
+    let discrimination = correlation(&pairs);
+    let option_frequencies = option_frequencies(question, attempts);
+
+    let mut flags = Vec::new();
+    if difficulty.is_some_and(|p| p > TOO_EASY_THRESHOLD) {
+        flags.push(ItemFlag::TooEasy);
+    }
+    if discrimination.is_some_and(|r| r < 0.0) {
+        flags.push(ItemFlag::NegativeDiscrimination);
+    }
+    if let Question::MultipleChoice { correct, .. } = question.unhinted() {
+        if !pairs.is_empty() {
+            for (option, frequency) in option_frequencies.iter().enumerate() {
+                if option != *correct && *frequency == 0.0 {
+                    flags.push(ItemFlag::UnusedDistractor(option));
+                }
+            }
+        }
+    }
+
+    ItemStatistics {
+        question_id: question.id().clone(),
+        responses: pairs.len(),
+        difficulty,
+        discrimination,
+        option_frequencies,
+        flags,
+    }
+}
+
+/// Fraction of responses choosing each option of a multiple-choice question.
+fn option_frequencies(question: &Question, attempts: &[&QuizAttempt]) -> Vec<f64> {
+    let Question::MultipleChoice { id, options, .. } = question.unhinted() else {
+        return Vec::new();
+    };
+
+    let mut counts = alloc::vec![0u32; options.len()];
+    let mut total = 0u32;
+    for outcome in attempts
+        .iter()
+        .flat_map(|a| a.outcomes.iter())
+        .filter(|o| &o.question_id == id)
+    {
+        total += 1;
+        if let Some(Answer::Choice(choice)) = outcome.answer.as_ref().map(Answer::response) {
+            if let Some(count) = counts.get_mut(*choice) {
+                *count += 1;
+            }
+        }
+    }
+
+    counts
+        .iter()
+        .map(|&c| {
+            if total == 0 {
+                0.0
+            } else {
+                f64::from(c) / f64::from(total)
+            }
+        })
+        .collect()
+}
+
+/// Cronbach's alpha over the given columns, using attempts that include all of them.
+fn cronbach_alpha(matrix: &[Vec<Option<f64>>], columns: &[usize]) -> Option<f64> {
+    let k = columns.len();
+    if k < 2 {
+        return None;
+    }
+
+    let rows: Vec<Vec<f64>> = matrix
+        .iter()
+        .filter_map(|row| columns.iter().map(|&c| row[c]).collect())
+        .collect();
+    if rows.len() < 2 {
+        return None;
+    }
+
+    let item_variances: f64 = (0..k)
+        .filter_map(|i| variance(rows.iter().map(|r| r[i])))
+        .sum();
+    let total_variance = variance(rows.iter().map(|r| r.iter().sum()))?;
+    if total_variance == 0.0 {
+        return None;
+    }
+
+    let k = k as f64;
+    Some(k / (k - 1.0) * (1.0 - item_variances / total_variance))
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0usize), |(s, n), v| (s + v, n + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+/// Population variance.
+fn variance(values: impl Iterator<Item = f64> + Clone) -> Option<f64> {
+    let m = mean(values.clone())?;
+    mean(values.map(|v| (v - m) * (v - m)))
+}
+
+/// Pearson correlation; `None` if either side has no variance.
+fn correlation(pairs: &[(f64, f64)]) -> Option<f64> {
+    let mx = mean(pairs.iter().map(|p| p.0))?;
+    let my = mean(pairs.iter().map(|p| p.1))?;
+    let cov: f64 = pairs.iter().map(|(x, y)| (x - mx) * (y - my)).sum();
+    let sx: f64 = pairs.iter().map(|(x, _)| (x - mx) * (x - mx)).sum();
+    let sy: f64 = pairs.iter().map(|(_, y)| (y - my) * (y - my)).sum();
+    if sx == 0.0 || sy == 0.0 {
+        return None;
+    }
+    Some(cov / libm::sqrt(sx * sy))
+}
+
+fn format_stat(value: Option<f64>) -> String {
+    value.map_or_else(String::new, |v| alloc::format!("{:.3}", v))
+}
+
+/// Quote a CSV field if it contains a separator, quote or newline.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        alloc::format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use profesor_core::progress::Timestamp;
+    use profesor_core::QuestionOutcome;
+
+    fn mc(id: &str, correct: usize) -> Question {
+        Question::MultipleChoice {
+            id: QuestionId::new(id),
+            prompt: "Q".into(),
+            options: alloc::vec!["A".into(), "B".into(), "C".into(), "D".into()],
+            correct,
+            explanation: "".into(),
+            points: 10,
+            option_explanations: Vec::new(),
+        }
+    }
+
+    fn quiz() -> Quiz {
+        Quiz::new("quiz", "Quiz")
+            .with_question(mc("easy", 0))
+            .with_question(mc("good", 1))
+            .with_question(mc("broken", 2))
+    }
+
+    /// Build an attempt from chosen options for easy, good and broken.
+    fn attempt(choices: [usize; 3]) -> QuizAttempt {
+        let outcomes = ["easy", "good", "broken"]
+            .iter()
+            .zip([0usize, 1, 2])
+            .zip(choices)
+            .map(|((id, correct), choice)| {
+                let points = if choice == correct { 10 } else { 0 };
+                QuestionOutcome::new(*id, choice == correct, points)
+                    .with_answer(Answer::Choice(choice))
+            })
+            .collect();
+        QuizAttempt::new(0.0, 60, Timestamp::ZERO, false).with_outcomes(outcomes)
+    }
+
+    fn attempts() -> Vec<QuizAttempt> {
+        alloc::vec![
+            // Strong learners: get "good" right but miss "broken"
+            attempt([0, 1, 3]),
+            attempt([0, 1, 3]),
+            attempt([0, 1, 1]),
+            // Weak learners: miss "good" but get "broken" right
+            attempt([0, 3, 2]),
+            attempt([0, 0, 2]),
+            attempt([1, 3, 2]),
+        ]
+    }
+
+    #[test]
+    fn test_difficulty_index() {
+        let analysis = ItemAnalysis::compute(&quiz(), &attempts());
+        assert_eq!(analysis.attempts, 6);
+        let easy = analysis
+            .item(&QuestionId::new("easy"))
+            .map(|i| i.difficulty);
+        assert_eq!(easy, Some(Some(5.0 / 6.0)));
+    }
+
+    #[test]
+    fn test_negative_discrimination_flag() {
+        let analysis = ItemAnalysis::compute(&quiz(), &attempts());
+        let broken = analysis.item(&QuestionId::new("broken")).unwrap();
+        assert!(broken.discrimination.unwrap() < 0.0);
+        assert!(broken.flags.contains(&ItemFlag::NegativeDiscrimination));
+    }
+
+    #[test]
+    fn test_option_frequencies_and_unused_distractors() {
+        let analysis = ItemAnalysis::compute(&quiz(), &attempts());
+        let easy = analysis.item(&QuestionId::new("easy")).unwrap();
+        assert_eq!(easy.option_frequencies.len(), 4);
+        assert!((easy.option_frequencies[0] - 5.0 / 6.0).abs() < 1e-9);
+        assert!(easy.flags.contains(&ItemFlag::UnusedDistractor(2)));
+        assert!(easy.flags.contains(&ItemFlag::UnusedDistractor(3)));
+        assert!(!easy.flags.contains(&ItemFlag::UnusedDistractor(1)));
+    }
+
+    #[test]
+    fn test_too_easy_flag() {
+        let all_right = alloc::vec![attempt([0, 1, 2]); 5];
+        let analysis = ItemAnalysis::compute(&quiz(), &all_right);
+        let easy = analysis.item(&QuestionId::new("easy")).unwrap();
+        assert!(easy.flags.contains(&ItemFlag::TooEasy));
+        assert_eq!(easy.discrimination, None);
+    }
+
+    #[test]
+    fn test_cronbach_alpha() {
+        // Perfectly consistent items: alpha of 1.
+        let consistent = alloc::vec![
+            attempt([0, 1, 2]),
+            attempt([0, 1, 2]),
+            attempt([1, 0, 0]),
+            attempt([1, 0, 0]),
+        ];
+        let alpha = ItemAnalysis::compute(&quiz(), &consistent).cronbach_alpha;
+        assert!(alpha.is_some_and(|a| (a - 1.0).abs() < 1e-9));
+
+        let mixed = ItemAnalysis::compute(&quiz(), &attempts()).cronbach_alpha;
+        assert!(mixed.is_some_and(|a| a < 0.5));
+    }
+
+    #[test]
+    fn test_no_attempts() {
+        let analysis = ItemAnalysis::compute(&quiz(), &[]);
+        assert_eq!(analysis.attempts, 0);
+        assert_eq!(analysis.cronbach_alpha, None);
+        assert!(analysis.items.iter().all(|i| i.difficulty.is_none()));
+        assert!(analysis.flagged().is_empty());
+    }
+
+    #[test]
+    fn test_attempts_without_outcomes_ignored() {
+        let mut list = attempts();
+        list.push(QuizAttempt::new(1.0, 60, Timestamp::ZERO, true));
+        assert_eq!(ItemAnalysis::compute(&quiz(), &list).attempts, 6);
+    }
+
+    #[test]
+    fn test_csv_export() {
+        let csv = ItemAnalysis::compute(&quiz(), &attempts()).to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("question_id,responses,difficulty"));
+        assert!(lines[1].starts_with("easy,6,0.833,"));
+        assert!(lines[1].contains("unused_distractor:2;unused_distractor:3"));
+    }
+
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
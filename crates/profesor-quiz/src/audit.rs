@@ -0,0 +1,328 @@
+//! Quiz activity log.
+//!
+//! The engine records each view, answer and navigation with a timestamp
+//! from an injected [`Clock`], for academic-integrity review and UX
+//! research. The log is a plain serializable event stream; [`AuditLog::summary`]
+//! condenses the latest attempt into per-question activity.
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicU64, Ordering};
+use profesor_core::progress::Timestamp;
+use profesor_core::{Answer, QuestionId};
+use serde::{Deserialize, Serialize};
+
+/// Source of event timestamps.
+pub trait Clock: core::fmt::Debug {
+    /// Get the current time.
+    fn now(&self) -> Timestamp;
+}
+
+/// Clock advanced by the host, e.g. from JS before each event.
+///
+/// Clones share the same time.
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock {
+    millis: Arc<AtomicU64>,
+}
+
+impl ManualClock {
+    /// Create a clock set to `now`.
+    #[must_use]
+    pub fn new(now: Timestamp) -> Self {
+        Self {
+            millis: Arc::new(AtomicU64::new(now.as_millis())),
+        }
+    }
+
+    /// Set the time.
+    pub fn set(&self, now: Timestamp) {
+        self.millis.store(now.as_millis(), Ordering::Relaxed);
+    }
+
+    /// Move the time forward.
+    pub fn advance(&self, millis: u64) {
+        self.millis.fetch_add(millis, Ordering::Relaxed);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Timestamp {
+        Timestamp::from_millis(self.millis.load(Ordering::Relaxed))
+    }
+}
+
+/// Direction of a navigation between questions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Direction {
+    /// Moved to the next question
+    Next,
+    /// Moved back to the previous question
+    Previous,
+}
+
+/// Something the learner did.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum QuizEventKind {
+    /// A new attempt began
+    AttemptStarted {
+        /// Attempt number (1-based)
+        attempt: u32,
+    },
+    /// A question was shown
+    QuestionViewed {
+        /// Question shown
+        question_id: QuestionId,
+    },
+    /// First answer to a question
+    AnswerSubmitted {
+        /// Question answered
+        question_id: QuestionId,
+        /// Answer given
+        answer: Answer,
+        /// Whether it was correct
+        correct: bool,
+    },
+    /// A question already answered was answered again
+    AnswerChanged {
+        /// Question answered
+        question_id: QuestionId,
+        /// New answer
+        answer: Answer,
+        /// Whether the new answer is correct
+        correct: bool,
+        /// Whether the replaced answer was correct
+        was_correct: bool,
+    },
+    /// A hint was revealed
+    HintRevealed {
+        /// Question the hint belongs to
+        question_id: QuestionId,
+        /// Index of the hint revealed
+        hint: usize,
+    },
+    /// Moved between questions
+    Navigated {
+        /// Question left
+        from: QuestionId,
+        /// Question reached
+        to: QuestionId,
+        /// Which way
+        direction: Direction,
+    },
+    /// The attempt was finished
+    Finished {
+        /// Points earned
+        points_earned: u32,
+    },
+}
+
+/// A timestamped event.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuizEvent {
+    /// When it happened
+    pub at: Timestamp,
+    /// What happened
+    pub kind: QuizEventKind,
+}
+
+/// Per-question activity in one attempt.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuestionActivity {
+    /// Question
+    pub question_id: QuestionId,
+    /// Total time the question was on screen, in milliseconds
+    pub time_on_task_ms: u64,
+    /// Times the question was shown
+    pub views: u32,
+    /// Times the answer was changed after the first submission
+    pub changes: u32,
+    /// Changes from a wrong answer to a right one
+    pub wrong_to_right: u32,
+    /// Changes from a right answer to a wrong one
+    pub right_to_wrong: u32,
+}
+
+impl QuestionActivity {
+    fn new(question_id: QuestionId) -> Self {
+        Self {
+            question_id,
+            time_on_task_ms: 0,
+            views: 0,
+            changes: 0,
+            wrong_to_right: 0,
+            right_to_wrong: 0,
+        }
+    }
+}
+
+/// Ordered record of quiz events.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct AuditLog {
+    /// Events, oldest first
+    pub events: Vec<QuizEvent>,
+}
+
+impl AuditLog {
+    /// Create an empty log.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append an event.
+    pub fn record(&mut self, at: Timestamp, kind: QuizEventKind) {
+        self.events.push(QuizEvent { at, kind });
+    }
+
+    /// Get the events of the most recent attempt.
+    #[must_use]
+    pub fn latest_attempt(&self) -> &[QuizEvent] {
+        let start = self
+            .events
+            .iter()
+            .rposition(|e| matches!(e.kind, QuizEventKind::AttemptStarted { .. }))
+            .unwrap_or(0);
+        &self.events[start..]
+    }
+
+    /// Summarize the most recent attempt per question, in first-view order.
+    ///
+    /// A question is on screen from its view until the next view or the end
+    /// of the attempt (or the last event, if it is still in progress).
+    #[must_use]
+    pub fn summary(&self) -> Vec<QuestionActivity> {
+        let events = self.latest_attempt();
+        let mut activity: Vec<QuestionActivity> = Vec::new();
+        let mut on_screen: Option<(usize, Timestamp)> = None;
+
+        for event in events {
+            match &event.kind {
+                QuizEventKind::QuestionViewed { question_id } => {
+                    close(&mut activity, on_screen.take(), event.at);
+                    let index = entry(&mut activity, question_id);
+                    activity[index].views += 1;
+                    on_screen = Some((index, event.at));
+                }
+                QuizEventKind::AnswerChanged {
+                    question_id,
+                    correct,
+                    was_correct,
+                    ..
+                } => {
+                    let index = entry(&mut activity, question_id);
+                    let a = &mut activity[index];
+                    a.changes += 1;
+                    match (was_correct, correct) {
+                        (false, true) => a.wrong_to_right += 1,
+                        (true, false) => a.right_to_wrong += 1,
+                        _ => {}
+                    }
+                }
+                QuizEventKind::Finished { .. } => {
+                    close(&mut activity, on_screen.take(), event.at);
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(last) = events.last() {
+            close(&mut activity, on_screen, last.at);
+        }
+        activity
+    }
+}
+
+/// Find or create the activity entry for a question.
+fn entry(activity: &mut Vec<QuestionActivity>, question_id: &QuestionId) -> usize {
+    match activity.iter().position(|a| &a.question_id == question_id) {
+        Some(index) => index,
+        None => {
+            activity.push(QuestionActivity::new(question_id.clone()));
+            activity.len() - 1
+        }
+    }
+}
+
+/// Add the time since a question was shown to its total.
+fn close(activity: &mut [QuestionActivity], viewed: Option<(usize, Timestamp)>, at: Timestamp) {
+    if let Some((index, since)) = viewed {
+        activity[index].time_on_task_ms += at.as_millis().saturating_sub(since.as_millis());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(millis: u64) -> Timestamp {
+        Timestamp::from_millis(millis)
+    }
+
+    fn viewed(id: &str) -> QuizEventKind {
+        QuizEventKind::QuestionViewed {
+            question_id: id.into(),
+        }
+    }
+
+    fn changed(id: &str, was_correct: bool, correct: bool) -> QuizEventKind {
+        QuizEventKind::AnswerChanged {
+            question_id: id.into(),
+            answer: Answer::Choice(0),
+            correct,
+            was_correct,
+        }
+    }
+
+    #[test]
+    fn test_manual_clock_shared() {
+        let clock = ManualClock::new(at(5));
+        let copy = clock.clone();
+        clock.advance(10);
+        assert_eq!(copy.now(), at(15));
+        copy.set(at(100));
+        assert_eq!(clock.now(), at(100));
+    }
+
+    #[test]
+    fn test_summary_time_and_flips() {
+        let mut log = AuditLog::new();
+        log.record(at(0), QuizEventKind::AttemptStarted { attempt: 1 });
+        log.record(at(0), viewed("q1"));
+        log.record(at(4_000), changed("q1", false, true));
+        log.record(at(5_000), viewed("q2"));
+        log.record(at(6_000), changed("q2", true, false));
+        log.record(at(7_000), viewed("q1"));
+        log.record(at(8_000), changed("q1", true, false));
+        log.record(at(8_500), changed("q1", false, true));
+        log.record(at(9_000), QuizEventKind::Finished { points_earned: 10 });
+
+        let summary = log.summary();
+        assert_eq!(summary.len(), 2);
+        assert_eq!(summary[0].question_id.as_str(), "q1");
+        assert_eq!(summary[0].time_on_task_ms, 7_000);
+        assert_eq!(summary[0].views, 2);
+        assert_eq!(summary[0].changes, 3);
+        assert_eq!(summary[0].wrong_to_right, 2);
+        assert_eq!(summary[0].right_to_wrong, 1);
+        assert_eq!(summary[1].time_on_task_ms, 2_000);
+        assert_eq!(summary[1].right_to_wrong, 1);
+    }
+
+    #[test]
+    fn test_summary_latest_attempt_in_progress() {
+        let mut log = AuditLog::new();
+        log.record(at(0), QuizEventKind::AttemptStarted { attempt: 1 });
+        log.record(at(0), viewed("old"));
+        log.record(at(50), QuizEventKind::AttemptStarted { attempt: 2 });
+        log.record(at(100), viewed("q1"));
+        log.record(at(400), changed("q1", false, false));
+
+        assert_eq!(log.latest_attempt().len(), 3);
+        let summary = log.summary();
+        assert_eq!(summary.len(), 1);
+        assert_eq!(summary[0].time_on_task_ms, 300);
+        assert_eq!(summary[0].wrong_to_right, 0);
+        assert!(AuditLog::new().summary().is_empty());
+    }
+}
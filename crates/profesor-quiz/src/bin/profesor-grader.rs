@@ -0,0 +1,67 @@
+//! Stand-in grading server.
+//!
+//! Reads a signed submission envelope on stdin, grades it against the quiz
+//! in the given JSON file and prints the signed score as JSON. With
+//! `--grant`, prints the signed grant an attempt must be started from.
+//!
+//! ```text
+//! PROFESOR_SCORE_KEY=... profesor-grader quiz.json --grant 1 > grant.json
+//! PROFESOR_SUBMISSION_KEY=... PROFESOR_SCORE_KEY=... profesor-grader quiz.json < envelope.json
+//! ```
+
+use std::io::Read;
+use std::process::ExitCode;
+
+use profesor_core::Quiz;
+use profesor_quiz::{grade_submission, AttemptGrant, SubmissionEnvelope};
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(score) => {
+            println!("{}", score);
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("profesor-grader: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+const USAGE: &str = "usage: profesor-grader <quiz.json> [--grant <attempt>] < envelope.json";
+
+fn run() -> Result<String, String> {
+    let mut args = std::env::args().skip(1);
+    let path = args.next().ok_or(USAGE)?;
+    let quiz = std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path, e))?;
+    let quiz: Quiz = serde_json::from_str(&quiz).map_err(|e| format!("{}: {}", path, e))?;
+
+    if let Some(flag) = args.next() {
+        let attempt = args
+            .next()
+            .filter(|_| flag == "--grant")
+            .and_then(|n| n.parse().ok())
+            .ok_or(USAGE)?;
+        let grant = AttemptGrant::issue(&quiz.id, attempt, &key("PROFESOR_SCORE_KEY")?)
+            .map_err(|e| e.to_string())?;
+        return Ok(grant.to_json());
+    }
+
+    let mut envelope = String::new();
+    std::io::stdin()
+        .read_to_string(&mut envelope)
+        .map_err(|e| e.to_string())?;
+    let envelope = SubmissionEnvelope::from_json(&envelope).map_err(|e| e.to_string())?;
+
+    let submission_key = key("PROFESOR_SUBMISSION_KEY")?;
+    let score_key = key("PROFESOR_SCORE_KEY")?;
+    let score = grade_submission(&quiz, &envelope, &submission_key, &score_key)
+        .map_err(|e| e.to_string())?;
+    Ok(score.to_json())
+}
+
+fn key(name: &str) -> Result<Vec<u8>, String> {
+    std::env::var(name)
+        .map(String::into_bytes)
+        .map_err(|_| format!("{} is not set", name))
+}
@@ -2,13 +2,30 @@
 //!
 //! Implements the quiz flow with immediate feedback (Jidoka principle).
 
+use alloc::string::String;
+use alloc::sync::Arc;
 use alloc::vec::Vec;
-use profesor_core::{Answer, Feedback, Question, Quiz, Score};
-
+use profesor_core::progress::Timestamp;
+use profesor_core::{
+    AbilityEstimate, AdaptiveConfig, Answer, DrawError, Feedback, ItemParameters, Question,
+    QuestionHint, QuestionId, QuestionOutcome, Quiz, Score, SeededRng,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::adaptive;
+use crate::audit::{AuditLog, Clock, Direction, QuizEventKind};
+use crate::envelope::{
+    quiz_version, EnvelopeError, SignedGrant, Submission, SubmissionEnvelope, SubmittedAnswer,
+    ENVELOPE_VERSION,
+};
 use crate::grader::Grader;
+use crate::output::OutputCache;
+use crate::review::{correct_answer, distractor_notes, QuestionReview};
+use crate::sealed::{ExplanationKey, SealedQuiz};
+use crate::snapshot::{AdaptiveSnapshot, QuizSnapshot, ResumeError, SNAPSHOT_VERSION};
 
 /// Quiz state machine.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum QuizState {
     /// Quiz has not been started
     NotStarted,
@@ -20,9 +37,15 @@ pub enum QuizState {
         answers: Vec<Option<Answer>>,
         /// Feedback for each answered question
         feedback: Vec<Option<Feedback>>,
+        /// Hints revealed for each question
+        #[serde(default)]
+        hints_revealed: Vec<usize>,
     },
     /// Quiz is being reviewed
     Reviewing {
+        /// Index of the question being reviewed (0-based)
+        #[serde(default)]
+        current_question: usize,
         /// All answers
         answers: Vec<Answer>,
         /// Feedback for all questions
@@ -45,19 +68,135 @@ pub struct QuizEngine {
     quiz: Quiz,
     state: QuizState,
     attempt_count: u32,
+    seed: u64,
+    attempt_seed: Option<u64>,
+    grant: Option<SignedGrant>,
+    questions: Vec<Question>,
+    adaptive: Option<AdaptiveSession>,
+    outcomes: Vec<QuestionOutcome>,
+    outputs: OutputCache,
+    deadline: Option<Timestamp>,
+    finished_at: Option<Timestamp>,
+    clock: Option<Arc<dyn Clock + Send + Sync>>,
+    log: AuditLog,
+    sealed: Option<SealedQuiz>,
+}
+
+/// Per-attempt state of an adaptive test.
+#[derive(Debug, Clone)]
+struct AdaptiveSession {
+    config: AdaptiveConfig,
+    /// Questions not yet presented
+    pool: Vec<Question>,
+    /// Scored responses, parallel to the presented questions
+    responses: Vec<Option<(ItemParameters, bool)>>,
+    estimate: AbilityEstimate,
+}
+
+impl AdaptiveSession {
+    /// Split off the most informative pool question at the current estimate.
+    fn take_next(&mut self) -> Option<Question> {
+        let params: Vec<ItemParameters> = self
+            .pool
+            .iter()
+            .map(|q| self.config.parameters(q.id()))
+            .collect();
+        let idx = adaptive::select_item(&params, self.estimate.theta)?;
+        self.responses.push(None);
+        Some(self.pool.remove(idx))
+    }
+
+    fn record(&mut self, index: usize, params: ItemParameters, correct: bool) {
+        if let Some(slot) = self.responses.get_mut(index) {
+            *slot = Some((params, correct));
+        }
+        let scored: Vec<(ItemParameters, bool)> =
+            self.responses.iter().flatten().copied().collect();
+        self.estimate = adaptive::estimate_ability(&scored);
+    }
+
+    fn should_stop(&self) -> bool {
+        let answered = self.responses.iter().flatten().count();
+        answered >= self.config.max_length
+            || self.pool.is_empty()
+            || (answered >= self.config.min_length
+                && self.estimate.standard_error < self.config.se_threshold)
+    }
 }
 
 impl QuizEngine {
     /// Create a new quiz engine.
+    ///
+    /// Runs every "predict the output" snippet once so grading can use the
+    /// cached results.
     #[must_use]
     pub fn new(quiz: Quiz) -> Self {
         Self {
+            outputs: OutputCache::for_quiz(&quiz),
             quiz,
             state: QuizState::NotStarted,
             attempt_count: 0,
+            seed: 0,
+            attempt_seed: None,
+            grant: None,
+            questions: Vec::new(),
+            adaptive: None,
+            outcomes: Vec::new(),
+            deadline: None,
+            finished_at: None,
+            clock: None,
+            log: AuditLog::new(),
+            sealed: None,
         }
     }
 
+    /// Create an engine for a sealed quiz.
+    ///
+    /// Answers are checked against the sealed hashes and review never shows
+    /// correct answers.
+    #[must_use]
+    pub fn sealed(sealed: SealedQuiz) -> Self {
+        let mut engine = Self::new(sealed.quiz.clone());
+        engine.sealed = Some(sealed);
+        engine
+    }
+
+    /// Set the base seed used to draw and order questions per attempt.
+    ///
+    /// Typically derived from the learner so each learner sees their own draw.
+    #[must_use]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Set the clock used to timestamp events and start the time limit.
+    ///
+    /// Without a clock, events are recorded at time zero and the attempt
+    /// policy cooldown is not enforced.
+    #[must_use]
+    pub fn with_clock(mut self, clock: Arc<dyn Clock + Send + Sync>) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Get the event log.
+    #[must_use]
+    pub fn audit_log(&self) -> &AuditLog {
+        &self.log
+    }
+
+    fn now(&self) -> Timestamp {
+        self.clock
+            .as_ref()
+            .map_or(Timestamp::ZERO, |clock| clock.now())
+    }
+
+    fn record(&mut self, kind: QuizEventKind) {
+        let now = self.now();
+        self.log.record(now, kind);
+    }
+
     /// Get the current state.
     #[must_use]
     pub fn state(&self) -> &QuizState {
@@ -77,35 +216,271 @@ impl QuizEngine {
     }
 
     /// Check if another attempt is allowed.
+    ///
+    /// Respects the attempt limit and, when a clock is set, the quiz's
+    /// attempt policy cooldown.
     #[must_use]
     pub fn can_attempt(&self) -> bool {
+        self.under_attempt_limit() && !self.is_cooling_down()
+    }
+
+    /// Get when the cooldown after the last finished attempt ends.
+    #[must_use]
+    pub fn next_attempt_at(&self) -> Option<Timestamp> {
+        self.quiz.attempt_policy.cooldown_until(self.finished_at?)
+    }
+
+    fn under_attempt_limit(&self) -> bool {
         match self.quiz.max_attempts {
             Some(max) => self.attempt_count < max,
             None => true,
         }
     }
 
+    fn is_cooling_down(&self) -> bool {
+        self.clock.is_some() && self.next_attempt_at().is_some_and(|at| self.now() < at)
+    }
+
+    /// Get the questions of the current attempt.
+    #[must_use]
+    pub fn questions(&self) -> &[Question] {
+        &self.questions
+    }
+
+    /// Get the IDs of the current attempt's questions, in order.
+    #[must_use]
+    pub fn question_ids(&self) -> Vec<QuestionId> {
+        self.questions.iter().map(|q| q.id().clone()).collect()
+    }
+
+    /// Get the per-question results of the last finished attempt.
+    #[must_use]
+    pub fn outcomes(&self) -> &[QuestionOutcome] {
+        &self.outcomes
+    }
+
+    /// Get the current ability estimate of an adaptive attempt.
+    #[must_use]
+    pub fn ability(&self) -> Option<AbilityEstimate> {
+        self.adaptive.as_ref().map(|session| session.estimate)
+    }
+
+    /// Get the cached sandbox output of "predict the output" questions.
+    #[must_use]
+    pub fn outputs(&self) -> &OutputCache {
+        &self.outputs
+    }
+
+    /// Get the seed of the current attempt.
+    ///
+    /// Passing it to [`Quiz::materialize`] reproduces the attempt's questions.
+    #[must_use]
+    pub fn attempt_seed(&self) -> Option<u64> {
+        self.attempt_seed
+    }
+
+    /// Start the time limit of the current attempt at `now`.
+    ///
+    /// Does nothing if the quiz has no time limit.
+    pub fn start_clock(&mut self, now: Timestamp) {
+        self.deadline = self.quiz.time_limit_secs.map(|seconds| {
+            Timestamp::from_millis(now.as_millis().saturating_add(u64::from(seconds) * 1000))
+        });
+    }
+
+    /// Get when the current attempt's time limit runs out.
+    #[must_use]
+    pub fn deadline(&self) -> Option<Timestamp> {
+        self.deadline
+    }
+
+    /// Check if the current attempt has run past its time limit.
+    #[must_use]
+    pub fn is_overtime(&self, now: Timestamp) -> bool {
+        self.deadline.is_some_and(|deadline| now > deadline)
+    }
+
+    /// Capture the engine state so the attempt can be resumed later.
+    #[must_use]
+    pub fn snapshot(&self) -> QuizSnapshot {
+        let key = |q: &Question| (q.id().clone(), q.points());
+        QuizSnapshot {
+            version: SNAPSHOT_VERSION,
+            quiz_id: self.quiz.id.clone(),
+            state: self.state.clone(),
+            attempt_count: self.attempt_count,
+            seed: self.seed,
+            attempt_seed: self.attempt_seed,
+            grant: self.grant.clone(),
+            questions: self.questions.iter().map(key).collect(),
+            adaptive: self.adaptive.as_ref().map(|session| AdaptiveSnapshot {
+                pool: session.pool.iter().map(key).collect(),
+                responses: session.responses.clone(),
+                estimate: session.estimate,
+            }),
+            outcomes: self.outcomes.clone(),
+            deadline: self.deadline,
+            finished_at: self.finished_at,
+            log: self.log.clone(),
+        }
+    }
+
+    /// Restore an engine from a snapshot.
+    ///
+    /// The attempt's questions are drawn again from `quiz` with the saved
+    /// seed; the snapshot is rejected if the quiz ID, question IDs or points
+    /// no longer match.
+    pub fn resume(quiz: Quiz, snapshot: QuizSnapshot) -> Result<Self, ResumeError> {
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(ResumeError::UnsupportedVersion(snapshot.version));
+        }
+        if snapshot.quiz_id != quiz.id {
+            return Err(ResumeError::QuizMismatch {
+                expected: quiz.id,
+                found: snapshot.quiz_id,
+            });
+        }
+        if snapshot.adaptive.is_some() != quiz.adaptive.is_some() {
+            return Err(ResumeError::QuestionsChanged);
+        }
+
+        let mut engine = Self::new(quiz).with_seed(snapshot.seed);
+        if let Some(seed) = snapshot.attempt_seed {
+            let mut available = engine
+                .quiz
+                .materialize(seed)
+                .map_err(ResumeError::DrawFailed)?;
+            engine.questions = take_questions(&mut available, &snapshot.questions)?;
+            if let (Some(saved), Some(config)) = (snapshot.adaptive, &engine.quiz.adaptive) {
+                engine.adaptive = Some(AdaptiveSession {
+                    config: config.clone(),
+                    pool: take_questions(&mut available, &saved.pool)?,
+                    responses: saved.responses,
+                    estimate: saved.estimate,
+                });
+            }
+            if !available.is_empty() {
+                return Err(ResumeError::QuestionsChanged);
+            }
+        }
+
+        let mut state = snapshot.state;
+        if let QuizState::InProgress {
+            current_question,
+            answers,
+            feedback,
+            hints_revealed,
+        } = &mut state
+        {
+            let count = engine.questions.len();
+            if hints_revealed.is_empty() {
+                hints_revealed.resize(count, 0);
+            }
+            if *current_question >= count
+                || answers.len() != count
+                || feedback.len() != count
+                || hints_revealed.len() != count
+            {
+                return Err(ResumeError::InconsistentState);
+            }
+        }
+
+        engine.state = state;
+        engine.attempt_count = snapshot.attempt_count;
+        engine.attempt_seed = snapshot.attempt_seed;
+        engine.grant = snapshot.grant;
+        engine.outcomes = snapshot.outcomes;
+        engine.deadline = snapshot.deadline;
+        engine.finished_at = snapshot.finished_at;
+        engine.log = snapshot.log;
+        Ok(engine)
+    }
+
+    /// Restore an engine for a sealed quiz from a snapshot.
+    pub fn resume_sealed(sealed: SealedQuiz, snapshot: QuizSnapshot) -> Result<Self, ResumeError> {
+        let mut engine = Self::resume(sealed.quiz.clone(), snapshot)?;
+        engine.sealed = Some(sealed);
+        Ok(engine)
+    }
+
     /// Start a new quiz attempt.
     ///
+    /// Each attempt draws with its own seed derived from the engine seed.
     /// Returns an error if maximum attempts have been reached.
     pub fn start(&mut self) -> Result<&Question, QuizError> {
-        if !self.can_attempt() {
+        let label = alloc::format!("attempt-{}", self.attempt_count + 1);
+        let seed = SeededRng::derive(self.seed, &label).next_u64();
+        self.start_with_seed(seed)
+    }
+
+    /// Start a new quiz attempt from a grant issued by the grader.
+    ///
+    /// The attempt draws with the grant's seed, and the grant travels with
+    /// the [`submission`](Self::submission) so the grader can check it.
+    pub fn start_with_grant(&mut self, grant: SignedGrant) -> Result<&Question, QuizError> {
+        let issued = grant.unverified().map_err(|_| QuizError::InvalidGrant)?;
+        if issued.quiz_id != self.quiz.id || issued.attempt_number != self.attempt_count + 1 {
+            return Err(QuizError::InvalidGrant);
+        }
+        self.start_with_seed(issued.seed)?;
+        self.grant = Some(grant);
+        self.current_question()
+    }
+
+    /// Start a new quiz attempt with an explicit seed.
+    fn start_with_seed(&mut self, seed: u64) -> Result<&Question, QuizError> {
+        if !self.under_attempt_limit() {
             return Err(QuizError::MaxAttemptsReached);
         }
+        if self.is_cooling_down() {
+            if let Some(until) = self.next_attempt_at() {
+                return Err(QuizError::CoolingDown { until });
+            }
+        }
 
-        if self.quiz.questions.is_empty() {
+        let questions = self.quiz.materialize(seed).map_err(QuizError::DrawFailed)?;
+        if questions.is_empty() {
             return Err(QuizError::NoQuestions);
         }
 
         self.attempt_count += 1;
-        let question_count = self.quiz.questions.len();
+        self.attempt_seed = Some(seed);
+        self.grant = None;
+        self.questions = questions;
+        self.adaptive = None;
+        self.deadline = None;
+
+        if let Some(config) = &self.quiz.adaptive {
+            let mut session = AdaptiveSession {
+                config: config.clone(),
+                pool: core::mem::take(&mut self.questions),
+                responses: Vec::new(),
+                estimate: adaptive::estimate_ability(&[]),
+            };
+            self.questions.extend(session.take_next());
+            self.adaptive = Some(session);
+        }
+
+        let question_count = self.questions.len();
 
         self.state = QuizState::InProgress {
             current_question: 0,
             answers: alloc::vec![None; question_count],
             feedback: alloc::vec![None; question_count],
+            hints_revealed: alloc::vec![0; question_count],
         };
 
+        if self.clock.is_some() {
+            self.start_clock(self.now());
+        }
+        self.record(QuizEventKind::AttemptStarted {
+            attempt: self.attempt_count,
+        });
+        if let Some(first) = self.questions.first() {
+            let question_id = first.id().clone();
+            self.record(QuizEventKind::QuestionViewed { question_id });
+        }
+
         self.current_question()
     }
 
@@ -115,7 +490,6 @@ impl QuizEngine {
             QuizState::InProgress {
                 current_question, ..
             } => self
-                .quiz
                 .questions
                 .get(*current_question)
                 .ok_or(QuizError::InvalidState),
@@ -125,14 +499,17 @@ impl QuizEngine {
 
     /// Submit an answer for the current question.
     ///
-    /// Returns immediate feedback (Jidoka principle).
+    /// Returns immediate feedback (Jidoka principle). With a clock, answers
+    /// after the attempt's deadline are rejected.
     pub fn submit_answer(&mut self, answer: Answer) -> Result<Feedback, QuizError> {
+        if self.clock.is_some() && self.is_overtime(self.now()) {
+            return Err(QuizError::TimeExpired);
+        }
         let (current_idx, question) = match &self.state {
             QuizState::InProgress {
                 current_question, ..
             } => {
                 let q = self
-                    .quiz
                     .questions
                     .get(*current_question)
                     .ok_or(QuizError::InvalidState)?;
@@ -142,31 +519,126 @@ impl QuizEngine {
         };
 
         // Grade the answer immediately
-        let feedback = Grader::grade_answer(&question, &answer);
+        let revealed = self.hints_revealed(current_idx);
+        let feedback = self
+            .grade(&question, &answer)
+            .with_hint_deduction(question.hint_deduction(revealed));
+
+        if let Some(session) = &mut self.adaptive {
+            let params = session.config.parameters(question.id());
+            session.record(current_idx, params, feedback.correct);
+        }
 
         // Update state
+        let mut previous = None;
+        let mut unchanged = false;
         if let QuizState::InProgress {
             answers,
             feedback: fb,
             ..
         } = &mut self.state
         {
-            answers[current_idx] = Some(answer);
-            fb[current_idx] = Some(feedback.clone());
+            unchanged = answers[current_idx].as_ref() == Some(&answer);
+            answers[current_idx] = Some(answer.clone());
+            previous = fb[current_idx].replace(feedback.clone());
+        }
+        if unchanged {
+            return Ok(feedback);
         }
 
+        let question_id = question.id().clone();
+        let correct = feedback.correct;
+        self.record(match previous {
+            Some(previous) => QuizEventKind::AnswerChanged {
+                question_id,
+                answer,
+                correct,
+                was_correct: previous.correct,
+            },
+            None => QuizEventKind::AnswerSubmitted {
+                question_id,
+                answer,
+                correct,
+            },
+        });
+
         Ok(feedback)
     }
 
+    /// Reveal the current question's next hint.
+    ///
+    /// Each hint deducts its cost from the question's points, including an
+    /// answer already given.
+    pub fn reveal_hint(&mut self) -> Result<&QuestionHint, QuizError> {
+        let QuizState::InProgress {
+            current_question,
+            feedback,
+            hints_revealed,
+            ..
+        } = &mut self.state
+        else {
+            return Err(QuizError::InvalidState);
+        };
+        let index = *current_question;
+        let question = self.questions.get(index).ok_or(QuizError::InvalidState)?;
+        let revealed = hints_revealed
+            .get_mut(index)
+            .ok_or(QuizError::InvalidState)?;
+        if *revealed >= question.hints().len() {
+            return Err(QuizError::NoMoreHints);
+        }
+
+        *revealed += 1;
+        let hint = *revealed - 1;
+        if let Some(fb) = feedback.get_mut(index).and_then(Option::as_mut) {
+            let deduction = question.hint_deduction(*revealed);
+            *fb = fb.clone().with_hint_deduction(deduction);
+        }
+
+        let question_id = question.id().clone();
+        self.record(QuizEventKind::HintRevealed { question_id, hint });
+        self.questions[index]
+            .hints()
+            .get(hint)
+            .ok_or(QuizError::InvalidState)
+    }
+
+    /// Get the hints revealed so far for the current question.
+    #[must_use]
+    pub fn revealed_hints(&self) -> &[QuestionHint] {
+        match &self.state {
+            QuizState::InProgress {
+                current_question, ..
+            } => {
+                let revealed = self.hints_revealed(*current_question);
+                self.questions.get(*current_question).map_or(&[], |q| {
+                    let hints = q.hints();
+                    &hints[..revealed.min(hints.len())]
+                })
+            }
+            _ => &[],
+        }
+    }
+
+    fn hints_revealed(&self, index: usize) -> usize {
+        match &self.state {
+            QuizState::InProgress { hints_revealed, .. } => {
+                hints_revealed.get(index).copied().unwrap_or(0)
+            }
+            _ => 0,
+        }
+    }
+
     /// Move to the next question.
     ///
     /// Returns the next question or an error if at the end.
     pub fn next_question(&mut self) -> Result<&Question, QuizError> {
-        match &mut self.state {
+        let (from, to) = match &mut self.state {
             QuizState::InProgress {
                 current_question,
                 answers,
-                ..
+                feedback,
+                hints_revealed,
             } => {
                 // Check if current question was answered
                 if answers.get(*current_question).map_or(true, |a| a.is_none()) {
@@ -174,23 +646,37 @@ impl QuizEngine {
                 }
 
                 let next_idx = *current_question + 1;
-                if next_idx >= self.quiz.questions.len() {
-                    return Err(QuizError::NoMoreQuestions);
+                if next_idx >= self.questions.len() {
+                    let next = match &mut self.adaptive {
+                        Some(session) if !session.should_stop() => session.take_next(),
+                        _ => None,
+                    };
+                    let question = next.ok_or(QuizError::NoMoreQuestions)?;
+                    self.questions.push(question);
+                    answers.push(None);
+                    feedback.push(None);
+                    hints_revealed.push(0);
                 }
 
+                let from = *current_question;
                 *current_question = next_idx;
-                self.quiz
-                    .questions
-                    .get(next_idx)
-                    .ok_or(QuizError::InvalidState)
+                (from, next_idx)
             }
-            _ => Err(QuizError::InvalidState),
-        }
+            _ => return Err(QuizError::InvalidState),
+        };
+        self.navigated(from, to, Direction::Next)
     }
 
     /// Move to the previous question.
+    ///
+    /// Not available in adaptive quizzes, where each question depends on the
+    /// answers before it.
     pub fn previous_question(&mut self) -> Result<&Question, QuizError> {
-        match &mut self.state {
+        if self.adaptive.is_some() {
+            return Err(QuizError::BackNavigationDisabled);
+        }
+
+        let (from, to) = match &mut self.state {
             QuizState::InProgress {
                 current_question, ..
             } => {
@@ -199,13 +685,31 @@ impl QuizEngine {
                 }
 
                 *current_question -= 1;
-                self.quiz
-                    .questions
-                    .get(*current_question)
-                    .ok_or(QuizError::InvalidState)
+                (*current_question + 1, *current_question)
             }
-            _ => Err(QuizError::InvalidState),
+            _ => return Err(QuizError::InvalidState),
+        };
+        self.navigated(from, to, Direction::Previous)
+    }
+
+    /// Log a move between questions and return the question reached.
+    fn navigated(
+        &mut self,
+        from: usize,
+        to: usize,
+        direction: Direction,
+    ) -> Result<&Question, QuizError> {
+        let ids = (self.questions.get(from), self.questions.get(to));
+        if let (Some(from), Some(to)) = ids {
+            let (from, to) = (from.id().clone(), to.id().clone());
+            self.record(QuizEventKind::Navigated {
+                from,
+                to: to.clone(),
+                direction,
+            });
+            self.record(QuizEventKind::QuestionViewed { question_id: to });
         }
+        self.questions.get(to).ok_or(QuizError::InvalidState)
     }
 
     /// Finish the quiz and calculate the final score.
@@ -214,6 +718,22 @@ impl QuizEngine {
             QuizState::InProgress {
                 answers, feedback, ..
             } => {
+                self.outcomes = self
+                    .questions
+                    .iter()
+                    .zip(answers.iter().zip(feedback))
+                    .map(|(question, (answer, fb))| {
+                        let outcome = match fb {
+                            Some(fb) => QuestionOutcome::from_feedback(question.id().clone(), fb),
+                            None => QuestionOutcome::new(question.id().clone(), false, 0),
+                        };
+                        match answer {
+                            Some(answer) => outcome.with_answer(answer.clone()),
+                            None => outcome,
+                        }
+                    })
+                    .collect();
+
                 // Collect all answers, using empty for unanswered
                 let collected_answers: Vec<Answer> = answers
                     .iter()
@@ -233,16 +753,231 @@ impl QuizEngine {
             _ => return Err(QuizError::InvalidState),
         };
 
-        let score = Grader::calculate_score(&self.quiz, &feedback);
+        let mut score =
+            Grader::score_questions(&self.questions, self.quiz.passing_score, &feedback);
+        if let Some(session) = &self.adaptive {
+            score = score.with_ability(session.estimate);
+        }
 
         self.state = QuizState::Completed {
             score: score.clone(),
             attempt_number: self.attempt_count,
         };
+        self.finished_at = Some(self.now());
+        self.record(QuizEventKind::Finished {
+            points_earned: score.points_earned,
+        });
 
         Ok(score)
     }
 
+    /// Review the finished attempt, starting at the first question.
+    pub fn start_review(&mut self) -> Result<QuestionReview, QuizError> {
+        let score = match &self.state {
+            QuizState::Completed { score, .. } | QuizState::Reviewing { score, .. } => {
+                score.clone()
+            }
+            _ => return Err(QuizError::InvalidState),
+        };
+
+        let mut answers = Vec::with_capacity(self.outcomes.len());
+        let mut feedback = Vec::with_capacity(self.outcomes.len());
+        for (question, outcome) in self.questions.iter().zip(&self.outcomes) {
+            let fb = match &outcome.answer {
+                Some(answer) => {
+                    let graded = self.grade(question, answer);
+                    let deduction = graded.points_earned.saturating_sub(outcome.points_earned);
+                    graded.with_hint_deduction(deduction)
+                }
+                None => Feedback::incorrect("Not answered"),
+            };
+            answers.push(outcome.answer.clone().unwrap_or(Answer::Choice(usize::MAX)));
+            feedback.push(fb);
+        }
+
+        self.state = QuizState::Reviewing {
+            current_question: 0,
+            answers,
+            feedback,
+            score,
+        };
+        self.current_review()
+    }
+
+    /// Get the question being reviewed.
+    pub fn current_review(&self) -> Result<QuestionReview, QuizError> {
+        let QuizState::Reviewing {
+            current_question,
+            feedback,
+            ..
+        } = &self.state
+        else {
+            return Err(QuizError::InvalidState);
+        };
+        let index = *current_question;
+        let question = self.questions.get(index).ok_or(QuizError::InvalidState)?;
+        let answer = self.outcomes.get(index).and_then(|o| o.answer.clone());
+        let notes = answer
+            .as_ref()
+            .map(|answer| distractor_notes(question, answer))
+            .unwrap_or_default();
+
+        Ok(QuestionReview {
+            index,
+            question: question.clone(),
+            answer,
+            feedback: feedback
+                .get(index)
+                .cloned()
+                .ok_or(QuizError::InvalidState)?,
+            correct_answer: if self.answers_revealed() {
+                correct_answer(question, &self.outputs)
+            } else {
+                None
+            },
+            notes,
+        })
+    }
+
+    /// Move to the next question in review.
+    pub fn review_next(&mut self) -> Result<QuestionReview, QuizError> {
+        match &mut self.state {
+            QuizState::Reviewing {
+                current_question, ..
+            } => {
+                if *current_question + 1 >= self.questions.len() {
+                    return Err(QuizError::NoMoreQuestions);
+                }
+                *current_question += 1;
+            }
+            _ => return Err(QuizError::InvalidState),
+        }
+        self.current_review()
+    }
+
+    /// Move to the previous question in review.
+    pub fn review_previous(&mut self) -> Result<QuestionReview, QuizError> {
+        match &mut self.state {
+            QuizState::Reviewing {
+                current_question, ..
+            } => {
+                if *current_question == 0 {
+                    return Err(QuizError::NoPreviousQuestion);
+                }
+                *current_question -= 1;
+            }
+            _ => return Err(QuizError::InvalidState),
+        }
+        self.current_review()
+    }
+
+    /// Check whether review shows the correct answers.
+    ///
+    /// Quizzes that conceal answers show them only once no attempts remain.
+    #[must_use]
+    pub fn answers_revealed(&self) -> bool {
+        self.sealed.is_none() && (!self.quiz.conceal_answers || !self.under_attempt_limit())
+    }
+
+    /// Decrypt the current question's explanation with its released key.
+    ///
+    /// Only works for sealed quizzes, once the question has been answered.
+    pub fn unlock_explanation(&self, key: &ExplanationKey) -> Result<String, QuizError> {
+        let sealed = self.sealed.as_ref().ok_or(QuizError::InvalidState)?;
+        let index = match &self.state {
+            QuizState::InProgress {
+                current_question,
+                feedback,
+                ..
+            } => {
+                if feedback
+                    .get(*current_question)
+                    .map_or(true, Option::is_none)
+                {
+                    return Err(QuizError::QuestionNotAnswered);
+                }
+                *current_question
+            }
+            QuizState::Reviewing {
+                current_question, ..
+            } => *current_question,
+            _ => return Err(QuizError::InvalidState),
+        };
+        let question = self.questions.get(index).ok_or(QuizError::InvalidState)?;
+        sealed
+            .explanation(question.id(), key)
+            .ok_or(QuizError::WrongKey)
+    }
+
+    /// Package the finished attempt for server-side grading, signed with `key`.
+    ///
+    /// The envelope carries the attempt's grant, the answers, per-question
+    /// time on task and hints revealed; see
+    /// [`grade_submission`](crate::grade_submission). Only attempts started
+    /// with [`start_with_grant`](Self::start_with_grant) can be submitted, and
+    /// adaptive attempts are rejected if finished before they stop.
+    pub fn submission(&self, key: &[u8]) -> Result<SubmissionEnvelope, QuizError> {
+        if !matches!(
+            self.state,
+            QuizState::Completed { .. } | QuizState::Reviewing { .. }
+        ) {
+            return Err(QuizError::InvalidState);
+        }
+        let grant = self.grant.clone().ok_or(QuizError::InvalidGrant)?;
+        let events = self.log.latest_attempt();
+        let activity = self.log.summary();
+
+        let answers = self
+            .questions
+            .iter()
+            .zip(&self.outcomes)
+            .map(|(question, outcome)| {
+                let id = question.id();
+                SubmittedAnswer {
+                    question_id: id.clone(),
+                    answer: outcome.answer.clone(),
+                    time_on_task_ms: activity
+                        .iter()
+                        .find(|a| &a.question_id == id)
+                        .map_or(0, |a| a.time_on_task_ms),
+                    hints_revealed: events
+                        .iter()
+                        .filter(|e| {
+                            matches!(&e.kind, QuizEventKind::HintRevealed { question_id, .. } if question_id == id)
+                        })
+                        .count(),
+                }
+            })
+            .collect();
+
+        let submission = Submission {
+            version: ENVELOPE_VERSION,
+            quiz_id: self.quiz.id.clone(),
+            quiz_version: quiz_version(&self.quiz).map_err(QuizError::Envelope)?,
+            attempt_number: self.attempt_count,
+            grant,
+            started_at: events.first().map_or(Timestamp::ZERO, |e| e.at),
+            finished_at: self.finished_at,
+            answers,
+        };
+        SubmissionEnvelope::sign(&submission, key).map_err(QuizError::Envelope)
+    }
+
+    /// Grade an answer against the quiz, or its hashes if sealed.
+    fn grade(&self, question: &Question, answer: &Answer) -> Feedback {
+        match &self.sealed {
+            Some(sealed) => {
+                Grader::grade_sealed(sealed, question, answer, &self.quiz.confidence_payoff)
+            }
+            None => Grader::grade_with_payoff(
+                question,
+                answer,
+                &self.outputs,
+                &self.quiz.confidence_payoff,
+            ),
+        }
+    }
+
     /// Get the progress through the quiz (0.0 - 1.0).
     #[must_use]
     pub fn progress(&self) -> f32 {
@@ -250,10 +985,12 @@ impl QuizEngine {
             QuizState::NotStarted => 0.0,
             QuizState::InProgress { answers, .. } => {
                 let answered = answers.iter().filter(|a| a.is_some()).count();
-                if self.quiz.questions.is_empty() {
+                if let Some(session) = &self.adaptive {
+                    (answered as f32 / session.config.max_length.max(1) as f32).min(1.0)
+                } else if self.questions.is_empty() {
                     0.0
                 } else {
-                    answered as f32 / self.quiz.questions.len() as f32
+                    answered as f32 / self.questions.len() as f32
                 }
             }
             QuizState::Reviewing { .. } | QuizState::Completed { .. } => 1.0,
@@ -261,6 +998,31 @@ impl QuizEngine {
     }
 }
 
+/// Move the saved questions out of `available`, in saved order, checking points.
+fn take_questions(
+    available: &mut Vec<Question>,
+    saved: &[(QuestionId, u32)],
+) -> Result<Vec<Question>, ResumeError> {
+    saved
+        .iter()
+        .map(|(id, points)| {
+            let index = available
+                .iter()
+                .position(|q| q.id() == id)
+                .ok_or(ResumeError::QuestionsChanged)?;
+            let question = available.remove(index);
+            if question.points() != *points {
+                return Err(ResumeError::PointsChanged {
+                    question_id: id.clone(),
+                    expected: *points,
+                    found: question.points(),
+                });
+            }
+            Ok(question)
+        })
+        .collect()
+}
+
 /// Errors that can occur during quiz operations.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum QuizError {
@@ -276,6 +1038,25 @@ pub enum QuizError {
     NoMoreQuestions,
     /// No previous question (already at first)
     NoPreviousQuestion,
+    /// Questions could not be drawn from the bank
+    DrawFailed(DrawError),
+    /// Going back is not allowed in adaptive quizzes
+    BackNavigationDisabled,
+    /// The current question has no more hints
+    NoMoreHints,
+    /// The key does not unlock this question's explanation
+    WrongKey,
+    /// The attempt policy requires a wait before the next attempt
+    CoolingDown {
+        /// When the next attempt may start
+        until: Timestamp,
+    },
+    /// The attempt's time limit has run out
+    TimeExpired,
+    /// The attempt grant is missing or is not for the next attempt
+    InvalidGrant,
+    /// The submission could not be packaged
+    Envelope(EnvelopeError),
 }
 
 impl core::fmt::Display for QuizError {
@@ -287,6 +1068,14 @@ impl core::fmt::Display for QuizError {
             Self::QuestionNotAnswered => write!(f, "Current question not answered"),
             Self::NoMoreQuestions => write!(f, "No more questions"),
             Self::NoPreviousQuestion => write!(f, "No previous question"),
+            Self::DrawFailed(err) => write!(f, "Could not draw questions: {}", err),
+            Self::BackNavigationDisabled => write!(f, "Cannot go back in an adaptive quiz"),
+            Self::NoMoreHints => write!(f, "No more hints"),
+            Self::WrongKey => write!(f, "Key does not unlock this explanation"),
+            Self::CoolingDown { .. } => write!(f, "Next attempt is not available yet"),
+            Self::TimeExpired => write!(f, "Time limit has run out"),
+            Self::InvalidGrant => write!(f, "Attempt grant is not for this attempt"),
+            Self::Envelope(err) => write!(f, "Could not package submission: {}", err),
         }
     }
 }
@@ -295,6 +1084,7 @@ impl core::fmt::Display for QuizError {
 #[allow(clippy::expect_used)]
 mod tests {
     use super::*;
+    use crate::envelope::AttemptGrant;
     use profesor_core::{Question, QuestionId, Quiz};
 
     fn create_test_quiz() -> Quiz {
@@ -306,6 +1096,7 @@ mod tests {
                 correct: 1,
                 explanation: "2+2=4".into(),
                 points: 10,
+                option_explanations: Vec::new(),
             })
             .with_question(Question::MultipleChoice {
                 id: QuestionId::new("q2"),
@@ -314,6 +1105,7 @@ mod tests {
                 correct: 1,
                 explanation: "3+3=6".into(),
                 points: 10,
+                option_explanations: Vec::new(),
             })
     }
 
@@ -532,6 +1324,7 @@ mod tests {
                 correct: 0,
                 explanation: "".into(),
                 points: 10,
+                option_explanations: Vec::new(),
             });
 
         let mut engine = QuizEngine::new(quiz);
@@ -546,23 +1339,208 @@ mod tests {
     }
 
     #[test]
-    fn test_progress() {
-        let quiz = create_test_quiz();
-        let mut engine = QuizEngine::new(quiz);
-
-        assert_eq!(engine.progress(), 0.0);
+    fn test_confidence_uses_quiz_payoff() {
+        use profesor_core::{Confidence, Payoff, PayoffMatrix};
 
+        let payoff = PayoffMatrix::new(Payoff::new(1, 0), Payoff::new(2, -2), Payoff::new(5, -10));
+        let mut engine = QuizEngine::new(create_test_quiz().with_confidence_payoff(payoff));
         engine.start().expect("Start");
-        assert_eq!(engine.progress(), 0.0);
+        let feedback = engine
+            .submit_answer(Answer::Choice(0).with_confidence(Confidence::High))
+            .expect("Submit");
+        assert_eq!(feedback.confidence_mark, -10);
+        assert!(feedback.misconception);
+        engine.next_question().expect("Next");
+        engine
+            .submit_answer(Answer::Choice(1).with_confidence(Confidence::High))
+            .expect("Submit");
 
-        engine.submit_answer(Answer::Choice(1)).expect("Submit");
-        assert!((engine.progress() - 0.5).abs() < f32::EPSILON);
+        let score = engine.finish().expect("Finish");
+        let calibration = score.calibration.expect("Calibration");
+        assert_eq!(calibration.high.answered, 2);
+        assert_eq!(calibration.marks, -5);
     }
 
-    #[test]
-    fn test_progress_completed() {
-        let quiz = create_test_quiz();
-        let mut engine = QuizEngine::new(quiz);
+    fn create_hinted_quiz() -> Quiz {
+        let mut quiz = create_test_quiz();
+        quiz.questions[0] = quiz.questions[0]
+            .clone()
+            .with_hint(QuestionHint::new("It's even", 0.2))
+            .with_hint(QuestionHint::new("It's 4", 0.5));
+        quiz
+    }
+
+    #[test]
+    fn test_reveal_hints_deducts_points() {
+        let mut engine = QuizEngine::new(create_hinted_quiz());
+        assert_eq!(engine.reveal_hint().err(), Some(QuizError::InvalidState));
+        engine.start().expect("Start");
+        assert!(engine.revealed_hints().is_empty());
+
+        assert_eq!(engine.reveal_hint().expect("Hint").text, "It's even");
+        let feedback = engine.submit_answer(Answer::Choice(1)).expect("Submit");
+        assert!(feedback.correct);
+        assert_eq!(feedback.points_earned, 8);
+        assert_eq!(feedback.hint_deduction, 2);
+
+        // A hint revealed after answering still costs points.
+        assert_eq!(engine.reveal_hint().expect("Hint").text, "It's 4");
+        assert_eq!(engine.revealed_hints().len(), 2);
+        assert_eq!(engine.reveal_hint().err(), Some(QuizError::NoMoreHints));
+
+        engine.next_question().expect("Next");
+        assert!(engine.revealed_hints().is_empty());
+        assert_eq!(engine.reveal_hint().err(), Some(QuizError::NoMoreHints));
+        engine.submit_answer(Answer::Choice(1)).expect("Submit");
+
+        let snapshot = engine.snapshot();
+        let score = engine.finish().expect("Finish");
+        assert_eq!(score.points_earned, 13);
+        assert_eq!(engine.outcomes()[0].points_earned, 3);
+
+        let mut resumed = QuizEngine::resume(create_hinted_quiz(), snapshot).expect("Resume");
+        resumed.previous_question().expect("Previous");
+        assert_eq!(resumed.revealed_hints().len(), 2);
+        assert!(resumed
+            .audit_log()
+            .events
+            .iter()
+            .any(|e| matches!(e.kind, QuizEventKind::HintRevealed { hint: 1, .. })));
+    }
+
+    fn create_review_quiz() -> Quiz {
+        let mut quiz = create_test_quiz();
+        if let Question::MultipleChoice {
+            option_explanations,
+            ..
+        } = &mut quiz.questions[1]
+        {
+            *option_explanations = alloc::vec!["3+3 is even".into()];
+        }
+        quiz
+    }
+
+    #[test]
+    fn test_review_mode() {
+        let mut engine = QuizEngine::new(create_review_quiz().with_max_attempts(2));
+        assert_eq!(engine.start_review().err(), Some(QuizError::InvalidState));
+        engine.start().expect("Start");
+        engine.submit_answer(Answer::Choice(1)).expect("Submit");
+        engine.next_question().expect("Next");
+        engine.submit_answer(Answer::Choice(0)).expect("Submit");
+        let score = engine.finish().expect("Finish");
+
+        let review = engine.start_review().expect("Review");
+        assert_eq!(review.index, 0);
+        assert_eq!(review.answer, Some(Answer::Choice(1)));
+        assert!(review.feedback.correct);
+        assert_eq!(review.correct_answer, Some(Answer::Choice(1)));
+        assert!(review.notes.is_empty());
+        assert_eq!(
+            engine.review_previous().err(),
+            Some(QuizError::NoPreviousQuestion)
+        );
+
+        let review = engine.review_next().expect("Next");
+        assert_eq!(review.index, 1);
+        assert!(!review.feedback.correct);
+        assert_eq!(
+            review.notes,
+            alloc::vec![alloc::string::String::from("5: 3+3 is even")]
+        );
+        assert_eq!(engine.review_next().err(), Some(QuizError::NoMoreQuestions));
+        assert_eq!(engine.review_previous().expect("Previous").index, 0);
+
+        match engine.state() {
+            QuizState::Reviewing {
+                score: reviewed, ..
+            } => assert_eq!(reviewed, &score),
+            other => assert_eq!(other, &QuizState::NotStarted),
+        }
+        engine.start().expect("Second attempt from review");
+    }
+
+    #[test]
+    fn test_review_conceals_answers_while_attempts_remain() {
+        let quiz = create_review_quiz()
+            .with_max_attempts(2)
+            .with_concealed_answers(true);
+        let mut engine = QuizEngine::new(quiz);
+        for attempt in 1..=2 {
+            engine.start().expect("Start");
+            engine.submit_answer(Answer::Choice(0)).expect("Submit");
+            engine.finish().expect("Finish");
+            let review = engine.start_review().expect("Review");
+            assert_eq!(engine.answers_revealed(), attempt == 2);
+            assert_eq!(review.correct_answer.is_some(), attempt == 2);
+        }
+    }
+
+    #[test]
+    fn test_attempt_cooldown_without_clock() {
+        let policy = profesor_core::AttemptPolicy::new().with_cooldown(60);
+        let mut engine = QuizEngine::new(create_test_quiz().with_attempt_policy(policy));
+        engine.start().expect("Start");
+        engine.finish().expect("Finish");
+        assert!(engine.can_attempt());
+        engine.start().expect("Retry without a clock");
+    }
+
+    #[test]
+    fn test_attempt_cooldown() {
+        let policy = profesor_core::AttemptPolicy::new().with_cooldown(60);
+        let clock = crate::audit::ManualClock::new(Timestamp::from_millis(1_000));
+        let mut engine = QuizEngine::new(create_test_quiz().with_attempt_policy(policy))
+            .with_clock(Arc::new(clock.clone()));
+        assert!(engine.next_attempt_at().is_none());
+
+        engine.start().expect("Start");
+        clock.advance(5_000);
+        engine.finish().expect("Finish");
+        assert_eq!(
+            engine.next_attempt_at(),
+            Some(Timestamp::from_millis(66_000))
+        );
+        assert!(!engine.can_attempt());
+        assert_eq!(
+            engine.start().err(),
+            Some(QuizError::CoolingDown {
+                until: Timestamp::from_millis(66_000)
+            })
+        );
+        assert_eq!(engine.attempt_count(), 1);
+
+        clock.set(Timestamp::from_millis(66_000));
+        assert!(engine.can_attempt());
+        engine.start().expect("Retry after cooldown");
+
+        engine.finish().expect("Finish");
+        let quiz = create_test_quiz().with_attempt_policy(policy);
+        let restored = QuizEngine::resume(quiz, engine.snapshot()).expect("Resume");
+        assert_eq!(
+            restored.next_attempt_at(),
+            Some(Timestamp::from_millis(126_000))
+        );
+    }
+
+    #[test]
+    fn test_progress() {
+        let quiz = create_test_quiz();
+        let mut engine = QuizEngine::new(quiz);
+
+        assert_eq!(engine.progress(), 0.0);
+
+        engine.start().expect("Start");
+        assert_eq!(engine.progress(), 0.0);
+
+        engine.submit_answer(Answer::Choice(1)).expect("Submit");
+        assert!((engine.progress() - 0.5).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_progress_completed() {
+        let quiz = create_test_quiz();
+        let mut engine = QuizEngine::new(quiz);
 
         engine.start().expect("Start");
         engine.submit_answer(Answer::Choice(1)).expect("Submit");
@@ -600,4 +1578,667 @@ mod tests {
             "No previous question"
         );
     }
+
+    fn create_bank_quiz() -> Quiz {
+        use profesor_core::{BankItem, DrawRule, QuestionBank, QuestionDifficulty, QuestionDraw};
+
+        let mut bank = QuestionBank::new();
+        for i in 0..8 {
+            let difficulty = if i < 5 {
+                QuestionDifficulty::Easy
+            } else {
+                QuestionDifficulty::Hard
+            };
+            bank = bank.with_item(
+                BankItem::new(
+                    Question::MultipleChoice {
+                        id: QuestionId::new(alloc::format!("bank-{}", i)),
+                        prompt: "Pick A".into(),
+                        options: alloc::vec!["A".into(), "B".into()],
+                        correct: 0,
+                        explanation: "A".into(),
+                        points: if i < 5 { 5 } else { 20 },
+                        option_explanations: Vec::new(),
+                    },
+                    difficulty,
+                )
+                .with_tag("ownership"),
+            );
+        }
+
+        Quiz::new("bank-quiz", "Bank Quiz").with_draw(
+            QuestionDraw::new(bank)
+                .with_rule(DrawRule::new(2).with_difficulty(QuestionDifficulty::Easy))
+                .with_rule(DrawRule::new(1).with_difficulty(QuestionDifficulty::Hard)),
+        )
+    }
+
+    #[test]
+    fn test_drawn_attempt() {
+        let mut engine = QuizEngine::new(create_bank_quiz()).with_seed(11);
+        engine.start().expect("Start");
+
+        assert_eq!(engine.questions().len(), 3);
+        let seed = engine.attempt_seed().expect("Seed");
+        let replay = engine.quiz().materialize(seed).expect("Materialize");
+        assert_eq!(engine.questions(), replay.as_slice());
+        assert_eq!(engine.question_ids().len(), 3);
+    }
+
+    #[test]
+    fn test_drawn_attempt_scored_on_drawn_questions() {
+        let mut engine = QuizEngine::new(create_bank_quiz());
+        engine.start().expect("Start");
+        for _ in 0..3 {
+            engine.submit_answer(Answer::Choice(0)).expect("Submit");
+            let _ = engine.next_question();
+        }
+        let score = engine.finish().expect("Finish");
+
+        assert_eq!(score.points_possible, 30);
+        assert_eq!(score.points_earned, 30);
+        assert_eq!(score.total_questions, 3);
+    }
+
+    #[test]
+    fn test_attempts_draw_independently() {
+        let mut engine = QuizEngine::new(create_bank_quiz());
+        let seeds: Vec<u64> = (0..3)
+            .map(|_| {
+                engine.start().expect("Start");
+                engine.attempt_seed().expect("Seed")
+            })
+            .collect();
+        assert_ne!(seeds[0], seeds[1]);
+        assert_ne!(seeds[1], seeds[2]);
+    }
+
+    #[test]
+    fn test_same_seed_same_draw() {
+        let mut a = QuizEngine::new(create_bank_quiz()).with_seed(3);
+        let mut b = QuizEngine::new(create_bank_quiz()).with_seed(3);
+        a.start().expect("Start");
+        b.start().expect("Start");
+        assert_eq!(a.question_ids(), b.question_ids());
+    }
+
+    #[test]
+    fn test_draw_failure_does_not_use_attempt() {
+        use profesor_core::{DrawRule, QuestionBank, QuestionDraw};
+
+        let quiz = Quiz::new("empty-bank", "Empty")
+            .with_draw(QuestionDraw::new(QuestionBank::new()).with_rule(DrawRule::new(1)))
+            .with_max_attempts(1);
+        let mut engine = QuizEngine::new(quiz);
+
+        assert!(matches!(engine.start(), Err(QuizError::DrawFailed(_))));
+        assert_eq!(engine.attempt_count(), 0);
+    }
+
+    fn create_adaptive_quiz(config: AdaptiveConfig) -> Quiz {
+        use profesor_core::ItemParameters;
+
+        let mut quiz = Quiz::new("placement", "Placement");
+        let mut config = config;
+        for i in 0..9 {
+            let id = alloc::format!("item-{}", i);
+            quiz = quiz.with_question(Question::MultipleChoice {
+                id: QuestionId::new(id.clone()),
+                prompt: "Pick A".into(),
+                options: alloc::vec!["A".into(), "B".into()],
+                correct: 0,
+                explanation: "A".into(),
+                points: 10,
+                option_explanations: Vec::new(),
+            });
+            config = config.with_item(id, ItemParameters::new(1.5, i as f64 - 4.0, 0.0));
+        }
+        quiz.with_adaptive(config)
+    }
+
+    #[test]
+    fn test_adaptive_starts_with_most_informative_item() {
+        let mut engine = QuizEngine::new(create_adaptive_quiz(AdaptiveConfig::new(0.3, 5)));
+        let first = engine.start().expect("Start");
+
+        assert_eq!(first.id().as_str(), "item-4");
+        assert_eq!(engine.questions().len(), 1);
+        assert!(engine.ability().is_some());
+    }
+
+    #[test]
+    fn test_adaptive_moves_toward_harder_items() {
+        let mut engine = QuizEngine::new(create_adaptive_quiz(AdaptiveConfig::new(0.0, 5)));
+        engine.start().expect("Start");
+        engine.submit_answer(Answer::Choice(0)).expect("Submit");
+        let next = engine.next_question().expect("Next");
+
+        assert!(next.id().as_str() > "item-4");
+        assert!(engine.ability().is_some_and(|a| a.theta > 0.0));
+    }
+
+    #[test]
+    fn test_adaptive_stops_at_max_length() {
+        let mut engine = QuizEngine::new(create_adaptive_quiz(AdaptiveConfig::new(0.0, 3)));
+        engine.start().expect("Start");
+        for _ in 0..2 {
+            engine.submit_answer(Answer::Choice(1)).expect("Submit");
+            engine.next_question().expect("Next");
+        }
+        engine.submit_answer(Answer::Choice(1)).expect("Submit");
+
+        assert_eq!(engine.next_question(), Err(QuizError::NoMoreQuestions));
+        assert!((engine.progress() - 1.0).abs() < f32::EPSILON);
+
+        let score = engine.finish().expect("Finish");
+        assert_eq!(score.total_questions, 3);
+        let ability = score.ability.expect("Ability");
+        assert!(ability.theta < 0.0);
+        let (low, high) = ability.confidence_interval();
+        assert!(low < ability.theta && ability.theta < high);
+    }
+
+    #[test]
+    fn test_adaptive_stops_on_standard_error() {
+        let config = AdaptiveConfig::new(2.0, 9).with_min_length(2);
+        let mut engine = QuizEngine::new(create_adaptive_quiz(config));
+        engine.start().expect("Start");
+        engine.submit_answer(Answer::Choice(0)).expect("Submit");
+        engine.next_question().expect("Next");
+        engine.submit_answer(Answer::Choice(0)).expect("Submit");
+
+        assert_eq!(engine.next_question(), Err(QuizError::NoMoreQuestions));
+    }
+
+    #[test]
+    fn test_adaptive_disallows_previous() {
+        let mut engine = QuizEngine::new(create_adaptive_quiz(AdaptiveConfig::new(0.0, 5)));
+        engine.start().expect("Start");
+        engine.submit_answer(Answer::Choice(0)).expect("Submit");
+        engine.next_question().expect("Next");
+
+        assert_eq!(
+            engine.previous_question(),
+            Err(QuizError::BackNavigationDisabled)
+        );
+    }
+
+    #[test]
+    fn test_outcomes_after_finish() {
+        let mut engine = QuizEngine::new(create_test_quiz());
+        engine.start().expect("Start");
+        engine.submit_answer(Answer::Choice(1)).expect("Submit");
+        assert!(engine.outcomes().is_empty());
+        engine.finish().expect("Finish");
+
+        let outcomes = engine.outcomes();
+        assert_eq!(outcomes.len(), 2);
+        assert!(outcomes[0].correct);
+        assert_eq!(outcomes[0].answer, Some(Answer::Choice(1)));
+        assert!(!outcomes[1].correct);
+        assert_eq!(outcomes[1].answer, None);
+    }
+
+    #[test]
+    fn test_non_adaptive_score_has_no_ability() {
+        let mut engine = QuizEngine::new(create_test_quiz());
+        engine.start().expect("Start");
+        assert!(engine.ability().is_none());
+        assert!(engine.finish().expect("Finish").ability.is_none());
+    }
+
+    #[test]
+    fn test_template_variants_per_learner() {
+        use profesor_core::{QuestionTemplate, TemplateAnswer, Tolerance, Variable};
+
+        let template = QuestionTemplate::new(
+            "product",
+            "What is {a} × {b}?",
+            TemplateAnswer::Numeric {
+                value: "a*b".into(),
+                tolerance: Tolerance::Absolute(0.0),
+                unit: None,
+            },
+            10,
+        )
+        .with_variable(Variable::new("a", 2.0, 99.0))
+        .with_variable(Variable::new("b", 2.0, 99.0));
+        let quiz = Quiz::new("algo", "Algorithmic").with_question(Question::Template(template));
+
+        let mut alice = QuizEngine::new(quiz.clone()).with_seed(1);
+        let mut bob = QuizEngine::new(quiz.clone()).with_seed(2);
+        let alice_question = alice.start().expect("Start").clone();
+        let bob_question = bob.start().expect("Start").clone();
+        assert_ne!(alice_question, bob_question);
+
+        // The stored attempt seed reproduces the same numbers for review.
+        let seed = alice.attempt_seed().expect("Seed");
+        let review = quiz.materialize(seed).expect("Materialize");
+        assert_eq!(review[0], alice_question);
+
+        let value = match alice_question {
+            Question::Numeric { value, .. } => value,
+            _ => f64::NAN,
+        };
+        let feedback = alice
+            .submit_answer(Answer::Numeric(alloc::format!("{}", value)))
+            .expect("Submit");
+        assert!(feedback.correct);
+    }
+
+    #[test]
+    fn test_hinted_template_is_graded() {
+        use profesor_core::{QuestionHint, QuestionTemplate, TemplateAnswer, Tolerance, Variable};
+
+        let template = QuestionTemplate::new(
+            "product",
+            "What is {a} × {b}?",
+            TemplateAnswer::Numeric {
+                value: "a*b".into(),
+                tolerance: Tolerance::Absolute(0.0),
+                unit: None,
+            },
+            10,
+        )
+        .with_variable(Variable::new("a", 2.0, 99.0))
+        .with_variable(Variable::new("b", 2.0, 99.0));
+        let quiz = Quiz::new("algo", "Algorithmic").with_question(
+            Question::Template(template).with_hint(QuestionHint::new("Multiply", 0.5)),
+        );
+
+        let mut engine = QuizEngine::new(quiz).with_seed(3);
+        let value = match engine.start().expect("Start") {
+            Question::Hinted { question, .. } => match question.as_ref() {
+                Question::Numeric { value, .. } => *value,
+                _ => f64::NAN,
+            },
+            _ => f64::NAN,
+        };
+        let feedback = engine
+            .submit_answer(Answer::Numeric(alloc::format!("{}", value)))
+            .expect("Submit");
+        assert!(feedback.correct);
+        assert_eq!(feedback.points_earned, 10);
+    }
+
+    fn round_trip(snapshot: &QuizSnapshot) -> QuizSnapshot {
+        let yaml = serde_yaml::to_string(snapshot).expect("Serialize");
+        serde_yaml::from_str(&yaml).expect("Deserialize")
+    }
+
+    #[test]
+    fn test_snapshot_resume_mid_quiz() {
+        let quiz = create_test_quiz().with_time_limit(600);
+        let mut engine = QuizEngine::new(quiz.clone());
+        engine.start().expect("Start");
+        engine.start_clock(Timestamp::from_millis(1_000));
+        engine.submit_answer(Answer::Choice(1)).expect("Submit");
+        engine.next_question().expect("Next");
+
+        let snapshot = round_trip(&engine.snapshot());
+        assert_eq!(snapshot, engine.snapshot());
+
+        let mut resumed = QuizEngine::resume(quiz, snapshot).expect("Resume");
+        assert_eq!(resumed.state(), engine.state());
+        assert_eq!(resumed.attempt_count(), 1);
+        assert_eq!(resumed.deadline(), Some(Timestamp::from_millis(601_000)));
+        assert!(resumed.is_overtime(Timestamp::from_millis(601_001)));
+        assert_eq!(
+            resumed.current_question().expect("Current").id().as_str(),
+            "q2"
+        );
+
+        resumed.submit_answer(Answer::Choice(1)).expect("Submit");
+        let score = resumed.finish().expect("Finish");
+        assert_eq!(score.points_earned, 20);
+    }
+
+    #[test]
+    fn test_snapshot_resume_drawn_attempt() {
+        let quiz = create_bank_quiz();
+        let mut engine = QuizEngine::new(quiz.clone()).with_seed(11);
+        engine.start().expect("Start");
+
+        let resumed = QuizEngine::resume(quiz, round_trip(&engine.snapshot())).expect("Resume");
+        assert_eq!(resumed.questions(), engine.questions());
+        assert_eq!(resumed.attempt_seed(), engine.attempt_seed());
+    }
+
+    #[test]
+    fn test_snapshot_resume_adaptive() {
+        let quiz = create_adaptive_quiz(AdaptiveConfig::new(0.3, 5));
+        let mut engine = QuizEngine::new(quiz.clone());
+        engine.start().expect("Start");
+        engine.submit_answer(Answer::Choice(0)).expect("Submit");
+
+        let mut resumed = QuizEngine::resume(quiz, round_trip(&engine.snapshot())).expect("Resume");
+        assert_eq!(resumed.ability(), engine.ability());
+        assert_eq!(
+            resumed.next_question().expect("Next").id(),
+            engine.next_question().expect("Next").id()
+        );
+    }
+
+    #[test]
+    fn test_snapshot_before_start() {
+        let quiz = create_test_quiz();
+        let engine = QuizEngine::new(quiz.clone());
+        let resumed = QuizEngine::resume(quiz, engine.snapshot()).expect("Resume");
+        assert_eq!(resumed.state(), &QuizState::NotStarted);
+    }
+
+    #[test]
+    fn test_resume_rejects_changed_quiz() {
+        let mut engine = QuizEngine::new(create_test_quiz());
+        engine.start().expect("Start");
+        let snapshot = engine.snapshot();
+
+        let other = Quiz::new("other", "Other");
+        assert_eq!(
+            QuizEngine::resume(other, snapshot.clone()).err(),
+            Some(ResumeError::QuizMismatch {
+                expected: "other".into(),
+                found: "test-quiz".into(),
+            })
+        );
+
+        let mut repriced = create_test_quiz();
+        if let Question::MultipleChoice { points, .. } = &mut repriced.questions[1] {
+            *points = 15;
+        }
+        assert_eq!(
+            QuizEngine::resume(repriced, snapshot.clone()).err(),
+            Some(ResumeError::PointsChanged {
+                question_id: "q2".into(),
+                expected: 10,
+                found: 15,
+            })
+        );
+
+        let mut extended = create_test_quiz();
+        extended.questions.push(extended.questions[0].clone());
+        if let Question::MultipleChoice { id, .. } = &mut extended.questions[2] {
+            *id = "q3".into();
+        }
+        assert_eq!(
+            QuizEngine::resume(extended, snapshot.clone()).err(),
+            Some(ResumeError::QuestionsChanged)
+        );
+
+        let mut shrunk = create_test_quiz();
+        shrunk.questions.pop();
+        assert_eq!(
+            QuizEngine::resume(shrunk, snapshot.clone()).err(),
+            Some(ResumeError::QuestionsChanged)
+        );
+
+        let future = QuizSnapshot {
+            version: SNAPSHOT_VERSION + 1,
+            ..snapshot
+        };
+        assert_eq!(
+            QuizEngine::resume(create_test_quiz(), future).err(),
+            Some(ResumeError::UnsupportedVersion(SNAPSHOT_VERSION + 1))
+        );
+    }
+
+    #[test]
+    fn test_resume_rejects_inconsistent_state() {
+        let mut engine = QuizEngine::new(create_test_quiz());
+        engine.start().expect("Start");
+        let mut snapshot = engine.snapshot();
+        snapshot.state = QuizState::InProgress {
+            current_question: 5,
+            answers: alloc::vec![None, None],
+            feedback: alloc::vec![None, None],
+            hints_revealed: alloc::vec![0, 0],
+        };
+        assert_eq!(
+            QuizEngine::resume(create_test_quiz(), snapshot).err(),
+            Some(ResumeError::InconsistentState)
+        );
+    }
+
+    #[test]
+    fn test_resume_error_display() {
+        let err = ResumeError::PointsChanged {
+            question_id: "q1".into(),
+            expected: 10,
+            found: 5,
+        };
+        assert_eq!(
+            alloc::format!("{}", err),
+            "Question 'q1' is now worth 5 points instead of 10"
+        );
+    }
+
+    #[test]
+    fn test_audit_log_records_events() {
+        let clock = crate::audit::ManualClock::new(Timestamp::from_millis(1_000));
+        let mut engine = QuizEngine::new(create_test_quiz().with_time_limit(60))
+            .with_clock(Arc::new(clock.clone()));
+        engine.start().expect("Start");
+        assert_eq!(engine.deadline(), Some(Timestamp::from_millis(61_000)));
+
+        clock.advance(3_000);
+        engine.submit_answer(Answer::Choice(0)).expect("Submit");
+        clock.advance(1_000);
+        engine.submit_answer(Answer::Choice(1)).expect("Change");
+        engine.submit_answer(Answer::Choice(1)).expect("Resubmit");
+        clock.advance(1_000);
+        engine.next_question().expect("Next");
+        clock.advance(2_000);
+        assert_eq!(
+            engine.next_question().err(),
+            Some(QuizError::QuestionNotAnswered)
+        );
+        engine.previous_question().expect("Previous");
+        clock.advance(500);
+        engine.finish().expect("Finish");
+
+        let kinds: Vec<&QuizEventKind> =
+            engine.audit_log().events.iter().map(|e| &e.kind).collect();
+        assert_eq!(kinds.len(), 9);
+        assert_eq!(kinds[0], &QuizEventKind::AttemptStarted { attempt: 1 });
+        assert!(matches!(
+            kinds[2],
+            QuizEventKind::AnswerSubmitted { correct: false, .. }
+        ));
+        assert!(matches!(
+            kinds[3],
+            QuizEventKind::AnswerChanged {
+                correct: true,
+                was_correct: false,
+                ..
+            }
+        ));
+        assert!(matches!(
+            kinds[6],
+            QuizEventKind::Navigated {
+                direction: Direction::Previous,
+                ..
+            }
+        ));
+        assert_eq!(kinds[8], &QuizEventKind::Finished { points_earned: 10 });
+        assert_eq!(
+            engine.audit_log().events[8].at,
+            Timestamp::from_millis(8_500)
+        );
+
+        let summary = engine.audit_log().summary();
+        assert_eq!(summary[0].question_id.as_str(), "q1");
+        assert_eq!(summary[0].time_on_task_ms, 5_500);
+        assert_eq!(summary[0].views, 2);
+        assert_eq!(summary[0].wrong_to_right, 1);
+        assert_eq!(summary[1].time_on_task_ms, 2_000);
+    }
+
+    #[test]
+    fn test_answers_after_deadline_rejected() {
+        let clock = crate::audit::ManualClock::new(Timestamp::from_millis(1_000));
+        let mut engine = QuizEngine::new(create_test_quiz().with_time_limit(60))
+            .with_clock(Arc::new(clock.clone()));
+        engine.start().expect("Start");
+
+        clock.set(Timestamp::from_millis(61_000));
+        engine.submit_answer(Answer::Choice(1)).expect("On time");
+        clock.advance(1);
+        assert_eq!(
+            engine.submit_answer(Answer::Choice(0)).err(),
+            Some(QuizError::TimeExpired)
+        );
+        assert!(matches!(
+            engine.state(),
+            QuizState::InProgress { answers, .. } if answers[0] == Some(Answer::Choice(1))
+        ));
+    }
+
+    #[test]
+    fn test_audit_log_survives_snapshot() {
+        let quiz = create_test_quiz();
+        let mut engine = QuizEngine::new(quiz.clone());
+        engine.start().expect("Start");
+        engine.submit_answer(Answer::Choice(1)).expect("Submit");
+
+        let resumed = QuizEngine::resume(quiz, round_trip(&engine.snapshot())).expect("Resume");
+        assert_eq!(resumed.audit_log(), engine.audit_log());
+        assert_eq!(resumed.audit_log().events.len(), 3);
+    }
+
+    #[test]
+    fn test_predict_output_uses_cached_run() {
+        let quiz = Quiz::new("predict", "Predict").with_question(Question::PredictOutput {
+            id: QuestionId::new("p1"),
+            prompt: "What does this print?".into(),
+            language: profesor_core::Language::Rust,
+            code: "fn main() {}".into(),
+            input: alloc::string::String::new(),
+            explanation: "".into(),
+            points: 10,
+        });
+        let mut engine = QuizEngine::new(quiz);
+        assert_eq!(engine.outputs().get("p1"), Some(&Ok("".into())));
+
+        engine.start().expect("Start");
+        let feedback = engine
+            .submit_answer(Answer::Text("  \n".into()))
+            .expect("Submit");
+        assert!(feedback.correct);
+        assert!(feedback.explanation.starts_with("The program prints:"));
+    }
+
+    #[test]
+    fn test_sealed_quiz() {
+        let (sealed, key) = SealedQuiz::seal(&create_test_quiz(), b"salt", [3; 32]).expect("Seal");
+        let mut engine = QuizEngine::sealed(sealed);
+        let q1 = QuestionId::new("q1");
+
+        engine.start().expect("Start");
+        assert_eq!(
+            engine.unlock_explanation(&key.release(&q1)),
+            Err(QuizError::QuestionNotAnswered)
+        );
+        let feedback = engine.submit_answer(Answer::Choice(1)).expect("Submit");
+        assert!(feedback.correct);
+        assert_eq!(feedback.points_earned, 10);
+        assert!(feedback.explanation.is_empty());
+        assert_eq!(
+            engine.unlock_explanation(&key.release(&q1)).as_deref(),
+            Ok("2+2=4")
+        );
+        assert_eq!(
+            engine.unlock_explanation(&key.release(&QuestionId::new("q2"))),
+            Err(QuizError::WrongKey)
+        );
+
+        engine.next_question().expect("Next");
+        assert!(
+            !engine
+                .submit_answer(Answer::Choice(0))
+                .expect("Submit")
+                .correct
+        );
+        let score = engine.finish().expect("Finish");
+        assert_eq!(score.points_earned, 10);
+
+        let review = engine.start_review().expect("Review");
+        assert!(review.feedback.correct);
+        assert_eq!(review.correct_answer, None);
+        assert!(!engine.answers_revealed());
+    }
+
+    #[test]
+    fn test_submission_envelope_regrades() {
+        let quiz = create_test_quiz().with_question(
+            Question::MultipleChoice {
+                id: QuestionId::new("q3"),
+                prompt: "What is 1+1?".into(),
+                options: alloc::vec!["2".into(), "3".into()],
+                correct: 0,
+                explanation: "".into(),
+                points: 10,
+                option_explanations: Vec::new(),
+            }
+            .with_hint(QuestionHint::new("Count", 0.5)),
+        );
+        let clock = crate::audit::ManualClock::new(Timestamp::from_millis(1_000));
+        let mut engine = QuizEngine::new(quiz.clone()).with_clock(Arc::new(clock.clone()));
+        assert_eq!(engine.submission(b"key"), Err(QuizError::InvalidState));
+
+        let grant = |attempt| AttemptGrant::issue(&quiz.id, attempt, b"server").expect("Grant");
+        assert_eq!(
+            engine.start_with_grant(grant(2)).err(),
+            Some(QuizError::InvalidGrant)
+        );
+        engine.start_with_grant(grant(1)).expect("Start");
+        clock.advance(5_000);
+        engine.submit_answer(Answer::Choice(1)).expect("Submit");
+        engine.next_question().expect("Next");
+        engine.submit_answer(Answer::Choice(0)).expect("Submit");
+        engine.next_question().expect("Next");
+        engine.reveal_hint().expect("Hint");
+        engine.submit_answer(Answer::Choice(0)).expect("Submit");
+        let score = engine.finish().expect("Finish");
+
+        let envelope = engine.submission(b"key").expect("Envelope");
+        let submission = envelope.verify(b"key").expect("Verify");
+        assert_eq!(submission.attempt_number, 1);
+        assert_eq!(submission.started_at, Timestamp::from_millis(1_000));
+        assert_eq!(submission.answers[0].time_on_task_ms, 5_000);
+        assert_eq!(submission.answers[1].answer, Some(Answer::Choice(0)));
+        assert_eq!(submission.answers[2].hints_revealed, 1);
+
+        let signed = crate::grade_submission(&quiz, &envelope, b"key", b"server").expect("Grade");
+        assert_eq!(signed.verify(b"server").expect("Verify").score, score);
+
+        // Attempts drawn with a seed the grader did not issue cannot be submitted.
+        engine.start().expect("Start");
+        engine.finish().expect("Finish");
+        assert_eq!(engine.submission(b"key"), Err(QuizError::InvalidGrant));
+    }
+
+    #[test]
+    fn test_adaptive_submission_regrades() {
+        let quiz = create_adaptive_quiz(AdaptiveConfig::new(0.0, 4).with_min_length(2));
+        let mut engine = QuizEngine::new(quiz.clone());
+        engine
+            .start_with_grant(AttemptGrant::issue(&quiz.id, 1, b"server").expect("Grant"))
+            .expect("Start");
+        let mut choice = 0;
+        loop {
+            engine
+                .submit_answer(Answer::Choice(choice))
+                .expect("Submit");
+            choice = 1 - choice;
+            if engine.next_question().is_err() {
+                break;
+            }
+        }
+        let score = engine.finish().expect("Finish");
+        assert_eq!(score.total_questions, 4);
+
+        let envelope = engine.submission(b"key").expect("Envelope");
+        let signed = crate::grade_submission(&quiz, &envelope, b"key", b"server").expect("Grade");
+        assert_eq!(signed.verify(b"server").expect("Verify").score, score);
+    }
 }
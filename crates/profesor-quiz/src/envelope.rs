@@ -0,0 +1,703 @@
+//! Signed submission envelopes.
+//!
+//! For high-stakes quizzes the browser's grade is not trusted. Before an
+//! attempt, the grader issues an [`AttemptGrant`] carrying the seed the
+//! attempt's questions are drawn with, and the engine starts from it with
+//! [`QuizEngine::start_with_grant`](crate::QuizEngine::start_with_grant).
+//! After the attempt, [`QuizEngine::submission`](crate::QuizEngine::submission)
+//! packs the grant, answers, timings and hint counts into a
+//! [`SubmissionEnvelope`]. A grading process that holds the quiz checks the
+//! envelope with [`grade_submission`], regrades every answer with the same
+//! [`Grader`] and returns a [`SignedScore`] signed with its own key.
+//!
+//! Every [`Signed`] value carries the exact JSON text its HMAC-SHA256 covers,
+//! so verification never depends on encoding the value again.
+//! [`ENVELOPE_VERSION`] changes whenever the format does.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use hmac::{Hmac, Mac};
+use profesor_core::progress::Timestamp;
+use profesor_core::{
+    Answer, DrawError, Feedback, ItemParameters, Question, QuestionId, Quiz, QuizId, Score,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::adaptive;
+use crate::grader::Grader;
+use crate::output::OutputCache;
+use crate::sealed::to_hex;
+
+/// Current submission envelope format version.
+pub const ENVELOPE_VERSION: u32 = 2;
+
+/// A value with an HMAC-SHA256 signature over its JSON encoding.
+///
+/// The payload is kept as the exact text that was signed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Signed<T> {
+    /// JSON encoding of the value
+    pub payload: String,
+    /// Hex-encoded HMAC-SHA256 of the payload
+    pub signature: String,
+    #[serde(skip)]
+    value: PhantomData<T>,
+}
+
+/// A submission signed by the client.
+pub type SubmissionEnvelope = Signed<Submission>;
+
+/// An attempt grant signed by the grader.
+pub type SignedGrant = Signed<AttemptGrant>;
+
+/// A graded submission signed by the grader.
+pub type SignedScore = Signed<GradedSubmission>;
+
+/// The seed an attempt must be drawn with, issued by the grader.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttemptGrant {
+    /// Quiz the attempt is for
+    pub quiz_id: QuizId,
+    /// Attempt number (1-based)
+    pub attempt_number: u32,
+    /// Seed the attempt's questions are drawn with
+    pub seed: u64,
+}
+
+/// One attempt's answers, as sent for grading.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Submission {
+    /// Envelope format version
+    pub version: u32,
+    /// Quiz answered
+    pub quiz_id: QuizId,
+    /// Hash of the quiz content the attempt was taken against
+    pub quiz_version: String,
+    /// Attempt number (1-based)
+    pub attempt_number: u32,
+    /// Grant the attempt was started from
+    pub grant: SignedGrant,
+    /// When the attempt started
+    pub started_at: Timestamp,
+    /// When the attempt finished
+    pub finished_at: Option<Timestamp>,
+    /// Answers in the order the questions were presented
+    pub answers: Vec<SubmittedAnswer>,
+}
+
+/// One question's answer and timing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubmittedAnswer {
+    /// Question answered
+    pub question_id: QuestionId,
+    /// The answer, if one was given
+    pub answer: Option<Answer>,
+    /// Time the question was on screen, in milliseconds
+    pub time_on_task_ms: u64,
+    /// Hints revealed
+    pub hints_revealed: usize,
+}
+
+/// The result of grading a submission.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GradedSubmission {
+    /// Quiz graded
+    pub quiz_id: QuizId,
+    /// Attempt number (1-based)
+    pub attempt_number: u32,
+    /// Signature of the graded envelope
+    pub submission_signature: String,
+    /// Score awarded by the grader
+    pub score: Score,
+}
+
+/// Reasons a submission or score is rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnvelopeError {
+    /// The envelope could not be parsed
+    Malformed(String),
+    /// The value could not be encoded
+    Encode(String),
+    /// The envelope uses a format this version does not read
+    UnsupportedVersion(u32),
+    /// The signature does not match the contents
+    BadSignature,
+    /// The submission is for a different quiz
+    QuizMismatch {
+        /// Quiz the grader holds
+        expected: QuizId,
+        /// Quiz in the submission
+        found: QuizId,
+    },
+    /// The quiz has changed since the attempt
+    QuizChanged,
+    /// The attempt grant is for a different quiz or attempt
+    GrantMismatch,
+    /// The attempt's questions could not be drawn again
+    DrawFailed(DrawError),
+    /// The answered questions are not the attempt's questions
+    QuestionsMismatch,
+    /// More hints were reported than the question has
+    TooManyHints(QuestionId),
+    /// The adaptive attempt ended before its stopping rule was met
+    AttemptIncomplete,
+}
+
+impl core::fmt::Display for EnvelopeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Malformed(err) => write!(f, "Malformed envelope: {}", err),
+            Self::Encode(err) => write!(f, "Could not encode: {}", err),
+            Self::UnsupportedVersion(v) => write!(f, "Unsupported envelope version {}", v),
+            Self::BadSignature => write!(f, "Signature does not match"),
+            Self::QuizMismatch { expected, found } => {
+                write!(f, "Submission is for quiz '{}', not '{}'", found, expected)
+            }
+            Self::QuizChanged => write!(f, "Quiz has changed since the attempt"),
+            Self::GrantMismatch => write!(f, "Attempt grant does not match the submission"),
+            Self::DrawFailed(err) => write!(f, "Could not draw questions: {}", err),
+            Self::QuestionsMismatch => write!(f, "Answers do not match the attempt's questions"),
+            Self::TooManyHints(id) => write!(f, "More hints reported for '{}' than it has", id),
+            Self::AttemptIncomplete => write!(f, "Attempt ended before it was complete"),
+        }
+    }
+}
+
+/// Hash a quiz's content, so graders can reject attempts on an older version.
+pub fn quiz_version(quiz: &Quiz) -> Result<String, EnvelopeError> {
+    let bytes =
+        serde_json::to_vec(quiz).map_err(|e| EnvelopeError::Encode(alloc::format!("{}", e)))?;
+    Ok(to_hex(&Sha256::digest(bytes)))
+}
+
+impl<T: Serialize + DeserializeOwned> Signed<T> {
+    /// Encode and sign a value.
+    pub fn sign(value: &T, key: &[u8]) -> Result<Self, EnvelopeError> {
+        let payload = serde_json::to_string(value)
+            .map_err(|e| EnvelopeError::Encode(alloc::format!("{}", e)))?;
+        let signature = to_hex(&mac(&payload, key)?.finalize().into_bytes());
+        Ok(Self {
+            payload,
+            signature,
+            value: PhantomData,
+        })
+    }
+
+    /// Check the signature in constant time, returning the decoded value.
+    pub fn verify(&self, key: &[u8]) -> Result<T, EnvelopeError> {
+        let bytes = from_hex(&self.signature).ok_or(EnvelopeError::BadSignature)?;
+        mac(&self.payload, key)?
+            .verify_slice(&bytes)
+            .map_err(|_| EnvelopeError::BadSignature)?;
+        self.unverified()
+    }
+
+    /// Decode the value without checking the signature.
+    ///
+    /// For the side that does not hold the key, such as the engine reading
+    /// the seed from a grant.
+    pub fn unverified(&self) -> Result<T, EnvelopeError> {
+        serde_json::from_str(&self.payload)
+            .map_err(|e| EnvelopeError::Malformed(alloc::format!("{}", e)))
+    }
+
+    /// Encode as JSON.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// Decode from JSON.
+    pub fn from_json(json: &str) -> Result<Self, EnvelopeError> {
+        serde_json::from_str(json).map_err(|e| EnvelopeError::Malformed(alloc::format!("{}", e)))
+    }
+}
+
+impl AttemptGrant {
+    /// Issue a signed grant for an attempt.
+    ///
+    /// The seed is derived from `key`, so the learner cannot predict or pick
+    /// it, and asking again for the same attempt returns the same seed.
+    pub fn issue(
+        quiz_id: &QuizId,
+        attempt_number: u32,
+        key: &[u8],
+    ) -> Result<SignedGrant, EnvelopeError> {
+        let label = alloc::format!("grant/{}/{}", quiz_id, attempt_number);
+        let digest = mac(&label, key)?.finalize().into_bytes();
+        let mut seed = [0u8; 8];
+        seed.copy_from_slice(&digest[..8]);
+        let grant = Self {
+            quiz_id: quiz_id.clone(),
+            attempt_number,
+            seed: u64::from_le_bytes(seed),
+        };
+        Signed::sign(&grant, key)
+    }
+}
+
+/// Verify a submission and grade it against the quiz.
+///
+/// The grant must carry `score_key`'s signature; the attempt's questions are
+/// drawn again from its seed, and adaptive item selection is replayed from
+/// the answers, so the grader needs only the quiz and the envelope. Hint deductions are recomputed from the quiz. The result is
+/// signed with `score_key`, which should be known only to the grader and
+/// the host.
+pub fn grade_submission(
+    quiz: &Quiz,
+    envelope: &SubmissionEnvelope,
+    submission_key: &[u8],
+    score_key: &[u8],
+) -> Result<SignedScore, EnvelopeError> {
+    let submission = envelope.verify(submission_key)?;
+    if submission.version != ENVELOPE_VERSION {
+        return Err(EnvelopeError::UnsupportedVersion(submission.version));
+    }
+    if submission.quiz_id != quiz.id {
+        return Err(EnvelopeError::QuizMismatch {
+            expected: quiz.id.clone(),
+            found: submission.quiz_id,
+        });
+    }
+    if submission.quiz_version != quiz_version(quiz)? {
+        return Err(EnvelopeError::QuizChanged);
+    }
+    let grant = submission.grant.verify(score_key)?;
+    if grant.quiz_id != submission.quiz_id || grant.attempt_number != submission.attempt_number {
+        return Err(EnvelopeError::GrantMismatch);
+    }
+
+    let outputs = OutputCache::for_quiz(quiz);
+    let questions = presented_questions(quiz, &submission, grant.seed, &outputs)?;
+    if let Some((_, submitted)) = questions
+        .iter()
+        .zip(&submission.answers)
+        .find(|(question, submitted)| submitted.hints_revealed > question.hints().len())
+    {
+        return Err(EnvelopeError::TooManyHints(submitted.question_id.clone()));
+    }
+
+    let feedback: Vec<Feedback> = questions
+        .iter()
+        .zip(&submission.answers)
+        .map(|(question, submitted)| match &submitted.answer {
+            Some(answer) => {
+                Grader::grade_with_payoff(question, answer, &outputs, &quiz.confidence_payoff)
+                    .with_hint_deduction(question.hint_deduction(submitted.hints_revealed))
+            }
+            None => Feedback::incorrect("Not answered"),
+        })
+        .collect();
+
+    let mut score = Grader::score_questions(&questions, quiz.passing_score, &feedback);
+    if let Some(config) = &quiz.adaptive {
+        let responses: Vec<(ItemParameters, bool)> = questions
+            .iter()
+            .zip(&submission.answers)
+            .zip(&feedback)
+            .filter(|((_, submitted), _)| submitted.answer.is_some())
+            .map(|((question, _), fb)| (config.parameters(question.id()), fb.correct))
+            .collect();
+        score = score.with_ability(adaptive::estimate_ability(&responses));
+    }
+
+    let graded = GradedSubmission {
+        quiz_id: submission.quiz_id,
+        attempt_number: submission.attempt_number,
+        submission_signature: envelope.signature.clone(),
+        score,
+    };
+    Signed::sign(&graded, score_key)
+}
+
+/// Draw the attempt's questions again, in the order they were answered.
+///
+/// Fixed-length quizzes must answer every drawn question in order. Adaptive
+/// quizzes are replayed: each item must be the one the engine would select
+/// from the answers so far, and the attempt must run until it stops.
+fn presented_questions(
+    quiz: &Quiz,
+    submission: &Submission,
+    seed: u64,
+    outputs: &OutputCache,
+) -> Result<Vec<Question>, EnvelopeError> {
+    let mut pool = quiz.materialize(seed).map_err(EnvelopeError::DrawFailed)?;
+    let Some(config) = &quiz.adaptive else {
+        let ids = submission.answers.iter().map(|a| &a.question_id);
+        if !pool.iter().map(Question::id).eq(ids) {
+            return Err(EnvelopeError::QuestionsMismatch);
+        }
+        return Ok(pool);
+    };
+
+    let mut responses: Vec<(ItemParameters, bool)> = Vec::new();
+    let mut estimate = adaptive::estimate_ability(&responses);
+    let should_stop = |answered: usize, pool: &[Question], se: f64| {
+        answered >= config.max_length
+            || pool.is_empty()
+            || (answered >= config.min_length && se < config.se_threshold)
+    };
+
+    let mut questions: Vec<Question> = Vec::new();
+    for submitted in &submission.answers {
+        // The engine only moves on from an answered item, and not past its stopping rule.
+        if !questions.is_empty()
+            && (responses.len() < questions.len()
+                || should_stop(responses.len(), &pool, estimate.standard_error))
+        {
+            return Err(EnvelopeError::QuestionsMismatch);
+        }
+        let params: Vec<ItemParameters> = pool.iter().map(|q| config.parameters(q.id())).collect();
+        let index = adaptive::select_item(&params, estimate.theta)
+            .filter(|&i| pool.get(i).map(Question::id) == Some(&submitted.question_id))
+            .ok_or(EnvelopeError::QuestionsMismatch)?;
+        let question = pool.remove(index);
+        if let Some(answer) = &submitted.answer {
+            let correct =
+                Grader::grade_with_payoff(&question, answer, outputs, &quiz.confidence_payoff)
+                    .correct;
+            responses.push((params[index], correct));
+            estimate = adaptive::estimate_ability(&responses);
+        }
+        questions.push(question);
+    }
+
+    if !should_stop(responses.len(), &pool, estimate.standard_error) {
+        return Err(EnvelopeError::AttemptIncomplete);
+    }
+    Ok(questions)
+}
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// HMAC a payload's bytes.
+fn mac(payload: &str, key: &[u8]) -> Result<HmacSha256, EnvelopeError> {
+    let mut mac = HmacSha256::new_from_slice(key).map_err(|_| EnvelopeError::BadSignature)?;
+    mac.update(payload.as_bytes());
+    Ok(mac)
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use profesor_core::{AdaptiveConfig, QuestionHint};
+
+    const KEY: &[u8] = b"submission-key";
+    const SCORE_KEY: &[u8] = b"score-key";
+
+    fn quiz() -> Quiz {
+        Quiz::new("exam", "Exam")
+            .with_question(Question::MultipleChoice {
+                id: "q1".into(),
+                prompt: "2+2?".into(),
+                options: alloc::vec!["3".into(), "4".into()],
+                correct: 1,
+                explanation: "".into(),
+                points: 10,
+                option_explanations: Vec::new(),
+            })
+            .with_question(Question::MultipleChoice {
+                id: "q2".into(),
+                prompt: "3+3?".into(),
+                options: alloc::vec!["6".into(), "7".into()],
+                correct: 0,
+                explanation: "".into(),
+                points: 10,
+                option_explanations: Vec::new(),
+            })
+    }
+
+    fn answer(id: &str, choice: usize) -> SubmittedAnswer {
+        SubmittedAnswer {
+            question_id: id.into(),
+            answer: Some(Answer::Choice(choice)),
+            time_on_task_ms: 1000,
+            hints_revealed: 0,
+        }
+    }
+
+    fn submission(quiz: &Quiz, answers: Vec<SubmittedAnswer>) -> Submission {
+        Submission {
+            version: ENVELOPE_VERSION,
+            quiz_id: quiz.id.clone(),
+            quiz_version: quiz_version(quiz).unwrap(),
+            attempt_number: 1,
+            grant: AttemptGrant::issue(&quiz.id, 1, SCORE_KEY).unwrap(),
+            started_at: Timestamp::ZERO,
+            finished_at: None,
+            answers,
+        }
+    }
+
+    fn sign_submission(submission: &Submission) -> SubmissionEnvelope {
+        SubmissionEnvelope::sign(submission, KEY).unwrap()
+    }
+
+    #[test]
+    fn test_grade_submission() {
+        let quiz = quiz();
+        let envelope = sign_submission(&submission(
+            &quiz,
+            alloc::vec![answer("q1", 1), answer("q2", 1)],
+        ));
+        let signed = grade_submission(&quiz, &envelope, KEY, SCORE_KEY).unwrap();
+        let graded = signed.verify(SCORE_KEY).unwrap();
+        assert_eq!(graded.score.points_earned, 10);
+        assert_eq!(graded.score.points_possible, 20);
+        assert_eq!(graded.submission_signature, envelope.signature);
+        assert_eq!(signed.verify(KEY), Err(EnvelopeError::BadSignature));
+    }
+
+    #[test]
+    fn test_tampering_is_rejected() {
+        let quiz = quiz();
+        let mut envelope = sign_submission(&submission(
+            &quiz,
+            alloc::vec![answer("q1", 0), answer("q2", 1)],
+        ));
+        assert!(envelope.verify(KEY).is_ok());
+        assert_eq!(envelope.verify(b"other"), Err(EnvelopeError::BadSignature));
+
+        envelope.payload = envelope
+            .payload
+            .replacen(r#""Choice":0"#, r#""Choice":1"#, 1);
+        assert_eq!(
+            grade_submission(&quiz, &envelope, KEY, SCORE_KEY),
+            Err(EnvelopeError::BadSignature)
+        );
+
+        let mut signed = grade_submission(
+            &quiz,
+            &sign_submission(&submission(
+                &quiz,
+                alloc::vec![answer("q1", 1), answer("q2", 0)],
+            )),
+            KEY,
+            SCORE_KEY,
+        )
+        .unwrap();
+        signed.payload = signed
+            .payload
+            .replace(r#""points_earned":20"#, r#""points_earned":0"#);
+        assert_eq!(signed.verify(SCORE_KEY), Err(EnvelopeError::BadSignature));
+    }
+
+    #[test]
+    fn test_signature_covers_payload_bytes() {
+        let quiz = quiz();
+        let mut envelope = sign_submission(&submission(&quiz, alloc::vec![answer("q1", 1)]));
+        // Same value, different bytes: still not what was signed.
+        envelope.payload.push(' ');
+        assert_eq!(envelope.verify(KEY), Err(EnvelopeError::BadSignature));
+    }
+
+    #[test]
+    fn test_grant_is_checked() {
+        let quiz = quiz();
+        let answers = alloc::vec![answer("q1", 1), answer("q2", 0)];
+
+        // A seed the learner picked is not signed by the grader.
+        let mut forged = submission(&quiz, answers.clone());
+        let grant = AttemptGrant {
+            quiz_id: quiz.id.clone(),
+            attempt_number: 1,
+            seed: 42,
+        };
+        forged.grant = Signed::sign(&grant, KEY).unwrap();
+        assert_eq!(
+            grade_submission(&quiz, &sign_submission(&forged), KEY, SCORE_KEY),
+            Err(EnvelopeError::BadSignature)
+        );
+
+        // A real grant for another attempt is not reusable.
+        let mut reused = submission(&quiz, answers);
+        reused.attempt_number = 2;
+        assert_eq!(
+            grade_submission(&quiz, &sign_submission(&reused), KEY, SCORE_KEY),
+            Err(EnvelopeError::GrantMismatch)
+        );
+    }
+
+    #[test]
+    fn test_issued_seed_depends_on_key_and_attempt() {
+        let id = QuizId::new("exam");
+        let seed = |attempt, key| {
+            AttemptGrant::issue(&id, attempt, key)
+                .unwrap()
+                .verify(key)
+                .unwrap()
+                .seed
+        };
+        assert_eq!(seed(1, SCORE_KEY), seed(1, SCORE_KEY));
+        assert_ne!(seed(1, SCORE_KEY), seed(2, SCORE_KEY));
+        assert_ne!(seed(1, SCORE_KEY), seed(1, KEY));
+    }
+
+    #[test]
+    fn test_hints_are_checked_and_recomputed() {
+        let quiz = Quiz::new("exam", "Exam").with_question(
+            Question::MultipleChoice {
+                id: "q1".into(),
+                prompt: "2+2?".into(),
+                options: alloc::vec!["3".into(), "4".into()],
+                correct: 1,
+                explanation: "".into(),
+                points: 10,
+                option_explanations: Vec::new(),
+            }
+            .with_hint(QuestionHint::new("Count", 0.5)),
+        );
+        let mut hinted = answer("q1", 1);
+        hinted.hints_revealed = 1;
+        let signed = grade_submission(
+            &quiz,
+            &sign_submission(&submission(&quiz, alloc::vec![hinted.clone()])),
+            KEY,
+            SCORE_KEY,
+        )
+        .unwrap();
+        assert_eq!(signed.verify(SCORE_KEY).unwrap().score.points_earned, 5);
+
+        hinted.hints_revealed = 2;
+        assert_eq!(
+            grade_submission(
+                &quiz,
+                &sign_submission(&submission(&quiz, alloc::vec![hinted])),
+                KEY,
+                SCORE_KEY
+            ),
+            Err(EnvelopeError::TooManyHints("q1".into()))
+        );
+    }
+
+    #[test]
+    fn test_rejects_other_quiz_or_questions() {
+        let quiz = quiz();
+        let changed = quiz.clone().with_passing_score(0.9);
+        let envelope = sign_submission(&submission(
+            &quiz,
+            alloc::vec![answer("q1", 1), answer("q2", 0)],
+        ));
+        assert_eq!(
+            grade_submission(&changed, &envelope, KEY, SCORE_KEY),
+            Err(EnvelopeError::QuizChanged)
+        );
+
+        let reordered = sign_submission(&submission(
+            &quiz,
+            alloc::vec![answer("q2", 0), answer("q1", 1)],
+        ));
+        assert_eq!(
+            grade_submission(&quiz, &reordered, KEY, SCORE_KEY),
+            Err(EnvelopeError::QuestionsMismatch)
+        );
+
+        let mut old = submission(&quiz, Vec::new());
+        old.version = 0;
+        assert_eq!(
+            grade_submission(&quiz, &sign_submission(&old), KEY, SCORE_KEY),
+            Err(EnvelopeError::UnsupportedVersion(0))
+        );
+    }
+
+    #[test]
+    fn test_adaptive_items_are_replayed() {
+        let mut config = AdaptiveConfig::new(0.0, 3);
+        let mut quiz = Quiz::new("placement", "Placement");
+        for i in 0..5 {
+            let id = alloc::format!("item-{}", i);
+            quiz = quiz.with_question(Question::MultipleChoice {
+                id: id.as_str().into(),
+                prompt: "Pick A".into(),
+                options: alloc::vec!["A".into(), "B".into()],
+                correct: 0,
+                explanation: "".into(),
+                points: 10,
+                option_explanations: Vec::new(),
+            });
+            config = config.with_item(id, ItemParameters::new(1.5, i as f64 - 2.0, 0.0));
+        }
+        let quiz = quiz.with_adaptive(config);
+        let grade = |answers| {
+            grade_submission(
+                &quiz,
+                &sign_submission(&submission(&quiz, answers)),
+                KEY,
+                SCORE_KEY,
+            )
+        };
+
+        // Cherry-picking the easiest item is not the attempt the engine would run.
+        assert_eq!(
+            grade(alloc::vec![answer("item-0", 0)]),
+            Err(EnvelopeError::QuestionsMismatch)
+        );
+        // Stopping early or carrying on past the maximum length is rejected.
+        assert_eq!(
+            grade(alloc::vec![answer("item-2", 0)]),
+            Err(EnvelopeError::AttemptIncomplete)
+        );
+        assert_eq!(
+            grade(alloc::vec![
+                answer("item-2", 0),
+                answer("item-3", 0),
+                answer("item-4", 0),
+                answer("item-1", 0),
+            ]),
+            Err(EnvelopeError::QuestionsMismatch)
+        );
+
+        let signed = grade(alloc::vec![
+            answer("item-2", 0),
+            answer("item-3", 0),
+            answer("item-4", 0),
+        ])
+        .unwrap();
+        let score = signed.verify(SCORE_KEY).unwrap().score;
+        assert_eq!(score.correct_count, 3);
+        assert!(score.ability.is_some_and(|a| a.theta > 0.0));
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let quiz = quiz();
+        let envelope = sign_submission(&submission(
+            &quiz,
+            alloc::vec![answer("q1", 1), answer("q2", 0)],
+        ));
+        let decoded = SubmissionEnvelope::from_json(&envelope.to_json()).unwrap();
+        assert_eq!(decoded, envelope);
+        assert!(decoded.verify(KEY).is_ok());
+        assert!(matches!(
+            SubmissionEnvelope::from_json("{"),
+            Err(EnvelopeError::Malformed(_))
+        ));
+    }
+}
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_hex_round_trip(bytes in proptest::collection::vec(any::<u8>(), 0..64)) {
+            prop_assert_eq!(from_hex(&to_hex(&bytes)), Some(bytes));
+        }
+    }
+}
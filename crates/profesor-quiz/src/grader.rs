@@ -2,15 +2,37 @@
 //!
 //! Provides automatic grading for all question types.
 
-use profesor_core::{Answer, Feedback, Question, Quiz, Score};
+use alloc::string::String;
+use alloc::vec::Vec;
+use profesor_core::{
+    Answer, Calibration, Feedback, HotspotClick, HotspotRegion, PayoffMatrix, Question, Quiz,
+    Score, TextAnswer, Tolerance,
+};
+
+use crate::matching::matches_text;
+use crate::output::{outputs_match, run_snippet, OutputCache};
+use crate::parsons::ParsonsProblem;
+use crate::sealed::SealedQuiz;
+use crate::units::{self, UnitError};
 
 /// Auto-grader for quizzes.
 pub struct Grader;
 
 impl Grader {
     /// Grade a single answer against a question.
+    ///
+    /// Answers with a confidence level are marked with the default payoff
+    /// matrix. Hint deductions are left to the engine.
     #[must_use]
     pub fn grade_answer(question: &Question, answer: &Answer) -> Feedback {
+        if let Question::Hinted { question, .. } = question {
+            return Self::grade_answer(question, answer);
+        }
+        if let Some(confidence) = answer.confidence() {
+            return Self::grade_answer(question, answer.response())
+                .with_confidence(confidence, &PayoffMatrix::default());
+        }
+
         let (correct, explanation, points) = match (question, answer) {
             (
                 Question::MultipleChoice {
@@ -35,8 +57,9 @@ impl Grader {
                 let mut sorted_answer = indices.clone();
                 sorted_correct.sort_unstable();
                 sorted_answer.sort_unstable();
+                let repeated = sorted_answer.windows(2).any(|pair| pair[0] == pair[1]);
                 (
-                    sorted_correct == sorted_answer,
+                    !repeated && sorted_correct == sorted_answer,
                     explanation.clone(),
                     *points,
                 )
@@ -55,6 +78,7 @@ impl Grader {
             (
                 Question::Matching {
                     correct_pairs,
+                    explanation,
                     points,
                     ..
                 },
@@ -66,19 +90,27 @@ impl Grader {
                 sorted_answer.sort_unstable();
                 (
                     sorted_correct == sorted_answer,
-                    alloc::string::String::new(),
+                    explanation.clone(),
                     *points,
                 )
             }
 
-            (Question::CodeCompletion { blanks, points, .. }, Answer::Blanks(filled)) => {
+            (
+                Question::CodeCompletion {
+                    blanks,
+                    explanation,
+                    points,
+                    ..
+                },
+                Answer::Blanks(filled),
+            ) => {
                 let all_correct = blanks.iter().all(|blank| {
                     filled
                         .iter()
                         .find(|(id, _)| id == &blank.id)
                         .is_some_and(|(_, ans)| blank.is_acceptable(ans))
                 });
-                (all_correct, alloc::string::String::new(), *points)
+                (all_correct, explanation.clone(), *points)
             }
 
             // FreeformCode requires execution - return pending for now
@@ -87,6 +119,108 @@ impl Grader {
                 (false, "Code execution required".into(), *points)
             }
 
+            (
+                Question::Numeric {
+                    value,
+                    tolerance,
+                    sig_figs,
+                    unit,
+                    explanation,
+                    points,
+                    ..
+                },
+                Answer::Numeric(text),
+            ) => {
+                return Self::grade_numeric(
+                    *value,
+                    *tolerance,
+                    *sig_figs,
+                    unit.as_deref(),
+                    explanation,
+                    *points,
+                    text,
+                )
+            }
+
+            (
+                Question::ShortAnswer {
+                    answers,
+                    explanation,
+                    points,
+                    ..
+                },
+                Answer::Text(text),
+            ) => return Self::grade_short_answer(answers, explanation, *points, text),
+
+            (
+                Question::Parsons {
+                    language,
+                    fragments,
+                    valid_orderings,
+                    test_cases,
+                    explanation,
+                    points,
+                    ..
+                },
+                Answer::Arrangement(arrangement),
+            ) => {
+                let problem = ParsonsProblem {
+                    language: *language,
+                    fragments,
+                    valid_orderings,
+                    test_cases,
+                    explanation,
+                    points: *points,
+                };
+                return problem.grade(arrangement);
+            }
+
+            (
+                Question::PredictOutput {
+                    language,
+                    code,
+                    input,
+                    explanation,
+                    points,
+                    ..
+                },
+                Answer::Text(text),
+            ) => {
+                let actual = run_snippet(*language, code, input);
+                return Self::grade_predicted_output(&actual, explanation, *points, text);
+            }
+
+            (
+                Question::Categorize {
+                    buckets,
+                    items,
+                    correct,
+                    explanation,
+                    points,
+                    ..
+                },
+                Answer::Categories(placements),
+            ) => {
+                return Self::grade_categorize(
+                    buckets,
+                    items,
+                    correct,
+                    explanation,
+                    *points,
+                    placements,
+                )
+            }
+
+            (
+                Question::Hotspot {
+                    regions,
+                    explanation,
+                    points,
+                    ..
+                },
+                Answer::Hotspots(clicks),
+            ) => return Self::grade_hotspot(regions, explanation, *points, clicks),
+
             // Type mismatch
             _ => (false, "Invalid answer type".into(), 0),
         };
@@ -98,21 +232,303 @@ impl Grader {
         }
     }
 
+    /// Grade an answer, using cached sandbox output for `PredictOutput` questions.
+    ///
+    /// Snippets missing from the cache are run on demand.
+    #[must_use]
+    pub fn grade_with_outputs(
+        question: &Question,
+        answer: &Answer,
+        outputs: &OutputCache,
+    ) -> Feedback {
+        Self::grade_with_payoff(question, answer, outputs, &PayoffMatrix::default())
+    }
+
+    /// Grade an answer, marking any confidence level with `payoff`.
+    #[must_use]
+    pub fn grade_with_payoff(
+        question: &Question,
+        answer: &Answer,
+        outputs: &OutputCache,
+        payoff: &PayoffMatrix,
+    ) -> Feedback {
+        if let Question::Hinted { question, .. } = question {
+            return Self::grade_with_payoff(question, answer, outputs, payoff);
+        }
+        if let Some(confidence) = answer.confidence() {
+            return Self::grade_with_payoff(question, answer.response(), outputs, payoff)
+                .with_confidence(confidence, payoff);
+        }
+
+        match (question, answer) {
+            (
+                Question::PredictOutput {
+                    id,
+                    explanation,
+                    points,
+                    ..
+                },
+                Answer::Text(text),
+            ) => match outputs.get(id.as_str()) {
+                Some(actual) => Self::grade_predicted_output(actual, explanation, *points, text),
+                None => Self::grade_answer(question, answer),
+            },
+            _ => Self::grade_answer(question, answer),
+        }
+    }
+
+    /// Grade an answer against a sealed quiz's answer hashes.
+    ///
+    /// Sealed answers are all-or-nothing and the feedback carries no
+    /// explanation; explanations are unlocked separately once released.
+    #[must_use]
+    pub fn grade_sealed(
+        sealed: &SealedQuiz,
+        question: &Question,
+        answer: &Answer,
+        payoff: &PayoffMatrix,
+    ) -> Feedback {
+        let feedback = if sealed.check(question.id(), answer) {
+            Feedback::correct(String::new(), question.points())
+        } else {
+            Feedback::incorrect(String::new())
+        };
+        match answer.confidence() {
+            Some(confidence) => feedback.with_confidence(confidence, payoff),
+            None => feedback,
+        }
+    }
+
+    /// Grade a predicted output against what the snippet actually printed.
+    fn grade_predicted_output(
+        actual: &Result<String, String>,
+        explanation: &str,
+        points: u32,
+        text: &str,
+    ) -> Feedback {
+        let output = match actual {
+            Ok(output) => output,
+            Err(err) => {
+                return Feedback::incorrect(alloc::format!("The snippet could not be run: {}", err))
+            }
+        };
+
+        let message = if explanation.is_empty() {
+            alloc::format!("The program prints:\n{}", output)
+        } else {
+            alloc::format!("{}\n\nThe program prints:\n{}", explanation, output)
+        };
+        if outputs_match(output, text) {
+            Feedback::correct(message, points)
+        } else {
+            Feedback::incorrect(message)
+        }
+    }
+
+    /// Grade a numeric answer, explaining unit and precision mistakes.
+    fn grade_numeric(
+        expected: f64,
+        tolerance: Tolerance,
+        sig_figs: Option<u32>,
+        unit: Option<&str>,
+        explanation: &str,
+        points: u32,
+        text: &str,
+    ) -> Feedback {
+        let Some(quantity) = units::parse_quantity(text) else {
+            return Feedback::incorrect("Enter a number, e.g. 9.8 or 9.8 m/s");
+        };
+
+        let value = match (unit, quantity.unit.as_deref()) {
+            (Some(expected_unit), None) => {
+                return if tolerance.accepts(expected, quantity.value) {
+                    Feedback::incorrect(alloc::format!(
+                        "Right value, but include the unit ({})",
+                        expected_unit
+                    ))
+                } else {
+                    Feedback::incorrect(explanation)
+                };
+            }
+            (Some(expected_unit), Some(given)) => {
+                match units::convert(quantity.value, given, expected_unit) {
+                    Ok(converted) => {
+                        if !tolerance.accepts(expected, converted)
+                            && tolerance.accepts(expected, quantity.value)
+                        {
+                            return Feedback::incorrect(alloc::format!(
+                                "Right magnitude, wrong unit: {} {} is {:.3} {}",
+                                quantity.value,
+                                given,
+                                converted,
+                                expected_unit
+                            ));
+                        }
+                        converted
+                    }
+                    Err(UnitError::Incompatible { from, to }) => {
+                        let prefix = if tolerance.accepts(expected, quantity.value) {
+                            "Right magnitude, wrong unit: "
+                        } else {
+                            ""
+                        };
+                        return Feedback::incorrect(alloc::format!(
+                            "{}{} measures {}, but the answer is a {}",
+                            prefix,
+                            given,
+                            from.label(),
+                            to.label()
+                        ));
+                    }
+                    Err(err) => return Feedback::incorrect(alloc::format!("{}", err)),
+                }
+            }
+            (None, _) => quantity.value,
+        };
+
+        if !tolerance.accepts(expected, value) {
+            return Feedback::incorrect(explanation);
+        }
+
+        match sig_figs {
+            Some(required) if quantity.sig_figs != required => Feedback::incorrect(alloc::format!(
+                "Right value, but give it to {} significant figures",
+                required
+            )),
+            _ => Feedback::correct(explanation, points),
+        }
+    }
+
+    /// Grade a short answer against the first matching expected answer.
+    fn grade_short_answer(
+        answers: &[TextAnswer],
+        explanation: &str,
+        points: u32,
+        text: &str,
+    ) -> Feedback {
+        let Some(matched) = answers.iter().find(|a| matches_text(&a.matcher, text)) else {
+            return Feedback::incorrect(explanation);
+        };
+
+        let message = matched.feedback.as_deref().unwrap_or(explanation);
+        if matched.credit >= 1.0 {
+            Feedback::correct(message, points)
+        } else if matched.credit > 0.0 {
+            let earned = (points as f32 * matched.credit + 0.5) as u32;
+            Feedback::partial(message, earned)
+        } else {
+            Feedback::incorrect(message)
+        }
+    }
+
+    /// Grade items sorted into buckets, with credit per correctly placed item.
+    fn grade_categorize(
+        buckets: &[String],
+        items: &[String],
+        correct: &[usize],
+        explanation: &str,
+        points: u32,
+        placements: &[(usize, usize)],
+    ) -> Feedback {
+        let mut notes = Vec::new();
+        let mut placed_correctly = 0;
+        for (item, expected) in correct.iter().enumerate() {
+            let label = items.get(item).map_or("?", String::as_str);
+            let bucket = buckets.get(*expected).map_or("?", String::as_str);
+            // The last placement wins if an item was dragged more than once.
+            match placements.iter().rev().find(|(i, _)| *i == item) {
+                Some((_, chosen)) if chosen == expected => placed_correctly += 1,
+                Some(_) => notes.push(alloc::format!("`{}` belongs in {}", label, bucket)),
+                None => notes.push(alloc::format!("`{}` was not sorted", label)),
+            }
+        }
+
+        if notes.is_empty() {
+            return Feedback::correct(explanation, points);
+        }
+        let credit = placed_correctly as f32 / correct.len() as f32;
+        Self::partial_credit(notes.join("\n"), points, credit)
+    }
+
+    /// Grade hotspot clicks: credit for each region found, less one for
+    /// each click that lands outside every region.
+    fn grade_hotspot(
+        regions: &[HotspotRegion],
+        explanation: &str,
+        points: u32,
+        clicks: &[HotspotClick],
+    ) -> Feedback {
+        let found = regions
+            .iter()
+            .filter(|region| clicks.iter().any(|click| region.contains(click)))
+            .count();
+        let stray = clicks
+            .iter()
+            .filter(|click| !regions.iter().any(|region| region.contains(click)))
+            .count();
+
+        if found == regions.len() && stray == 0 {
+            return Feedback::correct(explanation, points);
+        }
+
+        let mut message = alloc::format!("Found {} of {}", found, regions.len());
+        if stray > 0 {
+            message.push_str(&alloc::format!(
+                "; {} click{} missed",
+                stray,
+                if stray == 1 { "" } else { "s" }
+            ));
+        }
+        let credit = found.saturating_sub(stray) as f32 / regions.len().max(1) as f32;
+        Self::partial_credit(message, points, credit)
+    }
+
+    /// Award a share of the points, rounded, or none when `credit` is zero.
+    fn partial_credit(explanation: String, points: u32, credit: f32) -> Feedback {
+        let earned = (points as f32 * credit + 0.5) as u32;
+        if earned == 0 {
+            Feedback::incorrect(explanation)
+        } else {
+            Feedback::partial(explanation, earned)
+        }
+    }
+
     /// Calculate the final score for a quiz.
     #[must_use]
     pub fn calculate_score(quiz: &Quiz, feedback: &[Feedback]) -> Score {
+        Self::score_questions(&quiz.questions, quiz.passing_score, feedback)
+    }
+
+    /// Calculate the score for the questions actually presented in an attempt.
+    #[must_use]
+    pub fn score_questions(
+        questions: &[Question],
+        passing_score: f32,
+        feedback: &[Feedback],
+    ) -> Score {
         let points_earned: u32 = feedback.iter().map(|f| f.points_earned).sum();
-        let points_possible = quiz.total_points();
+        let points_possible = questions.iter().map(Question::points).sum();
         let correct_count = feedback.iter().filter(|f| f.correct).count();
-        let total_questions = quiz.question_count();
 
-        Score::calculate(
+        let score = Score::calculate(
             points_earned,
             points_possible,
-            quiz.passing_score,
+            passing_score,
             correct_count,
-            total_questions,
-        )
+            questions.len(),
+        );
+
+        let mut calibration = Calibration::new();
+        for f in feedback {
+            if let Some(confidence) = f.confidence {
+                calibration.record(confidence, f.correct, f.confidence_mark);
+            }
+        }
+        if calibration.answered() > 0 {
+            score.with_calibration(calibration)
+        } else {
+            score
+        }
     }
 
     /// Check if an answer matches the correct answer for a question.
@@ -125,7 +541,7 @@ impl Grader {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use profesor_core::{Blank, QuestionId};
+    use profesor_core::{Blank, CodeFragment, HotspotTarget, QuestionId};
 
     #[test]
     fn test_grade_multiple_choice_correct() {
@@ -136,6 +552,7 @@ mod tests {
             correct: 1,
             explanation: "B is correct".into(),
             points: 10,
+            option_explanations: Vec::new(),
         };
 
         let feedback = Grader::grade_answer(&question, &Answer::Choice(1));
@@ -152,6 +569,7 @@ mod tests {
             correct: 0,
             explanation: "A is correct".into(),
             points: 10,
+            option_explanations: Vec::new(),
         };
 
         let feedback = Grader::grade_answer(&question, &Answer::Choice(1));
@@ -168,6 +586,7 @@ mod tests {
             correct: alloc::vec![0, 2],
             explanation: "A and C".into(),
             points: 20,
+            option_explanations: Vec::new(),
         };
 
         // Correct (order doesn't matter)
@@ -177,6 +596,10 @@ mod tests {
         // Incorrect
         let feedback = Grader::grade_answer(&question, &Answer::MultiChoice(alloc::vec![0, 1]));
         assert!(!feedback.correct);
+
+        // Repeated selections are never correct
+        let feedback = Grader::grade_answer(&question, &Answer::MultiChoice(alloc::vec![0, 0, 2]));
+        assert!(!feedback.correct);
     }
 
     #[test]
@@ -206,10 +629,12 @@ mod tests {
             right: alloc::vec!["1".into(), "2".into()],
             correct_pairs: alloc::vec![(0, 1), (1, 0)],
             points: 20,
+            explanation: "A goes with 2".into(),
         };
 
         let feedback = Grader::grade_answer(&question, &Answer::Pairs(alloc::vec![(1, 0), (0, 1)]));
         assert!(feedback.correct);
+        assert_eq!(feedback.explanation, "A goes with 2");
     }
 
     #[test]
@@ -223,6 +648,7 @@ mod tests {
                 .with_answer("return a + b;"),],
             test_cases: alloc::vec![],
             points: 25,
+            explanation: "Return the sum".into(),
         };
 
         let feedback = Grader::grade_answer(
@@ -230,6 +656,7 @@ mod tests {
             &Answer::Blanks(alloc::vec![("blank1".into(), "a + b".into())]),
         );
         assert!(feedback.correct);
+        assert_eq!(feedback.explanation, "Return the sum");
 
         let feedback = Grader::grade_answer(
             &question,
@@ -249,6 +676,7 @@ mod tests {
                 correct: 0,
                 explanation: "".into(),
                 points: 10,
+                option_explanations: Vec::new(),
             })
             .with_question(Question::MultipleChoice {
                 id: "q2".into(),
@@ -257,6 +685,7 @@ mod tests {
                 correct: 0,
                 explanation: "".into(),
                 points: 10,
+                option_explanations: Vec::new(),
             });
 
         let feedback = alloc::vec![Feedback::correct("", 10), Feedback::correct("", 10),];
@@ -278,6 +707,7 @@ mod tests {
                 correct: 0,
                 explanation: "".into(),
                 points: 10,
+                option_explanations: Vec::new(),
             })
             .with_question(Question::MultipleChoice {
                 id: "q2".into(),
@@ -286,6 +716,7 @@ mod tests {
                 correct: 0,
                 explanation: "".into(),
                 points: 10,
+                option_explanations: Vec::new(),
             });
 
         let feedback = alloc::vec![Feedback::correct("", 10), Feedback::incorrect(""),];
@@ -295,6 +726,66 @@ mod tests {
         assert_eq!(score.points_earned, 10);
     }
 
+    #[test]
+    fn test_hinted_question_graded_as_inner() {
+        let question = Question::MultipleChoice {
+            id: "q1".into(),
+            prompt: "Q".into(),
+            options: alloc::vec!["A".into(), "B".into()],
+            correct: 1,
+            explanation: "B".into(),
+            points: 10,
+            option_explanations: Vec::new(),
+        }
+        .with_hint(profesor_core::QuestionHint::new("Not A", 0.5));
+        let feedback = Grader::grade_answer(&question, &Answer::Choice(1));
+        assert!(feedback.correct);
+        assert_eq!(feedback.points_earned, 10);
+        assert!(!Grader::is_correct(&question, &Answer::Choice(0)));
+    }
+
+    #[test]
+    fn test_confidence_marking() {
+        use profesor_core::{Confidence, Payoff};
+
+        let question = Question::MultipleChoice {
+            id: "q1".into(),
+            prompt: "Q".into(),
+            options: alloc::vec!["A".into(), "B".into()],
+            correct: 0,
+            explanation: "A".into(),
+            points: 10,
+            option_explanations: Vec::new(),
+        };
+        let sure_right = Answer::Choice(0).with_confidence(Confidence::High);
+        let sure_wrong = Answer::Choice(1).with_confidence(Confidence::High);
+        let guess_right = Answer::Choice(0).with_confidence(Confidence::Low);
+
+        let right = Grader::grade_answer(&question, &sure_right);
+        assert!(right.correct);
+        assert_eq!(right.points_earned, 10);
+        assert_eq!(right.confidence_mark, 3);
+
+        let wrong = Grader::grade_answer(&question, &sure_wrong);
+        assert_eq!(wrong.confidence_mark, -6);
+        assert!(wrong.misconception);
+
+        let strict = PayoffMatrix::new(Payoff::new(1, 0), Payoff::new(2, -3), Payoff::new(4, -12));
+        let wrong = Grader::grade_with_payoff(&question, &sure_wrong, &OutputCache::new(), &strict);
+        assert_eq!(wrong.confidence_mark, -12);
+
+        let feedback = [right, wrong, Grader::grade_answer(&question, &guess_right)];
+        let questions = [question.clone(), question.clone(), question];
+        let score = Grader::score_questions(&questions, 0.5, &feedback);
+        let calibration = score.calibration.unwrap_or_default();
+        assert_eq!(calibration.marks, 3 - 12 + 1);
+        assert_eq!(calibration.over_confidence(), 0.5);
+        assert_eq!(calibration.under_confidence(), 1.0);
+
+        let plain = Grader::score_questions(&questions[..1], 0.5, &[Feedback::correct("", 10)]);
+        assert!(plain.calibration.is_none());
+    }
+
     #[test]
     fn test_type_mismatch() {
         let question = Question::MultipleChoice {
@@ -304,12 +795,341 @@ mod tests {
             correct: 0,
             explanation: "".into(),
             points: 10,
+            option_explanations: Vec::new(),
         };
 
         // Wrong answer type
         let feedback = Grader::grade_answer(&question, &Answer::Code("code".into()));
         assert!(!feedback.correct);
     }
+
+    fn velocity_question(sig_figs: Option<u32>) -> Question {
+        Question::Numeric {
+            id: "v".into(),
+            prompt: "Velocity after 2 s of free fall?".into(),
+            value: 19.6,
+            tolerance: Tolerance::Relative(0.01),
+            sig_figs,
+            unit: Some("m/s".into()),
+            explanation: "v = g·t = 9.8 × 2".into(),
+            points: 10,
+        }
+    }
+
+    fn grade_numeric(question: &Question, text: &str) -> Feedback {
+        Grader::grade_answer(question, &Answer::Numeric(text.into()))
+    }
+
+    #[test]
+    fn test_grade_numeric_correct() {
+        let question = velocity_question(None);
+        let feedback = grade_numeric(&question, "19.6 m/s");
+        assert!(feedback.correct);
+        assert_eq!(feedback.points_earned, 10);
+        assert!(grade_numeric(&question, "19.7 m/s").correct);
+    }
+
+    #[test]
+    fn test_grade_numeric_converts_units() {
+        let question = velocity_question(None);
+        assert!(grade_numeric(&question, "70.56 km/h").correct);
+    }
+
+    #[test]
+    fn test_grade_numeric_outside_tolerance() {
+        let feedback = grade_numeric(&velocity_question(None), "9.8 m/s");
+        assert!(!feedback.correct);
+        assert_eq!(feedback.explanation, "v = g·t = 9.8 × 2");
+    }
+
+    #[test]
+    fn test_grade_numeric_right_magnitude_wrong_unit() {
+        let question = velocity_question(None);
+
+        let feedback = grade_numeric(&question, "19.6 km/h");
+        assert!(!feedback.correct);
+        assert!(feedback
+            .explanation
+            .starts_with("Right magnitude, wrong unit"));
+        assert!(feedback.explanation.contains("5.444 m/s"));
+
+        let feedback = grade_numeric(&question, "19.6 m");
+        assert!(!feedback.correct);
+        assert!(feedback.explanation.contains("m measures length"));
+    }
+
+    #[test]
+    fn test_grade_numeric_missing_or_unknown_unit() {
+        let question = velocity_question(None);
+        let feedback = grade_numeric(&question, "19.6");
+        assert!(!feedback.correct);
+        assert!(feedback.explanation.contains("include the unit (m/s)"));
+
+        let feedback = grade_numeric(&question, "19.6 parsecs");
+        assert_eq!(feedback.explanation, "Unknown unit 'parsecs'");
+    }
+
+    #[test]
+    fn test_grade_numeric_significant_figures() {
+        let question = velocity_question(Some(3));
+        assert!(grade_numeric(&question, "19.6 m/s").correct);
+
+        let feedback = grade_numeric(&question, "19.60 m/s");
+        assert!(!feedback.correct);
+        assert!(feedback.explanation.contains("3 significant figures"));
+    }
+
+    #[test]
+    fn test_grade_numeric_unitless() {
+        let question = Question::Numeric {
+            id: "n".into(),
+            prompt: "2 + 2?".into(),
+            value: 4.0,
+            tolerance: Tolerance::Absolute(0.0),
+            sig_figs: None,
+            unit: None,
+            explanation: "".into(),
+            points: 5,
+        };
+        assert!(grade_numeric(&question, "4").correct);
+        assert!(!grade_numeric(&question, "four").correct);
+    }
+
+    fn ownership_question() -> Question {
+        use profesor_core::TextMatcher;
+
+        Question::ShortAnswer {
+            id: "own".into(),
+            prompt: "What does Rust use instead of a garbage collector?".into(),
+            answers: alloc::vec![
+                TextAnswer::wrong(
+                    TextMatcher::Normalized("borrowing".into()),
+                    "You confused borrowing with ownership",
+                ),
+                TextAnswer::new(TextMatcher::Fuzzy {
+                    text: "ownership".into(),
+                    max_distance: 2,
+                }),
+                TextAnswer::new(TextMatcher::Regex("(?i)(the )?borrow checker".into()))
+                    .with_credit(0.5)
+                    .with_feedback("Close: the borrow checker enforces ownership"),
+            ],
+            explanation: "Ownership manages memory".into(),
+            points: 10,
+        }
+    }
+
+    fn grade_text(question: &Question, text: &str) -> Feedback {
+        Grader::grade_answer(question, &Answer::Text(text.into()))
+    }
+
+    #[test]
+    fn test_grade_short_answer_correct() {
+        let question = ownership_question();
+        let feedback = grade_text(&question, "Ownership");
+        assert!(feedback.correct);
+        assert_eq!(feedback.points_earned, 10);
+        assert!(grade_text(&question, "ownrship").correct);
+    }
+
+    #[test]
+    fn test_grade_short_answer_partial_credit() {
+        let feedback = grade_text(&ownership_question(), "The Borrow Checker");
+        assert!(!feedback.correct);
+        assert_eq!(feedback.points_earned, 5);
+        assert!(feedback.explanation.starts_with("Close"));
+    }
+
+    #[test]
+    fn test_grade_short_answer_known_wrong() {
+        let feedback = grade_text(&ownership_question(), "  Borrowing ");
+        assert!(!feedback.correct);
+        assert_eq!(feedback.points_earned, 0);
+        assert_eq!(
+            feedback.explanation,
+            "You confused borrowing with ownership"
+        );
+    }
+
+    #[test]
+    fn test_grade_short_answer_no_match() {
+        let feedback = grade_text(&ownership_question(), "reference counting");
+        assert!(!feedback.correct);
+        assert_eq!(feedback.explanation, "Ownership manages memory");
+    }
+
+    #[test]
+    fn test_grade_parsons() {
+        let question = Question::Parsons {
+            id: QuestionId::new("p1"),
+            prompt: "Arrange the function".into(),
+            language: profesor_core::Language::Rust,
+            fragments: alloc::vec![
+                CodeFragment::new("fn double(x: i32) -> i32 {", 0),
+                CodeFragment::new("x * 2", 1),
+                CodeFragment::new("}", 0),
+                CodeFragment::distractor("x + 2"),
+            ],
+            valid_orderings: alloc::vec![],
+            test_cases: alloc::vec![],
+            explanation: "Multiply by two".into(),
+            points: 10,
+        };
+
+        let answer = Answer::Arrangement(alloc::vec![(0, 0), (1, 1), (2, 0)]);
+        let feedback = Grader::grade_answer(&question, &answer);
+        assert!(feedback.correct);
+        assert_eq!(feedback.points_earned, 10);
+
+        let answer = Answer::Arrangement(alloc::vec![(0, 0), (3, 1), (2, 0)]);
+        assert!(!Grader::is_correct(&question, &answer));
+        assert!(!Grader::is_correct(
+            &question,
+            &Answer::Order(alloc::vec![0, 1, 2])
+        ));
+    }
+
+    fn predict_question() -> Question {
+        Question::PredictOutput {
+            id: QuestionId::new("p1"),
+            prompt: "What does this print?".into(),
+            language: profesor_core::Language::Python,
+            code: "for i in range(3): print(i)".into(),
+            input: String::new(),
+            explanation: "range stops before 3".into(),
+            points: 10,
+        }
+    }
+
+    #[test]
+    fn test_grade_predict_output_from_cache() {
+        let mut outputs = OutputCache::new();
+        outputs.outputs.insert("p1".into(), Ok("0\n1\n2\n".into()));
+        let question = predict_question();
+
+        let feedback =
+            Grader::grade_with_outputs(&question, &Answer::Text("0 \n1\n2".into()), &outputs);
+        assert!(feedback.correct);
+        assert_eq!(feedback.points_earned, 10);
+
+        let feedback =
+            Grader::grade_with_outputs(&question, &Answer::Text("0\n1\n2\n3".into()), &outputs);
+        assert!(!feedback.correct);
+        assert_eq!(
+            feedback.explanation,
+            "range stops before 3\n\nThe program prints:\n0\n1\n2\n"
+        );
+    }
+
+    #[test]
+    fn test_grade_predict_output_run_failure() {
+        let mut outputs = OutputCache::new();
+        outputs
+            .outputs
+            .insert("p1".into(), Err("SyntaxError".into()));
+        let feedback =
+            Grader::grade_with_outputs(&predict_question(), &Answer::Text("0".into()), &outputs);
+        assert!(!feedback.correct);
+        assert_eq!(
+            feedback.explanation,
+            "The snippet could not be run: SyntaxError"
+        );
+    }
+
+    fn traits_question() -> Question {
+        Question::Categorize {
+            id: QuestionId::new("c1"),
+            prompt: "Sort these types".into(),
+            buckets: alloc::vec!["Copy".into(), "Clone-only".into(), "Neither".into()],
+            items: alloc::vec![
+                "i32".into(),
+                "String".into(),
+                "Vec<u8>".into(),
+                "MutexGuard".into(),
+            ],
+            correct: alloc::vec![0, 1, 1, 2],
+            explanation: "Heap owners can't be Copy".into(),
+            points: 8,
+        }
+    }
+
+    #[test]
+    fn test_grade_categorize() {
+        let answer = Answer::Categories(alloc::vec![(0, 0), (1, 1), (2, 1), (3, 2)]);
+        let feedback = Grader::grade_answer(&traits_question(), &answer);
+        assert!(feedback.correct);
+        assert_eq!(feedback.points_earned, 8);
+    }
+
+    #[test]
+    fn test_grade_categorize_partial() {
+        let answer = Answer::Categories(alloc::vec![(0, 0), (1, 0), (2, 1), (1, 1), (3, 0)]);
+        let feedback = Grader::grade_answer(&traits_question(), &answer);
+        assert!(!feedback.correct);
+        assert_eq!(feedback.points_earned, 6);
+        assert_eq!(feedback.explanation, "`MutexGuard` belongs in Neither");
+
+        let feedback = Grader::grade_answer(&traits_question(), &Answer::Categories(alloc::vec![]));
+        assert_eq!(feedback.points_earned, 0);
+        assert!(feedback.explanation.contains("`i32` was not sorted"));
+    }
+
+    fn borrow_hotspot() -> Question {
+        Question::Hotspot {
+            id: QuestionId::new("h1"),
+            prompt: "Click the lines with borrow errors".into(),
+            target: HotspotTarget::Code {
+                language: profesor_core::Language::Rust,
+                code: "let r;\n{\n    let x = 5;\n    r = &x;\n}\nprintln!(\"{}\", r);".into(),
+            },
+            regions: alloc::vec![HotspotRegion::line(4), HotspotRegion::line(6)],
+            explanation: "x does not live long enough".into(),
+            points: 10,
+        }
+    }
+
+    #[test]
+    fn test_grade_hotspot() {
+        let clicks = alloc::vec![HotspotClick::Line(6), HotspotClick::Line(4)];
+        let feedback = Grader::grade_answer(&borrow_hotspot(), &Answer::Hotspots(clicks));
+        assert!(feedback.correct);
+        assert_eq!(feedback.points_earned, 10);
+    }
+
+    #[test]
+    fn test_grade_hotspot_partial() {
+        let clicks = alloc::vec![HotspotClick::Line(4)];
+        let feedback = Grader::grade_answer(&borrow_hotspot(), &Answer::Hotspots(clicks));
+        assert_eq!(feedback.points_earned, 5);
+        assert_eq!(feedback.explanation, "Found 1 of 2");
+
+        let clicks = alloc::vec![HotspotClick::Line(4), HotspotClick::Line(1)];
+        let feedback = Grader::grade_answer(&borrow_hotspot(), &Answer::Hotspots(clicks));
+        assert!(!feedback.correct);
+        assert_eq!(feedback.points_earned, 0);
+        assert_eq!(feedback.explanation, "Found 1 of 2; 1 click missed");
+    }
+
+    #[test]
+    fn test_grade_hotspot_simulation_region() {
+        let question = Question::Hotspot {
+            id: QuestionId::new("h2"),
+            prompt: "Click the collision".into(),
+            target: HotspotTarget::Simulation("bouncing-ball".into()),
+            regions: alloc::vec![HotspotRegion::Rect {
+                x: 0.4,
+                y: 0.8,
+                width: 0.2,
+                height: 0.2,
+            }],
+            explanation: "".into(),
+            points: 4,
+        };
+        let hit = Answer::Hotspots(alloc::vec![HotspotClick::Point { x: 0.5, y: 0.9 }]);
+        assert!(Grader::is_correct(&question, &hit));
+        let miss = Answer::Hotspots(alloc::vec![HotspotClick::Line(1)]);
+        assert!(!Grader::is_correct(&question, &miss));
+    }
 }
 
 #[cfg(test)]
@@ -334,6 +1154,7 @@ mod proptests {
                 correct: correct_idx,
                 explanation: "".into(),
                 points: 10,
+                option_explanations: Vec::new(),
             };
 
             let feedback = Grader::grade_answer(&question, &Answer::Choice(correct_idx));
@@ -360,6 +1181,7 @@ mod proptests {
                 correct: correct_idx,
                 explanation: "".into(),
                 points: 10,
+                option_explanations: Vec::new(),
             };
 
             let feedback = Grader::grade_answer(&question, &Answer::Choice(wrong_idx));
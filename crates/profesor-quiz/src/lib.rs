@@ -15,8 +15,38 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std as alloc;
 
+mod adaptive;
+mod analysis;
+mod audit;
 mod engine;
+mod envelope;
 mod grader;
+mod matching;
+mod output;
+mod parsons;
+mod review;
+mod sealed;
+mod snapshot;
+pub mod units;
 
+pub use adaptive::{estimate_ability, information, probability, select_item};
+pub use analysis::{ItemAnalysis, ItemFlag, ItemStatistics, TOO_EASY_THRESHOLD};
+pub use audit::{
+    AuditLog, Clock, Direction, ManualClock, QuestionActivity, QuizEvent, QuizEventKind,
+};
 pub use engine::{QuizEngine, QuizState};
+pub use envelope::{
+    grade_submission, quiz_version, AttemptGrant, EnvelopeError, GradedSubmission, Signed,
+    SignedGrant, SignedScore, Submission, SubmissionEnvelope, SubmittedAnswer, ENVELOPE_VERSION,
+};
 pub use grader::Grader;
+pub use matching::{
+    edit_distance, matches_text, normalize_text, Pattern, PatternError, MAX_ANSWER_CHARS,
+};
+pub use output::{outputs_match, run_snippet, OutputCache};
+pub use parsons::assemble_program;
+pub use review::{correct_answer, distractor_notes, QuestionReview};
+pub use sealed::{
+    AnswerKey, ExplanationKey, SealError, SealedAnswer, SealedQuestion, SealedQuiz, SEALED_VERSION,
+};
+pub use snapshot::{AdaptiveSnapshot, QuizSnapshot, ResumeError, SNAPSHOT_VERSION};
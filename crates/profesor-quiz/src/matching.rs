@@ -0,0 +1,680 @@
+//! Text answer matching.
+//!
+//! Normalization, edit distance and a small regular expression engine for
+//! short-answer questions. The engine supports literals, `.`, classes
+//! (`[a-z]`, `[^0-9]`, `\d`, `\w`, `\s`), groups, alternation, anchors and
+//! the quantifiers `* + ? {n} {n,} {n,m}`. A leading `(?i)` makes the
+//! pattern case-insensitive. Patterns match the whole answer.
+//!
+//! Patterns compile to a Thompson NFA that is simulated one input character
+//! at a time (a Pike VM), so matching never backtracks and takes time linear
+//! in the answer, whatever the learner types.
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use profesor_core::TextMatcher;
+
+/// Longest answer, in characters, that is matched at all.
+pub const MAX_ANSWER_CHARS: usize = 4096;
+
+/// Check if an answer satisfies a matcher.
+///
+/// Invalid regex patterns never match, and neither do answers longer than
+/// [`MAX_ANSWER_CHARS`].
+#[must_use]
+pub fn matches_text(matcher: &TextMatcher, answer: &str) -> bool {
+    let answer = answer.trim();
+    if answer.chars().nth(MAX_ANSWER_CHARS).is_some() {
+        return false;
+    }
+    match matcher {
+        TextMatcher::Exact(expected) => answer == expected,
+        TextMatcher::Normalized(expected) => normalize_text(answer) == normalize_text(expected),
+        TextMatcher::Regex(pattern) => Pattern::parse(pattern).is_ok_and(|p| p.is_match(answer)),
+        TextMatcher::Fuzzy { text, max_distance } => {
+            edit_distance(&normalize_text(answer), &normalize_text(text)) <= *max_distance
+        }
+    }
+}
+
+/// Lowercase and collapse runs of whitespace to single spaces.
+#[must_use]
+pub fn normalize_text(text: &str) -> String {
+    text.split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Levenshtein distance in characters.
+#[must_use]
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = alloc::vec![0; b.len() + 1];
+
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitute.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        core::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+/// Most instructions a compiled pattern may have.
+const MAX_PROGRAM_LEN: usize = 10_000;
+
+/// A compiled regular expression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern {
+    program: Vec<Inst>,
+    case_insensitive: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Char(char),
+    Any,
+    Class {
+        ranges: Vec<(char, char)>,
+        negated: bool,
+    },
+    Start,
+    End,
+    Group(Vec<Vec<Node>>),
+    Repeat {
+        node: Box<Node>,
+        min: usize,
+        max: Option<usize>,
+    },
+}
+
+/// Errors from parsing a pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatternError {
+    /// A `(` without `)` or a stray `)`
+    UnbalancedGroup,
+    /// A `[` without `]`
+    UnterminatedClass,
+    /// A quantifier with nothing to repeat
+    NothingToRepeat,
+    /// A malformed `{n,m}` quantifier
+    InvalidRepetition,
+    /// A pattern ending in `\`
+    TrailingEscape,
+    /// A pattern whose repetitions expand past the size limit
+    TooLarge,
+}
+
+impl core::fmt::Display for PatternError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::UnbalancedGroup => write!(f, "Unbalanced parentheses"),
+            Self::UnterminatedClass => write!(f, "Unterminated character class"),
+            Self::NothingToRepeat => write!(f, "Quantifier has nothing to repeat"),
+            Self::InvalidRepetition => write!(f, "Invalid repetition count"),
+            Self::TrailingEscape => write!(f, "Pattern ends with an escape"),
+            Self::TooLarge => write!(f, "Pattern is too large"),
+        }
+    }
+}
+
+impl Pattern {
+    /// Parse a pattern.
+    pub fn parse(pattern: &str) -> Result<Self, PatternError> {
+        let (case_insensitive, body) = match pattern.strip_prefix("(?i)") {
+            Some(rest) => (true, rest),
+            None => (false, pattern),
+        };
+        let mut parser = Parser {
+            chars: body.chars().collect(),
+            pos: 0,
+            case_insensitive,
+        };
+        let alternatives = parser.alternatives()?;
+        if parser.pos < parser.chars.len() {
+            return Err(PatternError::UnbalancedGroup);
+        }
+        let mut compiler = Compiler {
+            program: Vec::new(),
+        };
+        compiler.alternatives(&alternatives)?;
+        compiler.emit(Inst::Match)?;
+        Ok(Self {
+            program: compiler.program,
+            case_insensitive,
+        })
+    }
+
+    /// Check if the pattern matches the whole text.
+    #[must_use]
+    pub fn is_match(&self, text: &str) -> bool {
+        let input: Vec<char> = if self.case_insensitive {
+            text.to_lowercase().chars().collect()
+        } else {
+            text.chars().collect()
+        };
+
+        let mut current = Vec::new();
+        let mut next = Vec::new();
+        let mut seen = alloc::vec![false; self.program.len()];
+        self.add_thread(&mut current, &mut seen, 0, 0, input.len());
+        for pos in 0..=input.len() {
+            if current.is_empty() {
+                return false;
+            }
+            seen.fill(false);
+            for &pc in &current {
+                let advances = match (&self.program[pc], input.get(pos)) {
+                    (Inst::Match, _) if pos == input.len() => return true,
+                    (Inst::Char(expected), Some(c)) => expected == c,
+                    (Inst::Any, Some(_)) => true,
+                    (Inst::Class { ranges, negated }, Some(c)) => {
+                        ranges.iter().any(|(lo, hi)| (lo..=hi).contains(&c)) != *negated
+                    }
+                    _ => false,
+                };
+                if advances {
+                    self.add_thread(&mut next, &mut seen, pc + 1, pos + 1, input.len());
+                }
+            }
+            core::mem::swap(&mut current, &mut next);
+            next.clear();
+        }
+        false
+    }
+
+    /// Add the thread at `start` to `threads`, following jumps, splits and
+    /// anchors so only character tests and the final match are kept.
+    fn add_thread(
+        &self,
+        threads: &mut Vec<usize>,
+        seen: &mut [bool],
+        start: usize,
+        pos: usize,
+        len: usize,
+    ) {
+        let mut stack = alloc::vec![start];
+        while let Some(pc) = stack.pop() {
+            match seen.get_mut(pc) {
+                Some(visited) if !*visited => *visited = true,
+                _ => continue,
+            }
+            match &self.program[pc] {
+                Inst::Split(first, second) => {
+                    stack.push(*second);
+                    stack.push(*first);
+                }
+                Inst::Jump(target) => stack.push(*target),
+                Inst::Start if pos == 0 => stack.push(pc + 1),
+                Inst::End if pos == len => stack.push(pc + 1),
+                Inst::Start | Inst::End => {}
+                _ => threads.push(pc),
+            }
+        }
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    case_insensitive: bool,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        Some(c)
+    }
+
+    fn literal(&self, c: char) -> char {
+        if self.case_insensitive {
+            c.to_lowercase().next().unwrap_or(c)
+        } else {
+            c
+        }
+    }
+
+    fn alternatives(&mut self) -> Result<Vec<Vec<Node>>, PatternError> {
+        let mut alternatives = alloc::vec![self.sequence()?];
+        while self.peek() == Some('|') {
+            self.pos += 1;
+            alternatives.push(self.sequence()?);
+        }
+        Ok(alternatives)
+    }
+
+    fn sequence(&mut self) -> Result<Vec<Node>, PatternError> {
+        let mut nodes = Vec::new();
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            let atom = self.atom()?;
+            nodes.push(self.quantified(atom)?);
+        }
+        Ok(nodes)
+    }
+
+    fn atom(&mut self) -> Result<Node, PatternError> {
+        match self.next() {
+            Some('(') => {
+                if self.chars[self.pos..].starts_with(&['?', ':']) {
+                    self.pos += 2;
+                }
+                let group = self.alternatives()?;
+                if self.next() != Some(')') {
+                    return Err(PatternError::UnbalancedGroup);
+                }
+                Ok(Node::Group(group))
+            }
+            Some('[') => self.class(),
+            Some('.') => Ok(Node::Any),
+            Some('^') => Ok(Node::Start),
+            Some('$') => Ok(Node::End),
+            Some('\\') => self.escape(),
+            Some('*' | '+' | '?' | '{') => Err(PatternError::NothingToRepeat),
+            Some(c) => Ok(Node::Char(self.literal(c))),
+            None => Err(PatternError::UnbalancedGroup),
+        }
+    }
+
+    fn escape(&mut self) -> Result<Node, PatternError> {
+        let c = self.next().ok_or(PatternError::TrailingEscape)?;
+        Ok(match shorthand_class(c) {
+            Some((ranges, negated)) => Node::Class { ranges, negated },
+            None => Node::Char(self.literal(c)),
+        })
+    }
+
+    fn class(&mut self) -> Result<Node, PatternError> {
+        let negated = self.peek() == Some('^');
+        if negated {
+            self.pos += 1;
+        }
+
+        let mut ranges = Vec::new();
+        let mut first = true;
+        loop {
+            let c = self.next().ok_or(PatternError::UnterminatedClass)?;
+            if c == ']' && !first {
+                break;
+            }
+            first = false;
+
+            let start = if c == '\\' {
+                let escaped = self.next().ok_or(PatternError::UnterminatedClass)?;
+                match shorthand_class(escaped) {
+                    Some((shorthand, false)) => {
+                        ranges.extend(shorthand);
+                        continue;
+                    }
+                    _ => escaped,
+                }
+            } else {
+                c
+            };
+
+            let is_range =
+                self.peek() == Some('-') && self.chars.get(self.pos + 1).is_some_and(|&n| n != ']');
+            if is_range {
+                self.pos += 1;
+                let end = self.next().ok_or(PatternError::UnterminatedClass)?;
+                ranges.push((self.literal(start), self.literal(end)));
+            } else {
+                ranges.push((self.literal(start), self.literal(start)));
+            }
+        }
+
+        Ok(Node::Class { ranges, negated })
+    }
+
+    fn quantified(&mut self, atom: Node) -> Result<Node, PatternError> {
+        let (min, max) = match self.peek() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => {
+                self.pos += 1;
+                return self.counted(atom);
+            }
+            _ => return Ok(atom),
+        };
+        self.pos += 1;
+        Ok(repeat(atom, min, max))
+    }
+
+    fn counted(&mut self, atom: Node) -> Result<Node, PatternError> {
+        let min = self.number().ok_or(PatternError::InvalidRepetition)?;
+        let max = match self.next() {
+            Some('}') => Some(min),
+            Some(',') if self.peek() == Some('}') => {
+                self.pos += 1;
+                None
+            }
+            Some(',') => {
+                let max = self.number().ok_or(PatternError::InvalidRepetition)?;
+                if self.next() != Some('}') || max < min {
+                    return Err(PatternError::InvalidRepetition);
+                }
+                Some(max)
+            }
+            _ => return Err(PatternError::InvalidRepetition),
+        };
+        Ok(repeat(atom, min, max))
+    }
+
+    fn number(&mut self) -> Option<usize> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos]
+            .iter()
+            .collect::<String>()
+            .parse()
+            .ok()
+    }
+}
+
+fn repeat(node: Node, min: usize, max: Option<usize>) -> Node {
+    Node::Repeat {
+        node: Box::new(node),
+        min,
+        max,
+    }
+}
+
+/// Ranges for `\d`, `\w`, `\s` and their negations.
+fn shorthand_class(c: char) -> Option<(Vec<(char, char)>, bool)> {
+    let (ranges, negated) = match c.to_ascii_lowercase() {
+        'd' => (alloc::vec![('0', '9')], c == 'D'),
+        'w' => (
+            alloc::vec![('a', 'z'), ('A', 'Z'), ('0', '9'), ('_', '_')],
+            c == 'W',
+        ),
+        's' => (alloc::vec![(' ', ' '), ('\t', '\r')], c == 'S'),
+        _ => return None,
+    };
+    Some((ranges, negated))
+}
+
+/// One instruction of a compiled pattern.
+#[derive(Debug, Clone, PartialEq)]
+enum Inst {
+    Char(char),
+    Any,
+    Class {
+        ranges: Vec<(char, char)>,
+        negated: bool,
+    },
+    Start,
+    End,
+    /// Continue at both targets
+    Split(usize, usize),
+    Jump(usize),
+    Match,
+}
+
+struct Compiler {
+    program: Vec<Inst>,
+}
+
+impl Compiler {
+    fn emit(&mut self, inst: Inst) -> Result<usize, PatternError> {
+        if self.program.len() >= MAX_PROGRAM_LEN {
+            return Err(PatternError::TooLarge);
+        }
+        self.program.push(inst);
+        Ok(self.program.len() - 1)
+    }
+
+    fn alternatives(&mut self, alternatives: &[Vec<Node>]) -> Result<(), PatternError> {
+        let mut jumps = Vec::new();
+        for (i, sequence) in alternatives.iter().enumerate() {
+            if i + 1 == alternatives.len() {
+                self.sequence(sequence)?;
+                break;
+            }
+            let split = self.emit(Inst::Split(0, 0))?;
+            self.sequence(sequence)?;
+            jumps.push(self.emit(Inst::Jump(0))?);
+            self.program[split] = Inst::Split(split + 1, self.program.len());
+        }
+        let end = self.program.len();
+        for jump in jumps {
+            self.program[jump] = Inst::Jump(end);
+        }
+        Ok(())
+    }
+
+    fn sequence(&mut self, sequence: &[Node]) -> Result<(), PatternError> {
+        sequence.iter().try_for_each(|node| self.node(node))
+    }
+
+    fn node(&mut self, node: &Node) -> Result<(), PatternError> {
+        match node {
+            Node::Char(c) => self.emit(Inst::Char(*c)).map(|_| ()),
+            Node::Any => self.emit(Inst::Any).map(|_| ()),
+            Node::Class { ranges, negated } => self
+                .emit(Inst::Class {
+                    ranges: ranges.clone(),
+                    negated: *negated,
+                })
+                .map(|_| ()),
+            Node::Start => self.emit(Inst::Start).map(|_| ()),
+            Node::End => self.emit(Inst::End).map(|_| ()),
+            Node::Group(alternatives) => self.alternatives(alternatives),
+            Node::Repeat { node, min, max } => self.repeat(node, *min, *max),
+        }
+    }
+
+    /// Expand a repetition into `min` copies followed by optional ones, or
+    /// a loop when unbounded.
+    fn repeat(&mut self, node: &Node, min: usize, max: Option<usize>) -> Result<(), PatternError> {
+        for _ in 0..min {
+            self.node(node)?;
+        }
+        match max {
+            None => {
+                let split = self.emit(Inst::Split(0, 0))?;
+                self.node(node)?;
+                self.emit(Inst::Jump(split))?;
+                self.program[split] = Inst::Split(split + 1, self.program.len());
+            }
+            Some(max) => {
+                let mut splits = Vec::new();
+                for _ in min..max {
+                    splits.push(self.emit(Inst::Split(0, 0))?);
+                    self.node(node)?;
+                }
+                let end = self.program.len();
+                for split in splits {
+                    self.program[split] = Inst::Split(split + 1, end);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_match(pattern: &str, text: &str) -> bool {
+        Pattern::parse(pattern).unwrap().is_match(text)
+    }
+
+    #[test]
+    fn test_normalize_text() {
+        assert_eq!(
+            normalize_text("  The   Borrow\tChecker "),
+            "the borrow checker"
+        );
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("ownership", "ownrship"), 1);
+        assert_eq!(edit_distance("abc", ""), 3);
+    }
+
+    #[test]
+    fn test_regex_literals_and_anchoring() {
+        assert!(is_match("borrow", "borrow"));
+        assert!(!is_match("borrow", "borrowing"));
+        assert!(is_match("^borrow$", "borrow"));
+    }
+
+    #[test]
+    fn test_regex_quantifiers() {
+        assert!(is_match("ab*c", "ac"));
+        assert!(is_match("ab*c", "abbbc"));
+        assert!(!is_match("ab+c", "ac"));
+        assert!(is_match("colou?r", "color"));
+        assert!(is_match("colou?r", "colour"));
+        assert!(is_match("a{2,3}", "aaa"));
+        assert!(!is_match("a{2,3}", "aaaa"));
+        assert!(is_match("a{2,}", "aaaaa"));
+        assert!(is_match("a{2}", "aa"));
+    }
+
+    #[test]
+    fn test_regex_classes() {
+        assert!(is_match("[a-c]+", "abcab"));
+        assert!(!is_match("[a-c]+", "abd"));
+        assert!(is_match("[^0-9]+", "abc"));
+        assert!(is_match(r"\d+\.\d+", "3.14"));
+        assert!(is_match(r"\w+\s\w+", "move semantics"));
+        assert!(is_match(r"[\d_]+", "1_000"));
+        assert!(is_match("[-a]+", "-a-"));
+    }
+
+    #[test]
+    fn test_regex_groups_and_alternation() {
+        assert!(is_match("(mut|immutable) borrow", "mut borrow"));
+        assert!(is_match("(?:ab)+", "ababab"));
+        assert!(!is_match("(ab)+", "aba"));
+        assert!(is_match("cat|dog", "dog"));
+        assert!(is_match("(a|)b", "b"));
+    }
+
+    #[test]
+    fn test_regex_case_insensitive() {
+        assert!(is_match("(?i)Box<T>", "box<t>"));
+        assert!(is_match("(?i)[A-Z]+", "rust"));
+        assert!(!is_match("Box", "box"));
+    }
+
+    #[test]
+    fn test_regex_backtracking() {
+        assert!(is_match("a*a", "aaaa"));
+        assert!(is_match("(a|ab)c", "abc"));
+        assert!(is_match(".*ship", "ownership"));
+        assert!(is_match("(a*)*b", "aaab"));
+    }
+
+    #[test]
+    fn test_regex_errors() {
+        assert_eq!(Pattern::parse("(ab"), Err(PatternError::UnbalancedGroup));
+        assert_eq!(Pattern::parse("ab)"), Err(PatternError::UnbalancedGroup));
+        assert_eq!(Pattern::parse("[ab"), Err(PatternError::UnterminatedClass));
+        assert_eq!(Pattern::parse("*a"), Err(PatternError::NothingToRepeat));
+        assert_eq!(
+            Pattern::parse("a{3,1}"),
+            Err(PatternError::InvalidRepetition)
+        );
+        assert_eq!(Pattern::parse("a\\"), Err(PatternError::TrailingEscape));
+        assert_eq!(
+            Pattern::parse("(a{1000}){1000}"),
+            Err(PatternError::TooLarge)
+        );
+    }
+
+    #[test]
+    fn test_regex_long_answers() {
+        let long = "a".repeat(100_000);
+        let pattern = Pattern::parse("(?i).*rust.*").unwrap();
+        assert!(!pattern.is_match(&long));
+        assert!(pattern.is_match(&(long.clone() + "Rust")));
+        assert!(!matches_text(
+            &TextMatcher::Regex("(?i).*rust.*".into()),
+            &(long + "rust")
+        ));
+        assert!(matches_text(
+            &TextMatcher::Regex("(?i).*rust.*".into()),
+            &("a".repeat(MAX_ANSWER_CHARS - 4) + "rust")
+        ));
+    }
+
+    #[test]
+    fn test_regex_does_not_backtrack() {
+        let pattern = Pattern::parse("(a|aa)*").unwrap();
+        assert!(pattern.is_match(&"a".repeat(40)));
+        assert!(!pattern.is_match(&("a".repeat(40) + "b")));
+        assert!(Pattern::parse("(a*)*").unwrap().is_match(&"a".repeat(1000)));
+    }
+
+    #[test]
+    fn test_matches_text() {
+        assert!(matches_text(&TextMatcher::Exact("Rc".into()), " Rc "));
+        assert!(!matches_text(&TextMatcher::Exact("Rc".into()), "rc"));
+        assert!(matches_text(
+            &TextMatcher::Normalized("Move Semantics".into()),
+            "move   semantics"
+        ));
+        assert!(matches_text(
+            &TextMatcher::Regex(r"Rc<\w+>".into()),
+            "Rc<T>"
+        ));
+        assert!(!matches_text(&TextMatcher::Regex("(".into()), "("));
+        assert!(matches_text(
+            &TextMatcher::Fuzzy {
+                text: "ownership".into(),
+                max_distance: 2,
+            },
+            "Ownrship"
+        ));
+    }
+}
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_edit_distance_symmetric(a in "[a-z]{0,12}", b in "[a-z]{0,12}") {
+            prop_assert_eq!(edit_distance(&a, &b), edit_distance(&b, &a));
+        }
+
+        #[test]
+        fn test_edit_distance_identity(a in "[a-z ]{0,20}") {
+            prop_assert_eq!(edit_distance(&a, &a), 0);
+        }
+
+        #[test]
+        fn test_escaped_literal_matches_itself(text in "[a-z.*+?()]{0,12}") {
+            let pattern: String = text
+                .chars()
+                .flat_map(|c| if c.is_ascii_alphabetic() { alloc::vec![c] } else { alloc::vec!['\\', c] })
+                .collect();
+            prop_assert!(Pattern::parse(&pattern).unwrap().is_match(&text));
+        }
+    }
+}
@@ -0,0 +1,166 @@
+//! Expected output for "predict the output" questions.
+//!
+//! Authors don't write the expected answer: each snippet is run once in the
+//! lab sandbox when the quiz is loaded and the result is cached by question
+//! ID, so grading never re-runs code.
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use profesor_core::{Language, Question, Quiz};
+use profesor_lab::{ExecutionResult, Sandbox};
+use serde::{Deserialize, Serialize};
+
+/// Sandbox results for a quiz's `PredictOutput` questions.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OutputCache {
+    /// Program output, or the error that stopped the run, by question ID
+    pub outputs: BTreeMap<String, Result<String, String>>,
+}
+
+impl OutputCache {
+    /// Create an empty cache.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run every snippet in a quiz, including questions in its bank draw.
+    #[must_use]
+    pub fn for_quiz(quiz: &Quiz) -> Self {
+        let banked = quiz
+            .draw
+            .iter()
+            .flat_map(|draw| draw.bank.items.iter().map(|item| &item.question));
+        let mut cache = Self::new();
+        for question in quiz.questions.iter().chain(banked) {
+            cache.insert(question);
+        }
+        cache
+    }
+
+    /// Run a question's snippet if it is a `PredictOutput` question not yet cached.
+    pub fn insert(&mut self, question: &Question) {
+        if let Question::PredictOutput {
+            id,
+            language,
+            code,
+            input,
+            ..
+        } = question.unhinted()
+        {
+            if !self.outputs.contains_key(id.as_str()) {
+                let result = run_snippet(*language, code, input);
+                self.outputs.insert(id.as_str().into(), result);
+            }
+        }
+    }
+
+    /// Get the cached result for a question.
+    #[must_use]
+    pub fn get(&self, question_id: &str) -> Option<&Result<String, String>> {
+        self.outputs.get(question_id)
+    }
+}
+
+/// Run a snippet in the sandbox, returning its output or an error message.
+pub fn run_snippet(language: Language, code: &str, input: &str) -> Result<String, String> {
+    match Sandbox::new().execute(code, language, input) {
+        ExecutionResult::Success { output, .. } => Ok(output),
+        other => Err(other
+            .error_message()
+            .map_or_else(|| "Execution failed".into(), Into::into)),
+    }
+}
+
+/// Compare outputs, ignoring trailing whitespace, repeated spaces within a
+/// line and blank lines at either end.
+#[must_use]
+pub fn outputs_match(expected: &str, actual: &str) -> bool {
+    normalize_output(expected) == normalize_output(actual)
+}
+
+fn normalize_output(text: &str) -> Vec<String> {
+    let lines: Vec<String> = text
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect();
+    let start = lines
+        .iter()
+        .position(|l| !l.is_empty())
+        .unwrap_or(lines.len());
+    let end = lines
+        .iter()
+        .rposition(|l| !l.is_empty())
+        .map_or(start, |i| i + 1);
+    lines[start..end].to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use profesor_core::{BankItem, DrawRule, QuestionBank, QuestionDifficulty, QuestionDraw};
+
+    fn predict(id: &str, language: Language, code: &str) -> Question {
+        Question::PredictOutput {
+            id: id.into(),
+            prompt: "What does this print?".into(),
+            language,
+            code: code.into(),
+            input: String::new(),
+            explanation: "".into(),
+            points: 10,
+        }
+    }
+
+    #[test]
+    fn test_outputs_match_whitespace() {
+        assert!(outputs_match("1 2\n3\n", "  1   2 \n3"));
+        assert!(outputs_match("\nhello\n\n", "hello"));
+        assert!(outputs_match("", "\n \n"));
+        assert!(!outputs_match("1\n2", "1 2"));
+        assert!(!outputs_match("a\n\nb", "a\nb"));
+    }
+
+    #[test]
+    fn test_for_quiz_runs_each_snippet() {
+        let bank = QuestionBank::new().with_item(BankItem::new(
+            predict("banked", Language::Python, "print(1)"),
+            QuestionDifficulty::Easy,
+        ));
+        let quiz = Quiz::new("q", "Quiz")
+            .with_question(predict("rust", Language::Rust, "fn main() {}"))
+            .with_question(predict("sql", Language::Sql, "SELECT 1"))
+            .with_draw(QuestionDraw::new(bank).with_rule(DrawRule::new(1)));
+
+        let cache = OutputCache::for_quiz(&quiz);
+        assert_eq!(cache.outputs.len(), 3);
+        assert!(cache.get("rust").is_some_and(Result::is_ok));
+        assert!(cache.get("banked").is_some());
+        assert!(cache.get("sql").is_some_and(Result::is_err));
+        assert!(cache.get("missing").is_none());
+    }
+
+    #[test]
+    fn test_run_snippet_error() {
+        assert_eq!(
+            run_snippet(Language::Rust, "", ""),
+            Err("Empty code".into())
+        );
+    }
+}
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_outputs_match_reflexive(text in "[a-z0-9 \\n]{0,40}") {
+            prop_assert!(outputs_match(&text, &text));
+            let padded = alloc::format!("\n{}  \n", text);
+            prop_assert!(outputs_match(&text, &padded));
+        }
+    }
+}
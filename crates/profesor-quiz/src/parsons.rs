@@ -0,0 +1,319 @@
+//! Parsons problem grading.
+//!
+//! A learner arranges code fragments and picks an indentation level for
+//! each. Without test cases the arrangement is compared line by line with
+//! the closest valid ordering; with test cases the assembled program is run
+//! in the lab sandbox instead, so any working arrangement is accepted.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use profesor_core::{CodeFragment, Feedback, Language, TestCase};
+use profesor_lab::Sandbox;
+
+/// Spaces per indentation level in assembled programs.
+const INDENT_WIDTH: usize = 4;
+
+/// Assemble an arrangement of fragments into program text.
+///
+/// Out-of-range fragment indices are skipped.
+#[must_use]
+pub fn assemble_program(fragments: &[CodeFragment], arrangement: &[(usize, usize)]) -> String {
+    let mut program = String::new();
+    for &(index, indent) in arrangement {
+        if let Some(fragment) = fragments.get(index) {
+            program.extend(core::iter::repeat(' ').take(indent * INDENT_WIDTH));
+            program.push_str(&fragment.code);
+            program.push('\n');
+        }
+    }
+    program
+}
+
+/// Question data needed to grade a Parsons answer.
+pub(crate) struct ParsonsProblem<'a> {
+    pub language: Language,
+    pub fragments: &'a [CodeFragment],
+    pub valid_orderings: &'a [Vec<usize>],
+    pub test_cases: &'a [TestCase],
+    pub explanation: &'a str,
+    pub points: u32,
+}
+
+impl ParsonsProblem<'_> {
+    /// Grade an arrangement.
+    pub(crate) fn grade(&self, arrangement: &[(usize, usize)]) -> Feedback {
+        let mut seen = Vec::new();
+        for &(index, _) in arrangement {
+            let Some(fragment) = self.fragments.get(index) else {
+                return Feedback::incorrect("Invalid answer type");
+            };
+            if seen.contains(&index) {
+                return Feedback::incorrect(alloc::format!(
+                    "`{}` is used more than once",
+                    fragment.code
+                ));
+            }
+            if fragment.distractor {
+                return Feedback::incorrect(alloc::format!(
+                    "`{}` does not belong in the solution",
+                    fragment.code
+                ));
+            }
+            seen.push(index);
+        }
+
+        if self.test_cases.is_empty() {
+            self.grade_lines(arrangement)
+        } else {
+            self.grade_tests(arrangement)
+        }
+    }
+
+    /// Run the assembled program against every test case.
+    fn grade_tests(&self, arrangement: &[(usize, usize)]) -> Feedback {
+        let program = assemble_program(self.fragments, arrangement);
+        let sandbox = Sandbox::new();
+        let failed: Vec<&str> = self
+            .test_cases
+            .iter()
+            .filter(|test| {
+                let result = sandbox.execute(&program, self.language, &test.input);
+                result.output().map(str::trim) != Some(test.expected_output.trim())
+            })
+            .map(|test| test.name.as_str())
+            .collect();
+
+        if failed.is_empty() {
+            Feedback::correct(self.explanation, self.points)
+        } else {
+            Feedback::incorrect(alloc::format!(
+                "Failed {} of {} tests: {}",
+                failed.len(),
+                self.test_cases.len(),
+                failed.join(", ")
+            ))
+        }
+    }
+
+    /// Compare the arrangement with the closest valid ordering.
+    fn grade_lines(&self, arrangement: &[(usize, usize)]) -> Feedback {
+        let authored: Vec<usize> = (0..self.fragments.len())
+            .filter(|&i| !self.fragments[i].distractor)
+            .collect();
+        let orderings: &[Vec<usize>] = if self.valid_orderings.is_empty() {
+            core::slice::from_ref(&authored)
+        } else {
+            self.valid_orderings
+        };
+
+        let Some(notes) = orderings
+            .iter()
+            .map(|ordering| self.line_notes(arrangement, ordering))
+            .min_by_key(Vec::len)
+        else {
+            return Feedback::incorrect(self.explanation);
+        };
+
+        if notes.is_empty() {
+            Feedback::correct(self.explanation, self.points)
+        } else {
+            Feedback::incorrect(notes.join("\n"))
+        }
+    }
+
+    /// Describe each line that differs from `ordering`.
+    fn line_notes(&self, arrangement: &[(usize, usize)], ordering: &[usize]) -> Vec<String> {
+        let placed: Vec<usize> = arrangement.iter().map(|&(index, _)| index).collect();
+        let in_place = longest_common_subsequence(&placed, ordering);
+        let mut notes = Vec::new();
+
+        for (line, &(index, indent)) in arrangement.iter().enumerate() {
+            let fragment = &self.fragments[index];
+            if !ordering.contains(&index) {
+                notes.push(alloc::format!(
+                    "Line {} (`{}`) is not part of this solution",
+                    line + 1,
+                    fragment.code
+                ));
+            } else if !in_place[line] {
+                notes.push(alloc::format!(
+                    "Line {} (`{}`) is out of place",
+                    line + 1,
+                    fragment.code
+                ));
+            } else if indent != fragment.indent {
+                notes.push(alloc::format!(
+                    "Line {} (`{}`) should be indented {} level{}",
+                    line + 1,
+                    fragment.code,
+                    fragment.indent,
+                    if fragment.indent == 1 { "" } else { "s" }
+                ));
+            }
+        }
+
+        let missing = ordering.iter().filter(|i| !placed.contains(i)).count();
+        if missing > 0 {
+            notes.push(alloc::format!(
+                "{} line{} missing",
+                missing,
+                if missing == 1 { " is" } else { "s are" }
+            ));
+        }
+        notes
+    }
+}
+
+/// Mark which elements of `answer` belong to a longest common subsequence
+/// with `expected`, i.e. which lines are already in the right relative order.
+fn longest_common_subsequence(answer: &[usize], expected: &[usize]) -> Vec<bool> {
+    let (n, m) = (answer.len(), expected.len());
+    let mut table = alloc::vec![alloc::vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            table[i][j] = if answer[i] == expected[j] {
+                table[i + 1][j + 1] + 1
+            } else {
+                table[i + 1][j].max(table[i][j + 1])
+            };
+        }
+    }
+
+    let mut keep = alloc::vec![false; n];
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if answer[i] == expected[j] {
+            keep[i] = true;
+            i += 1;
+            j += 1;
+        } else if table[i + 1][j] >= table[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    keep
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fragments() -> Vec<CodeFragment> {
+        alloc::vec![
+            CodeFragment::new("def total(xs):", 0),
+            CodeFragment::new("s = 0", 1),
+            CodeFragment::new("for x in xs:", 1),
+            CodeFragment::new("s += x", 2),
+            CodeFragment::new("return s", 1),
+            CodeFragment::distractor("return x"),
+        ]
+    }
+
+    fn problem<'a>(
+        fragments: &'a [CodeFragment],
+        orderings: &'a [Vec<usize>],
+        tests: &'a [TestCase],
+    ) -> ParsonsProblem<'a> {
+        ParsonsProblem {
+            language: Language::Python,
+            fragments,
+            valid_orderings: orderings,
+            test_cases: tests,
+            explanation: "Accumulate, then return",
+            points: 10,
+        }
+    }
+
+    fn solution() -> Vec<(usize, usize)> {
+        alloc::vec![(0, 0), (1, 1), (2, 1), (3, 2), (4, 1)]
+    }
+
+    #[test]
+    fn test_assemble_program() {
+        let program = assemble_program(&fragments(), &[(0, 0), (4, 1), (99, 0)]);
+        assert_eq!(program, "def total(xs):\n    return s\n");
+    }
+
+    #[test]
+    fn test_authored_order_is_default_solution() {
+        let fragments = fragments();
+        let feedback = problem(&fragments, &[], &[]).grade(&solution());
+        assert!(feedback.correct);
+        assert_eq!(feedback.points_earned, 10);
+    }
+
+    #[test]
+    fn test_alternative_ordering() {
+        let fragments = fragments();
+        let orderings = [alloc::vec![0, 1, 2, 3, 4], alloc::vec![0, 2, 3, 1, 4]];
+        let swapped = [(0, 0), (2, 1), (3, 2), (1, 1), (4, 1)];
+        assert!(problem(&fragments, &orderings, &[]).grade(&swapped).correct);
+        assert!(!problem(&fragments, &[], &[]).grade(&swapped).correct);
+    }
+
+    #[test]
+    fn test_distractor_named() {
+        let fragments = fragments();
+        let mut answer = solution();
+        answer[4] = (5, 1);
+        let feedback = problem(&fragments, &[], &[]).grade(&answer);
+        assert!(!feedback.correct);
+        assert!(feedback.explanation.contains("`return x` does not belong"));
+    }
+
+    #[test]
+    fn test_line_feedback() {
+        let fragments = fragments();
+        let answer = [(0, 0), (2, 1), (3, 1), (4, 1), (1, 1)];
+        let feedback = problem(&fragments, &[], &[]).grade(&answer);
+        assert!(!feedback.correct);
+        assert_eq!(
+            feedback.explanation,
+            "Line 3 (`s += x`) should be indented 2 levels\nLine 5 (`s = 0`) is out of place"
+        );
+    }
+
+    #[test]
+    fn test_missing_and_repeated_lines() {
+        let fragments = fragments();
+        let feedback = problem(&fragments, &[], &[]).grade(&[(0, 0), (1, 1)]);
+        assert_eq!(feedback.explanation, "3 lines are missing");
+
+        let feedback = problem(&fragments, &[], &[]).grade(&[(0, 0), (0, 0)]);
+        assert!(feedback.explanation.contains("more than once"));
+
+        let feedback = problem(&fragments, &[], &[]).grade(&[(42, 0)]);
+        assert!(!feedback.correct);
+    }
+
+    #[test]
+    fn test_graded_by_sandbox_tests() {
+        let fragments = fragments();
+        let tests = [TestCase::new("runs")];
+        // Any arrangement that produces the expected output passes.
+        let reordered = [(0, 0), (2, 1), (3, 2), (1, 1), (4, 1)];
+        assert!(problem(&fragments, &[], &tests).grade(&reordered).correct);
+
+        let tests = [TestCase::new("sums").with_expected("6")];
+        let feedback = problem(&fragments, &[], &tests).grade(&solution());
+        assert!(!feedback.correct);
+        assert_eq!(feedback.explanation, "Failed 1 of 1 tests: sums");
+    }
+}
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_lcs_marks_subsequence(answer in proptest::collection::vec(0usize..6, 0..8)) {
+            let expected = [0, 1, 2, 3, 4, 5];
+            let keep = longest_common_subsequence(&answer, &expected);
+            let kept: Vec<usize> = answer.iter().zip(&keep).filter(|(_, k)| **k).map(|(a, _)| *a).collect();
+            prop_assert!(kept.windows(2).all(|w| w[0] < w[1]));
+        }
+    }
+}
@@ -0,0 +1,294 @@
+//! Post-attempt review.
+//!
+//! After an attempt is finished the engine can step through each question
+//! with the learner's answer, the correct answer and notes on why each
+//! chosen distractor or wrong blank is wrong.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use profesor_core::{Answer, Feedback, HotspotClick, HotspotRegion, Question, TextMatcher};
+use serde::{Deserialize, Serialize};
+
+use crate::output::OutputCache;
+
+/// One question of a finished attempt, as shown in review.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuestionReview {
+    /// Position in the attempt (0-based)
+    pub index: usize,
+    /// The question
+    pub question: Question,
+    /// The learner's answer, if they gave one
+    pub answer: Option<Answer>,
+    /// Feedback for the answer
+    pub feedback: Feedback,
+    /// The correct answer, unless hidden while attempts remain
+    pub correct_answer: Option<Answer>,
+    /// Why each wrong choice or blank was wrong
+    pub notes: Vec<String>,
+}
+
+/// Get an answer that would be marked correct, if one can be stated.
+///
+/// Free-form code and regular-expression answers have no single correct
+/// answer; templates must be instantiated first.
+#[must_use]
+pub fn correct_answer(question: &Question, outputs: &OutputCache) -> Option<Answer> {
+    match question {
+        Question::MultipleChoice { correct, .. } => Some(Answer::Choice(*correct)),
+        Question::MultipleSelect { correct, .. } => Some(Answer::MultiChoice(correct.clone())),
+        Question::CodeCompletion { blanks, .. } => blanks
+            .iter()
+            .map(|blank| {
+                let first = blank.acceptable_answers.first()?;
+                Some((blank.id.clone(), first.clone()))
+            })
+            .collect::<Option<Vec<_>>>()
+            .map(Answer::Blanks),
+        Question::Ordering { correct_order, .. } => Some(Answer::Order(correct_order.clone())),
+        Question::Matching { correct_pairs, .. } => Some(Answer::Pairs(correct_pairs.clone())),
+        Question::Numeric { value, unit, .. } => Some(Answer::Numeric(match unit {
+            Some(unit) => alloc::format!("{} {}", value, unit),
+            None => alloc::format!("{}", value),
+        })),
+        Question::ShortAnswer { answers, .. } => answers
+            .iter()
+            .filter(|a| a.credit >= 1.0)
+            .find_map(|a| match &a.matcher {
+                TextMatcher::Exact(text) | TextMatcher::Normalized(text) => Some(text.clone()),
+                TextMatcher::Fuzzy { text, .. } => Some(text.clone()),
+                TextMatcher::Regex(_) => None,
+            })
+            .map(Answer::Text),
+        Question::Parsons {
+            fragments,
+            valid_orderings,
+            ..
+        } => {
+            let ordering: Vec<usize> = match valid_orderings.first() {
+                Some(ordering) => ordering.clone(),
+                None => (0..fragments.len())
+                    .filter(|&i| !fragments[i].distractor)
+                    .collect(),
+            };
+            ordering
+                .into_iter()
+                .map(|i| fragments.get(i).map(|f| (i, f.indent)))
+                .collect::<Option<Vec<_>>>()
+                .map(Answer::Arrangement)
+        }
+        Question::PredictOutput { id, .. } => match outputs.get(id.as_str()) {
+            Some(Ok(output)) => Some(Answer::Text(output.clone())),
+            _ => None,
+        },
+        Question::Categorize { correct, .. } => Some(Answer::Categories(
+            correct.iter().copied().enumerate().collect(),
+        )),
+        Question::Hotspot { regions, .. } => Some(Answer::Hotspots(
+            regions
+                .iter()
+                .map(|region| match region {
+                    HotspotRegion::Lines { start, .. } => HotspotClick::Line(*start),
+                    HotspotRegion::Rect {
+                        x,
+                        y,
+                        width,
+                        height,
+                    } => HotspotClick::Point {
+                        x: x + width / 2.0,
+                        y: y + height / 2.0,
+                    },
+                })
+                .collect(),
+        )),
+        Question::Hinted { question, .. } => correct_answer(question, outputs),
+        Question::FreeformCode { .. } | Question::Template(_) => None,
+    }
+}
+
+/// Explain each chosen distractor and wrongly filled blank.
+///
+/// Uses the question's per-option and per-blank explanations; choices
+/// without one are skipped.
+#[must_use]
+pub fn distractor_notes(question: &Question, answer: &Answer) -> Vec<String> {
+    let option_note = |options: &[String], explanations: &[String], index: usize| {
+        let explanation = explanations.get(index).filter(|e| !e.is_empty())?;
+        let option = options.get(index)?;
+        Some(alloc::format!("{}: {}", option, explanation))
+    };
+
+    match (question.unhinted(), answer.response()) {
+        (
+            Question::MultipleChoice {
+                options,
+                correct,
+                option_explanations,
+                ..
+            },
+            Answer::Choice(choice),
+        ) if choice != correct => option_note(options, option_explanations, *choice)
+            .into_iter()
+            .collect(),
+        (
+            Question::MultipleSelect {
+                options,
+                correct,
+                option_explanations,
+                ..
+            },
+            Answer::MultiChoice(choices),
+        ) => choices
+            .iter()
+            .filter(|choice| !correct.contains(choice))
+            .filter_map(|&choice| option_note(options, option_explanations, choice))
+            .collect(),
+        (Question::CodeCompletion { blanks, .. }, Answer::Blanks(filled)) => blanks
+            .iter()
+            .filter(|blank| !blank.explanation.is_empty())
+            .filter(|blank| {
+                !filled
+                    .iter()
+                    .find(|(id, _)| id == &blank.id)
+                    .is_some_and(|(_, answer)| blank.is_acceptable(answer))
+            })
+            .map(|blank| alloc::format!("{}: {}", blank.id, blank.explanation))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use profesor_core::{Blank, CodeFragment, Confidence, Language, QuestionHint};
+
+    fn multiple_choice() -> Question {
+        Question::MultipleChoice {
+            id: "q1".into(),
+            prompt: "Which type owns its data?".into(),
+            options: alloc::vec!["&str".into(), "String".into(), "&[u8]".into()],
+            correct: 1,
+            explanation: "String owns a heap buffer".into(),
+            points: 10,
+            option_explanations: alloc::vec!["A string slice borrows".into(), "".into(), "".into()],
+        }
+    }
+
+    #[test]
+    fn test_multiple_choice_notes() {
+        let question = multiple_choice();
+        assert_eq!(
+            distractor_notes(&question, &Answer::Choice(0)),
+            alloc::vec![String::from("&str: A string slice borrows")]
+        );
+        // Distractors without an explanation and correct answers have no notes.
+        assert!(distractor_notes(&question, &Answer::Choice(2)).is_empty());
+        assert!(distractor_notes(&question, &Answer::Choice(1)).is_empty());
+
+        let hinted = question.with_hint(QuestionHint::new("Think heap", 0.1));
+        let confident = Answer::Choice(0).with_confidence(Confidence::High);
+        assert_eq!(distractor_notes(&hinted, &confident).len(), 1);
+        assert_eq!(
+            correct_answer(&hinted, &OutputCache::new()),
+            Some(Answer::Choice(1))
+        );
+    }
+
+    #[test]
+    fn test_multiple_select_notes() {
+        let question = Question::MultipleSelect {
+            id: "q".into(),
+            prompt: "Which are Copy?".into(),
+            options: alloc::vec!["i32".into(), "String".into(), "bool".into()],
+            correct: alloc::vec![0, 2],
+            explanation: "".into(),
+            points: 5,
+            option_explanations: alloc::vec!["".into(), "String owns heap memory".into()],
+        };
+        let notes = distractor_notes(&question, &Answer::MultiChoice(alloc::vec![0, 1]));
+        assert_eq!(
+            notes,
+            alloc::vec![String::from("String: String owns heap memory")]
+        );
+    }
+
+    #[test]
+    fn test_blank_notes_and_answer() {
+        let question = Question::CodeCompletion {
+            id: "q".into(),
+            prompt: "Borrow it".into(),
+            code_template: "let r = __x;".into(),
+            blanks: alloc::vec![
+                Blank::new("b1")
+                    .with_answer("&")
+                    .with_explanation("Borrowing uses &"),
+                Blank::new("b2").with_answer("mut"),
+            ],
+            test_cases: alloc::vec![],
+            points: 5,
+            explanation: "".into(),
+        };
+        let answer = Answer::Blanks(alloc::vec![("b1".into(), "*".into())]);
+        assert_eq!(
+            distractor_notes(&question, &answer),
+            alloc::vec![String::from("b1: Borrowing uses &")]
+        );
+        assert_eq!(
+            correct_answer(&question, &OutputCache::new()),
+            Some(Answer::Blanks(alloc::vec![
+                ("b1".into(), "&".into()),
+                ("b2".into(), "mut".into()),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_correct_answers() {
+        let outputs = OutputCache::new();
+        let numeric = Question::Numeric {
+            id: "n".into(),
+            prompt: "g?".into(),
+            value: 9.81,
+            tolerance: profesor_core::Tolerance::Absolute(0.01),
+            sig_figs: None,
+            unit: Some("m/s^2".into()),
+            explanation: "".into(),
+            points: 1,
+        };
+        assert_eq!(
+            correct_answer(&numeric, &outputs),
+            Some(Answer::Numeric("9.81 m/s^2".into()))
+        );
+
+        let parsons = Question::Parsons {
+            id: "p".into(),
+            prompt: "Arrange".into(),
+            language: Language::Python,
+            fragments: alloc::vec![
+                CodeFragment::new("def f():", 0),
+                CodeFragment::distractor("pass"),
+                CodeFragment::new("return 1", 1),
+            ],
+            valid_orderings: alloc::vec![],
+            test_cases: alloc::vec![],
+            explanation: "".into(),
+            points: 1,
+        };
+        assert_eq!(
+            correct_answer(&parsons, &outputs),
+            Some(Answer::Arrangement(alloc::vec![(0, 0), (2, 1)]))
+        );
+
+        let freeform = Question::FreeformCode {
+            id: "f".into(),
+            prompt: "Write".into(),
+            language: Language::Rust,
+            starter_code: "".into(),
+            test_cases: alloc::vec![],
+            hidden_test_cases: alloc::vec![],
+            points: 1,
+        };
+        assert_eq!(correct_answer(&freeform, &outputs), None);
+    }
+}
@@ -0,0 +1,541 @@
+//! Sealed quizzes.
+//!
+//! A sealed quiz can be shipped to the client without its answer key.
+//! Correct answers are replaced by salted SHA-256 hashes that the
+//! [`Grader`](crate::Grader) checks submissions against, and explanations
+//! are encrypted per question. The host keeps the [`AnswerKey`] and
+//! releases a question's [`ExplanationKey`] once an answer has been
+//! submitted.
+//!
+//! Hashing keeps answers out of plain sight, but questions with few
+//! possible answers (e.g. four options) can still be enumerated by a
+//! determined learner; grade high-stakes quizzes on the server.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use profesor_core::{Answer, Question, QuestionId, Quiz, QuizId};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Current sealed quiz format version.
+pub const SEALED_VERSION: u32 = 1;
+
+/// A quiz whose answers and explanations are hidden.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SealedQuiz {
+    /// Sealed format version
+    pub version: u32,
+    /// The quiz with answers and explanations removed
+    pub quiz: Quiz,
+    /// Hex-encoded salt mixed into every answer hash
+    pub salt: String,
+    /// Answer hashes and encrypted explanations, by question
+    pub questions: Vec<SealedQuestion>,
+}
+
+/// The hidden parts of one question.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SealedQuestion {
+    /// Question these belong to
+    pub question_id: QuestionId,
+    /// Hash of the correct answer
+    pub answer: SealedAnswer,
+    /// Encrypted explanation
+    pub explanation: Vec<u8>,
+    /// Hex-encoded hash identifying the explanation key
+    pub key_check: String,
+}
+
+/// Hashed correct answer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SealedAnswer {
+    /// Hash of the single correct answer
+    Exact(String),
+    /// Hashes of the acceptable answers for each blank, as (blank ID, hashes)
+    Blanks(Vec<(String, Vec<String>)>),
+}
+
+/// Secret that releases explanations; never shipped to the client.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnswerKey {
+    /// Quiz the key belongs to
+    pub quiz_id: QuizId,
+    secret: [u8; 32],
+}
+
+/// Key that decrypts one question's explanation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExplanationKey([u8; 32]);
+
+/// Reasons a quiz cannot be sealed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SealError {
+    /// The question's answer cannot be checked by hash (e.g. numeric tolerance)
+    Unsupported(QuestionId),
+    /// The quiz draws questions from a bank, which is not sealed
+    BankNotSupported,
+}
+
+impl core::fmt::Display for SealError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Unsupported(id) => write!(f, "Question '{}' cannot be sealed", id),
+            Self::BankNotSupported => write!(f, "Quizzes with a question bank cannot be sealed"),
+        }
+    }
+}
+
+impl AnswerKey {
+    /// Release the key for a question's explanation.
+    #[must_use]
+    pub fn release(&self, question_id: &QuestionId) -> ExplanationKey {
+        ExplanationKey(
+            Sha256::new()
+                .chain_update(b"explanation")
+                .chain_update(self.secret)
+                .chain_update(question_id.as_str())
+                .finalize()
+                .into(),
+        )
+    }
+}
+
+impl SealedQuiz {
+    /// Seal a quiz.
+    ///
+    /// `salt` and `secret` should be random and unique per quiz; the salt
+    /// ships with the quiz, the secret stays in the returned key.
+    pub fn seal(
+        quiz: &Quiz,
+        salt: &[u8],
+        secret: [u8; 32],
+    ) -> Result<(Self, AnswerKey), SealError> {
+        if quiz.draw.is_some() {
+            return Err(SealError::BankNotSupported);
+        }
+
+        let key = AnswerKey {
+            quiz_id: quiz.id.clone(),
+            secret,
+        };
+        let salt = to_hex(salt);
+        let mut public = quiz.clone();
+        let mut questions = Vec::with_capacity(quiz.questions.len());
+
+        for question in &mut public.questions {
+            let id = question.id().clone();
+            let answer = seal_answer(&salt, &id, question.unhinted())
+                .ok_or_else(|| SealError::Unsupported(id.clone()))?;
+            let explanation = scrub(question);
+            let explanation_key = key.release(&id);
+            questions.push(SealedQuestion {
+                answer,
+                explanation: apply_keystream(&explanation_key, explanation.as_bytes()),
+                key_check: key_check(&explanation_key),
+                question_id: id,
+            });
+        }
+
+        let sealed = Self {
+            version: SEALED_VERSION,
+            quiz: public,
+            salt,
+            questions,
+        };
+        Ok((sealed, key))
+    }
+
+    /// Get the sealed parts of a question.
+    #[must_use]
+    pub fn question(&self, question_id: &QuestionId) -> Option<&SealedQuestion> {
+        self.questions
+            .iter()
+            .find(|q| &q.question_id == question_id)
+    }
+
+    /// Check an answer against its hash.
+    ///
+    /// Confidence levels are ignored.
+    #[must_use]
+    pub fn check(&self, question_id: &QuestionId, answer: &Answer) -> bool {
+        let Some(sealed) = self.question(question_id) else {
+            return false;
+        };
+        match (&sealed.answer, answer.response()) {
+            (SealedAnswer::Blanks(expected), Answer::Blanks(filled)) => {
+                expected.iter().all(|(blank_id, hashes)| {
+                    filled
+                        .iter()
+                        .find(|(id, _)| id == blank_id)
+                        .is_some_and(|(_, text)| {
+                            let hash = hash_blank(&self.salt, question_id, blank_id, text);
+                            hashes.contains(&hash)
+                        })
+                })
+            }
+            (SealedAnswer::Exact(expected), response) => canonical(response)
+                .is_some_and(|text| &hash_answer(&self.salt, question_id, &text) == expected),
+            _ => false,
+        }
+    }
+
+    /// Decrypt a question's explanation with its released key.
+    ///
+    /// Returns `None` if the key does not belong to the question.
+    #[must_use]
+    pub fn explanation(&self, question_id: &QuestionId, key: &ExplanationKey) -> Option<String> {
+        let sealed = self.question(question_id)?;
+        if key_check(key) != sealed.key_check {
+            return None;
+        }
+        String::from_utf8(apply_keystream(key, &sealed.explanation)).ok()
+    }
+}
+
+/// Hash the correct answer of a question, if it can be sealed.
+fn seal_answer(salt: &str, id: &QuestionId, question: &Question) -> Option<SealedAnswer> {
+    let correct = match question {
+        Question::MultipleChoice { correct, .. } => Answer::Choice(*correct),
+        Question::MultipleSelect { correct, .. } => Answer::MultiChoice(correct.clone()),
+        Question::Ordering { correct_order, .. } => Answer::Order(correct_order.clone()),
+        Question::Matching { correct_pairs, .. } => Answer::Pairs(correct_pairs.clone()),
+        Question::CodeCompletion { blanks, .. } => {
+            return Some(SealedAnswer::Blanks(
+                blanks
+                    .iter()
+                    .map(|blank| {
+                        let hashes = blank
+                            .acceptable_answers
+                            .iter()
+                            .map(|text| hash_blank(salt, id, &blank.id, text))
+                            .collect();
+                        (blank.id.clone(), hashes)
+                    })
+                    .collect(),
+            ))
+        }
+        _ => return None,
+    };
+    let text = canonical(&correct)?;
+    Some(SealedAnswer::Exact(hash_answer(salt, id, &text)))
+}
+
+/// Remove the answer and explanations from a question, returning the
+/// explanation to encrypt.
+///
+/// Hinted questions are scrubbed through every layer; the hints stay, as
+/// they are shown to the learner anyway.
+fn scrub(question: &mut Question) -> String {
+    match question {
+        Question::Hinted { question, .. } => scrub(question),
+        Question::MultipleChoice {
+            correct,
+            explanation,
+            option_explanations,
+            ..
+        } => {
+            *correct = 0;
+            option_explanations.clear();
+            core::mem::take(explanation)
+        }
+        Question::MultipleSelect {
+            correct,
+            explanation,
+            option_explanations,
+            ..
+        } => {
+            correct.clear();
+            option_explanations.clear();
+            core::mem::take(explanation)
+        }
+        Question::Ordering {
+            correct_order,
+            explanation,
+            ..
+        } => {
+            correct_order.clear();
+            core::mem::take(explanation)
+        }
+        Question::Matching {
+            correct_pairs,
+            explanation,
+            ..
+        } => {
+            correct_pairs.clear();
+            core::mem::take(explanation)
+        }
+        Question::CodeCompletion {
+            blanks,
+            explanation,
+            ..
+        } => {
+            for blank in blanks.iter_mut() {
+                blank.acceptable_answers.clear();
+                blank.explanation.clear();
+            }
+            core::mem::take(explanation)
+        }
+        _ => String::new(),
+    }
+}
+
+/// Canonical text of an answer, independent of selection order.
+///
+/// Selections that repeat an option have none, as the unsealed grader never
+/// accepts them.
+fn canonical(answer: &Answer) -> Option<String> {
+    let join = |values: &mut dyn Iterator<Item = String>| values.collect::<Vec<_>>().join(",");
+    match answer {
+        Answer::Choice(choice) => Some(alloc::format!("choice:{}", choice)),
+        Answer::MultiChoice(choices) => {
+            let mut sorted = choices.clone();
+            sorted.sort_unstable();
+            if sorted.windows(2).any(|pair| pair[0] == pair[1]) {
+                return None;
+            }
+            Some(alloc::format!(
+                "multi:{}",
+                join(&mut sorted.iter().map(|c| alloc::format!("{}", c)))
+            ))
+        }
+        Answer::Order(order) => Some(alloc::format!(
+            "order:{}",
+            join(&mut order.iter().map(|i| alloc::format!("{}", i)))
+        )),
+        Answer::Pairs(pairs) => {
+            let mut sorted = pairs.clone();
+            sorted.sort_unstable();
+            Some(alloc::format!(
+                "pairs:{}",
+                join(&mut sorted.iter().map(|(l, r)| alloc::format!("{}-{}", l, r)))
+            ))
+        }
+        _ => None,
+    }
+}
+
+fn hash_answer(salt: &str, question_id: &QuestionId, text: &str) -> String {
+    digest_hex(&[salt, question_id.as_str(), text])
+}
+
+fn hash_blank(salt: &str, question_id: &QuestionId, blank_id: &str, text: &str) -> String {
+    digest_hex(&[salt, question_id.as_str(), blank_id, text])
+}
+
+/// Hash length-prefixed parts, so part boundaries cannot be shifted.
+fn digest_hex(parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part.as_bytes());
+    }
+    to_hex(&hasher.finalize())
+}
+
+fn key_check(key: &ExplanationKey) -> String {
+    to_hex(
+        &Sha256::new()
+            .chain_update(b"check")
+            .chain_update(key.0)
+            .finalize(),
+    )
+}
+
+/// XOR data with a SHA-256 counter-mode keystream; encrypts and decrypts.
+fn apply_keystream(key: &ExplanationKey, data: &[u8]) -> Vec<u8> {
+    data.chunks(32)
+        .zip(0u64..)
+        .flat_map(|(chunk, counter)| {
+            let block = Sha256::new()
+                .chain_update(key.0)
+                .chain_update(counter.to_le_bytes())
+                .finalize();
+            chunk
+                .iter()
+                .zip(block)
+                .map(|(byte, pad)| byte ^ pad)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        hex.push(char::from(DIGITS[usize::from(byte >> 4)]));
+        hex.push(char::from(DIGITS[usize::from(byte & 0x0f)]));
+    }
+    hex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grader::Grader;
+    use profesor_core::{Blank, QuestionHint, Tolerance};
+
+    const SECRET: [u8; 32] = [7; 32];
+
+    fn quiz() -> Quiz {
+        Quiz::new("sealed", "Sealed")
+            .with_question(Question::MultipleChoice {
+                id: "mc".into(),
+                prompt: "Which owns?".into(),
+                options: alloc::vec!["&str".into(), "String".into()],
+                correct: 1,
+                explanation: "String owns its buffer".into(),
+                points: 10,
+                option_explanations: alloc::vec!["Slices borrow".into()],
+            })
+            .with_question(Question::MultipleSelect {
+                id: "ms".into(),
+                prompt: "Which are Copy?".into(),
+                options: alloc::vec!["i32".into(), "Vec".into(), "bool".into()],
+                correct: alloc::vec![2, 0],
+                explanation: "".into(),
+                points: 5,
+                option_explanations: Vec::new(),
+            })
+            .with_question(Question::CodeCompletion {
+                id: "cc".into(),
+                prompt: "Borrow".into(),
+                code_template: "let r = __x;".into(),
+                blanks: alloc::vec![Blank::new("b1").with_answer("&").with_answer("& ")],
+                test_cases: alloc::vec![],
+                points: 5,
+                explanation: "Use a reference".into(),
+            })
+    }
+
+    #[test]
+    fn test_seal_removes_answers() {
+        let (sealed, _) = SealedQuiz::seal(&quiz(), b"salt", SECRET).unwrap();
+        let yaml = serde_yaml::to_string(&sealed).unwrap();
+        assert!(!yaml.contains("String owns"));
+        assert!(!yaml.contains("Slices borrow"));
+        assert!(!yaml.contains("Use a reference"));
+        assert_eq!(sealed.salt, "73616c74");
+        match &sealed.quiz.questions[2] {
+            Question::CodeCompletion { blanks, .. } => {
+                assert!(blanks[0].acceptable_answers.is_empty())
+            }
+            other => assert_eq!(other.id().as_str(), "cc"),
+        }
+    }
+
+    #[test]
+    fn test_check_answers() {
+        let (sealed, _) = SealedQuiz::seal(&quiz(), b"salt", SECRET).unwrap();
+        assert!(sealed.check(&"mc".into(), &Answer::Choice(1)));
+        assert!(!sealed.check(&"mc".into(), &Answer::Choice(0)));
+        assert!(sealed.check(&"ms".into(), &Answer::MultiChoice(alloc::vec![0, 2])));
+        assert!(!sealed.check(&"ms".into(), &Answer::MultiChoice(alloc::vec![0])));
+        assert!(!sealed.check(&"ms".into(), &Answer::MultiChoice(alloc::vec![0, 0, 2])));
+        let blanks = |text: &str| Answer::Blanks(alloc::vec![("b1".into(), text.into())]);
+        assert!(sealed.check(&"cc".into(), &blanks("&")));
+        assert!(!sealed.check(&"cc".into(), &blanks("*")));
+        assert!(!sealed.check(&"mc".into(), &blanks("&")));
+        assert!(!sealed.check(&"missing".into(), &Answer::Choice(1)));
+    }
+
+    #[test]
+    fn test_repeated_selections_agree_with_grader() {
+        let quiz = quiz();
+        let (sealed, _) = SealedQuiz::seal(&quiz, b"salt", SECRET).unwrap();
+        let repeated = Answer::MultiChoice(alloc::vec![0, 0, 2]);
+        assert_eq!(
+            sealed.check(&"ms".into(), &repeated),
+            Grader::grade_answer(&quiz.questions[1], &repeated).correct
+        );
+    }
+
+    #[test]
+    fn test_seal_scrubs_nested_hints() {
+        let nested = Question::MultipleChoice {
+            id: "h".into(),
+            prompt: "Which borrows?".into(),
+            options: alloc::vec!["&T".into(), "T".into()],
+            correct: 0,
+            explanation: "A reference borrows".into(),
+            points: 10,
+            option_explanations: alloc::vec!["Shared borrow".into()],
+        }
+        .with_hint(QuestionHint::new("Look for &", 0.5))
+        .with_hint(QuestionHint::new("It is the first", 0.5));
+        let nested = Question::Hinted {
+            question: alloc::boxed::Box::new(nested),
+            hints: alloc::vec![QuestionHint::new("Outer", 0.1)],
+        };
+        let quiz = Quiz::new("hinted", "Hinted").with_question(nested);
+
+        let (sealed, key) = SealedQuiz::seal(&quiz, b"salt", SECRET).unwrap();
+        let yaml = serde_yaml::to_string(&sealed).unwrap();
+        assert!(!yaml.contains("A reference borrows"));
+        assert!(!yaml.contains("Shared borrow"));
+        let id: QuestionId = "h".into();
+        assert_eq!(
+            sealed.explanation(&id, &key.release(&id)).as_deref(),
+            Some("A reference borrows")
+        );
+        assert!(sealed.check(&id, &Answer::Choice(0)));
+    }
+
+    #[test]
+    fn test_salt_changes_hashes() {
+        let (a, _) = SealedQuiz::seal(&quiz(), b"one", SECRET).unwrap();
+        let (b, _) = SealedQuiz::seal(&quiz(), b"two", SECRET).unwrap();
+        assert_ne!(a.questions[0].answer, b.questions[0].answer);
+    }
+
+    #[test]
+    fn test_explanations_need_released_key() {
+        let (sealed, key) = SealedQuiz::seal(&quiz(), b"salt", SECRET).unwrap();
+        let mc: QuestionId = "mc".into();
+        assert_eq!(
+            sealed.explanation(&mc, &key.release(&mc)).as_deref(),
+            Some("String owns its buffer")
+        );
+        assert_eq!(sealed.explanation(&mc, &key.release(&"cc".into())), None);
+
+        let (_, other) = SealedQuiz::seal(&quiz(), b"salt", [9; 32]).unwrap();
+        assert_eq!(sealed.explanation(&mc, &other.release(&mc)), None);
+    }
+
+    #[test]
+    fn test_unsupported_questions() {
+        let quiz = quiz().with_question(Question::Numeric {
+            id: "n".into(),
+            prompt: "g?".into(),
+            value: 9.81,
+            tolerance: Tolerance::Absolute(0.1),
+            sig_figs: None,
+            unit: None,
+            explanation: "".into(),
+            points: 1,
+        });
+        assert_eq!(
+            SealedQuiz::seal(&quiz, b"salt", SECRET).err(),
+            Some(SealError::Unsupported("n".into()))
+        );
+        assert_eq!(
+            alloc::format!("{}", SealError::Unsupported("n".into())),
+            "Question 'n' cannot be sealed"
+        );
+    }
+}
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_keystream_round_trip(data in proptest::collection::vec(any::<u8>(), 0..100), seed in any::<u8>()) {
+            let key = ExplanationKey([seed; 32]);
+            let sealed = apply_keystream(&key, &data);
+            prop_assert_eq!(apply_keystream(&key, &sealed), data);
+        }
+    }
+}
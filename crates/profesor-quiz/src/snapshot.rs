@@ -0,0 +1,119 @@
+//! Saved quiz engine state.
+//!
+//! A snapshot captures an in-progress attempt so it survives a page reload.
+//! It records questions by ID and points rather than full content; resuming
+//! draws them again from the live `Quiz` and rejects the snapshot if they no
+//! longer match.
+
+use alloc::vec::Vec;
+use profesor_core::progress::Timestamp;
+use profesor_core::{
+    AbilityEstimate, DrawError, ItemParameters, QuestionId, QuestionOutcome, QuizId,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::audit::AuditLog;
+use crate::engine::QuizState;
+use crate::envelope::SignedGrant;
+
+/// Current snapshot format version.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Serializable state of a [`QuizEngine`](crate::QuizEngine).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuizSnapshot {
+    /// Snapshot format version
+    pub version: u32,
+    /// Quiz the snapshot belongs to
+    pub quiz_id: QuizId,
+    /// Engine state, including the current question, answers and feedback
+    pub state: QuizState,
+    /// Attempts started so far
+    pub attempt_count: u32,
+    /// Base seed for drawing attempts
+    pub seed: u64,
+    /// Seed of the current attempt
+    pub attempt_seed: Option<u64>,
+    /// Grant the current attempt was started from
+    #[serde(default)]
+    pub grant: Option<SignedGrant>,
+    /// Presented questions as (ID, points), in order
+    pub questions: Vec<(QuestionId, u32)>,
+    /// Adaptive test state, if the quiz is adaptive
+    pub adaptive: Option<AdaptiveSnapshot>,
+    /// Outcomes of the last finished attempt
+    pub outcomes: Vec<QuestionOutcome>,
+    /// When the time limit runs out
+    pub deadline: Option<Timestamp>,
+    /// When the last attempt finished, for the attempt cooldown
+    #[serde(default)]
+    pub finished_at: Option<Timestamp>,
+    /// Events recorded so far
+    #[serde(default)]
+    pub log: AuditLog,
+}
+
+/// Serializable state of an adaptive attempt.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AdaptiveSnapshot {
+    /// Questions not yet presented, as (ID, points)
+    pub pool: Vec<(QuestionId, u32)>,
+    /// Scored responses, parallel to the presented questions
+    pub responses: Vec<Option<(ItemParameters, bool)>>,
+    /// Current ability estimate
+    pub estimate: AbilityEstimate,
+}
+
+/// Reasons a snapshot cannot be resumed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResumeError {
+    /// The snapshot was written by an unsupported format version
+    UnsupportedVersion(u32),
+    /// The snapshot belongs to a different quiz
+    QuizMismatch {
+        /// ID of the quiz being resumed
+        expected: QuizId,
+        /// ID recorded in the snapshot
+        found: QuizId,
+    },
+    /// Questions were added, removed or replaced since the snapshot
+    QuestionsChanged,
+    /// A question's points changed since the snapshot
+    PointsChanged {
+        /// The changed question
+        question_id: QuestionId,
+        /// Points recorded in the snapshot
+        expected: u32,
+        /// Points in the quiz now
+        found: u32,
+    },
+    /// The attempt's questions could not be drawn again
+    DrawFailed(DrawError),
+    /// The saved state does not fit the saved questions
+    InconsistentState,
+}
+
+impl core::fmt::Display for ResumeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::UnsupportedVersion(version) => {
+                write!(f, "Unsupported snapshot version {}", version)
+            }
+            Self::QuizMismatch { expected, found } => {
+                write!(f, "Snapshot is for quiz '{}', not '{}'", found, expected)
+            }
+            Self::QuestionsChanged => write!(f, "Quiz questions changed since the snapshot"),
+            Self::PointsChanged {
+                question_id,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Question '{}' is now worth {} points instead of {}",
+                question_id, found, expected
+            ),
+            Self::DrawFailed(err) => write!(f, "Could not draw questions: {}", err),
+            Self::InconsistentState => write!(f, "Snapshot state is inconsistent"),
+        }
+    }
+}
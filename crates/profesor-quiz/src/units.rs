@@ -0,0 +1,327 @@
+//! Physical units for numeric answers.
+//!
+//! A small table of common units with their SI conversion factors, enough
+//! to accept "72 km/h" for an answer of 20 m/s and to recognise when a
+//! learner wrote the right number in the wrong unit.
+
+use alloc::string::String;
+use serde::{Deserialize, Serialize};
+
+/// Physical quantity a unit measures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Dimension {
+    /// Length
+    Length,
+    /// Time
+    Time,
+    /// Mass
+    Mass,
+    /// Velocity
+    Velocity,
+    /// Acceleration
+    Acceleration,
+    /// Force
+    Force,
+    /// Energy
+    Energy,
+    /// Power
+    Power,
+}
+
+impl Dimension {
+    /// Get a human-readable label.
+    #[must_use]
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Length => "length",
+            Self::Time => "time",
+            Self::Mass => "mass",
+            Self::Velocity => "velocity",
+            Self::Acceleration => "acceleration",
+            Self::Force => "force",
+            Self::Energy => "energy",
+            Self::Power => "power",
+        }
+    }
+}
+
+/// A unit and its conversion to SI.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Unit {
+    /// Symbol as written in answers
+    pub symbol: &'static str,
+    /// Quantity measured
+    pub dimension: Dimension,
+    /// Multiply by this to convert to the SI unit
+    pub to_si: f64,
+}
+
+const fn unit(symbol: &'static str, dimension: Dimension, to_si: f64) -> Unit {
+    Unit {
+        symbol,
+        dimension,
+        to_si,
+    }
+}
+
+/// Known units. Symbols are case-sensitive (`m` is not `M`).
+const UNITS: &[Unit] = &[
+    unit("m", Dimension::Length, 1.0),
+    unit("km", Dimension::Length, 1000.0),
+    unit("cm", Dimension::Length, 0.01),
+    unit("mm", Dimension::Length, 0.001),
+    unit("ft", Dimension::Length, 0.3048),
+    unit("in", Dimension::Length, 0.0254),
+    unit("mi", Dimension::Length, 1609.344),
+    unit("s", Dimension::Time, 1.0),
+    unit("ms", Dimension::Time, 0.001),
+    unit("min", Dimension::Time, 60.0),
+    unit("h", Dimension::Time, 3600.0),
+    unit("kg", Dimension::Mass, 1.0),
+    unit("g", Dimension::Mass, 0.001),
+    unit("lb", Dimension::Mass, 0.453_592_37),
+    unit("m/s", Dimension::Velocity, 1.0),
+    unit("km/h", Dimension::Velocity, 1000.0 / 3600.0),
+    unit("kph", Dimension::Velocity, 1000.0 / 3600.0),
+    unit("mph", Dimension::Velocity, 0.447_04),
+    unit("ft/s", Dimension::Velocity, 0.3048),
+    unit("m/s^2", Dimension::Acceleration, 1.0),
+    unit("m/s²", Dimension::Acceleration, 1.0),
+    unit("N", Dimension::Force, 1.0),
+    unit("kN", Dimension::Force, 1000.0),
+    unit("J", Dimension::Energy, 1.0),
+    unit("kJ", Dimension::Energy, 1000.0),
+    unit("W", Dimension::Power, 1.0),
+    unit("kW", Dimension::Power, 1000.0),
+];
+
+/// Look up a unit by symbol.
+#[must_use]
+pub fn lookup(symbol: &str) -> Option<&'static Unit> {
+    UNITS.iter().find(|u| u.symbol == symbol.trim())
+}
+
+/// Convert a value between units of the same dimension.
+pub fn convert(value: f64, from: &str, to: &str) -> Result<f64, UnitError> {
+    let source = lookup(from).ok_or_else(|| UnitError::Unknown(from.into()))?;
+    let target = lookup(to).ok_or_else(|| UnitError::Unknown(to.into()))?;
+    if source.dimension != target.dimension {
+        return Err(UnitError::Incompatible {
+            from: source.dimension,
+            to: target.dimension,
+        });
+    }
+    Ok(value * source.to_si / target.to_si)
+}
+
+/// Errors from unit conversion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnitError {
+    /// The symbol is not a known unit
+    Unknown(String),
+    /// The units measure different quantities
+    Incompatible {
+        /// Dimension of the source unit
+        from: Dimension,
+        /// Dimension of the target unit
+        to: Dimension,
+    },
+}
+
+impl core::fmt::Display for UnitError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Unknown(symbol) => write!(f, "Unknown unit '{}'", symbol),
+            Self::Incompatible { from, to } => {
+                write!(f, "Cannot convert {} to {}", from.label(), to.label())
+            }
+        }
+    }
+}
+
+/// A number typed by a learner, with its precision and unit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quantity {
+    /// Numeric value
+    pub value: f64,
+    /// Significant figures as written
+    pub sig_figs: u32,
+    /// Unit text following the number, if any
+    pub unit: Option<String>,
+}
+
+/// Parse an answer such as `"19.6 m/s"`, `"-3"` or `"1.20e3 J"`.
+#[must_use]
+pub fn parse_quantity(text: &str) -> Option<Quantity> {
+    let text = text.trim();
+    let end = number_end(text);
+    let number = &text[..end];
+    let value: f64 = number.parse().ok()?;
+    let unit = text[end..].trim();
+
+    Some(Quantity {
+        value,
+        sig_figs: significant_figures(number),
+        unit: (!unit.is_empty()).then(|| unit.into()),
+    })
+}
+
+/// Find where the leading number of `text` ends.
+fn number_end(text: &str) -> usize {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    if matches!(bytes.first(), Some(b'+' | b'-')) {
+        i += 1;
+    }
+    while i < bytes.len() && (bytes[i].is_ascii_digit() || bytes[i] == b'.') {
+        i += 1;
+    }
+    // Only treat `e` as an exponent when digits follow, so "5 e" stays a unit.
+    if matches!(bytes.get(i), Some(b'e' | b'E')) {
+        let mut j = i + 1;
+        if matches!(bytes.get(j), Some(b'+' | b'-')) {
+            j += 1;
+        }
+        if bytes.get(j).is_some_and(u8::is_ascii_digit) {
+            while j < bytes.len() && bytes[j].is_ascii_digit() {
+                j += 1;
+            }
+            i = j;
+        }
+    }
+    i
+}
+
+/// Count significant figures in a number as written.
+///
+/// Leading zeros never count; trailing zeros count only after a decimal
+/// point, so `"1200"` has 2 but `"1200."` and `"1.200e3"` have 4.
+#[must_use]
+pub fn significant_figures(number: &str) -> u32 {
+    let mantissa = number
+        .split(['e', 'E'])
+        .next()
+        .unwrap_or("")
+        .trim_start_matches(['+', '-']);
+    let has_point = mantissa.contains('.');
+    let digits: String = mantissa.chars().filter(char::is_ascii_digit).collect();
+    let significant = digits.trim_start_matches('0');
+
+    if significant.is_empty() {
+        // A written zero: "0.00" shows two decimal places of precision.
+        let decimals = mantissa.split('.').nth(1).map_or(0, str::len);
+        return decimals.max(1) as u32;
+    }
+
+    if has_point {
+        significant.len() as u32
+    } else {
+        significant.trim_end_matches('0').len() as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_velocity() {
+        let mps = convert(72.0, "km/h", "m/s").unwrap();
+        assert!((mps - 20.0).abs() < 1e-9);
+        let kmh = convert(20.0, "m/s", "km/h").unwrap();
+        assert!((kmh - 72.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_convert_errors() {
+        assert_eq!(
+            convert(1.0, "furlong", "m"),
+            Err(UnitError::Unknown("furlong".into()))
+        );
+        assert_eq!(
+            convert(1.0, "s", "m"),
+            Err(UnitError::Incompatible {
+                from: Dimension::Time,
+                to: Dimension::Length,
+            })
+        );
+    }
+
+    #[test]
+    fn test_lookup_aliases() {
+        assert_eq!(
+            lookup("m/s²").map(|u| u.dimension),
+            Some(Dimension::Acceleration)
+        );
+        assert_eq!(
+            lookup("m/s^2").map(|u| u.dimension),
+            Some(Dimension::Acceleration)
+        );
+        assert!(lookup("M").is_none());
+    }
+
+    #[test]
+    fn test_parse_quantity() {
+        let q = parse_quantity(" 19.6 m/s ").unwrap();
+        assert!((q.value - 19.6).abs() < 1e-12);
+        assert_eq!(q.sig_figs, 3);
+        assert_eq!(q.unit.as_deref(), Some("m/s"));
+
+        let q = parse_quantity("-1.20e3J").unwrap();
+        assert!((q.value + 1200.0).abs() < 1e-9);
+        assert_eq!(q.unit.as_deref(), Some("J"));
+
+        let q = parse_quantity("42").unwrap();
+        assert_eq!(q.unit, None);
+    }
+
+    #[test]
+    fn test_parse_quantity_invalid() {
+        assert!(parse_quantity("").is_none());
+        assert!(parse_quantity("fast").is_none());
+        assert!(parse_quantity("m/s 12").is_none());
+    }
+
+    #[test]
+    fn test_significant_figures() {
+        assert_eq!(significant_figures("19.6"), 3);
+        assert_eq!(significant_figures("0.0050"), 2);
+        assert_eq!(significant_figures("1200"), 2);
+        assert_eq!(significant_figures("1200."), 4);
+        assert_eq!(significant_figures("1.200e3"), 4);
+        assert_eq!(significant_figures("-20.0"), 3);
+        assert_eq!(significant_figures("0"), 1);
+        assert_eq!(significant_figures("0.00"), 2);
+    }
+
+    #[test]
+    fn test_unit_error_display() {
+        assert_eq!(
+            alloc::format!("{}", UnitError::Unknown("xyz".into())),
+            "Unknown unit 'xyz'"
+        );
+    }
+}
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_round_trip_conversion(value in -1e6f64..1e6) {
+            let there = convert(value, "m/s", "km/h").unwrap();
+            let back = convert(there, "km/h", "m/s").unwrap();
+            prop_assert!((back - value).abs() <= 1e-9 * value.abs().max(1.0));
+        }
+
+        #[test]
+        fn test_parse_formatted_number(value in -1e6f64..1e6) {
+            let text = alloc::format!("{} m", value);
+            let q = parse_quantity(&text).unwrap();
+            prop_assert_eq!(q.value, value);
+            prop_assert_eq!(q.unit.as_deref(), Some("m"));
+        }
+    }
+}
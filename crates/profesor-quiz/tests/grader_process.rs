@@ -0,0 +1,93 @@
+//! Grading a submission in a separate process with the stand-in grader.
+
+#![allow(clippy::expect_used)]
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use profesor_core::{Answer, Question, QuestionId, Quiz};
+use profesor_quiz::{QuizEngine, SignedGrant, SignedScore};
+
+fn quiz() -> Quiz {
+    Quiz::new("exam", "Exam")
+        .with_question(Question::MultipleChoice {
+            id: QuestionId::new("q1"),
+            prompt: "What is 2 + 2?".into(),
+            options: vec!["3".into(), "4".into()],
+            correct: 1,
+            explanation: "".into(),
+            points: 10,
+            option_explanations: Vec::new(),
+        })
+        .with_question(Question::MultipleChoice {
+            id: QuestionId::new("q2"),
+            prompt: "What is 3 + 3?".into(),
+            options: vec!["6".into(), "7".into()],
+            correct: 0,
+            explanation: "".into(),
+            points: 10,
+            option_explanations: Vec::new(),
+        })
+}
+
+fn grade(quiz_path: &std::path::Path, envelope: &str) -> std::process::Output {
+    grader(quiz_path, &[], envelope)
+}
+
+fn grant(quiz_path: &std::path::Path, attempt: u32) -> SignedGrant {
+    let output = grader(quiz_path, &["--grant", &attempt.to_string()], "");
+    assert!(output.status.success());
+    SignedGrant::from_json(&String::from_utf8_lossy(&output.stdout)).expect("Decode grant")
+}
+
+fn grader(quiz_path: &std::path::Path, args: &[&str], stdin: &str) -> std::process::Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_profesor-grader"))
+        .arg(quiz_path)
+        .args(args)
+        .env("PROFESOR_SUBMISSION_KEY", "session-key")
+        .env("PROFESOR_SCORE_KEY", "server-key")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Spawn grader");
+    child
+        .stdin
+        .take()
+        .expect("Stdin")
+        .write_all(stdin.as_bytes())
+        .expect("Write envelope");
+    child.wait_with_output().expect("Wait for grader")
+}
+
+#[test]
+fn test_grader_process_signs_score() {
+    let quiz = quiz();
+    let path = std::env::temp_dir().join(format!("profesor-grader-{}.json", std::process::id()));
+    std::fs::write(&path, serde_json::to_string(&quiz).expect("Encode quiz")).expect("Write quiz");
+
+    let mut engine = QuizEngine::new(quiz);
+    engine.start_with_grant(grant(&path, 1)).expect("Start");
+    engine.submit_answer(Answer::Choice(1)).expect("Submit");
+    engine.next_question().expect("Next");
+    engine.submit_answer(Answer::Choice(1)).expect("Submit");
+    let local = engine.finish().expect("Finish");
+    let mut envelope = engine.submission(b"session-key").expect("Envelope");
+
+    let output = grade(&path, &envelope.to_json());
+    assert!(output.status.success());
+    let signed = SignedScore::from_json(&String::from_utf8_lossy(&output.stdout)).expect("Decode");
+    let graded = signed.verify(b"server-key").expect("Verify");
+    assert_eq!(graded.score, local);
+    assert_eq!(graded.score.points_earned, 10);
+
+    // A client that rewrites its answers is rejected.
+    envelope.payload = envelope
+        .payload
+        .replacen(r#"{"Choice":1}"#, r#"{"Choice":0}"#, 1);
+    let output = grade(&path, &envelope.to_json());
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Signature does not match"));
+
+    std::fs::remove_file(&path).expect("Remove quiz");
+}
@@ -228,6 +228,7 @@ fn create_basics_quiz() -> Quiz {
             correct: 1,
             explanation: "The 'mut' keyword is used to make a variable mutable.".into(),
             points: 10,
+            option_explanations: Vec::new(),
         })
         .with_question(Question::MultipleChoice {
             id: QuestionId::new("q2"),
@@ -236,6 +237,7 @@ fn create_basics_quiz() -> Quiz {
             correct: 1,
             explanation: "Variables are immutable by default. Use 'mut' for mutability.".into(),
             points: 10,
+            option_explanations: Vec::new(),
         })
 }
 
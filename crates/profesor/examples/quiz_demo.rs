@@ -171,6 +171,7 @@ fn create_rust_fundamentals_quiz() -> Quiz {
             explanation: "In Rust, 'let' declares a variable. Variables are immutable by default."
                 .into(),
             points: 10,
+            option_explanations: Vec::new(),
         })
         .with_question(Question::MultipleChoice {
             id: QuestionId::new("q2"),
@@ -181,6 +182,7 @@ fn create_rust_fundamentals_quiz() -> Quiz {
                 "String is a compound type (heap-allocated). Scalar types are i32, bool, char, f64."
                     .into(),
             points: 10,
+            option_explanations: Vec::new(),
         })
         .with_question(Question::MultipleChoice {
             id: QuestionId::new("q3"),
@@ -195,6 +197,7 @@ fn create_rust_fundamentals_quiz() -> Quiz {
             explanation: "The '!' indicates a macro call. Macros are expanded at compile time."
                 .into(),
             points: 10,
+            option_explanations: Vec::new(),
         })
 }
 
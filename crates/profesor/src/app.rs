@@ -3,9 +3,12 @@
 //! Manages the overall application lifecycle and user interactions.
 
 use alloc::string::String;
+use alloc::sync::Arc;
 use alloc::vec::Vec;
-use profesor_core::{Course, CourseId, LearnerProgress, Quiz};
-use profesor_quiz::QuizEngine;
+use profesor_core::progress::Timestamp;
+use profesor_core::{Course, CourseId, Lab, LabId, LearnerProgress, Quiz, RecallQuality};
+use profesor_lab::{LabHistory, RunMode, SubmissionKind, TestResults, TestRunner};
+use profesor_quiz::{Clock, ManualClock, QuizEngine};
 use serde::{Deserialize, Serialize};
 
 /// Application state.
@@ -19,6 +22,13 @@ pub struct AppState {
     pub current_view: View,
     /// Active quiz engine (if taking a quiz)
     pub active_quiz: Option<ActiveQuiz>,
+    /// Active lab session (if doing a lab)
+    pub active_lab: Option<ActiveLab>,
+    /// Lab submission history for the current learner
+    pub lab_history: Option<LabHistory>,
+    /// Current time, set by JS interop before dispatching events and shared
+    /// with the active quiz engine
+    pub clock: ManualClock,
 }
 
 impl Default for AppState {
@@ -36,6 +46,9 @@ impl AppState {
             progress: None,
             current_view: View::CourseList,
             active_quiz: None,
+            active_lab: None,
+            lab_history: None,
+            clock: ManualClock::default(),
         }
     }
 
@@ -45,16 +58,45 @@ impl AppState {
     }
 
     /// Set the learner progress.
+    ///
+    /// Starts an empty lab history unless one for this learner is loaded.
     pub fn set_progress(&mut self, progress: LearnerProgress) {
+        if self
+            .lab_history
+            .as_ref()
+            .map_or(true, |h| h.learner_id != progress.learner_id)
+        {
+            self.lab_history = Some(LabHistory::new(progress.learner_id.clone()));
+        }
         self.progress = Some(progress);
     }
 
+    /// Set the lab submission history (restored alongside progress).
+    pub fn set_lab_history(&mut self, history: LabHistory) {
+        self.lab_history = Some(history);
+    }
+
+    /// Set the current time.
+    pub fn set_time(&mut self, now: Timestamp) {
+        self.clock.set(now);
+    }
+
     /// Get a course by ID.
     #[must_use]
     pub fn get_course(&self, id: &CourseId) -> Option<&Course> {
         self.courses.iter().find(|c| &c.id == id)
     }
 
+    /// Find a lab in any loaded course.
+    #[must_use]
+    pub fn get_lab(&self, id: &LabId) -> Option<&Lab> {
+        self.courses
+            .iter()
+            .flat_map(|c| &c.modules)
+            .filter_map(|m| m.lab.as_ref())
+            .find(|lab| &lab.id == id)
+    }
+
     /// Navigate to a view.
     pub fn navigate(&mut self, view: View) {
         self.current_view = view;
@@ -62,7 +104,7 @@ impl AppState {
 
     /// Start a quiz.
     pub fn start_quiz(&mut self, quiz: Quiz) -> Result<(), AppError> {
-        let mut engine = QuizEngine::new(quiz);
+        let mut engine = QuizEngine::new(quiz).with_clock(Arc::new(self.clock.clone()));
         engine
             .start()
             .map_err(|_| AppError::QuizError("Failed to start quiz".into()))?;
@@ -86,6 +128,59 @@ impl AppState {
     pub fn quiz_engine_mut(&mut self) -> Option<&mut QuizEngine> {
         self.active_quiz.as_mut().map(|aq| &mut aq.engine)
     }
+
+    /// Update the learner's review schedule from the finished quiz.
+    pub fn schedule_reviews(&mut self) {
+        let (Some(progress), Some(active)) = (self.progress.as_mut(), self.active_quiz.as_ref())
+        else {
+            return;
+        };
+        for outcome in active.engine.outcomes() {
+            let quality = RecallQuality::from_outcome(outcome.correct, outcome.points_earned);
+            progress
+                .review
+                .review(&outcome.question_id, quality, self.clock.now());
+        }
+    }
+
+    /// Start a lab, loading the first editable starter file into the editor.
+    pub fn start_lab(&mut self, lab: Lab) {
+        let code = lab
+            .starter_files
+            .iter()
+            .find(|f| !f.readonly)
+            .map(|f| f.content.clone())
+            .unwrap_or_default();
+
+        self.active_lab = Some(ActiveLab {
+            lab,
+            code,
+            runner: TestRunner::new(),
+            last_results: None,
+        });
+
+        self.current_view = View::Lab;
+    }
+
+    /// Run the active lab's tests and record the version in the history.
+    pub fn run_lab(&mut self, kind: SubmissionKind) -> Result<&TestResults, AppError> {
+        let active = self.active_lab.as_mut().ok_or(AppError::InvalidState)?;
+        let results = active
+            .runner
+            .run_tests_cached(&active.code, &active.lab, RunMode::Full);
+
+        if let Some(history) = self.lab_history.as_mut() {
+            history.record(
+                &active.lab.id,
+                &active.code,
+                kind,
+                results.clone(),
+                self.clock.now(),
+            );
+        }
+
+        Ok(active.last_results.insert(results))
+    }
 }
 
 /// Active quiz session.
@@ -97,6 +192,19 @@ pub struct ActiveQuiz {
     pub start_time_ms: u64,
 }
 
+/// Active lab session.
+#[derive(Debug, Clone)]
+pub struct ActiveLab {
+    /// The lab being worked on
+    pub lab: Lab,
+    /// Current editor contents
+    pub code: String,
+    /// Test runner (caches results between runs)
+    pub runner: TestRunner,
+    /// Results of the most recent run
+    pub last_results: Option<TestResults>,
+}
+
 /// Application views.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum View {
@@ -139,7 +247,9 @@ pub enum AppEvent {
     /// Finish the quiz
     FinishQuiz,
     /// Start a lab
-    StartLab(profesor_core::LabId),
+    StartLab(LabId),
+    /// Replace the lab editor contents
+    EditLabCode(String),
     /// Run lab tests
     RunLabTests,
     /// Submit lab
@@ -259,12 +369,38 @@ impl App {
                     engine
                         .finish()
                         .map_err(|e| AppError::QuizError(alloc::format!("{}", e)))?;
+                    self.state.schedule_reviews();
                     Ok(())
                 } else {
                     Err(AppError::InvalidState)
                 }
             }
 
+            AppEvent::StartLab(id) => {
+                let lab =
+                    self.state.get_lab(&id).cloned().ok_or_else(|| {
+                        AppError::LabError(alloc::format!("Lab not found: {}", id))
+                    })?;
+                self.state.start_lab(lab);
+                Ok(())
+            }
+
+            // Lab events without an active lab are ignored.
+            AppEvent::EditLabCode(code) => {
+                if let Some(active) = self.state.active_lab.as_mut() {
+                    active.code = code;
+                }
+                Ok(())
+            }
+
+            AppEvent::RunLabTests if self.state.active_lab.is_some() => {
+                self.state.run_lab(SubmissionKind::Run).map(|_| ())
+            }
+
+            AppEvent::SubmitLab if self.state.active_lab.is_some() => {
+                self.state.run_lab(SubmissionKind::Submit).map(|_| ())
+            }
+
             AppEvent::GoBack => {
                 self.state.navigate(View::CourseList);
                 Ok(())
@@ -300,6 +436,8 @@ mod tests {
         assert!(state.courses.is_empty());
         assert!(state.progress.is_none());
         assert!(state.active_quiz.is_none());
+        assert!(state.active_lab.is_none());
+        assert!(state.lab_history.is_none());
     }
 
     #[test]
@@ -350,6 +488,7 @@ mod tests {
             correct: 0,
             explanation: "A is correct".into(),
             points: 10,
+            option_explanations: Vec::new(),
         });
 
         app.state_mut().start_quiz(quiz).expect("Should start quiz");
@@ -368,6 +507,7 @@ mod tests {
             correct: 0,
             explanation: "A is correct".into(),
             points: 10,
+            option_explanations: Vec::new(),
         });
 
         app.state_mut().start_quiz(quiz).expect("Should start quiz");
@@ -418,6 +558,7 @@ mod tests {
             correct: 0,
             explanation: "A is correct".into(),
             points: 10,
+            option_explanations: Vec::new(),
         });
 
         app.state_mut().start_quiz(quiz).expect("Should start quiz");
@@ -449,6 +590,7 @@ mod tests {
             correct: 0,
             explanation: "A is correct".into(),
             points: 10,
+            option_explanations: Vec::new(),
         });
 
         app.state_mut().start_quiz(quiz).expect("Should start quiz");
@@ -458,6 +600,59 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_finish_quiz_schedules_reviews() {
+        let mut app = App::new();
+        app.state_mut()
+            .set_progress(LearnerProgress::new("learner-1"));
+        app.state_mut().set_time(Timestamp::from_millis(1_000));
+        let quiz = Quiz::new("test-quiz", "Test Quiz").with_question(Question::MultipleChoice {
+            id: QuestionId::new("q1"),
+            prompt: "Test?".into(),
+            options: alloc::vec!["A".into(), "B".into()],
+            correct: 0,
+            explanation: "A is correct".into(),
+            points: 10,
+            option_explanations: Vec::new(),
+        });
+
+        app.state_mut().start_quiz(quiz).expect("Should start quiz");
+        app.handle_event(AppEvent::SubmitAnswer(profesor_core::Answer::Choice(1)))
+            .expect("Should submit");
+        app.handle_event(AppEvent::FinishQuiz)
+            .expect("Should finish");
+
+        let review = &app.state().progress.as_ref().expect("Progress").review;
+        let card = review.card(&QuestionId::new("q1")).expect("Card");
+        assert_eq!(card.lapses, 1);
+        assert!(card.due > Timestamp::from_millis(1_000));
+    }
+
+    #[test]
+    fn test_quiz_events_use_app_time() {
+        let mut app = App::new();
+        app.state_mut().set_time(Timestamp::from_millis(1_000));
+        let quiz = Quiz::new("test-quiz", "Test Quiz").with_question(Question::MultipleChoice {
+            id: QuestionId::new("q1"),
+            prompt: "Test?".into(),
+            options: alloc::vec!["A".into(), "B".into()],
+            correct: 0,
+            explanation: "A is correct".into(),
+            points: 10,
+            option_explanations: Vec::new(),
+        });
+
+        app.state_mut().start_quiz(quiz).expect("Should start quiz");
+        app.state_mut().set_time(Timestamp::from_millis(4_000));
+        app.handle_event(AppEvent::SubmitAnswer(profesor_core::Answer::Choice(0)))
+            .expect("Should submit");
+
+        let log = app.state().quiz_engine().expect("Engine").audit_log();
+        assert_eq!(log.events[0].at, Timestamp::from_millis(1_000));
+        assert_eq!(log.events[2].at, Timestamp::from_millis(4_000));
+        assert_eq!(log.summary()[0].time_on_task_ms, 3_000);
+    }
+
     #[test]
     fn test_go_back() {
         let mut app = App::new();
@@ -489,12 +684,75 @@ mod tests {
         assert!(result.is_ok());
         let result = app.handle_event(AppEvent::SelectLesson(profesor_core::LessonId::new("l1")));
         assert!(result.is_ok());
-        let result = app.handle_event(AppEvent::StartLab(profesor_core::LabId::new("lab1")));
-        assert!(result.is_ok());
         let result = app.handle_event(AppEvent::RunLabTests);
         assert!(result.is_ok());
         let result = app.handle_event(AppEvent::SubmitLab);
         assert!(result.is_ok());
+        let result = app.handle_event(AppEvent::EditLabCode("fn main() {}".into()));
+        assert!(result.is_ok());
+        assert!(app.state().active_lab.is_none());
+    }
+
+    #[test]
+    fn test_start_lab_event() {
+        use profesor_core::{Module, StarterFile};
+
+        let mut app = App::new();
+        let lab =
+            Lab::new("lab-1", "Lab").with_starter_file(StarterFile::new("main.rs", "fn main() {}"));
+        app.state_mut().load_courses(alloc::vec![
+            Course::new("c1", "Course").with_module(Module::new("m1", "Module").with_lab(lab))
+        ]);
+
+        let result = app.handle_event(AppEvent::StartLab(LabId::new("missing")));
+        assert_eq!(
+            result,
+            Err(AppError::LabError("Lab not found: missing".into()))
+        );
+
+        app.handle_event(AppEvent::StartLab(LabId::new("lab-1")))
+            .expect("Should start lab");
+        assert_eq!(app.state().current_view, View::Lab);
+        let active = app
+            .state()
+            .active_lab
+            .as_ref()
+            .expect("Lab should be active");
+        assert_eq!(active.code, "fn main() {}");
+    }
+
+    #[test]
+    fn test_lab_runs_are_recorded() {
+        use profesor_core::StarterFile;
+
+        let mut app = App::new();
+        app.state_mut()
+            .set_progress(LearnerProgress::new("learner-1"));
+        app.state_mut().start_lab(
+            Lab::new("lab-1", "Lab").with_starter_file(StarterFile::new("main.rs", "fn main() {}")),
+        );
+        assert_eq!(app.state().current_view, View::Lab);
+
+        app.state_mut().set_time(Timestamp::from_millis(100));
+        app.handle_event(AppEvent::RunLabTests).expect("Should run");
+        app.handle_event(AppEvent::EditLabCode("fn main() { todo() }".into()))
+            .expect("Should edit");
+        app.state_mut().set_time(Timestamp::from_millis(200));
+        app.handle_event(AppEvent::SubmitLab)
+            .expect("Should submit");
+
+        let history = app
+            .state()
+            .lab_history
+            .as_ref()
+            .and_then(|h| h.lab(&LabId::new("lab-1")))
+            .expect("History should exist");
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.restore(1).as_deref(), Some("fn main() {}"));
+        assert_eq!(
+            history.last_submitted().map(|s| s.recorded_at),
+            Some(Timestamp::from_millis(200))
+        );
     }
 
     #[test]
@@ -207,6 +207,7 @@ pub extern "C" fn quiz_create_sample() -> QuizHandle {
             correct: 1,
             explanation: "2 + 2 = 4".into(),
             points: 10,
+            option_explanations: Vec::new(),
         })
         .with_question(Question::MultipleChoice {
             id: QuestionId::new("q2"),
@@ -215,6 +216,7 @@ pub extern "C" fn quiz_create_sample() -> QuizHandle {
             correct: 2,
             explanation: "Paris is the capital of France.".into(),
             points: 10,
+            option_explanations: Vec::new(),
         });
 
     Box::into_raw(Box::new(QuizEngine::new(quiz)))
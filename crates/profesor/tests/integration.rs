@@ -21,6 +21,7 @@ fn test_complete_quiz_workflow() {
             correct: 1,
             explanation: "1 + 1 = 2".into(),
             points: 10,
+            option_explanations: Vec::new(),
         })
         .with_question(Question::MultipleChoice {
             id: QuestionId::new("q2"),
@@ -29,6 +30,7 @@ fn test_complete_quiz_workflow() {
             correct: 1,
             explanation: "2 + 2 = 4".into(),
             points: 10,
+            option_explanations: Vec::new(),
         });
 
     // Create engine and take quiz
@@ -126,6 +128,7 @@ fn test_quiz_grading_edge_cases() {
             correct: 0,
             explanation: "A is correct".into(),
             points: 10,
+            option_explanations: Vec::new(),
         });
 
     let mut engine = QuizEngine::new(quiz);